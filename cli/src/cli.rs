@@ -8,7 +8,7 @@ use crate::{
     vote::*,
 };
 use chrono::prelude::*;
-use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use clap::{value_t, App, AppSettings, Arg, ArgMatches, SubCommand};
 use log::*;
 use num_traits::FromPrimitive;
 use serde_json::{self, json, Value};
@@ -21,6 +21,7 @@ use solana_faucet::faucet::request_airdrop_transaction;
 use solana_faucet::faucet_mock::request_airdrop_transaction;
 use solana_sdk::{
     bpf_loader,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
     clock::{Epoch, Slot},
     commitment_config::CommitmentConfig,
     fee_calculator::FeeCalculator,
@@ -142,6 +143,19 @@ pub enum CliCommand {
     },
     // Program Deployment
     Deploy(String),
+    DeployUpgradeable {
+        program_location: String,
+        upgrade_authority: Option<Pubkey>,
+        max_len: Option<usize>,
+    },
+    Upgrade {
+        program_id: Pubkey,
+        program_location: String,
+    },
+    SetUpgradeAuthority {
+        program_id: Pubkey,
+        new_upgrade_authority: Option<Pubkey>,
+    },
     // Stake Commands
     CreateStakeAccount {
         stake_account: KeypairEq,
@@ -241,6 +255,7 @@ pub enum CliCommand {
         witnesses: Option<Vec<Pubkey>>,
         cancelable: bool,
         sign_only: bool,
+        dry_run: bool,
         signers: Option<Vec<(Pubkey, Signature)>>,
         blockhash: Option<Hash>,
     },
@@ -361,8 +376,34 @@ pub fn parse_command(matches: &ArgMatches<'_>) -> Result<CliCommandInfo, Box<dyn
             parse_withdraw_from_nonce_account(matches)
         }
         // Program Deployment
-        ("deploy", Some(matches)) => Ok(CliCommandInfo {
-            command: CliCommand::Deploy(matches.value_of("program_location").unwrap().to_string()),
+        ("deploy", Some(matches)) => {
+            let program_location = matches.value_of("program_location").unwrap().to_string();
+            let command = if matches.is_present("upgradeable") {
+                CliCommand::DeployUpgradeable {
+                    program_location,
+                    upgrade_authority: pubkey_of(matches, "upgrade_authority"),
+                    max_len: value_t!(matches, "max_len", usize).ok(),
+                }
+            } else {
+                CliCommand::Deploy(program_location)
+            };
+            Ok(CliCommandInfo {
+                command,
+                require_keypair: true,
+            })
+        }
+        ("upgrade", Some(matches)) => Ok(CliCommandInfo {
+            command: CliCommand::Upgrade {
+                program_id: pubkey_of(matches, "program_id").unwrap(),
+                program_location: matches.value_of("program_location").unwrap().to_string(),
+            },
+            require_keypair: true,
+        }),
+        ("set-upgrade-authority", Some(matches)) => Ok(CliCommandInfo {
+            command: CliCommand::SetUpgradeAuthority {
+                program_id: pubkey_of(matches, "program_id").unwrap(),
+                new_upgrade_authority: pubkey_of(matches, "new_upgrade_authority"),
+            },
             require_keypair: true,
         }),
         // Stake Commands
@@ -495,6 +536,7 @@ pub fn parse_command(matches: &ArgMatches<'_>) -> Result<CliCommandInfo, Box<dyn
             let witnesses = values_of(&matches, "witness");
             let cancelable = matches.is_present("cancelable");
             let sign_only = matches.is_present("sign_only");
+            let dry_run = matches.is_present("dry_run");
             let signers = pubkeys_sigs_of(&matches, "signer");
             let blockhash = value_of(&matches, "blockhash");
 
@@ -507,6 +549,7 @@ pub fn parse_command(matches: &ArgMatches<'_>) -> Result<CliCommandInfo, Box<dyn
                     witnesses,
                     cancelable,
                     sign_only,
+                    dry_run,
                     signers,
                     blockhash,
                 },
@@ -626,6 +669,20 @@ pub fn get_blockhash_fee_calculator(
     })
 }
 
+pub fn return_simulation(rpc_client: &RpcClient, tx: &Transaction) -> ProcessResult {
+    let result = rpc_client.simulate_transaction(tx, true)?.value;
+    if let Some(logs) = &result.logs {
+        for log in logs {
+            println!("  {}", log);
+        }
+    }
+    Ok(json!({
+        "err": result.err,
+        "logs": result.logs,
+    })
+    .to_string())
+}
+
 pub fn return_signers(tx: &Transaction) -> ProcessResult {
     println_signers(tx);
     let signers: Vec<_> = tx
@@ -801,13 +858,7 @@ fn process_deploy(
     program_location: &str,
 ) -> ProcessResult {
     let program_id = Keypair::new();
-    let mut file = File::open(program_location).map_err(|err| {
-        CliError::DynamicProgramError(format!("Unable to open program file: {}", err))
-    })?;
-    let mut program_data = Vec::new();
-    file.read_to_end(&mut program_data).map_err(|err| {
-        CliError::DynamicProgramError(format!("Unable to read program file: {}", err))
-    })?;
+    let program_data = read_program_data(program_location)?;
 
     // Build transactions to calculate fees
     let mut messages: Vec<&Message> = Vec::new();
@@ -874,6 +925,269 @@ fn process_deploy(
     .to_string())
 }
 
+fn read_program_data(program_location: &str) -> Result<Vec<u8>, CliError> {
+    let mut file = File::open(program_location).map_err(|err| {
+        CliError::DynamicProgramError(format!("Unable to open program file: {}", err))
+    })?;
+    let mut program_data = Vec::new();
+    file.read_to_end(&mut program_data).map_err(|err| {
+        CliError::DynamicProgramError(format!("Unable to read program file: {}", err))
+    })?;
+    Ok(program_data)
+}
+
+fn upgradeable_loader_error(err: InstructionError) -> CliError {
+    CliError::DynamicProgramError(format!("Invalid program length: {:?}", err))
+}
+
+/// Build the transactions that stage `program_data` in a new Buffer account of the upgradeable
+/// loader, with the client keypair as the Buffer's authority
+fn build_buffer_transactions(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    buffer: &Keypair,
+    program_data: &[u8],
+    blockhash: Hash,
+) -> Result<(Transaction, Vec<Transaction>), Box<dyn error::Error>> {
+    let buffer_len =
+        UpgradeableLoaderState::buffer_len(program_data.len()).map_err(upgradeable_loader_error)?;
+    let minimum_balance = rpc_client.get_minimum_balance_for_rent_exemption(buffer_len)?;
+    let instructions = bpf_loader_upgradeable::create_buffer(
+        &config.keypair.pubkey(),
+        &buffer.pubkey(),
+        &config.keypair.pubkey(),
+        minimum_balance.max(1),
+        program_data.len(),
+    )
+    .map_err(upgradeable_loader_error)?;
+    let create_buffer_tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&config.keypair.pubkey()),
+        &[&config.keypair, buffer],
+        blockhash,
+    );
+
+    let write_transactions = program_data
+        .chunks(USERDATA_CHUNK_SIZE)
+        .zip(0..)
+        .map(|(chunk, i)| {
+            let instruction = bpf_loader_upgradeable::write(
+                &buffer.pubkey(),
+                &config.keypair.pubkey(),
+                (i * USERDATA_CHUNK_SIZE) as u32,
+                chunk.to_vec(),
+            );
+            Transaction::new_signed_with_payer(
+                vec![instruction],
+                Some(&config.keypair.pubkey()),
+                &[&config.keypair],
+                blockhash,
+            )
+        })
+        .collect();
+    Ok((create_buffer_tx, write_transactions))
+}
+
+fn send_buffer_transactions(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    buffer: &Keypair,
+    mut create_buffer_tx: Transaction,
+    write_transactions: Vec<Transaction>,
+) -> Result<(), Box<dyn error::Error>> {
+    trace!("Creating buffer account");
+    let result =
+        rpc_client.send_and_confirm_transaction(&mut create_buffer_tx, &[&config.keypair, buffer]);
+    log_instruction_custom_error::<SystemError>(result)
+        .map_err(|_| CliError::DynamicProgramError("Buffer allocate space failed".to_string()))?;
+
+    trace!("Writing program data");
+    rpc_client.send_and_confirm_transactions(write_transactions, &[&config.keypair])?;
+    Ok(())
+}
+
+fn process_deploy_upgradeable(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    program_location: &str,
+    upgrade_authority: Option<Pubkey>,
+    max_len: Option<usize>,
+) -> ProcessResult {
+    let program_id = Keypair::new();
+    let buffer = Keypair::new();
+    let program_data = read_program_data(program_location)?;
+    // Leave room for the program to grow in later upgrades
+    let max_len = max_len.unwrap_or_else(|| program_data.len() * 2);
+    if max_len < program_data.len() {
+        return Err(CliError::BadParameter(format!(
+            "Max program length {} is less than the program length {}",
+            max_len,
+            program_data.len()
+        ))
+        .into());
+    }
+    let upgrade_authority = upgrade_authority.unwrap_or_else(|| config.keypair.pubkey());
+
+    // Build transactions to calculate fees
+    let mut messages: Vec<&Message> = Vec::new();
+    let (blockhash, fee_calculator) = rpc_client.get_recent_blockhash()?;
+    let (create_buffer_tx, write_transactions) =
+        build_buffer_transactions(rpc_client, config, &buffer, &program_data, blockhash)?;
+    messages.push(&create_buffer_tx.message);
+    for transaction in write_transactions.iter() {
+        messages.push(&transaction.message);
+    }
+
+    let program_lamports = rpc_client.get_minimum_balance_for_rent_exemption(
+        UpgradeableLoaderState::program_len().map_err(upgradeable_loader_error)?,
+    )?;
+    let instructions = bpf_loader_upgradeable::deploy_with_max_program_len(
+        &config.keypair.pubkey(),
+        &program_id.pubkey(),
+        &buffer.pubkey(),
+        &config.keypair.pubkey(),
+        Some(&upgrade_authority),
+        program_lamports.max(1),
+        max_len,
+    )
+    .map_err(upgradeable_loader_error)?;
+    let signers = [&config.keypair, &program_id];
+    let mut deploy_tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&config.keypair.pubkey()),
+        &signers,
+        blockhash,
+    );
+    messages.push(&deploy_tx.message);
+
+    check_account_for_multiple_fees(
+        rpc_client,
+        &config.keypair.pubkey(),
+        &fee_calculator,
+        &messages,
+    )?;
+
+    send_buffer_transactions(
+        rpc_client,
+        config,
+        &buffer,
+        create_buffer_tx,
+        write_transactions,
+    )?;
+
+    trace!("Deploying program");
+    rpc_client
+        .send_and_confirm_transaction(&mut deploy_tx, &signers)
+        .map_err(|_| {
+            CliError::DynamicProgramError("Program deploy transaction failed".to_string())
+        })?;
+
+    Ok(json!({
+        "programId": format!("{}", program_id.pubkey()),
+        "upgradeAuthority": format!("{}", upgrade_authority),
+    })
+    .to_string())
+}
+
+fn process_upgrade(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    program_id: &Pubkey,
+    program_location: &str,
+) -> ProcessResult {
+    let buffer = Keypair::new();
+    let program_data = read_program_data(program_location)?;
+
+    // Build transactions to calculate fees
+    let mut messages: Vec<&Message> = Vec::new();
+    let (blockhash, fee_calculator) = rpc_client.get_recent_blockhash()?;
+    let (create_buffer_tx, write_transactions) =
+        build_buffer_transactions(rpc_client, config, &buffer, &program_data, blockhash)?;
+    messages.push(&create_buffer_tx.message);
+    for transaction in write_transactions.iter() {
+        messages.push(&transaction.message);
+    }
+
+    // The buffer's lamports are returned to the fee payer once the upgrade is done
+    let instruction = bpf_loader_upgradeable::upgrade(
+        program_id,
+        &buffer.pubkey(),
+        &config.keypair.pubkey(),
+        &config.keypair.pubkey(),
+    );
+    let mut upgrade_tx = Transaction::new_signed_with_payer(
+        vec![instruction],
+        Some(&config.keypair.pubkey()),
+        &[&config.keypair],
+        blockhash,
+    );
+    messages.push(&upgrade_tx.message);
+
+    check_account_for_multiple_fees(
+        rpc_client,
+        &config.keypair.pubkey(),
+        &fee_calculator,
+        &messages,
+    )?;
+
+    send_buffer_transactions(
+        rpc_client,
+        config,
+        &buffer,
+        create_buffer_tx,
+        write_transactions,
+    )?;
+
+    trace!("Upgrading program");
+    rpc_client
+        .send_and_confirm_transaction(&mut upgrade_tx, &[&config.keypair])
+        .map_err(|_| {
+            CliError::DynamicProgramError("Program upgrade transaction failed".to_string())
+        })?;
+
+    Ok(json!({
+        "programId": format!("{}", program_id),
+    })
+    .to_string())
+}
+
+fn process_set_upgrade_authority(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    program_id: &Pubkey,
+    new_upgrade_authority: Option<Pubkey>,
+) -> ProcessResult {
+    let (recent_blockhash, fee_calculator) = rpc_client.get_recent_blockhash()?;
+
+    let ix = bpf_loader_upgradeable::set_upgrade_authority(
+        program_id,
+        &config.keypair.pubkey(),
+        new_upgrade_authority.as_ref(),
+    );
+    let mut tx = Transaction::new_signed_with_payer(
+        vec![ix],
+        Some(&config.keypair.pubkey()),
+        &[&config.keypair],
+        recent_blockhash,
+    );
+    check_account_for_fee(
+        rpc_client,
+        &config.keypair.pubkey(),
+        &fee_calculator,
+        &tx.message,
+    )?;
+    rpc_client
+        .send_and_confirm_transaction(&mut tx, &[&config.keypair])
+        .map_err(|_| {
+            CliError::DynamicProgramError("Set upgrade authority transaction failed".to_string())
+        })?;
+
+    Ok(json!({
+        "upgradeAuthority": new_upgrade_authority.map(|authority| authority.to_string()),
+    })
+    .to_string())
+}
+
 #[allow(clippy::too_many_arguments)]
 fn process_pay(
     rpc_client: &RpcClient,
@@ -885,6 +1199,7 @@ fn process_pay(
     witnesses: &Option<Vec<Pubkey>>,
     cancelable: bool,
     sign_only: bool,
+    dry_run: bool,
     signers: &Option<Vec<(Pubkey, Signature)>>,
     blockhash: Option<Hash>,
 ) -> ProcessResult {
@@ -910,6 +1225,8 @@ fn process_pay(
 
         if sign_only {
             return_signers(&tx)
+        } else if dry_run {
+            return_simulation(rpc_client, &tx)
         } else {
             check_account_for_fee(
                 rpc_client,
@@ -949,6 +1266,8 @@ fn process_pay(
         }
         if sign_only {
             return_signers(&tx)
+        } else if dry_run {
+            return_simulation(rpc_client, &tx)
         } else {
             check_account_for_fee(
                 rpc_client,
@@ -997,6 +1316,8 @@ fn process_pay(
         }
         if sign_only {
             return_signers(&tx)
+        } else if dry_run {
+            return_simulation(rpc_client, &tx)
         } else {
             let result = rpc_client
                 .send_and_confirm_transaction(&mut tx, &[&config.keypair, &contract_state]);
@@ -1205,6 +1526,31 @@ pub fn process_command(config: &CliConfig) -> ProcessResult {
             process_deploy(&rpc_client, config, program_location)
         }
 
+        // Deploy a program that its upgrade authority can later replace
+        CliCommand::DeployUpgradeable {
+            ref program_location,
+            upgrade_authority,
+            max_len,
+        } => process_deploy_upgradeable(
+            &rpc_client,
+            config,
+            program_location,
+            *upgrade_authority,
+            *max_len,
+        ),
+
+        // Replace the code of an upgradeable program
+        CliCommand::Upgrade {
+            program_id,
+            ref program_location,
+        } => process_upgrade(&rpc_client, config, program_id, program_location),
+
+        // Change or revoke the upgrade authority of an upgradeable program
+        CliCommand::SetUpgradeAuthority {
+            program_id,
+            new_upgrade_authority,
+        } => process_set_upgrade_authority(&rpc_client, config, program_id, *new_upgrade_authority),
+
         // Stake Commands
 
         // Create stake account
@@ -1446,6 +1792,7 @@ pub fn process_command(config: &CliConfig) -> ProcessResult {
             ref witnesses,
             cancelable,
             sign_only,
+            dry_run,
             ref signers,
             blockhash,
         } => process_pay(
@@ -1458,6 +1805,7 @@ pub fn process_command(config: &CliConfig) -> ProcessResult {
             witnesses,
             *cancelable,
             *sign_only,
+            *dry_run,
             signers,
             *blockhash,
         ),
@@ -1733,6 +2081,85 @@ pub fn app<'ab, 'v>(name: &str, about: &'ab str, version: &'v str) -> App<'ab, '
                         .takes_value(true)
                         .required(true)
                         .help("/path/to/program.o"),
+                )
+                .arg(
+                    Arg::with_name("upgradeable")
+                        .long("upgradeable")
+                        .takes_value(false)
+                        .help("Deploy with the upgradeable BPF loader"),
+                )
+                .arg(
+                    Arg::with_name("upgrade_authority")
+                        .long("upgrade-authority")
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .requires("upgradeable")
+                        .validator(is_pubkey_or_keypair)
+                        .help("Upgrade authority of the program, defaults to client keypair"),
+                )
+                .arg(
+                    Arg::with_name("max_len")
+                        .long("max-len")
+                        .value_name("BYTES")
+                        .takes_value(true)
+                        .requires("upgradeable")
+                        .validator(|value| {
+                            value
+                                .parse::<usize>()
+                                .map(|_| ())
+                                .map_err(|err| format!("{}", err))
+                        })
+                        .help("Maximum program length for upgrades, defaults to twice its length"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("upgrade")
+                .about("Upgrade a program deployed with the upgradeable BPF loader")
+                .arg(
+                    Arg::with_name("program_id")
+                        .index(1)
+                        .value_name("PROGRAM ID")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_pubkey_or_keypair)
+                        .help("Address of the program to upgrade"),
+                )
+                .arg(
+                    Arg::with_name("program_location")
+                        .index(2)
+                        .value_name("PATH TO BPF PROGRAM")
+                        .takes_value(true)
+                        .required(true)
+                        .help("/path/to/program.o"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("set-upgrade-authority")
+                .about("Change or revoke the upgrade authority of an upgradeable program")
+                .arg(
+                    Arg::with_name("program_id")
+                        .index(1)
+                        .value_name("PROGRAM ID")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_pubkey_or_keypair)
+                        .help("Address of the program"),
+                )
+                .arg(
+                    Arg::with_name("new_upgrade_authority")
+                        .long("new-upgrade-authority")
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .required_unless("final")
+                        .validator(is_pubkey_or_keypair)
+                        .help("New upgrade authority of the program"),
+                )
+                .arg(
+                    Arg::with_name("final")
+                        .long("final")
+                        .takes_value(false)
+                        .conflicts_with("new_upgrade_authority")
+                        .help("Revoke the upgrade authority, making the program immutable"),
                 ),
         )
         .subcommand(
@@ -1801,6 +2228,13 @@ pub fn app<'ab, 'v>(name: &str, about: &'ab str, version: &'v str) -> App<'ab, '
                         .takes_value(false)
                         .help("Sign the transaction offline"),
                 )
+                .arg(
+                    Arg::with_name("dry_run")
+                        .long("dry-run")
+                        .takes_value(false)
+                        .conflicts_with("sign_only")
+                        .help("Simulate the transaction against the cluster without submitting it"),
+                )
                 .arg(
                     Arg::with_name("signer")
                         .long("signer")
@@ -2094,6 +2528,84 @@ mod tests {
                 require_keypair: true
             }
         );
+        let test_deploy_upgradeable = test_commands.clone().get_matches_from(vec![
+            "test",
+            "deploy",
+            "/Users/test/program.o",
+            "--upgradeable",
+            "--upgrade-authority",
+            &pubkey_string,
+            "--max-len",
+            "42",
+        ]);
+        assert_eq!(
+            parse_command(&test_deploy_upgradeable).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::DeployUpgradeable {
+                    program_location: "/Users/test/program.o".to_string(),
+                    upgrade_authority: Some(pubkey),
+                    max_len: Some(42),
+                },
+                require_keypair: true
+            }
+        );
+
+        // Test Upgrade Subcommand
+        let test_upgrade = test_commands.clone().get_matches_from(vec![
+            "test",
+            "upgrade",
+            &pubkey_string,
+            "/Users/test/program.o",
+        ]);
+        assert_eq!(
+            parse_command(&test_upgrade).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Upgrade {
+                    program_id: pubkey,
+                    program_location: "/Users/test/program.o".to_string(),
+                },
+                require_keypair: true
+            }
+        );
+
+        // Test SetUpgradeAuthority Subcommand
+        let test_set_upgrade_authority = test_commands.clone().get_matches_from(vec![
+            "test",
+            "set-upgrade-authority",
+            &pubkey_string,
+            "--new-upgrade-authority",
+            &pubkey_string,
+        ]);
+        assert_eq!(
+            parse_command(&test_set_upgrade_authority).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::SetUpgradeAuthority {
+                    program_id: pubkey,
+                    new_upgrade_authority: Some(pubkey),
+                },
+                require_keypair: true
+            }
+        );
+        let test_set_upgrade_authority = test_commands.clone().get_matches_from(vec![
+            "test",
+            "set-upgrade-authority",
+            &pubkey_string,
+            "--final",
+        ]);
+        assert_eq!(
+            parse_command(&test_set_upgrade_authority).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::SetUpgradeAuthority {
+                    program_id: pubkey,
+                    new_upgrade_authority: None,
+                },
+                require_keypair: true
+            }
+        );
+        assert!(test_commands
+            .clone()
+            .get_matches_from_safe(vec!["test", "set-upgrade-authority", &pubkey_string])
+            .is_err());
 
         // Test Simple Pay Subcommand
         let test_pay = test_commands.clone().get_matches_from(vec![
@@ -2114,6 +2626,7 @@ mod tests {
                     witnesses: None,
                     cancelable: false,
                     sign_only: false,
+                    dry_run: false,
                     signers: None,
                     blockhash: None,
                 },
@@ -2144,6 +2657,7 @@ mod tests {
                     witnesses: Some(vec![witness0, witness1]),
                     cancelable: false,
                     sign_only: false,
+                    dry_run: false,
                     signers: None,
                     blockhash: None,
                 },
@@ -2170,6 +2684,7 @@ mod tests {
                     witnesses: Some(vec![witness0]),
                     cancelable: false,
                     sign_only: false,
+                    dry_run: false,
                     signers: None,
                     blockhash: None,
                 },
@@ -2200,6 +2715,7 @@ mod tests {
                     witnesses: None,
                     cancelable: false,
                     sign_only: false,
+                    dry_run: false,
                     signers: None,
                     blockhash: None,
                 },
@@ -2227,6 +2743,35 @@ mod tests {
                     witnesses: None,
                     cancelable: false,
                     sign_only: true,
+                    dry_run: false,
+                    signers: None,
+                    blockhash: None,
+                },
+                require_keypair: true,
+            }
+        );
+
+        // Test Pay Subcommand w/ dry run
+        let test_pay = test_commands.clone().get_matches_from(vec![
+            "test",
+            "pay",
+            &pubkey_string,
+            "50",
+            "lamports",
+            "--dry-run",
+        ]);
+        assert_eq!(
+            parse_command(&test_pay).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Pay {
+                    lamports: 50,
+                    to: pubkey,
+                    timestamp: None,
+                    timestamp_pubkey: None,
+                    witnesses: None,
+                    cancelable: false,
+                    sign_only: false,
+                    dry_run: true,
                     signers: None,
                     blockhash: None,
                 },
@@ -2258,6 +2803,7 @@ mod tests {
                     witnesses: None,
                     cancelable: false,
                     sign_only: false,
+                    dry_run: false,
                     signers: Some(vec![(key1, sig1)]),
                     blockhash: None,
                 },
@@ -2291,6 +2837,7 @@ mod tests {
                     witnesses: None,
                     cancelable: false,
                     sign_only: false,
+                    dry_run: false,
                     signers: Some(vec![(key1, sig1), (key2, sig2)]),
                     blockhash: None,
                 },
@@ -2321,6 +2868,7 @@ mod tests {
                     witnesses: None,
                     cancelable: false,
                     sign_only: false,
+                    dry_run: false,
                     signers: None,
                     blockhash: Some(blockhash),
                 },
@@ -2368,6 +2916,7 @@ mod tests {
                     witnesses: Some(vec![witness0, witness1]),
                     cancelable: false,
                     sign_only: false,
+                    dry_run: false,
                     signers: None,
                     blockhash: None,
                 },
@@ -2516,12 +3065,29 @@ mod tests {
             witnesses: None,
             cancelable: false,
             sign_only: false,
+            dry_run: false,
             signers: None,
             blockhash: None,
         };
         let signature = process_command(&config);
         assert_eq!(signature.unwrap(), SIGNATURE.to_string());
 
+        config.command = CliCommand::Pay {
+            lamports: 10,
+            to: bob_pubkey,
+            timestamp: None,
+            timestamp_pubkey: None,
+            witnesses: None,
+            cancelable: false,
+            sign_only: false,
+            dry_run: true,
+            signers: None,
+            blockhash: None,
+        };
+        let result = process_command(&config);
+        let json: Value = serde_json::from_str(&result.unwrap()).unwrap();
+        assert_eq!(json, json!({ "err": null, "logs": null }));
+
         let date_string = "\"2018-09-19T17:30:59Z\"";
         let dt: DateTime<Utc> = serde_json::from_str(&date_string).unwrap();
         config.command = CliCommand::Pay {
@@ -2532,6 +3098,7 @@ mod tests {
             witnesses: None,
             cancelable: false,
             sign_only: false,
+            dry_run: false,
             signers: None,
             blockhash: None,
         };
@@ -2556,6 +3123,7 @@ mod tests {
             witnesses: Some(vec![witness]),
             cancelable: true,
             sign_only: false,
+            dry_run: false,
             signers: None,
             blockhash: None,
         };
@@ -2677,6 +3245,7 @@ mod tests {
             witnesses: None,
             cancelable: false,
             sign_only: false,
+            dry_run: false,
             signers: None,
             blockhash: None,
         };
@@ -2690,6 +3259,7 @@ mod tests {
             witnesses: None,
             cancelable: false,
             sign_only: false,
+            dry_run: false,
             signers: None,
             blockhash: None,
         };
@@ -2703,6 +3273,7 @@ mod tests {
             witnesses: Some(vec![witness]),
             cancelable: true,
             sign_only: false,
+            dry_run: false,
             signers: None,
             blockhash: None,
         };
@@ -2742,4 +3313,70 @@ mod tests {
         config.command = CliCommand::Deploy("bad/file/location.so".to_string());
         assert!(process_command(&config).is_err());
     }
+
+    #[test]
+    fn test_cli_deploy_upgradeable() {
+        solana_logger::setup();
+        let mut pathbuf = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        pathbuf.push("tests");
+        pathbuf.push("fixtures");
+        pathbuf.push("noop");
+        pathbuf.set_extension("so");
+        let program_location = pathbuf.to_str().unwrap().to_string();
+
+        let mut config = CliConfig::default();
+        config.rpc_client = Some(RpcClient::new_mock("deploy_succeeds".to_string()));
+
+        config.command = CliCommand::DeployUpgradeable {
+            program_location: program_location.clone(),
+            upgrade_authority: None,
+            max_len: None,
+        };
+        let result = process_command(&config);
+        let json: Value = serde_json::from_str(&result.unwrap()).unwrap();
+        let program_id = json
+            .as_object()
+            .unwrap()
+            .get("programId")
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .parse::<Pubkey>()
+            .unwrap();
+        assert_eq!(
+            json.as_object()
+                .unwrap()
+                .get("upgradeAuthority")
+                .unwrap()
+                .as_str()
+                .unwrap(),
+            config.keypair.pubkey().to_string()
+        );
+
+        // Max length smaller than the program
+        config.command = CliCommand::DeployUpgradeable {
+            program_location: program_location.clone(),
+            upgrade_authority: None,
+            max_len: Some(1),
+        };
+        assert!(process_command(&config).is_err());
+
+        config.command = CliCommand::Upgrade {
+            program_id,
+            program_location,
+        };
+        assert!(process_command(&config).is_ok());
+
+        config.command = CliCommand::SetUpgradeAuthority {
+            program_id,
+            new_upgrade_authority: None,
+        };
+        assert!(process_command(&config).is_ok());
+
+        config.command = CliCommand::Upgrade {
+            program_id,
+            program_location: "bad/file/location.so".to_string(),
+        };
+        assert!(process_command(&config).is_err());
+    }
 }
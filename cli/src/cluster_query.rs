@@ -9,7 +9,10 @@ use clap::{value_t, value_t_or_exit, App, Arg, ArgMatches, SubCommand};
 use console::{style, Emoji};
 use indicatif::{ProgressBar, ProgressStyle};
 use solana_clap_utils::{input_parsers::*, input_validators::*};
-use solana_client::{rpc_client::RpcClient, rpc_request::RpcVoteAccountInfo};
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_request::{RpcBlockProductionConfig, RpcBlockProductionConfigRange, RpcVoteAccountInfo},
+};
 use solana_sdk::{
     clock::{self, Slot},
     commitment_config::CommitmentConfig,
@@ -445,23 +448,110 @@ pub fn process_show_block_production(
     } else {
         first_slot_in_epoch
     };
+    let progress_bar = new_spinner_progress_bar();
+    progress_bar.set_message(&format!(
+        "Fetching block production between slots {} and {}...",
+        start_slot, end_slot
+    ));
+    let block_production = rpc_client
+        .get_block_production(RpcBlockProductionConfig {
+            identity: None,
+            range: Some(RpcBlockProductionConfigRange {
+                first_slot: start_slot,
+                last_slot: Some(end_slot),
+            }),
+            commitment: Some(CommitmentConfig::max()),
+        })?
+        .value;
+
+    let individual_slot_status = if config.verbose {
+        get_individual_slot_status(
+            rpc_client,
+            &progress_bar,
+            epoch,
+            first_slot_in_epoch,
+            start_slot,
+            end_slot,
+        )?
+    } else {
+        vec![]
+    };
+
+    progress_bar.finish_and_clear();
+    println!(
+        "\n{}",
+        style(format!(
+            "  {:<44}  {:>15}  {:>15}  {:>15}  {:>23}",
+            "Identity Pubkey",
+            "Leader Slots",
+            "Blocks Produced",
+            "Skipped Slots",
+            "Skipped Slot Percentage",
+        ))
+        .bold()
+    );
+
+    let mut total_slots = 0;
+    let mut total_blocks = 0;
+    let mut table = vec![];
+    for (leader, (leader_slots, blocks_produced)) in block_production.by_identity.iter() {
+        let skipped_slots = leader_slots - blocks_produced;
+        total_slots += leader_slots;
+        total_blocks += blocks_produced;
+        table.push(format!(
+            "  {:<44}  {:>15}  {:>15}  {:>15}  {:>22.2}%",
+            leader,
+            leader_slots,
+            blocks_produced,
+            skipped_slots,
+            skipped_slots as f64 / *leader_slots as f64 * 100.
+        ));
+    }
+    table.sort();
+    let total_slots_skipped = total_slots - total_blocks;
+
+    println!(
+        "{}\n\n  {:<44}  {:>15}  {:>15}  {:>15}  {:>22.2}%",
+        table.join("\n"),
+        format!("Epoch {} total:", epoch),
+        total_slots,
+        total_blocks,
+        total_slots_skipped,
+        total_slots_skipped as f64 / total_slots as f64 * 100.
+    );
+    println!(
+        "  (using data from {} slots: {} to {})",
+        total_slots, start_slot, end_slot
+    );
+
+    if config.verbose {
+        println!(
+            "\n\n{}\n{}",
+            style(format!("  {:<15} {:<44}", "Slot", "Identity Pubkey")).bold(),
+            individual_slot_status.join("\n")
+        );
+    }
+    Ok("".to_string())
+}
+
+fn get_individual_slot_status(
+    rpc_client: &RpcClient,
+    progress_bar: &ProgressBar,
+    epoch: Epoch,
+    first_slot_in_epoch: Slot,
+    start_slot: Slot,
+    end_slot: Slot,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
     let start_slot_index = (start_slot - first_slot_in_epoch) as usize;
     let end_slot_index = (end_slot - first_slot_in_epoch) as usize;
+    let total_slots = end_slot_index - start_slot_index + 1;
 
-    let progress_bar = new_spinner_progress_bar();
     progress_bar.set_message(&format!(
         "Fetching confirmed blocks between slots {} and {}...",
         start_slot, end_slot
     ));
     let confirmed_blocks = rpc_client.get_confirmed_blocks(start_slot, Some(end_slot))?;
 
-    let total_slots = end_slot_index - start_slot_index + 1;
-    let total_blocks = confirmed_blocks.len();
-    assert!(total_blocks <= total_slots);
-    let total_slots_skipped = total_slots - total_blocks;
-    let mut leader_slot_count = HashMap::new();
-    let mut leader_skipped_slots = HashMap::new();
-
     progress_bar.set_message(&format!("Fetching leader schedule for epoch {}...", epoch));
     let leader_schedule = rpc_client
         .get_leader_schedule_with_commitment(Some(start_slot), CommitmentConfig::max())?;
@@ -481,18 +571,15 @@ pub fn process_show_block_production(
     }
 
     progress_bar.set_message(&format!(
-        "Processing {} slots containing {} blocks and {} empty slots...",
-        total_slots, total_blocks, total_slots_skipped
+        "Processing {} slots containing {} blocks...",
+        total_slots,
+        confirmed_blocks.len()
     ));
 
     let mut confirmed_blocks_index = 0;
     let mut individual_slot_status = vec![];
     for (slot_index, leader) in leader_per_slot_index.iter().enumerate() {
         let slot = start_slot + slot_index as u64;
-        let slot_count = leader_slot_count.entry(leader).or_insert(0);
-        *slot_count += 1;
-        let skipped_slots = leader_skipped_slots.entry(leader).or_insert(0);
-
         loop {
             if confirmed_blocks_index < confirmed_blocks.len() {
                 let slot_of_next_confirmed_block = confirmed_blocks[confirmed_blocks_index];
@@ -506,7 +593,6 @@ pub fn process_show_block_production(
                     break;
                 }
             }
-            *skipped_slots += 1;
             individual_slot_status.push(
                 style(format!("  {:<15} {:<44} SKIPPED", slot, leader))
                     .red()
@@ -515,58 +601,7 @@ pub fn process_show_block_production(
             break;
         }
     }
-
-    progress_bar.finish_and_clear();
-    println!(
-        "\n{}",
-        style(format!(
-            "  {:<44}  {:>15}  {:>15}  {:>15}  {:>23}",
-            "Identity Pubkey",
-            "Leader Slots",
-            "Blocks Produced",
-            "Skipped Slots",
-            "Skipped Slot Percentage",
-        ))
-        .bold()
-    );
-
-    let mut table = vec![];
-    for (leader, leader_slots) in leader_slot_count.iter() {
-        let skipped_slots = leader_skipped_slots.get(leader).unwrap();
-        let blocks_produced = leader_slots - skipped_slots;
-        table.push(format!(
-            "  {:<44}  {:>15}  {:>15}  {:>15}  {:>22.2}%",
-            leader,
-            leader_slots,
-            blocks_produced,
-            skipped_slots,
-            *skipped_slots as f64 / *leader_slots as f64 * 100.
-        ));
-    }
-    table.sort();
-
-    println!(
-        "{}\n\n  {:<44}  {:>15}  {:>15}  {:>15}  {:>22.2}%",
-        table.join("\n"),
-        format!("Epoch {} total:", epoch),
-        total_slots,
-        total_blocks,
-        total_slots_skipped,
-        total_slots_skipped as f64 / total_slots as f64 * 100.
-    );
-    println!(
-        "  (using data from {} slots: {} to {})",
-        total_slots, start_slot, end_slot
-    );
-
-    if config.verbose {
-        println!(
-            "\n\n{}\n{}",
-            style(format!("  {:<15} {:<44}", "Slot", "Identity Pubkey")).bold(),
-            individual_slot_status.join("\n")
-        );
-    }
-    Ok("".to_string())
+    Ok(individual_slot_status)
 }
 
 pub fn process_get_transaction_count(
@@ -7,7 +7,7 @@ use crate::cli::{
 use clap::{App, Arg, ArgMatches, SubCommand};
 use console::style;
 use solana_clap_utils::{input_parsers::*, input_validators::*};
-use solana_client::rpc_client::RpcClient;
+use solana_client::{rpc_client::RpcClient, rpc_request::StakeActivationState};
 use solana_sdk::signature::{Keypair, Signature};
 use solana_sdk::{
     account_utils::State,
@@ -16,7 +16,7 @@ use solana_sdk::{
     signature::KeypairUtil,
     system_instruction::SystemError,
     sysvar::{
-        stake_history::{self, StakeHistory},
+        stake_history::{self, StakeHistory, StakeHistoryEntry, MAX_ENTRIES},
         Sysvar,
     },
     transaction::Transaction,
@@ -705,6 +705,46 @@ pub fn process_show_stake_account(
                     stake.delegation.deactivation_epoch
                 );
             }
+            let activation = rpc_client.get_stake_activation(stake_account_pubkey, None)?;
+            println!("stake activation state: {}", activation.state);
+            println!(
+                "active stake: {}",
+                build_balance_message(activation.active, use_lamports_unit, true)
+            );
+            println!(
+                "inactive stake: {}",
+                build_balance_message(activation.inactive, use_lamports_unit, true)
+            );
+            let (remaining, activating, target) = match activation.state {
+                StakeActivationState::Activating => (
+                    stake.delegation.stake.saturating_sub(activation.active),
+                    true,
+                    "active",
+                ),
+                StakeActivationState::Deactivating => (activation.active, false, "inactive"),
+                _ => (0, false, ""),
+            };
+            if remaining > 0 {
+                let stake_history_account = rpc_client.get_account(&stake_history::id())?;
+                let latest_entry = StakeHistory::from_account(&stake_history_account)
+                    .and_then(|stake_history| stake_history.first().cloned());
+                if let Some((_, entry)) = latest_entry {
+                    match projected_epochs_until_settled(
+                        remaining,
+                        &entry,
+                        stake.delegation.warmup_cooldown_rate,
+                        activating,
+                    ) {
+                        Some(epochs) => {
+                            println!("projected epochs until fully {}: {}", target, epochs)
+                        }
+                        None => println!(
+                            "projected epochs until fully {}: more than {}",
+                            target, MAX_ENTRIES
+                        ),
+                    }
+                }
+            }
             show_authorized(&authorized);
             show_lockup(&lockup);
             Ok("".to_string())
@@ -727,6 +767,49 @@ pub fn process_show_stake_account(
     }
 }
 
+/// Projects the number of epochs until `remaining` lamports of stake finish warming up (or
+/// cooling down), assuming the cluster-wide stake evolves from the latest stake history `entry`
+/// with no new activations or deactivations
+fn projected_epochs_until_settled(
+    mut remaining: u64,
+    entry: &StakeHistoryEntry,
+    warmup_cooldown_rate: f64,
+    activating: bool,
+) -> Option<u64> {
+    let mut cluster_effective = entry.effective;
+    let mut cluster_changing = if activating {
+        entry.activating
+    } else {
+        entry.deactivating
+    }
+    .max(remaining);
+
+    let mut epochs = 0;
+    while remaining > 0 {
+        if epochs >= MAX_ENTRIES as u64 {
+            return None;
+        }
+        // The cluster may only change its effective stake by a fraction of it each epoch, and
+        // each account is entitled to its share of that change
+        let cluster_change = ((cluster_effective as f64 * warmup_cooldown_rate) as u64)
+            .max(1)
+            .min(cluster_changing);
+        let weight = remaining as f64 / cluster_changing as f64;
+        remaining = remaining.saturating_sub(
+            ((weight * cluster_effective as f64 * warmup_cooldown_rate) as u64).max(1),
+        );
+
+        cluster_changing = (cluster_changing - cluster_change).max(remaining);
+        if activating {
+            cluster_effective += cluster_change;
+        } else {
+            cluster_effective = cluster_effective.saturating_sub(cluster_change);
+        }
+        epochs += 1;
+    }
+    Some(epochs)
+}
+
 pub fn process_show_stake_history(
     rpc_client: &RpcClient,
     _config: &CliConfig,
@@ -1248,4 +1331,42 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_projected_epochs_until_settled() {
+        let entry = StakeHistoryEntry {
+            effective: 1_000,
+            activating: 100,
+            deactivating: 100,
+        };
+        assert_eq!(
+            projected_epochs_until_settled(100, &entry, 0.25, true),
+            Some(1)
+        );
+        assert_eq!(
+            projected_epochs_until_settled(0, &entry, 0.25, true),
+            Some(0)
+        );
+
+        // Warmup takes several epochs when the cluster is mostly activating stake
+        let entry = StakeHistoryEntry {
+            effective: 100,
+            activating: 100,
+            deactivating: 0,
+        };
+        let epochs = projected_epochs_until_settled(50, &entry, 0.25, true).unwrap();
+        assert!(epochs > 1);
+        assert_eq!(
+            projected_epochs_until_settled(100, &entry, 0.25, true),
+            Some(epochs)
+        );
+
+        // Cooldown slows as the effective stake shrinks
+        let entry = StakeHistoryEntry {
+            effective: 100,
+            activating: 0,
+            deactivating: 100,
+        };
+        assert!(projected_epochs_until_settled(100, &entry, 0.25, false).unwrap() > epochs);
+    }
 }
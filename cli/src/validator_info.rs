@@ -13,7 +13,8 @@ use solana_clap_utils::{
     input_validators::{is_pubkey, is_url},
 };
 use solana_client::rpc_client::RpcClient;
-use solana_config_program::{config_instruction, get_config_data, ConfigKeys, ConfigState};
+use solana_config_program::{config_instruction, ConfigKeys, ConfigState};
+use solana_decoder::parse_config::{parse_config, ConfigAccountType, UiConfig};
 use solana_sdk::{
     account::Account,
     commitment_config::CommitmentConfig,
@@ -29,7 +30,7 @@ pub const MAX_SHORT_FIELD_LENGTH: usize = 70;
 pub const MAX_LONG_FIELD_LENGTH: usize = 300;
 pub const MAX_VALIDATOR_INFO: u64 = 576;
 
-solana_sdk::declare_id!("Va1idator1nfo111111111111111111111111111111");
+pub use solana_decoder::validator_info::{check_id, id};
 
 #[derive(Debug, Deserialize, PartialEq, Serialize, Default)]
 pub struct ValidatorInfo {
@@ -131,14 +132,12 @@ fn parse_validator_info(
     if account.owner != solana_config_program::id() {
         return Err(format!("{} is not a validator info account", pubkey).into());
     }
-    let key_list: ConfigKeys = deserialize(&account.data)?;
-    if !key_list.keys.is_empty() {
-        let (validator_pubkey, _) = key_list.keys[1];
-        let validator_info_string: String = deserialize(&get_config_data(&account.data)?)?;
-        let validator_info: Map<_, _> = serde_json::from_str(&validator_info_string)?;
-        Ok((validator_pubkey, validator_info))
-    } else {
-        Err(format!("{} could not be parsed as a validator info account", pubkey).into())
+    match parse_config(&account.data, pubkey) {
+        Ok(ConfigAccountType::ValidatorInfo(UiConfig {
+            keys,
+            config_data: Value::Object(validator_info),
+        })) if keys.len() > 1 => Ok((keys[1].pubkey.parse()?, validator_info)),
+        _ => Err(format!("{} could not be parsed as a validator info account", pubkey).into()),
     }
 }
 
@@ -77,6 +77,7 @@ fn test_cli_timestamp_tx() {
         witnesses: None,
         cancelable: false,
         sign_only: false,
+        dry_run: false,
         signers: None,
         blockhash: None,
     };
@@ -152,6 +153,7 @@ fn test_cli_witness_tx() {
         witnesses: Some(vec![config_witness.keypair.pubkey()]),
         cancelable: false,
         sign_only: false,
+        dry_run: false,
         signers: None,
         blockhash: None,
     };
@@ -220,6 +222,7 @@ fn test_cli_cancel_tx() {
         witnesses: Some(vec![config_witness.keypair.pubkey()]),
         cancelable: true,
         sign_only: false,
+        dry_run: false,
         signers: None,
         blockhash: None,
     };
@@ -296,6 +299,7 @@ fn test_offline_pay_tx() {
         witnesses: None,
         cancelable: false,
         sign_only: true,
+        dry_run: false,
         signers: None,
         blockhash: None,
     };
@@ -326,6 +330,7 @@ fn test_offline_pay_tx() {
         witnesses: None,
         cancelable: false,
         sign_only: false,
+        dry_run: false,
         signers: Some(signers),
         blockhash: Some(blockhash_str.parse::<Hash>().unwrap()),
     };
@@ -0,0 +1,253 @@
+use crate::{
+    client_error::ClientError,
+    generic_rpc_client_request::GenericRpcClientRequest,
+    rpc_request::{RpcError, RpcRequest},
+};
+use log::*;
+use rand::{thread_rng, Rng};
+use reqwest::StatusCode;
+use solana_sdk::clock::Slot;
+use std::{
+    cmp,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        RwLock,
+    },
+    thread::sleep,
+    time::Duration,
+};
+
+// JSON-RPC server errors that another node, or the same node a little later, may not return
+const NODE_UNHEALTHY_ERROR_CODE: i64 = -32_005;
+const RATE_LIMIT_ERROR_CODE: i64 = -32_007;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// How many times a request that failed with a transient error is retried, across all
+    /// endpoints
+    pub max_retries: usize,
+    /// Upper bound of the delay before the first retry. Each retry doubles it
+    pub initial_backoff: Duration,
+    /// Upper bound of the delay before any retry
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The delay before retry number `attempt`, starting from 0. Exponential backoff with jitter,
+    /// so that clients failing at the same time don't all come back at the same time
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        let ceiling = self
+            .initial_backoff
+            .checked_mul(1 << cmp::min(attempt, 16))
+            .map_or(self.max_backoff, |backoff| {
+                cmp::min(backoff, self.max_backoff)
+            });
+        let ceiling_ms = ceiling.as_millis() as u64;
+        if ceiling_ms == 0 {
+            return ceiling;
+        }
+        Duration::from_millis(thread_rng().gen_range(ceiling_ms / 2, ceiling_ms + 1))
+    }
+}
+
+/// Whether a request that failed with `err` may succeed if it is retried, possibly against a
+/// different endpoint
+pub fn is_transient_error(err: &ClientError) -> bool {
+    match err {
+        ClientError::Io(_) => true,
+        ClientError::Reqwest(err) => {
+            if err.is_serialization() {
+                false
+            } else if let Some(status) = err.status() {
+                status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+            } else {
+                // Connection failures and timeouts
+                true
+            }
+        }
+        ClientError::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            *code == NODE_UNHEALTHY_ERROR_CODE || *code == RATE_LIMIT_ERROR_CODE
+        }
+        ClientError::RpcError(RpcError::MinContextSlotNotReached { .. }) => true,
+        ClientError::RpcError(RpcError::RpcRequestError(_))
+        | ClientError::SerdeJson(_)
+        | ClientError::TransactionError(_) => false,
+    }
+}
+
+/// Sends requests to one of several endpoints. Requests that fail with a transient error are
+/// retried against the next endpoint, with backoff, and the client keeps using whichever endpoint
+/// last succeeded.
+///
+/// Responses that carry a context are rejected if their slot is older than the newest slot
+/// seen so far, so switching endpoints never moves the client back in time.
+pub struct FailoverRpcClientRequest {
+    endpoints: Vec<Box<dyn GenericRpcClientRequest + Send + Sync>>,
+    current_endpoint: AtomicUsize,
+    retry_policy: RetryPolicy,
+    min_context_slot: RwLock<Slot>,
+}
+
+impl FailoverRpcClientRequest {
+    pub(crate) fn new(
+        endpoints: Vec<Box<dyn GenericRpcClientRequest + Send + Sync>>,
+        retry_policy: RetryPolicy,
+    ) -> Self {
+        assert!(!endpoints.is_empty(), "no RPC endpoints");
+        Self {
+            endpoints,
+            current_endpoint: AtomicUsize::new(0),
+            retry_policy,
+            min_context_slot: RwLock::new(0),
+        }
+    }
+
+    fn check_context_slot(&self, result: &serde_json::Value) -> Result<(), ClientError> {
+        if let Some(context_slot) = result["context"]["slot"].as_u64() {
+            let mut min_context_slot = self.min_context_slot.write().unwrap();
+            if context_slot < *min_context_slot {
+                return Err(RpcError::MinContextSlotNotReached {
+                    context_slot,
+                    min_context_slot: *min_context_slot,
+                }
+                .into());
+            }
+            *min_context_slot = context_slot;
+        }
+        Ok(())
+    }
+}
+
+impl GenericRpcClientRequest for FailoverRpcClientRequest {
+    fn send(
+        &self,
+        request: &RpcRequest,
+        params: serde_json::Value,
+        retries: usize,
+    ) -> Result<serde_json::Value, ClientError> {
+        let max_retries = cmp::max(retries, self.retry_policy.max_retries);
+        let mut attempt = 0;
+        loop {
+            let endpoint = self.current_endpoint.load(Ordering::Relaxed);
+            let result = self.endpoints[endpoint]
+                .send(request, params.clone(), 0)
+                .and_then(|result| {
+                    self.check_context_slot(&result)?;
+                    Ok(result)
+                });
+            match result {
+                Ok(result) => return Ok(result),
+                Err(err) => {
+                    if attempt >= max_retries || !is_transient_error(&err) {
+                        return Err(err);
+                    }
+                    let next_endpoint = (endpoint + 1) % self.endpoints.len();
+                    info!(
+                        "{:?} failed on endpoint {}, retrying on endpoint {}: {:?}",
+                        request, endpoint, next_endpoint, err
+                    );
+                    self.current_endpoint
+                        .store(next_endpoint, Ordering::Relaxed);
+                    sleep(self.retry_policy.backoff(attempt));
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    fn set_min_context_slot(&self, slot: Slot) {
+        let mut min_context_slot = self.min_context_slot.write().unwrap();
+        *min_context_slot = cmp::max(*min_context_slot, slot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_rpc_client_request::{MockRpcClientRequest, PUBKEY};
+    use serde_json::json;
+    use std::io;
+
+    #[test]
+    fn test_backoff() {
+        let retry_policy = RetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1_000),
+        };
+        for attempt in 0..10 {
+            let ceiling = cmp::min(100 << attempt, 1_000);
+            let backoff = retry_policy.backoff(attempt).as_millis() as u64;
+            assert!(backoff >= ceiling / 2 && backoff <= ceiling);
+        }
+
+        let retry_policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(0),
+            ..retry_policy
+        };
+        assert_eq!(retry_policy.backoff(3), Duration::from_millis(0));
+    }
+
+    #[test]
+    fn test_is_transient_error() {
+        let io_error = io::Error::new(io::ErrorKind::ConnectionRefused, "refused");
+        assert!(is_transient_error(&io_error.into()));
+        assert!(is_transient_error(
+            &RpcError::RpcResponseError {
+                code: RATE_LIMIT_ERROR_CODE,
+                message: "".to_string(),
+            }
+            .into()
+        ));
+        assert!(!is_transient_error(
+            &RpcError::RpcResponseError {
+                code: -32602,
+                message: "Invalid params".to_string(),
+            }
+            .into()
+        ));
+        assert!(!is_transient_error(
+            &serde_json::from_str::<serde_json::Value>("")
+                .unwrap_err()
+                .into()
+        ));
+    }
+
+    #[test]
+    fn test_context_slot_never_goes_back() {
+        let failover = FailoverRpcClientRequest::new(
+            vec![
+                Box::new(MockRpcClientRequest::new("succeeds".to_string()).with_context_slot(12)),
+                Box::new(MockRpcClientRequest::new("succeeds".to_string()).with_context_slot(5)),
+            ],
+            RetryPolicy {
+                max_retries: 1,
+                initial_backoff: Duration::from_millis(0),
+                max_backoff: Duration::from_millis(0),
+            },
+        );
+        let request = RpcRequest::GetBalance;
+        let params = json!([PUBKEY]);
+        assert!(failover.send(&request, params.clone(), 0).is_ok());
+
+        // Switch to the endpoint that is behind, as if the first one had failed
+        failover.current_endpoint.store(1, Ordering::Relaxed);
+        let result = failover.send(&request, params.clone(), 0).unwrap();
+        assert_eq!(result["context"]["slot"], 12);
+        assert_eq!(failover.current_endpoint.load(Ordering::Relaxed), 0);
+
+        // Unless it catches up, the second endpoint is never used again
+        failover.set_min_context_slot(13);
+        assert!(failover.send(&request, params, 0).is_err());
+    }
+}
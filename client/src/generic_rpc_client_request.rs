@@ -1,4 +1,5 @@
 use crate::{client_error::ClientError, rpc_request::RpcRequest};
+use solana_sdk::clock::Slot;
 
 pub(crate) trait GenericRpcClientRequest {
     fn send(
@@ -7,4 +8,8 @@ pub(crate) trait GenericRpcClientRequest {
         params: serde_json::Value,
         retries: usize,
     ) -> Result<serde_json::Value, ClientError>;
+
+    // Reject responses whose context is older than `slot`. Only meaningful for clients that can
+    // switch between nodes
+    fn set_min_context_slot(&self, _slot: Slot) {}
 }
@@ -2,10 +2,14 @@
 extern crate serde_derive;
 
 pub mod client_error;
+pub mod failover_rpc_client_request;
 mod generic_rpc_client_request;
 pub mod mock_rpc_client_request;
 pub mod perf_utils;
+pub mod pubsub_client;
 pub mod rpc_client;
 pub mod rpc_client_request;
+pub mod rpc_filter;
 pub mod rpc_request;
 pub mod thin_client;
+pub mod tpu_client;
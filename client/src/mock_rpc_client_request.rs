@@ -1,14 +1,19 @@
-use crate::rpc_request::{Response, RpcResponseContext};
+use crate::rpc_request::{Response, RpcResponseContext, RpcSimulateTransactionResult};
 use crate::{
     client_error::ClientError, generic_rpc_client_request::GenericRpcClientRequest,
     rpc_request::RpcRequest,
 };
 use serde_json::{Number, Value};
 use solana_sdk::{
+    clock::Slot,
     fee_calculator::FeeCalculator,
     instruction::InstructionError,
     transaction::{self, TransactionError},
 };
+use std::{
+    io,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 pub const PUBKEY: &str = "7RoSF9fUmdphVCpabEoefH81WwrW7orsWonXWqTXkKV8";
 pub const SIGNATURE: &str =
@@ -16,11 +21,28 @@ pub const SIGNATURE: &str =
 
 pub struct MockRpcClientRequest {
     url: String,
+    failures: AtomicUsize,
+    context_slot: Slot,
 }
 
 impl MockRpcClientRequest {
     pub fn new(url: String) -> Self {
-        Self { url }
+        Self::new_with_failures(url, 0)
+    }
+
+    /// The first `failures` requests fail as if the node could not be reached
+    pub fn new_with_failures(url: String, failures: usize) -> Self {
+        Self {
+            url,
+            failures: AtomicUsize::new(failures),
+            context_slot: 1,
+        }
+    }
+
+    /// Responses with a context report `context_slot`
+    pub fn with_context_slot(mut self, context_slot: Slot) -> Self {
+        self.context_slot = context_slot;
+        self
     }
 }
 
@@ -31,6 +53,14 @@ impl GenericRpcClientRequest for MockRpcClientRequest {
         params: serde_json::Value,
         _retries: usize,
     ) -> Result<serde_json::Value, ClientError> {
+        if self.failures.load(Ordering::Relaxed) > 0 {
+            self.failures.fetch_sub(1, Ordering::Relaxed);
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                format!("{} is unreachable", self.url),
+            )
+            .into());
+        }
         if self.url == "fails" {
             return Ok(Value::Null);
         }
@@ -49,12 +79,16 @@ impl GenericRpcClientRequest for MockRpcClientRequest {
             RpcRequest::GetBalance => {
                 let n = if self.url == "airdrop" { 0 } else { 50 };
                 serde_json::to_value(Response {
-                    context: RpcResponseContext { slot: 1 },
+                    context: RpcResponseContext {
+                        slot: self.context_slot,
+                    },
                     value: Value::Number(Number::from(n)),
                 })?
             }
             RpcRequest::GetRecentBlockhash => serde_json::to_value(Response {
-                context: RpcResponseContext { slot: 1 },
+                context: RpcResponseContext {
+                    slot: self.context_slot,
+                },
                 value: (
                     Value::String(PUBKEY.to_string()),
                     serde_json::to_value(FeeCalculator::default()).unwrap(),
@@ -78,6 +112,15 @@ impl GenericRpcClientRequest for MockRpcClientRequest {
             RpcRequest::GetTransactionCount => Value::Number(Number::from(1234)),
             RpcRequest::GetSlot => Value::Number(Number::from(0)),
             RpcRequest::SendTransaction => Value::String(SIGNATURE.to_string()),
+            RpcRequest::SimulateTransaction => serde_json::to_value(Response {
+                context: RpcResponseContext {
+                    slot: self.context_slot,
+                },
+                value: RpcSimulateTransactionResult {
+                    err: None,
+                    logs: None,
+                },
+            })?,
             RpcRequest::GetMinimumBalanceForRentExemption => Value::Number(Number::from(1234)),
             _ => Value::Null,
         };
@@ -0,0 +1,351 @@
+//! The `pubsub_client` module implements a blocking client for the validator's
+//! WebSocket subscription service
+
+use crate::rpc_request::SlotInfo;
+use log::*;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use solana_sdk::{account::Account, pubkey::Pubkey, signature::Signature, transaction};
+use std::{
+    fmt, io,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, sleep, JoinHandle},
+    time::Duration,
+};
+use tungstenite::{client::AutoStream, connect, Message, WebSocket};
+use url::Url;
+
+/// How long the subscription thread waits for a message before checking whether it should exit
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// How long to wait between attempts to reconnect a dropped subscription
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+pub enum PubsubClientError {
+    ConnectionError(tungstenite::Error),
+    JsonParseError(serde_json::error::Error),
+    UnexpectedMessageError(String),
+    UrlParseError(url::ParseError),
+}
+
+impl fmt::Display for PubsubClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PubsubClientError::ConnectionError(err) => write!(f, "connection error: {}", err),
+            PubsubClientError::JsonParseError(err) => write!(f, "json parse error: {}", err),
+            PubsubClientError::UnexpectedMessageError(message) => {
+                write!(f, "unexpected message format: {}", message)
+            }
+            PubsubClientError::UrlParseError(err) => write!(f, "url parse error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for PubsubClientError {}
+
+impl From<tungstenite::Error> for PubsubClientError {
+    fn from(err: tungstenite::Error) -> PubsubClientError {
+        PubsubClientError::ConnectionError(err)
+    }
+}
+
+impl From<serde_json::error::Error> for PubsubClientError {
+    fn from(err: serde_json::error::Error) -> PubsubClientError {
+        PubsubClientError::JsonParseError(err)
+    }
+}
+
+impl From<url::ParseError> for PubsubClientError {
+    fn from(err: url::ParseError) -> PubsubClientError {
+        PubsubClientError::UrlParseError(err)
+    }
+}
+
+/// A live subscription. Notifications are delivered to the `Receiver` returned alongside it
+/// until the subscription is shut down. A dropped connection is re-established, and the
+/// subscription re-issued, in the background.
+pub struct PubsubClientSubscription<T>
+where
+    T: DeserializeOwned,
+{
+    message_type: PhantomData<T>,
+    operation: &'static str,
+    socket: Arc<Mutex<WebSocket<AutoStream>>>,
+    subscription_id: Arc<AtomicU64>,
+    t_subscription: Option<JoinHandle<()>>,
+    exit: Arc<AtomicBool>,
+}
+
+impl<T> PubsubClientSubscription<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    fn subscribe(
+        url: &str,
+        operation: &'static str,
+        params: Value,
+    ) -> Result<(Self, Receiver<T>), PubsubClientError> {
+        let url = Url::parse(url)?;
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": format!("{}Subscribe", operation),
+            "params": params,
+        })
+        .to_string();
+        let (socket, subscription_id) = Self::connect_and_subscribe(&url, &request)?;
+
+        let socket = Arc::new(Mutex::new(socket));
+        let subscription_id = Arc::new(AtomicU64::new(subscription_id));
+        let exit = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = channel();
+
+        let t_subscription = {
+            let socket = socket.clone();
+            let subscription_id = subscription_id.clone();
+            let exit = exit.clone();
+            thread::Builder::new()
+                .name(format!("solana-pubsub-client-{}", operation))
+                .spawn(move || {
+                    Self::run(
+                        url,
+                        request,
+                        operation,
+                        socket,
+                        subscription_id,
+                        exit,
+                        sender,
+                    )
+                })
+                .unwrap()
+        };
+
+        Ok((
+            Self {
+                message_type: PhantomData,
+                operation,
+                socket,
+                subscription_id,
+                t_subscription: Some(t_subscription),
+                exit,
+            },
+            receiver,
+        ))
+    }
+
+    fn connect_and_subscribe(
+        url: &Url,
+        request: &str,
+    ) -> Result<(WebSocket<AutoStream>, u64), PubsubClientError> {
+        let (mut socket, _response) = connect(url.clone())?;
+        socket.write_message(Message::Text(request.to_string()))?;
+        let message = socket.read_message()?.into_text()?;
+        let json: Value = serde_json::from_str(&message)?;
+        let subscription_id = json["result"]
+            .as_u64()
+            .ok_or_else(|| PubsubClientError::UnexpectedMessageError(message.clone()))?;
+
+        // Bound blocking reads so the subscription thread notices a shutdown request
+        socket
+            .get_ref()
+            .set_read_timeout(Some(READ_TIMEOUT))
+            .map_err(tungstenite::Error::Io)?;
+        Ok((socket, subscription_id))
+    }
+
+    fn run(
+        url: Url,
+        request: String,
+        operation: &'static str,
+        socket: Arc<Mutex<WebSocket<AutoStream>>>,
+        subscription_id: Arc<AtomicU64>,
+        exit: Arc<AtomicBool>,
+        sender: Sender<T>,
+    ) {
+        while !exit.load(Ordering::Relaxed) {
+            let message = socket.lock().unwrap().read_message();
+            match message {
+                Ok(Message::Text(text)) => {
+                    let id = subscription_id.load(Ordering::Relaxed);
+                    match Self::extract_notification(operation, id, &text) {
+                        Ok(Some(notification)) => {
+                            if sender.send(notification).is_err() {
+                                // Nobody is listening anymore
+                                break;
+                            }
+                        }
+                        Ok(None) => (),
+                        Err(err) => warn!("{} notification error: {}", operation, err),
+                    }
+                }
+                Ok(Message::Close(_)) => {
+                    Self::reconnect(&url, &request, operation, &socket, &subscription_id, &exit)
+                }
+                Ok(_) => (),
+                Err(tungstenite::Error::Io(ref err))
+                    if err.kind() == io::ErrorKind::WouldBlock
+                        || err.kind() == io::ErrorKind::TimedOut => {}
+                Err(err) => {
+                    if exit.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    info!("{} subscription connection lost: {}", operation, err);
+                    Self::reconnect(&url, &request, operation, &socket, &subscription_id, &exit);
+                }
+            }
+        }
+    }
+
+    fn reconnect(
+        url: &Url,
+        request: &str,
+        operation: &str,
+        socket: &Mutex<WebSocket<AutoStream>>,
+        subscription_id: &AtomicU64,
+        exit: &AtomicBool,
+    ) {
+        while !exit.load(Ordering::Relaxed) {
+            sleep(RECONNECT_INTERVAL);
+            match Self::connect_and_subscribe(url, request) {
+                Ok((new_socket, new_subscription_id)) => {
+                    *socket.lock().unwrap() = new_socket;
+                    subscription_id.store(new_subscription_id, Ordering::Relaxed);
+                    info!("{} subscription re-established", operation);
+                    return;
+                }
+                Err(err) => debug!("{} subscription reconnect failed: {}", operation, err),
+            }
+        }
+    }
+
+    fn extract_notification(
+        operation: &str,
+        subscription_id: u64,
+        message: &str,
+    ) -> Result<Option<T>, PubsubClientError> {
+        let json: Value = serde_json::from_str(message)?;
+        if json["method"] != format!("{}Notification", operation)
+            || json["params"]["subscription"].as_u64() != Some(subscription_id)
+        {
+            // Subscription and unsubscription responses
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_value(
+            json["params"]["result"].clone(),
+        )?))
+    }
+
+    pub fn send_unsubscribe(&self) -> Result<(), PubsubClientError> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": format!("{}Unsubscribe", self.operation),
+            "params": [self.subscription_id.load(Ordering::Relaxed)],
+        })
+        .to_string();
+        self.socket
+            .lock()
+            .unwrap()
+            .write_message(Message::Text(request))
+            .map_err(|err| err.into())
+    }
+
+    /// Unsubscribes, closes the connection and waits for the subscription thread to exit
+    pub fn shutdown(&mut self) -> thread::Result<()> {
+        if let Some(t_subscription) = self.t_subscription.take() {
+            if let Err(err) = self.send_unsubscribe() {
+                warn!("{} unsubscribe failed: {}", self.operation, err);
+            }
+            self.exit.store(true, Ordering::Relaxed);
+            let _ = self.socket.lock().unwrap().close(None);
+            t_subscription.join()
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<T> Drop for PubsubClientSubscription<T>
+where
+    T: DeserializeOwned,
+{
+    fn drop(&mut self) {
+        // Let the subscription thread wind down on its own
+        self.exit.store(true, Ordering::Relaxed);
+    }
+}
+
+pub type AccountSubscription = PubsubClientSubscription<Account>;
+pub type SignatureSubscription = PubsubClientSubscription<transaction::Result<()>>;
+pub type SlotSubscription = PubsubClientSubscription<SlotInfo>;
+
+pub struct PubsubClient {}
+
+impl PubsubClient {
+    /// Subscribe to changes of the account at `pubkey`. `url` is the validator's pubsub
+    /// endpoint, eg. "ws://127.0.0.1:8900"
+    pub fn account_subscribe(
+        url: &str,
+        pubkey: &Pubkey,
+    ) -> Result<(AccountSubscription, Receiver<Account>), PubsubClientError> {
+        PubsubClientSubscription::subscribe(url, "account", json!([pubkey.to_string()]))
+    }
+
+    /// Subscribe to the status of the transaction with `signature`
+    pub fn signature_subscribe(
+        url: &str,
+        signature: &Signature,
+    ) -> Result<(SignatureSubscription, Receiver<transaction::Result<()>>), PubsubClientError> {
+        PubsubClientSubscription::subscribe(url, "signature", json!([signature.to_string()]))
+    }
+
+    /// Subscribe to each slot the validator processes
+    pub fn slot_subscribe(
+        url: &str,
+    ) -> Result<(SlotSubscription, Receiver<SlotInfo>), PubsubClientError> {
+        PubsubClientSubscription::subscribe(url, "slot", json!([]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_notification() {
+        let notification = r#"{"jsonrpc":"2.0","method":"slotNotification","params":{"result":{"parent":1,"root":0,"slot":2},"subscription":3}}"#;
+        assert_eq!(
+            SlotSubscription::extract_notification("slot", 3, notification).unwrap(),
+            Some(SlotInfo {
+                slot: 2,
+                parent: 1,
+                root: 0,
+            })
+        );
+
+        // Notifications for other subscriptions are skipped
+        assert_eq!(
+            SlotSubscription::extract_notification("slot", 4, notification).unwrap(),
+            None
+        );
+        assert_eq!(
+            SlotSubscription::extract_notification("root", 3, notification).unwrap(),
+            None
+        );
+
+        // As are subscription responses
+        let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+        assert_eq!(
+            SlotSubscription::extract_notification("slot", 3, response).unwrap(),
+            None
+        );
+
+        assert!(SlotSubscription::extract_notification("slot", 3, "not json").is_err());
+    }
+}
@@ -1,12 +1,16 @@
 use crate::rpc_request::{Response, RpcResponse};
 use crate::{
     client_error::ClientError,
+    failover_rpc_client_request::{FailoverRpcClientRequest, RetryPolicy},
     generic_rpc_client_request::GenericRpcClientRequest,
     mock_rpc_client_request::MockRpcClientRequest,
     rpc_client_request::RpcClientRequest,
     rpc_request::{
-        RpcConfirmedBlock, RpcContactInfo, RpcEpochInfo, RpcLeaderSchedule, RpcRequest,
-        RpcVersionInfo, RpcVoteAccountStatus,
+        RpcBlockProduction, RpcBlockProductionConfig, RpcConfirmedBlock, RpcConfirmedSignature,
+        RpcConfirmedTransaction, RpcContactInfo, RpcEpochInfo, RpcInflationReward,
+        RpcInflationRewardConfig, RpcLeaderSchedule, RpcPerfSample, RpcProgramAccountsConfig,
+        RpcRequest, RpcSimulateTransactionConfig, RpcSimulateTransactionResult, RpcStakeActivation,
+        RpcStakeActivationConfig, RpcVersionInfo, RpcVoteAccountStatus,
     },
 };
 use bincode::serialize;
@@ -14,7 +18,7 @@ use log::*;
 use serde_json::{json, Value};
 use solana_sdk::{
     account::Account,
-    clock::{Slot, UnixTimestamp, DEFAULT_TICKS_PER_SECOND, DEFAULT_TICKS_PER_SLOT},
+    clock::{Epoch, Slot, UnixTimestamp, DEFAULT_TICKS_PER_SECOND, DEFAULT_TICKS_PER_SLOT},
     commitment_config::CommitmentConfig,
     epoch_schedule::EpochSchedule,
     fee_calculator::FeeCalculator,
@@ -48,6 +52,34 @@ impl RpcClient {
         }
     }
 
+    /// A client that sends each request to one of `urls`, retrying transient failures against
+    /// the next url as directed by `retry_policy`
+    pub fn new_with_failover(urls: Vec<String>, retry_policy: RetryPolicy) -> Self {
+        let endpoints = urls
+            .into_iter()
+            .map(|url| {
+                Box::new(RpcClientRequest::new(url))
+                    as Box<dyn GenericRpcClientRequest + Send + Sync>
+            })
+            .collect();
+        Self {
+            client: Box::new(FailoverRpcClientRequest::new(endpoints, retry_policy)),
+        }
+    }
+
+    pub fn new_mock_with_failover(
+        mocks: Vec<MockRpcClientRequest>,
+        retry_policy: RetryPolicy,
+    ) -> Self {
+        let endpoints = mocks
+            .into_iter()
+            .map(|mock| Box::new(mock) as Box<dyn GenericRpcClientRequest + Send + Sync>)
+            .collect();
+        Self {
+            client: Box::new(FailoverRpcClientRequest::new(endpoints, retry_policy)),
+        }
+    }
+
     pub fn new_socket(addr: SocketAddr) -> Self {
         Self::new(get_rpc_request_str(addr, false))
     }
@@ -59,6 +91,13 @@ impl RpcClient {
         }
     }
 
+    /// Require the responses to later requests to come from a node that has processed at least
+    /// up to `slot`. Only clients created with `new_with_failover` enforce this, by failing over
+    /// to another endpoint
+    pub fn set_min_context_slot(&self, slot: Slot) {
+        self.client.set_min_context_slot(slot);
+    }
+
     pub fn confirm_transaction(&self, signature: &str) -> io::Result<bool> {
         Ok(self
             .confirm_transaction_with_commitment(signature, CommitmentConfig::default())?
@@ -108,6 +147,47 @@ impl RpcClient {
         }
     }
 
+    pub fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+        sig_verify: bool,
+    ) -> RpcResponse<RpcSimulateTransactionResult> {
+        self.simulate_transaction_with_config(
+            transaction,
+            RpcSimulateTransactionConfig {
+                sig_verify,
+                commitment: None,
+            },
+        )
+    }
+
+    pub fn simulate_transaction_with_config(
+        &self,
+        transaction: &Transaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> RpcResponse<RpcSimulateTransactionResult> {
+        let serialized = serialize(transaction).unwrap();
+        let response = self
+            .client
+            .send(
+                &RpcRequest::SimulateTransaction,
+                json!([serialized, config]),
+                0,
+            )
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("SimulateTransaction request failure: {:?}", err),
+                )
+            })?;
+        serde_json::from_value(response).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("SimulateTransaction parse failure: {}", err),
+            )
+        })
+    }
+
     pub fn get_signature_status(
         &self,
         signature: &str,
@@ -240,6 +320,71 @@ impl RpcClient {
         })
     }
 
+    pub fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        start_slot: Slot,
+        end_slot: Slot,
+        limit: Option<usize>,
+        before: Option<&Signature>,
+    ) -> io::Result<Vec<RpcConfirmedSignature>> {
+        let response = self
+            .client
+            .send(
+                &RpcRequest::GetConfirmedSignaturesForAddress,
+                json!([
+                    address.to_string(),
+                    start_slot,
+                    end_slot,
+                    limit,
+                    before.map(|signature| signature.to_string())
+                ]),
+                0,
+            )
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!(
+                        "GetConfirmedSignaturesForAddress request failure: {:?}",
+                        err
+                    ),
+                )
+            })?;
+
+        serde_json::from_value(response).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("GetConfirmedSignaturesForAddress parse failure: {}", err),
+            )
+        })
+    }
+
+    pub fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> io::Result<Option<RpcConfirmedTransaction>> {
+        let response = self
+            .client
+            .send(
+                &RpcRequest::GetConfirmedTransaction,
+                json!([signature.to_string()]),
+                0,
+            )
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("GetConfirmedTransaction request failure: {:?}", err),
+                )
+            })?;
+
+        serde_json::from_value(response).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("GetConfirmedTransaction parse failure: {}", err),
+            )
+        })
+    }
+
     pub fn get_block_time(&self, slot: Slot) -> io::Result<UnixTimestamp> {
         let response = self
             .client
@@ -291,6 +436,50 @@ impl RpcClient {
         })
     }
 
+    pub fn get_block_production(
+        &self,
+        config: RpcBlockProductionConfig,
+    ) -> RpcResponse<RpcBlockProduction> {
+        let response = self
+            .client
+            .send(&RpcRequest::GetBlockProduction, json!([config]), 0)
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("GetBlockProduction request failure: {:?}", err),
+                )
+            })?;
+
+        serde_json::from_value(response).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("GetBlockProduction parse failure: {}", err),
+            )
+        })
+    }
+
+    pub fn get_recent_performance_samples(
+        &self,
+        limit: Option<usize>,
+    ) -> io::Result<Vec<RpcPerfSample>> {
+        let response = self
+            .client
+            .send(&RpcRequest::GetRecentPerformanceSamples, json!([limit]), 0)
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("GetRecentPerformanceSamples request failure: {:?}", err),
+                )
+            })?;
+
+        serde_json::from_value(response).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("GetRecentPerformanceSamples parse failure: {}", err),
+            )
+        })
+    }
+
     pub fn get_leader_schedule(&self, slot: Option<Slot>) -> io::Result<Option<RpcLeaderSchedule>> {
         self.get_leader_schedule_with_commitment(slot, CommitmentConfig::default())
     }
@@ -584,6 +773,39 @@ impl RpcClient {
             })?
     }
 
+    pub fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> io::Result<Vec<Option<Account>>> {
+        Ok(self
+            .get_multiple_accounts_with_commitment(pubkeys, CommitmentConfig::default())?
+            .value)
+    }
+
+    pub fn get_multiple_accounts_with_commitment(
+        &self,
+        pubkeys: &[Pubkey],
+        commitment_config: CommitmentConfig,
+    ) -> RpcResponse<Vec<Option<Account>>> {
+        let pubkeys: Vec<_> = pubkeys.iter().map(|pubkey| pubkey.to_string()).collect();
+        let response = self
+            .client
+            .send(
+                &RpcRequest::GetMultipleAccounts,
+                json!([pubkeys, commitment_config]),
+                0,
+            )
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("GetMultipleAccounts request failure: {:?}", err),
+                )
+            })?;
+        serde_json::from_value(response).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("GetMultipleAccounts parse failure: {}", err),
+            )
+        })
+    }
+
     pub fn get_account_data(&self, pubkey: &Pubkey) -> io::Result<Vec<u8>> {
         Ok(self.get_account(pubkey)?.data)
     }
@@ -654,12 +876,86 @@ impl RpcClient {
         })
     }
 
+    pub fn get_stake_activation(
+        &self,
+        stake_account: &Pubkey,
+        epoch: Option<Epoch>,
+    ) -> io::Result<RpcStakeActivation> {
+        let config = RpcStakeActivationConfig {
+            epoch,
+            commitment: None,
+        };
+        let response = self
+            .client
+            .send(
+                &RpcRequest::GetStakeActivation,
+                json!([stake_account.to_string(), config]),
+                0,
+            )
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("GetStakeActivation request failure: {:?}", err),
+                )
+            })?;
+
+        serde_json::from_value(response).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("GetStakeActivation parse failure: {}", err),
+            )
+        })
+    }
+
+    pub fn get_inflation_reward(
+        &self,
+        addresses: &[Pubkey],
+        epoch: Option<Epoch>,
+    ) -> io::Result<Vec<Option<RpcInflationReward>>> {
+        let addresses: Vec<_> = addresses
+            .iter()
+            .map(|address| address.to_string())
+            .collect();
+        let config = RpcInflationRewardConfig {
+            epoch,
+            commitment: None,
+        };
+        let response = self
+            .client
+            .send(
+                &RpcRequest::GetInflationReward,
+                json!([addresses, config]),
+                0,
+            )
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("GetInflationReward request failure: {:?}", err),
+                )
+            })?;
+
+        serde_json::from_value(response).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("GetInflationReward parse failure: {}", err),
+            )
+        })
+    }
+
     pub fn get_program_accounts(&self, pubkey: &Pubkey) -> io::Result<Vec<(Pubkey, Account)>> {
+        self.get_program_accounts_with_config(pubkey, RpcProgramAccountsConfig::default())
+    }
+
+    pub fn get_program_accounts_with_config(
+        &self,
+        pubkey: &Pubkey,
+        config: RpcProgramAccountsConfig,
+    ) -> io::Result<Vec<(Pubkey, Account)>> {
         let response = self
             .client
             .send(
                 &RpcRequest::GetProgramAccounts,
-                json!([pubkey.to_string()]),
+                json!([pubkey.to_string(), config]),
                 0,
             )
             .map_err(|err| {
@@ -1299,4 +1595,48 @@ mod tests {
         let rpc_client = RpcClient::new_mock("succeeds".to_string());
         thread::spawn(move || rpc_client);
     }
+
+    #[test]
+    fn test_failover() {
+        let retry_policy = RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(0),
+            max_backoff: Duration::from_millis(0),
+        };
+        let pubkey = Pubkey::new_rand();
+
+        // The first endpoint is down, so the client switches to the second one
+        let rpc_client = RpcClient::new_mock_with_failover(
+            vec![
+                MockRpcClientRequest::new_with_failures("succeeds".to_string(), 100),
+                MockRpcClientRequest::new("succeeds".to_string()),
+            ],
+            retry_policy.clone(),
+        );
+        assert_eq!(rpc_client.get_balance(&pubkey).unwrap(), 50);
+
+        // Retries are given up on once the policy is exhausted
+        let rpc_client = RpcClient::new_mock_with_failover(
+            vec![
+                MockRpcClientRequest::new_with_failures("succeeds".to_string(), 2),
+                MockRpcClientRequest::new_with_failures("succeeds".to_string(), 2),
+            ],
+            retry_policy.clone(),
+        );
+        assert!(rpc_client.get_balance(&pubkey).is_err());
+        assert_eq!(rpc_client.get_balance(&pubkey).unwrap(), 50);
+
+        // Endpoints behind the required slot are skipped
+        let rpc_client = RpcClient::new_mock_with_failover(
+            vec![
+                MockRpcClientRequest::new("succeeds".to_string()).with_context_slot(5),
+                MockRpcClientRequest::new("succeeds".to_string()).with_context_slot(12),
+            ],
+            retry_policy,
+        );
+        rpc_client.set_min_context_slot(10);
+        assert_eq!(rpc_client.get_balance(&pubkey).unwrap(), 50);
+        rpc_client.set_min_context_slot(20);
+        assert!(rpc_client.get_balance(&pubkey).is_err());
+    }
 }
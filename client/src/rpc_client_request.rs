@@ -58,10 +58,13 @@ impl GenericRpcClientRequest for RpcClientRequest {
 
                     let json: serde_json::Value = serde_json::from_str(&response.text()?)?;
                     if json["error"].is_object() {
-                        return Err(RpcError::RpcRequestError(format!(
-                            "RPC Error response: {}",
-                            serde_json::to_string(&json["error"]).unwrap()
-                        ))
+                        return Err(RpcError::RpcResponseError {
+                            code: json["error"]["code"].as_i64().unwrap_or_default(),
+                            message: json["error"]["message"]
+                                .as_str()
+                                .unwrap_or_default()
+                                .to_string(),
+                        }
                         .into());
                     }
                     return Ok(json["result"].clone());
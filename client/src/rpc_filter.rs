@@ -0,0 +1,201 @@
+//! Filters that can be applied server-side to the accounts returned by `getProgramAccounts`
+
+use solana_sdk::account::Account;
+use std::{borrow::Cow, error, fmt};
+
+/// Maximum number of filters accepted by a single request
+pub const MAX_FILTERS: usize = 4;
+/// Maximum number of bytes a `Memcmp` filter may compare
+pub const MAX_MEMCMP_BYTES: usize = 128;
+// Upper bound of the base-58 encoding of MAX_MEMCMP_BYTES bytes
+const MAX_MEMCMP_BASE58_SIZE: usize = 175;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcFilterType {
+    /// Only accept accounts whose data is exactly this many bytes long
+    DataSize(u64),
+    /// Only accept accounts whose data matches the given bytes at an offset
+    Memcmp(Memcmp),
+}
+
+impl RpcFilterType {
+    pub fn verify(&self) -> Result<(), RpcFilterError> {
+        match self {
+            RpcFilterType::DataSize(_) => Ok(()),
+            RpcFilterType::Memcmp(memcmp) => {
+                if let MemcmpEncodedBytes::Binary(bytes) = &memcmp.bytes {
+                    if bytes.len() > MAX_MEMCMP_BASE58_SIZE {
+                        return Err(RpcFilterError::DataTooLarge);
+                    }
+                }
+                let bytes = memcmp.bytes().ok_or(RpcFilterError::DecodeError)?;
+                if bytes.len() > MAX_MEMCMP_BYTES {
+                    Err(RpcFilterError::DataTooLarge)
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    pub fn allows(&self, account: &Account) -> bool {
+        match self {
+            RpcFilterType::DataSize(size) => account.data.len() as u64 == *size,
+            RpcFilterType::Memcmp(memcmp) => memcmp.bytes_match(&account.data),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MemcmpEncodedBytes {
+    /// Base-58 encoded, as sent in requests
+    Binary(String),
+    /// Already decoded, so that matching many accounts does not decode the bytes each time.
+    /// Requests always carry encoded bytes
+    #[serde(skip_deserializing)]
+    Bytes(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Memcmp {
+    /// Data offset to begin the comparison at
+    pub offset: usize,
+    /// Bytes to compare
+    pub bytes: MemcmpEncodedBytes,
+}
+
+impl Memcmp {
+    pub fn new(offset: usize, bytes: &[u8]) -> Self {
+        Self {
+            offset,
+            bytes: MemcmpEncodedBytes::Binary(bs58::encode(bytes).into_string()),
+        }
+    }
+
+    pub fn bytes(&self) -> Option<Cow<[u8]>> {
+        match &self.bytes {
+            MemcmpEncodedBytes::Binary(bytes) => {
+                bs58::decode(bytes).into_vec().ok().map(Cow::Owned)
+            }
+            MemcmpEncodedBytes::Bytes(bytes) => Some(Cow::Borrowed(bytes)),
+        }
+    }
+
+    /// Replace encoded bytes with their decoding. Returns false if they cannot be decoded
+    pub fn decode(&mut self) -> bool {
+        match self.bytes().map(Cow::into_owned) {
+            Some(bytes) => {
+                self.bytes = MemcmpEncodedBytes::Bytes(bytes);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn bytes_match(&self, data: &[u8]) -> bool {
+        match self.bytes() {
+            Some(bytes) => {
+                let end = self.offset.saturating_add(bytes.len());
+                end <= data.len() && data[self.offset..end] == bytes[..]
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RpcFilterError {
+    DataTooLarge,
+    DecodeError,
+}
+
+impl fmt::Display for RpcFilterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RpcFilterError::DataTooLarge => {
+                write!(f, "memcmp bytes exceed the {} byte limit", MAX_MEMCMP_BYTES)
+            }
+            RpcFilterError::DecodeError => write!(f, "memcmp bytes are not valid base-58"),
+        }
+    }
+}
+
+impl error::Error for RpcFilterError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_filter_json() {
+        let filter: RpcFilterType = serde_json::from_value(json!({ "dataSize": 42 })).unwrap();
+        assert_eq!(filter, RpcFilterType::DataSize(42));
+
+        let filter: RpcFilterType =
+            serde_json::from_value(json!({ "memcmp": { "offset": 4, "bytes": "2" } })).unwrap();
+        assert_eq!(filter, RpcFilterType::Memcmp(Memcmp::new(4, &[1])));
+        // Bytes must be base-58 encoded
+        assert!(serde_json::from_value::<RpcFilterType>(
+            json!({ "memcmp": { "offset": 4, "bytes": [1] } })
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_filter_allows() {
+        let account = Account {
+            data: vec![1, 2, 3, 4, 5],
+            ..Account::default()
+        };
+        let data_len = account.data.len() as u64;
+
+        assert!(RpcFilterType::DataSize(data_len).allows(&account));
+        assert!(!RpcFilterType::DataSize(data_len + 1).allows(&account));
+
+        let data = account.data.clone();
+        assert!(RpcFilterType::Memcmp(Memcmp::new(0, &data)).allows(&account));
+        assert!(RpcFilterType::Memcmp(Memcmp::new(2, &data[2..4])).allows(&account));
+        assert!(!RpcFilterType::Memcmp(Memcmp::new(3, &data[2..4])).allows(&account));
+        // Comparisons running past the end of the data never match
+        assert!(!RpcFilterType::Memcmp(Memcmp::new(data.len() - 1, &data[0..2])).allows(&account));
+        assert!(!RpcFilterType::Memcmp(Memcmp::new(std::usize::MAX, &[1])).allows(&account));
+
+        // Decoded bytes match the same way
+        let mut memcmp = Memcmp::new(2, &data[2..4]);
+        assert!(memcmp.decode());
+        assert_eq!(memcmp.bytes, MemcmpEncodedBytes::Bytes(data[2..4].to_vec()));
+        assert!(RpcFilterType::Memcmp(memcmp).allows(&account));
+    }
+
+    #[test]
+    fn test_filter_verify() {
+        assert_eq!(RpcFilterType::DataSize(0).verify(), Ok(()));
+        assert_eq!(
+            RpcFilterType::Memcmp(Memcmp::new(0, &[1; MAX_MEMCMP_BYTES])).verify(),
+            Ok(())
+        );
+        assert_eq!(
+            RpcFilterType::Memcmp(Memcmp::new(0, &[1; MAX_MEMCMP_BYTES + 1])).verify(),
+            Err(RpcFilterError::DataTooLarge)
+        );
+        assert_eq!(
+            RpcFilterType::Memcmp(Memcmp {
+                offset: 0,
+                bytes: MemcmpEncodedBytes::Binary("I0O".to_string()),
+            })
+            .verify(),
+            Err(RpcFilterError::DecodeError)
+        );
+        assert_eq!(
+            RpcFilterType::Memcmp(Memcmp {
+                offset: 0,
+                bytes: MemcmpEncodedBytes::Bytes(vec![1; MAX_MEMCMP_BYTES + 1]),
+            })
+            .verify(),
+            Err(RpcFilterError::DataTooLarge)
+        );
+    }
+}
@@ -1,9 +1,13 @@
+use crate::rpc_filter::RpcFilterType;
 use jsonrpc_core::Result as JsonResult;
 use serde_json::{json, Value};
+use solana_decoder::{UiEncoding, UiTransaction};
 use solana_sdk::{
-    clock::{Epoch, Slot},
+    clock::{Epoch, Slot, UnixTimestamp},
+    commitment_config::CommitmentConfig,
     hash::Hash,
-    transaction::{Result, Transaction},
+    reward_type::RewardType,
+    transaction::{Result, Transaction, TransactionError},
 };
 use std::{collections::HashMap, error, fmt, io, net::SocketAddr};
 
@@ -21,13 +25,68 @@ pub struct Response<T> {
     pub value: T,
 }
 
-#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcConfirmedBlock {
     pub previous_blockhash: Hash,
     pub blockhash: Hash,
     pub parent_slot: Slot,
     pub transactions: Vec<(Transaction, Option<RpcTransactionStatus>)>,
+    pub rewards: RpcRewards,
+}
+
+/// A confirmed block whose transactions are encoded as requested by the caller
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcEncodedConfirmedBlock {
+    pub previous_blockhash: Hash,
+    pub blockhash: Hash,
+    pub parent_slot: Slot,
+    pub transactions: Vec<(UiTransaction, Option<RpcTransactionStatus>)>,
+    pub rewards: RpcRewards,
+}
+
+impl RpcConfirmedBlock {
+    pub fn encode(self, encoding: UiEncoding) -> RpcEncodedConfirmedBlock {
+        RpcEncodedConfirmedBlock {
+            previous_blockhash: self.previous_blockhash,
+            blockhash: self.blockhash,
+            parent_slot: self.parent_slot,
+            transactions: self
+                .transactions
+                .into_iter()
+                .map(|(transaction, status)| (UiTransaction::encode(transaction, encoding), status))
+                .collect(),
+            rewards: self.rewards,
+        }
+    }
+}
+
+/// Lamports credited to an account by the bank outside of any transaction
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcReward {
+    /// Account address as base-58 encoded string
+    pub pubkey: String,
+
+    /// Reward amount, in lamports
+    pub lamports: i64,
+
+    /// Account balance, in lamports, after the reward was applied
+    pub post_balance: u64,
+
+    pub reward_type: Option<RewardType>,
+}
+
+pub type RpcRewards = Vec<RpcReward>;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcConfirmedTransaction {
+    pub slot: Slot,
+    pub transaction: Transaction,
+    pub meta: Option<RpcTransactionStatus>,
+    pub block_time: Option<UnixTimestamp>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -37,6 +96,313 @@ pub struct RpcTransactionStatus {
     pub fee: u64,
     pub pre_balances: Vec<u64>,
     pub post_balances: Vec<u64>,
+    pub log_messages: Option<Vec<String>>,
+    pub compute_units_consumed: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcConfirmedSignature {
+    /// Transaction signature as base-58 encoded string
+    pub signature: String,
+
+    /// The rooted slot that contains the transaction
+    pub slot: Slot,
+
+    /// The transaction error, if the transaction failed
+    pub err: Option<TransactionError>,
+}
+
+/// Restricts `getLargestAccounts` to one side of the circulating supply
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcLargestAccountsFilter {
+    Circulating,
+    NonCirculating,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcLargestAccountsConfig {
+    /// Only return accounts from this part of the supply
+    pub filter: Option<RpcLargestAccountsFilter>,
+
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountBalance {
+    /// Account address as base-58 encoded string
+    pub address: String,
+
+    /// Account balance, in lamports
+    pub lamports: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSupply {
+    /// Total supply, in lamports
+    pub total: u64,
+
+    /// Circulating supply, in lamports
+    pub circulating: u64,
+
+    /// Non-circulating supply, in lamports
+    pub non_circulating: u64,
+
+    /// Addresses of the non-circulating accounts, as base-58 encoded strings
+    pub non_circulating_accounts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcStakeActivationConfig {
+    /// Epoch for which to calculate activation details, defaults to the current epoch
+    pub epoch: Option<Epoch>,
+
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum StakeActivationState {
+    Activating,
+    Active,
+    Deactivating,
+    Inactive,
+}
+
+impl fmt::Display for StakeActivationState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self {
+            StakeActivationState::Activating => "activating",
+            StakeActivationState::Active => "active",
+            StakeActivationState::Deactivating => "deactivating",
+            StakeActivationState::Inactive => "inactive",
+        };
+        write!(f, "{}", state)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcStakeActivation {
+    /// The activation state of the stake account
+    pub state: StakeActivationState,
+
+    /// Stake active during the epoch, in lamports
+    pub active: u64,
+
+    /// Stake inactive during the epoch, in lamports
+    pub inactive: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInflationRewardConfig {
+    /// Epoch to report rewards for, defaults to the most recently completed epoch
+    pub epoch: Option<Epoch>,
+
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInflationReward {
+    /// Epoch the reward was earned in
+    pub epoch: Epoch,
+
+    /// Slot of the block that credited the reward
+    pub effective_slot: Slot,
+
+    /// Reward amount, in lamports
+    pub amount: u64,
+
+    /// Account balance, in lamports, after the reward was credited
+    pub post_balance: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountInfoConfig {
+    /// How to encode the returned accounts
+    pub encoding: Option<UiEncoding>,
+
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcProgramAccountsConfig {
+    /// Only return accounts that pass every filter
+    pub filters: Option<Vec<RpcFilterType>>,
+
+    /// Only return the requested range of each account's data
+    pub data_slice: Option<RpcDataSliceConfig>,
+
+    /// How to encode the returned accounts
+    pub encoding: Option<UiEncoding>,
+
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcDataSliceConfig {
+    /// Offset into the account data to begin the slice at
+    pub offset: usize,
+
+    /// Maximum number of bytes to return
+    pub length: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateTransactionConfig {
+    /// Verify the transaction signatures before simulating it
+    #[serde(default)]
+    pub sig_verify: bool,
+
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateTransactionResult {
+    /// The transaction error, if the simulated transaction failed
+    pub err: Option<TransactionError>,
+
+    /// Log messages emitted by the programs the transaction invoked
+    pub logs: Option<Vec<String>>,
+}
+
+/// Selects the transactions whose logs a `logsSubscribe` subscription receives
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcTransactionLogsFilter {
+    /// All transactions except simple vote transactions
+    All,
+    /// All transactions, including simple vote transactions
+    AllWithVotes,
+    /// Only transactions that mention the given address, as a base-58 encoded string
+    Mentions(Vec<String>),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcLogsResponse {
+    /// The transaction signature, as a base-58 encoded string
+    pub signature: String,
+
+    /// The transaction error, if the transaction failed
+    pub err: Option<TransactionError>,
+
+    /// Log messages emitted by the programs the transaction invoked
+    pub logs: Vec<String>,
+}
+
+/// Selects the transactions included in `blockSubscribe` notifications
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcBlockSubscribeFilter {
+    /// Every transaction in the block
+    All,
+    /// Only transactions that mention the given account or program, as a base-58 encoded string
+    MentionsAccountOrProgram(String),
+}
+
+/// A processed slot, as delivered to `slotSubscribe` subscribers
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SlotInfo {
+    pub slot: Slot,
+    pub parent: Slot,
+    pub root: Slot,
+}
+
+/// A newly rooted block, as delivered to `blockSubscribe` subscribers
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockUpdate {
+    pub slot: Slot,
+    pub block: RpcConfirmedBlock,
+}
+
+/// A vote observed in gossip, as delivered to `voteSubscribe` subscribers
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcVote {
+    /// The slots covered by the vote
+    pub slots: Vec<Slot>,
+
+    /// The bank hash of the most recent voted slot, as a base-58 encoded string
+    pub hash: String,
+
+    /// The processing timestamp of the most recent voted slot, if the vote carries one
+    pub timestamp: Option<UnixTimestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockProductionConfigRange {
+    /// First slot to report block production for, inclusive
+    pub first_slot: Slot,
+
+    /// Last slot to report block production for, inclusive; defaults to the latest slot
+    pub last_slot: Option<Slot>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockProductionConfig {
+    /// Only report block production for this validator identity, as a base-58 encoded string
+    pub identity: Option<String>,
+
+    /// Slot range to report block production for; defaults to the current epoch
+    pub range: Option<RpcBlockProductionConfigRange>,
+
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockProductionRange {
+    pub first_slot: Slot,
+    pub last_slot: Slot,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockProduction {
+    /// Map of leader base58 identity pubkeys to a tuple of `(number of leader slots, number of blocks produced)`
+    pub by_identity: HashMap<String, (usize, usize)>,
+
+    /// The slot range block production was reported for
+    pub range: RpcBlockProductionRange,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPerfSample {
+    /// Root slot at the end of the sample
+    pub slot: Slot,
+
+    /// Number of transactions processed during the sample
+    pub num_transactions: u64,
+
+    /// Number of slots rooted during the sample
+    pub num_slots: u64,
+
+    /// Length of the sample, in seconds
+    pub sample_period_secs: u16,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -120,24 +486,31 @@ pub enum RpcRequest {
     ValidatorExit,
     GetAccountInfo,
     GetBalance,
+    GetBlockProduction,
     GetBlockTime,
     GetClusterNodes,
     GetConfirmedBlock,
     GetConfirmedBlocks,
+    GetConfirmedSignaturesForAddress,
+    GetConfirmedTransaction,
     GetEpochInfo,
     GetEpochSchedule,
     GetGenesisHash,
     GetInflation,
+    GetInflationReward,
     GetLeaderSchedule,
+    GetMultipleAccounts,
     GetNumBlocksSinceSignatureConfirmation,
     GetProgramAccounts,
     GetRecentBlockhash,
+    GetRecentPerformanceSamples,
     GetSignatureStatus,
     GetSlot,
     GetSlotLeader,
     GetStorageTurn,
     GetStorageTurnRate,
     GetSlotsPerSegment,
+    GetStakeActivation,
     GetStoragePubkeysForSlot,
     GetTransactionCount,
     GetVersion,
@@ -146,6 +519,7 @@ pub enum RpcRequest {
     RequestAirdrop,
     SendTransaction,
     SignVote,
+    SimulateTransaction,
     GetMinimumBalanceForRentExemption,
 }
 
@@ -158,26 +532,33 @@ impl RpcRequest {
             RpcRequest::ValidatorExit => "validatorExit",
             RpcRequest::GetAccountInfo => "getAccountInfo",
             RpcRequest::GetBalance => "getBalance",
+            RpcRequest::GetBlockProduction => "getBlockProduction",
             RpcRequest::GetBlockTime => "getBlockTime",
             RpcRequest::GetClusterNodes => "getClusterNodes",
             RpcRequest::GetConfirmedBlock => "getConfirmedBlock",
             RpcRequest::GetConfirmedBlocks => "getConfirmedBlocks",
+            RpcRequest::GetConfirmedSignaturesForAddress => "getConfirmedSignaturesForAddress",
+            RpcRequest::GetConfirmedTransaction => "getConfirmedTransaction",
             RpcRequest::GetEpochInfo => "getEpochInfo",
             RpcRequest::GetEpochSchedule => "getEpochSchedule",
             RpcRequest::GetGenesisHash => "getGenesisHash",
             RpcRequest::GetInflation => "getInflation",
+            RpcRequest::GetInflationReward => "getInflationReward",
             RpcRequest::GetLeaderSchedule => "getLeaderSchedule",
+            RpcRequest::GetMultipleAccounts => "getMultipleAccounts",
             RpcRequest::GetNumBlocksSinceSignatureConfirmation => {
                 "getNumBlocksSinceSignatureConfirmation"
             }
             RpcRequest::GetProgramAccounts => "getProgramAccounts",
             RpcRequest::GetRecentBlockhash => "getRecentBlockhash",
+            RpcRequest::GetRecentPerformanceSamples => "getRecentPerformanceSamples",
             RpcRequest::GetSignatureStatus => "getSignatureStatus",
             RpcRequest::GetSlot => "getSlot",
             RpcRequest::GetSlotLeader => "getSlotLeader",
             RpcRequest::GetStorageTurn => "getStorageTurn",
             RpcRequest::GetStorageTurnRate => "getStorageTurnRate",
             RpcRequest::GetSlotsPerSegment => "getSlotsPerSegment",
+            RpcRequest::GetStakeActivation => "getStakeActivation",
             RpcRequest::GetStoragePubkeysForSlot => "getStoragePubkeysForSlot",
             RpcRequest::GetTransactionCount => "getTransactionCount",
             RpcRequest::GetVersion => "getVersion",
@@ -186,6 +567,7 @@ impl RpcRequest {
             RpcRequest::RequestAirdrop => "requestAirdrop",
             RpcRequest::SendTransaction => "sendTransaction",
             RpcRequest::SignVote => "signVote",
+            RpcRequest::SimulateTransaction => "simulateTransaction",
             RpcRequest::GetMinimumBalanceForRentExemption => "getMinimumBalanceForRentExemption",
         };
         json!({
@@ -200,6 +582,14 @@ impl RpcRequest {
 #[derive(Debug, Clone, PartialEq)]
 pub enum RpcError {
     RpcRequestError(String),
+    RpcResponseError {
+        code: i64,
+        message: String,
+    },
+    MinContextSlotNotReached {
+        context_slot: Slot,
+        min_context_slot: Slot,
+    },
 }
 
 impl fmt::Display for RpcError {
@@ -0,0 +1,577 @@
+//! The `tpu_client` module implements a client that sends transactions straight to the TPU
+//! ports of the upcoming leaders, rather than through an RPC node
+
+use crate::{
+    client_error::ClientError,
+    pubsub_client::{PubsubClient, PubsubClientError},
+    rpc_client::RpcClient,
+    rpc_request::RpcContactInfo,
+};
+use bincode::serialize;
+use log::*;
+use solana_sdk::{
+    clock::{Epoch, Slot, MAX_PROCESSING_AGE},
+    epoch_schedule::EpochSchedule,
+    message::Message,
+    pubkey::Pubkey,
+    signature::{KeypairUtil, Signature},
+    transaction::{Transaction, TransactionError},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, io,
+    net::{SocketAddr, UdpSocket},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    thread::{sleep, Builder, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// Default number of upcoming slots whose leaders each transaction is sent to
+pub const DEFAULT_FANOUT_SLOTS: u64 = 12;
+
+/// Maximum number of upcoming slots whose leaders each transaction is sent to
+pub const MAX_FANOUT_SLOTS: u64 = 100;
+
+// How often the cluster nodes are re-fetched, to pick up changed TPU addresses
+const CLUSTER_NODES_REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+// How often the leader service checks whether it is missing a leader schedule
+const LEADER_SCHEDULE_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+// How often unconfirmed transactions are re-sent and their statuses polled
+const SEND_AND_CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// How many times `send_and_confirm_messages` re-signs unconfirmed transactions before giving up
+const MAX_SEND_AND_CONFIRM_ROUNDS: usize = 5;
+
+#[derive(Debug)]
+pub enum TpuSenderError {
+    IoError(io::Error),
+    PubsubError(PubsubClientError),
+    RpcError(ClientError),
+    Custom(String),
+}
+
+impl fmt::Display for TpuSenderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TpuSenderError::IoError(err) => write!(f, "io error: {}", err),
+            TpuSenderError::PubsubError(err) => write!(f, "pubsub error: {}", err),
+            TpuSenderError::RpcError(err) => write!(f, "rpc error: {:?}", err),
+            TpuSenderError::Custom(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for TpuSenderError {}
+
+impl From<io::Error> for TpuSenderError {
+    fn from(err: io::Error) -> TpuSenderError {
+        TpuSenderError::IoError(err)
+    }
+}
+
+impl From<PubsubClientError> for TpuSenderError {
+    fn from(err: PubsubClientError) -> TpuSenderError {
+        TpuSenderError::PubsubError(err)
+    }
+}
+
+impl From<ClientError> for TpuSenderError {
+    fn from(err: ClientError) -> TpuSenderError {
+        TpuSenderError::RpcError(err)
+    }
+}
+
+type Result<T> = std::result::Result<T, TpuSenderError>;
+
+#[derive(Clone, Debug)]
+pub struct TpuClientConfig {
+    /// The number of upcoming slots whose leaders each transaction is sent to, at most
+    /// `MAX_FANOUT_SLOTS`
+    pub fanout_slots: u64,
+}
+
+impl Default for TpuClientConfig {
+    fn default() -> Self {
+        Self {
+            fanout_slots: DEFAULT_FANOUT_SLOTS,
+        }
+    }
+}
+
+/// Sends wire transactions to the TPU ports of the next `fanout_slots` leaders
+pub struct TpuClient {
+    send_socket: UdpSocket,
+    fanout_slots: u64,
+    leader_tpu_service: LeaderTpuService,
+    exit: Arc<AtomicBool>,
+    rpc_client: Arc<RpcClient>,
+}
+
+impl TpuClient {
+    /// Create a client that learns the leader schedule and cluster nodes through `rpc_client`
+    /// and follows the current slot through the pubsub endpoint at `websocket_url`
+    pub fn new(
+        rpc_client: Arc<RpcClient>,
+        websocket_url: &str,
+        config: TpuClientConfig,
+    ) -> Result<Self> {
+        let exit = Arc::new(AtomicBool::new(false));
+        let leader_tpu_service = LeaderTpuService::new(rpc_client.clone(), websocket_url, &exit)?;
+        Ok(Self {
+            send_socket: UdpSocket::bind("0.0.0.0:0")?,
+            fanout_slots: config.fanout_slots.min(MAX_FANOUT_SLOTS),
+            leader_tpu_service,
+            exit,
+            rpc_client,
+        })
+    }
+
+    /// Serialize and send `transaction` to the upcoming leaders. Returns whether it reached at
+    /// least one of them
+    pub fn send_transaction(&self, transaction: &Transaction) -> bool {
+        let wire_transaction = serialize(transaction).expect("serialization should succeed");
+        self.send_wire_transaction(&wire_transaction)
+    }
+
+    /// Send a serialized transaction to the upcoming leaders. Returns whether it reached at least
+    /// one of them
+    pub fn send_wire_transaction(&self, wire_transaction: &[u8]) -> bool {
+        let mut sent = false;
+        for tpu_address in self
+            .leader_tpu_service
+            .leader_tpu_sockets(self.fanout_slots)
+        {
+            match self.send_socket.send_to(wire_transaction, tpu_address) {
+                Ok(_) => sent = true,
+                Err(err) => debug!("Failed to send transaction to {}: {}", tpu_address, err),
+            }
+        }
+        sent
+    }
+
+    /// Sign each of `messages` with `signers`, send them to the upcoming leaders and wait for
+    /// them to be confirmed. Transactions that are not confirmed before their blockhash expires
+    /// are re-signed with a new blockhash and sent again.
+    ///
+    /// Returns the error each transaction failed with, or `None` for those that succeeded
+    pub fn send_and_confirm_messages<T: KeypairUtil>(
+        &self,
+        messages: &[Message],
+        signers: &[&T],
+    ) -> Result<Vec<Option<TransactionError>>> {
+        let mut transaction_errors = vec![None; messages.len()];
+        let mut unconfirmed: Vec<usize> = (0..messages.len()).collect();
+
+        for _ in 0..MAX_SEND_AND_CONFIRM_ROUNDS {
+            let response = self
+                .rpc_client
+                .get_recent_blockhash_with_commitment(Default::default())?;
+            let (blockhash, _fee_calculator) = response.value;
+            let last_valid_slot = response.context.slot + MAX_PROCESSING_AGE as u64;
+
+            let mut pending: HashMap<Signature, (usize, Vec<u8>)> = unconfirmed
+                .iter()
+                .map(|index| {
+                    let transaction =
+                        Transaction::new(signers, messages[*index].clone(), blockhash);
+                    let wire_transaction =
+                        serialize(&transaction).expect("serialization should succeed");
+                    (transaction.signatures[0], (*index, wire_transaction))
+                })
+                .collect();
+
+            while !pending.is_empty() && self.rpc_client.get_slot()? <= last_valid_slot {
+                for (_index, wire_transaction) in pending.values() {
+                    self.send_wire_transaction(wire_transaction);
+                }
+                sleep(SEND_AND_CONFIRM_POLL_INTERVAL);
+                self.confirm_pending(&mut pending, &mut transaction_errors);
+            }
+            // Transactions sent since the last poll may still have landed before the blockhash
+            // expired, they must not be re-signed
+            if !pending.is_empty() {
+                self.confirm_pending(&mut pending, &mut transaction_errors);
+            }
+
+            if pending.is_empty() {
+                return Ok(transaction_errors);
+            }
+            unconfirmed = pending.values().map(|(index, _)| *index).collect();
+            unconfirmed.sort();
+            info!(
+                "Blockhash {} expired with {} transactions unconfirmed, re-signing",
+                blockhash,
+                unconfirmed.len()
+            );
+        }
+
+        Err(TpuSenderError::Custom(format!(
+            "{} transactions failed to confirm",
+            unconfirmed.len()
+        )))
+    }
+
+    // Remove the transactions that have a status from `pending`, recording their errors
+    fn confirm_pending(
+        &self,
+        pending: &mut HashMap<Signature, (usize, Vec<u8>)>,
+        transaction_errors: &mut [Option<TransactionError>],
+    ) {
+        pending.retain(|signature, (index, _wire_transaction)| {
+            match self.rpc_client.get_signature_status(&signature.to_string()) {
+                Ok(Some(result)) => {
+                    transaction_errors[*index] = result.err();
+                    false
+                }
+                _ => true,
+            }
+        });
+    }
+
+    pub fn rpc_client(&self) -> &RpcClient {
+        &self.rpc_client
+    }
+}
+
+impl Drop for TpuClient {
+    fn drop(&mut self) {
+        self.exit.store(true, Ordering::Relaxed);
+        if let Err(err) = self.leader_tpu_service.join() {
+            warn!("Leader tpu service failed to exit: {:?}", err);
+        }
+    }
+}
+
+/// The leader schedule and TPU addresses of the cluster, as last fetched over RPC
+struct LeaderTpuCache {
+    epoch_schedule: EpochSchedule,
+    leader_schedules: HashMap<Epoch, Vec<Pubkey>>,
+    leader_tpu_map: HashMap<Pubkey, SocketAddr>,
+    last_cluster_nodes_refresh: Instant,
+}
+
+impl LeaderTpuCache {
+    fn new(rpc_client: &RpcClient, current_slot: Slot) -> Result<Self> {
+        let epoch_schedule = rpc_client.get_epoch_schedule()?;
+        let epoch = epoch_schedule.get_epoch(current_slot);
+        let mut leader_schedules = HashMap::new();
+        if let Some(leaders) = Self::fetch_leader_schedule(rpc_client, &epoch_schedule, epoch)? {
+            leader_schedules.insert(epoch, leaders);
+        }
+        Ok(Self {
+            epoch_schedule,
+            leader_schedules,
+            leader_tpu_map: Self::fetch_leader_tpu_map(rpc_client)?,
+            last_cluster_nodes_refresh: Instant::now(),
+        })
+    }
+
+    fn fetch_leader_tpu_map(rpc_client: &RpcClient) -> Result<HashMap<Pubkey, SocketAddr>> {
+        Ok(Self::leader_tpu_map(rpc_client.get_cluster_nodes()?))
+    }
+
+    fn leader_tpu_map(cluster_nodes: Vec<RpcContactInfo>) -> HashMap<Pubkey, SocketAddr> {
+        cluster_nodes
+            .into_iter()
+            .filter_map(|contact_info| {
+                let pubkey = Pubkey::from_str(&contact_info.pubkey).ok()?;
+                Some((pubkey, contact_info.tpu?))
+            })
+            .collect()
+    }
+
+    // Returns the leader of each slot of `epoch`, if the schedule is available
+    fn fetch_leader_schedule(
+        rpc_client: &RpcClient,
+        epoch_schedule: &EpochSchedule,
+        epoch: Epoch,
+    ) -> Result<Option<Vec<Pubkey>>> {
+        let first_slot = epoch_schedule.get_first_slot_in_epoch(epoch);
+        match rpc_client.get_leader_schedule(Some(first_slot))? {
+            Some(leader_schedule) => {
+                let slots_in_epoch = epoch_schedule.get_slots_in_epoch(epoch);
+                let mut leaders = vec![Pubkey::default(); slots_in_epoch as usize];
+                for (pubkey, slot_indexes) in leader_schedule {
+                    let pubkey = Pubkey::from_str(&pubkey).map_err(|err| {
+                        TpuSenderError::Custom(format!("Invalid leader pubkey: {:?}", err))
+                    })?;
+                    for slot_index in slot_indexes {
+                        if let Some(leader) = leaders.get_mut(slot_index) {
+                            *leader = pubkey;
+                        }
+                    }
+                }
+                Ok(Some(leaders))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn get_slot_leader(&self, slot: Slot) -> Option<&Pubkey> {
+        let (epoch, slot_index) = self.epoch_schedule.get_epoch_and_slot_index(slot);
+        self.leader_schedules
+            .get(&epoch)?
+            .get(slot_index as usize)
+            .filter(|leader| **leader != Pubkey::default())
+    }
+
+    // The TPU addresses of the leaders of the `fanout_slots` slots starting at `current_slot`,
+    // each leader listed once
+    fn leader_tpu_sockets(&self, current_slot: Slot, fanout_slots: u64) -> Vec<SocketAddr> {
+        let mut leaders = HashSet::new();
+        let mut leader_tpu_sockets = vec![];
+        for slot in current_slot..current_slot + fanout_slots {
+            if let Some(leader) = self.get_slot_leader(slot) {
+                if !leaders.insert(*leader) {
+                    continue;
+                }
+                match self.leader_tpu_map.get(leader) {
+                    Some(tpu_socket) => leader_tpu_sockets.push(*tpu_socket),
+                    None => trace!("TPU address of leader {} is unknown", leader),
+                }
+            } else {
+                trace!("Leader of slot {} is unknown", slot);
+            }
+        }
+        leader_tpu_sockets
+    }
+
+    // The epochs the fanout may reach whose schedules are missing
+    fn missing_epochs(&self, current_slot: Slot) -> Vec<Epoch> {
+        let current_epoch = self.epoch_schedule.get_epoch(current_slot);
+        let last_epoch = self
+            .epoch_schedule
+            .get_epoch(current_slot + MAX_FANOUT_SLOTS);
+        (current_epoch..=last_epoch)
+            .filter(|epoch| !self.leader_schedules.contains_key(epoch))
+            .collect()
+    }
+
+    // Add what a refresh fetched, and drop the schedules of the epochs already past
+    fn update(
+        &mut self,
+        current_slot: Slot,
+        leader_tpu_map: Option<HashMap<Pubkey, SocketAddr>>,
+        leader_schedules: Vec<(Epoch, Vec<Pubkey>)>,
+    ) {
+        if let Some(leader_tpu_map) = leader_tpu_map {
+            self.leader_tpu_map = leader_tpu_map;
+            self.last_cluster_nodes_refresh = Instant::now();
+        }
+        self.leader_schedules.extend(leader_schedules);
+        let current_epoch = self.epoch_schedule.get_epoch(current_slot);
+        self.leader_schedules
+            .retain(|epoch, _| *epoch >= current_epoch);
+    }
+}
+
+/// Follows the current slot through a slot subscription, and keeps the leader schedule and TPU
+/// addresses up to date in the background
+struct LeaderTpuService {
+    current_slot: Arc<AtomicU64>,
+    leader_tpu_cache: Arc<RwLock<LeaderTpuCache>>,
+    t_leader_tpu_service: Option<JoinHandle<()>>,
+}
+
+impl LeaderTpuService {
+    fn new(
+        rpc_client: Arc<RpcClient>,
+        websocket_url: &str,
+        exit: &Arc<AtomicBool>,
+    ) -> Result<Self> {
+        let start_slot = rpc_client.get_slot()?;
+        let current_slot = Arc::new(AtomicU64::new(start_slot));
+        let leader_tpu_cache = Arc::new(RwLock::new(LeaderTpuCache::new(&rpc_client, start_slot)?));
+        let (mut slot_subscription, slot_receiver) = PubsubClient::slot_subscribe(websocket_url)?;
+
+        let t_leader_tpu_service = {
+            let current_slot = current_slot.clone();
+            let leader_tpu_cache = leader_tpu_cache.clone();
+            let exit = exit.clone();
+            Builder::new()
+                .name("solana-leader-tpu-service".to_string())
+                .spawn(move || {
+                    let mut last_refresh = Instant::now();
+                    while !exit.load(Ordering::Relaxed) {
+                        if let Ok(slot_info) =
+                            slot_receiver.recv_timeout(Duration::from_millis(100))
+                        {
+                            // Slots may arrive out of order from different forks; only move forward
+                            if slot_info.slot > current_slot.load(Ordering::Relaxed) {
+                                current_slot.store(slot_info.slot, Ordering::Relaxed);
+                            }
+                        }
+                        if last_refresh.elapsed() >= LEADER_SCHEDULE_REFRESH_INTERVAL {
+                            Self::refresh(
+                                &rpc_client,
+                                &leader_tpu_cache,
+                                current_slot.load(Ordering::Relaxed),
+                            );
+                            last_refresh = Instant::now();
+                        }
+                    }
+                    if let Err(err) = slot_subscription.shutdown() {
+                        warn!("Slot subscription failed to shut down: {:?}", err);
+                    }
+                })
+                .unwrap()
+        };
+
+        Ok(Self {
+            current_slot,
+            leader_tpu_cache,
+            t_leader_tpu_service: Some(t_leader_tpu_service),
+        })
+    }
+
+    // Fetch what the cache is missing without holding its lock, which senders wait on, and only
+    // take the lock to apply it
+    fn refresh(
+        rpc_client: &RpcClient,
+        leader_tpu_cache: &RwLock<LeaderTpuCache>,
+        current_slot: Slot,
+    ) {
+        let (epoch_schedule, cluster_nodes_stale, missing_epochs) = {
+            let leader_tpu_cache = leader_tpu_cache.read().unwrap();
+            (
+                leader_tpu_cache.epoch_schedule,
+                leader_tpu_cache.last_cluster_nodes_refresh.elapsed()
+                    >= CLUSTER_NODES_REFRESH_INTERVAL,
+                leader_tpu_cache.missing_epochs(current_slot),
+            )
+        };
+
+        let leader_tpu_map = if cluster_nodes_stale {
+            LeaderTpuCache::fetch_leader_tpu_map(rpc_client)
+                .map_err(|err| warn!("Failed to fetch cluster nodes: {}", err))
+                .ok()
+        } else {
+            None
+        };
+        let leader_schedules: Vec<_> = missing_epochs
+            .into_iter()
+            .filter_map(|epoch| {
+                match LeaderTpuCache::fetch_leader_schedule(rpc_client, &epoch_schedule, epoch) {
+                    Ok(leaders) => leaders.map(|leaders| (epoch, leaders)),
+                    Err(err) => {
+                        warn!(
+                            "Failed to fetch the leader schedule of epoch {}: {}",
+                            epoch, err
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        leader_tpu_cache
+            .write()
+            .unwrap()
+            .update(current_slot, leader_tpu_map, leader_schedules);
+    }
+
+    fn leader_tpu_sockets(&self, fanout_slots: u64) -> Vec<SocketAddr> {
+        self.leader_tpu_cache
+            .read()
+            .unwrap()
+            .leader_tpu_sockets(self.current_slot.load(Ordering::Relaxed), fanout_slots)
+    }
+
+    fn join(&mut self) -> std::thread::Result<()> {
+        match self.t_leader_tpu_service.take() {
+            Some(t_leader_tpu_service) => t_leader_tpu_service.join(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact_info(pubkey: &Pubkey, tpu: Option<SocketAddr>) -> RpcContactInfo {
+        RpcContactInfo {
+            pubkey: pubkey.to_string(),
+            gossip: None,
+            tpu,
+            rpc: None,
+        }
+    }
+
+    #[test]
+    fn test_leader_tpu_sockets() {
+        let leader0 = Pubkey::new_rand();
+        let leader1 = Pubkey::new_rand();
+        let leader2 = Pubkey::new_rand();
+        let tpu0: SocketAddr = "127.0.0.1:8000".parse().unwrap();
+        let tpu1: SocketAddr = "127.0.0.1:8001".parse().unwrap();
+
+        let epoch_schedule = EpochSchedule::custom(32, 32, false);
+        // Only the leaders of the first 8 slots are known
+        let mut leaders = vec![
+            leader0, leader0, leader1, leader1, leader2, leader2, leader0, leader0,
+        ];
+        leaders.resize(32, Pubkey::default());
+        let mut leader_schedules = HashMap::new();
+        leader_schedules.insert(0, leaders);
+        let leader_tpu_cache = LeaderTpuCache {
+            epoch_schedule,
+            leader_schedules,
+            // leader2 has no TPU address
+            leader_tpu_map: LeaderTpuCache::leader_tpu_map(vec![
+                contact_info(&leader0, Some(tpu0)),
+                contact_info(&leader1, Some(tpu1)),
+                contact_info(&leader2, None),
+            ]),
+            last_cluster_nodes_refresh: Instant::now(),
+        };
+
+        assert_eq!(leader_tpu_cache.get_slot_leader(2), Some(&leader1));
+        assert_eq!(leader_tpu_cache.get_slot_leader(8), None);
+        assert_eq!(leader_tpu_cache.get_slot_leader(32), None);
+
+        // Each leader is sent to once
+        assert_eq!(leader_tpu_cache.leader_tpu_sockets(0, 4), vec![tpu0, tpu1]);
+        assert_eq!(leader_tpu_cache.leader_tpu_sockets(1, 8), vec![tpu0, tpu1]);
+        assert_eq!(leader_tpu_cache.leader_tpu_sockets(4, 2), vec![]);
+
+        // Slots with unknown leaders are skipped
+        assert_eq!(leader_tpu_cache.leader_tpu_sockets(6, 10), vec![tpu0]);
+    }
+
+    #[test]
+    fn test_leader_tpu_cache_update() {
+        let epoch_schedule = EpochSchedule::custom(32, 32, false);
+        let leader = Pubkey::new_rand();
+        let mut leader_schedules = HashMap::new();
+        leader_schedules.insert(0, vec![leader; 32]);
+        let mut leader_tpu_cache = LeaderTpuCache {
+            epoch_schedule,
+            leader_schedules,
+            leader_tpu_map: HashMap::new(),
+            last_cluster_nodes_refresh: Instant::now(),
+        };
+
+        // The fanout from slot 0 reaches the first 4 epochs
+        assert_eq!(leader_tpu_cache.missing_epochs(0), vec![1, 2, 3]);
+
+        let tpu: SocketAddr = "127.0.0.1:8000".parse().unwrap();
+        let mut leader_tpu_map = HashMap::new();
+        leader_tpu_map.insert(leader, tpu);
+        leader_tpu_cache.update(
+            32,
+            Some(leader_tpu_map),
+            vec![(1, vec![leader; 32]), (2, vec![leader; 32])],
+        );
+        // The schedule of epoch 0 is past, and that of epoch 3 is still missing
+        assert_eq!(leader_tpu_cache.missing_epochs(32), vec![3, 4]);
+        assert!(!leader_tpu_cache.leader_schedules.contains_key(&0));
+        assert_eq!(leader_tpu_cache.leader_tpu_sockets(32, 1), vec![tpu]);
+    }
+}
@@ -516,8 +516,21 @@ impl BankingStage {
         } else {
             vec![]
         };
-        let (mut loaded_accounts, results, mut retryable_txs, tx_count, signature_count) =
-            bank.load_and_execute_transactions(batch, MAX_PROCESSING_AGE);
+        let (
+            mut loaded_accounts,
+            results,
+            transaction_logs,
+            transaction_compute_units,
+            transaction_rewards,
+            mut retryable_txs,
+            tx_count,
+            signature_count,
+        ) = bank.load_and_execute_transactions(
+            batch,
+            MAX_PROCESSING_AGE,
+            transaction_status_sender.is_some(),
+            transaction_status_sender.is_some(),
+        );
         load_execute_time.stop();
 
         let freeze_lock = bank.freeze_lock();
@@ -542,6 +555,7 @@ impl BankingStage {
                     None,
                     &mut loaded_accounts,
                     &results,
+                    &transaction_rewards,
                     tx_count,
                     signature_count,
                 )
@@ -554,6 +568,8 @@ impl BankingStage {
                     batch.transactions(),
                     transaction_statuses,
                     TransactionBalancesSet::new(pre_balances, post_balances),
+                    transaction_logs,
+                    transaction_compute_units,
                     sender,
                 );
             }
@@ -2,9 +2,12 @@ use crate::cluster_info::{ClusterInfo, GOSSIP_SLEEP_MILLIS};
 use crate::packet::Packets;
 use crate::poh_recorder::PohRecorder;
 use crate::result::Result;
+use crate::rpc_subscriptions::RpcSubscriptions;
 use crate::{packet, sigverify};
 use crossbeam_channel::Sender as CrossbeamSender;
 use solana_metrics::inc_new_counter_debug;
+use solana_sdk::{instruction_processor_utils::limited_deserialize, transaction::Transaction};
+use solana_vote_program::{vote_instruction::VoteInstruction, vote_state::Vote};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
 use std::thread::{self, sleep, Builder, JoinHandle};
@@ -21,9 +24,11 @@ impl ClusterInfoVoteListener {
         sigverify_disabled: bool,
         sender: CrossbeamSender<Vec<Packets>>,
         poh_recorder: &Arc<Mutex<PohRecorder>>,
+        subscriptions: &Arc<RpcSubscriptions>,
     ) -> Self {
         let exit = exit.clone();
         let poh_recorder = poh_recorder.clone();
+        let subscriptions = subscriptions.clone();
         let thread = Builder::new()
             .name("solana-cluster_info_vote_listener".to_string())
             .spawn(move || {
@@ -33,6 +38,7 @@ impl ClusterInfoVoteListener {
                     sigverify_disabled,
                     &sender,
                     poh_recorder,
+                    &subscriptions,
                 );
             })
             .unwrap();
@@ -46,11 +52,24 @@ impl ClusterInfoVoteListener {
         sigverify_disabled: bool,
         sender: &CrossbeamSender<Vec<Packets>>,
         poh_recorder: Arc<Mutex<PohRecorder>>,
+        subscriptions: &RpcSubscriptions,
     ) -> Result<()> {
+        // Gossip votes are reported to vote subscribers whether or not this node is leader, once
+        // their signatures are verified
+        let mut last_notified_ts = 0;
         loop {
             if exit.load(Ordering::Relaxed) {
                 return Ok(());
             }
+            let (votes, new_ts) = cluster_info.read().unwrap().get_votes(last_notified_ts);
+            last_notified_ts = new_ts;
+            for vote in votes
+                .iter()
+                .filter(|tx| sigverify_disabled || Self::verify_vote_transaction(tx))
+                .flat_map(Self::parse_vote_transaction)
+            {
+                subscriptions.notify_vote(&vote);
+            }
             if let Some(bank) = poh_recorder.lock().unwrap().bank() {
                 let last_ts = bank.last_vote_sync.load(Ordering::Relaxed);
                 let (votes, new_ts) = cluster_info.read().unwrap().get_votes(last_ts);
@@ -72,6 +91,33 @@ impl ClusterInfoVoteListener {
         }
     }
 
+    fn verify_vote_transaction(tx: &Transaction) -> bool {
+        tx.signatures.len() == tx.message.header.num_required_signatures as usize
+            && tx.verify_refs()
+            && tx.verify().is_ok()
+    }
+
+    /// Get the votes of all the vote instructions in `tx`
+    fn parse_vote_transaction(tx: &Transaction) -> Vec<Vote> {
+        tx.message
+            .instructions
+            .iter()
+            .filter_map(|instruction| {
+                let program_id = tx
+                    .message
+                    .account_keys
+                    .get(instruction.program_id_index as usize)?;
+                if *program_id != solana_vote_program::id() {
+                    return None;
+                }
+                match limited_deserialize(&instruction.data) {
+                    Ok(VoteInstruction::Vote(vote)) => Some(vote),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
     pub fn join(self) -> thread::Result<()> {
         for thread_hdl in self.thread_hdls {
             thread_hdl.join()?;
@@ -82,12 +128,12 @@ impl ClusterInfoVoteListener {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::packet;
     use solana_sdk::hash::Hash;
-    use solana_sdk::signature::{Keypair, KeypairUtil};
-    use solana_sdk::transaction::Transaction;
+    use solana_sdk::signature::{Keypair, KeypairUtil, Signature};
+    use solana_sdk::{system_instruction, system_transaction};
     use solana_vote_program::vote_instruction;
-    use solana_vote_program::vote_state::Vote;
 
     #[test]
     fn test_max_vote_tx_fits() {
@@ -110,4 +156,68 @@ mod tests {
 
         assert_eq!(msgs.len(), 1);
     }
+
+    #[test]
+    fn test_parse_vote_transaction() {
+        let node_keypair = Keypair::new();
+        let vote_keypair = Keypair::new();
+        let vote = Vote::new(vec![1, 2, 3], Hash::default());
+        let vote_ix =
+            vote_instruction::vote(&vote_keypair.pubkey(), &vote_keypair.pubkey(), vote.clone());
+        let vote_tx = Transaction::new_with_payer(vec![vote_ix], Some(&node_keypair.pubkey()));
+        assert_eq!(
+            ClusterInfoVoteListener::parse_vote_transaction(&vote_tx),
+            vec![vote.clone()]
+        );
+
+        // Every vote instruction is parsed, wherever it is in the transaction
+        let other_vote = Vote::new(vec![4], Hash::default());
+        let transfer_ix =
+            system_instruction::transfer(&node_keypair.pubkey(), &vote_keypair.pubkey(), 1);
+        let vote_ixs = vec![
+            transfer_ix,
+            vote_instruction::vote(&vote_keypair.pubkey(), &vote_keypair.pubkey(), vote.clone()),
+            vote_instruction::vote(
+                &vote_keypair.pubkey(),
+                &vote_keypair.pubkey(),
+                other_vote.clone(),
+            ),
+        ];
+        let vote_tx = Transaction::new_with_payer(vote_ixs, Some(&node_keypair.pubkey()));
+        assert_eq!(
+            ClusterInfoVoteListener::parse_vote_transaction(&vote_tx),
+            vec![vote, other_vote]
+        );
+
+        let transfer_tx =
+            system_transaction::transfer(&node_keypair, &vote_keypair.pubkey(), 1, Hash::default());
+        assert_eq!(
+            ClusterInfoVoteListener::parse_vote_transaction(&transfer_tx),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_verify_vote_transaction() {
+        let node_keypair = Keypair::new();
+        let vote_keypair = Keypair::new();
+        let vote = Vote::new(vec![1, 2, 3], Hash::default());
+        let vote_ix = vote_instruction::vote(&vote_keypair.pubkey(), &vote_keypair.pubkey(), vote);
+        let mut vote_tx = Transaction::new_signed_with_payer(
+            vec![vote_ix],
+            Some(&node_keypair.pubkey()),
+            &[&node_keypair, &vote_keypair],
+            Hash::default(),
+        );
+        assert!(ClusterInfoVoteListener::verify_vote_transaction(&vote_tx));
+
+        // Forged and missing signatures are rejected
+        let mut forged_tx = vote_tx.clone();
+        forged_tx.signatures[1] = Signature::default();
+        assert!(!ClusterInfoVoteListener::verify_vote_transaction(
+            &forged_tx
+        ));
+        vote_tx.signatures.pop();
+        assert!(!ClusterInfoVoteListener::verify_vote_transaction(&vote_tx));
+    }
 }
@@ -103,8 +103,10 @@ impl LedgerCleanupService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use solana_client::rpc_request::RpcTransactionStatus;
     use solana_ledger::blocktree::make_many_slot_entries;
     use solana_ledger::get_tmp_ledger_path;
+    use solana_sdk::{pubkey::Pubkey, signature::Signature};
     use std::sync::mpsc::channel;
 
     #[test]
@@ -113,6 +115,26 @@ mod tests {
         let blocktree = Blocktree::open(&blocktree_path).unwrap();
         let (shreds, _) = make_many_slot_entries(0, 50, 5);
         blocktree.insert_shreds(shreds, None, false).unwrap();
+        let address = Pubkey::new_rand();
+        let status = RpcTransactionStatus {
+            status: Ok(()),
+            fee: 0,
+            pre_balances: vec![],
+            post_balances: vec![],
+            log_messages: Some(vec![]),
+            compute_units_consumed: Some(0),
+        };
+        for slot in 0..50 {
+            blocktree
+                .write_transaction_status(
+                    (slot, Signature::new(&[slot as u8; 64])),
+                    vec![&address],
+                    vec![],
+                    &status,
+                )
+                .unwrap();
+        }
+        blocktree.set_roots(&(0..50).collect::<Vec<_>>()).unwrap();
         let blocktree = Arc::new(blocktree);
         let (sender, receiver) = channel();
 
@@ -127,6 +149,12 @@ mod tests {
             .slot_meta_iterator(0)
             .unwrap()
             .for_each(|(slot, _)| assert!(slot > 40));
+        //check that the address signatures for 0-40 are gone
+        blocktree
+            .get_confirmed_signatures_for_address(address, 0, 50, None, 100)
+            .unwrap()
+            .iter()
+            .for_each(|(slot, _)| assert!(*slot > 40));
 
         drop(blocktree);
         Blocktree::destroy(&blocktree_path).expect("Expected successful database destruction");
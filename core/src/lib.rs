@@ -33,6 +33,7 @@ pub mod genesis_utils;
 pub mod gossip_service;
 pub mod ledger_cleanup_service;
 pub mod local_vote_signer_service;
+pub mod non_circulating_supply;
 pub mod packet;
 pub mod partition_cfg;
 pub mod poh_recorder;
@@ -45,8 +46,10 @@ pub mod retransmit_stage;
 pub mod rpc;
 pub mod rpc_pubsub;
 pub mod rpc_pubsub_service;
+pub mod rpc_rate_limiter;
 pub mod rpc_service;
 pub mod rpc_subscriptions;
+pub mod sample_performance_service;
 pub mod sendmmsg;
 pub mod sigverify;
 pub mod sigverify_shreds;
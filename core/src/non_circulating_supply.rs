@@ -0,0 +1,130 @@
+//! The `non_circulating_supply` module computes the portion of the supply that is not in
+//! circulation: locked stake and the balances of the configured non-circulating accounts.
+
+use solana_runtime::bank::Bank;
+use solana_sdk::{
+    pubkey::Pubkey,
+    sysvar::{self, clock::Clock, Sysvar},
+};
+use solana_stake_program::stake_state::StakeState;
+use std::collections::HashSet;
+
+pub struct NonCirculatingSupply {
+    pub lamports: u64,
+    pub accounts: Vec<Pubkey>,
+}
+
+/// A stake account is non-circulating while its lockup is in force, or when its withdraw
+/// authority or lockup custodian is one of the `non_circulating_accounts`
+pub fn calculate_non_circulating_supply(
+    bank: &Bank,
+    non_circulating_accounts: &HashSet<Pubkey>,
+) -> NonCirculatingSupply {
+    let clock = bank
+        .get_account(&sysvar::clock::id())
+        .and_then(|account| Clock::from_account(&account))
+        .unwrap_or_default();
+    let no_signers = HashSet::new();
+
+    let mut non_circulating_accounts_set = non_circulating_accounts.clone();
+    for (pubkey, account) in bank.get_program_accounts(&solana_stake_program::id()) {
+        let meta = match StakeState::from(&account) {
+            Some(StakeState::Initialized(meta)) | Some(StakeState::Stake(meta, _)) => meta,
+            _ => continue,
+        };
+        if meta.lockup.is_in_force(&clock, &no_signers)
+            || non_circulating_accounts.contains(&meta.authorized.withdrawer)
+            || non_circulating_accounts.contains(&meta.lockup.custodian)
+        {
+            non_circulating_accounts_set.insert(pubkey);
+        }
+    }
+
+    let lamports = non_circulating_accounts_set
+        .iter()
+        .map(|pubkey| bank.get_balance(pubkey))
+        .sum();
+
+    NonCirculatingSupply {
+        lamports,
+        accounts: non_circulating_accounts_set.into_iter().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        account::Account,
+        genesis_config::{create_genesis_config, GenesisConfig},
+    };
+    use solana_stake_program::stake_state::{create_lockup_stake_account, Authorized, Lockup};
+
+    #[test]
+    fn test_calculate_non_circulating_supply() {
+        let (mut genesis_config, _mint_keypair) = create_genesis_config(10_000);
+        let balance = 10;
+
+        let foundation = Pubkey::new_rand();
+        genesis_config.add_account(foundation, Account::new(balance, 0, &Pubkey::default()));
+
+        let add_stake_account = |genesis_config: &mut GenesisConfig, withdrawer, lockup| {
+            let authorized = Authorized {
+                staker: Pubkey::new_rand(),
+                withdrawer,
+            };
+            let pubkey = Pubkey::new_rand();
+            let account = create_lockup_stake_account(
+                &authorized,
+                &lockup,
+                &genesis_config.rent,
+                genesis_config
+                    .rent
+                    .minimum_balance(std::mem::size_of::<StakeState>())
+                    + balance,
+            );
+            genesis_config.add_account(pubkey, account);
+            pubkey
+        };
+        let locked_stake = add_stake_account(
+            &mut genesis_config,
+            Pubkey::new_rand(),
+            Lockup {
+                epoch: 1,
+                ..Lockup::default()
+            },
+        );
+        let foundation_stake =
+            add_stake_account(&mut genesis_config, foundation, Lockup::default());
+        let custodian_stake = add_stake_account(
+            &mut genesis_config,
+            Pubkey::new_rand(),
+            Lockup {
+                custodian: foundation,
+                ..Lockup::default()
+            },
+        );
+        let circulating_stake =
+            add_stake_account(&mut genesis_config, Pubkey::new_rand(), Lockup::default());
+
+        let bank = Bank::new(&genesis_config);
+        let non_circulating_accounts: HashSet<_> = vec![foundation].into_iter().collect();
+        let non_circulating_supply =
+            calculate_non_circulating_supply(&bank, &non_circulating_accounts);
+
+        let mut accounts = non_circulating_supply.accounts.clone();
+        accounts.sort();
+        let mut expected_accounts =
+            vec![foundation, locked_stake, foundation_stake, custodian_stake];
+        expected_accounts.sort();
+        assert_eq!(accounts, expected_accounts);
+        assert!(!accounts.contains(&circulating_stake));
+        assert_eq!(
+            non_circulating_supply.lamports,
+            expected_accounts
+                .iter()
+                .map(|pubkey| bank.get_balance(pubkey))
+                .sum::<u64>()
+        );
+    }
+}
@@ -9,6 +9,7 @@ use crate::{
     rpc_subscriptions::RpcSubscriptions,
     thread_mem_usage,
 };
+use solana_client::rpc_request::{RpcReward, RpcRewards};
 use solana_ledger::entry::EntryVerificationStatus;
 use solana_ledger::{
     bank_forks::BankForks,
@@ -319,6 +320,7 @@ impl ReplayStage {
                                 &lockouts_sender,
                                 &snapshot_package_sender,
                                 &latest_root_senders,
+                                &subscriptions,
                             )?;
                         }
                         datapoint_debug!(
@@ -620,6 +622,7 @@ impl ReplayStage {
         lockouts_sender: &Sender<CommitmentAggregationData>,
         snapshot_package_sender: &Option<SnapshotPackageSender>,
         latest_root_senders: &[Sender<Slot>],
+        subscriptions: &Arc<RpcSubscriptions>,
     ) -> Result<()> {
         if bank.is_empty() {
             inc_new_counter_info!("replay_stage-voted_empty_bank", 1);
@@ -634,6 +637,7 @@ impl ReplayStage {
                 .get(new_root)
                 .expect("Root bank doesn't exist")
                 .clone();
+            let old_root = bank_forks.read().unwrap().root();
             let mut rooted_banks = root_bank.parents();
             rooted_banks.push(root_bank);
             let rooted_slots: Vec<_> = rooted_banks.iter().map(|bank| bank.slot()).collect();
@@ -650,6 +654,14 @@ impl ReplayStage {
                 .unwrap()
                 .set_root(new_root, snapshot_package_sender);
             Self::handle_new_root(&bank_forks, progress);
+            // The chain of parents ends at the previous root, which subscribers were already
+            // notified of
+            let new_rooted_slots: Vec<_> = rooted_slots
+                .into_iter()
+                .filter(|slot| *slot > old_root)
+                .collect();
+            subscriptions.notify_blocks(&new_rooted_slots, blocktree);
+            subscriptions.notify_roots(new_rooted_slots);
             latest_root_senders.iter().for_each(|s| {
                 if let Err(e) = s.send(new_root) {
                     trace!("latest root send failed: {:?}", e);
@@ -779,7 +791,14 @@ impl ReplayStage {
                         .report_stats(bank_progress.num_entries, bank_progress.num_shreds);
                 }
                 did_complete_bank = true;
-                Self::process_completed_bank(my_pubkey, bank, slot_full_senders);
+                let record_rewards = transaction_status_sender.is_some();
+                Self::process_completed_bank(
+                    my_pubkey,
+                    bank,
+                    slot_full_senders,
+                    blocktree,
+                    record_rewards,
+                );
             } else {
                 trace!(
                     "bank {} not completed tick_height: {}, max_tick_height: {}",
@@ -1106,9 +1125,14 @@ impl ReplayStage {
         my_pubkey: &Pubkey,
         bank: Arc<Bank>,
         slot_full_senders: &[Sender<(u64, Pubkey)>],
+        blocktree: &Blocktree,
+        record_rewards: bool,
     ) {
         bank.freeze();
         info!("bank frozen {}", bank.slot());
+        if record_rewards {
+            Self::record_rewards(&bank, blocktree);
+        }
         slot_full_senders.iter().for_each(|sender| {
             if let Err(e) = sender.send((bank.slot(), *bank.collector_id())) {
                 trace!("{} slot_full alert failed: {:?}", my_pubkey, e);
@@ -1116,6 +1140,32 @@ impl ReplayStage {
         });
     }
 
+    /// Persists the rewards a frozen bank credited, for `getConfirmedBlock` and
+    /// `getInflationReward`
+    fn record_rewards(bank: &Bank, blocktree: &Blocktree) {
+        let rewards: RpcRewards = bank
+            .rewards
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(pubkey, reward_info)| RpcReward {
+                pubkey: pubkey.to_string(),
+                lamports: reward_info.lamports,
+                post_balance: reward_info.post_balance,
+                reward_type: Some(reward_info.reward_type),
+            })
+            .collect();
+        if !rewards.is_empty() {
+            if let Err(err) = blocktree.write_rewards(bank.slot(), rewards) {
+                warn!(
+                    "failed to record rewards for slot {}: {:?}",
+                    bank.slot(),
+                    err
+                );
+            }
+        }
+    }
+
     fn generate_new_bank_forks(
         blocktree: &Blocktree,
         forks_lock: &RwLock<BankForks>,
@@ -3,40 +3,59 @@
 use crate::{
     cluster_info::ClusterInfo,
     commitment::{BlockCommitment, BlockCommitmentCache},
+    consensus::VOTE_THRESHOLD_SIZE,
     contact_info::ContactInfo,
+    non_circulating_supply::calculate_non_circulating_supply,
     packet::PACKET_DATA_SIZE,
+    rpc_rate_limiter::RpcRateLimitConfig,
+    sample_performance_service::{PerfSamples, MAX_PERF_SAMPLES},
     storage_stage::StorageState,
     validator::ValidatorExit,
 };
 use bincode::serialize;
-use jsonrpc_core::{Error, Metadata, Result};
+use jsonrpc_core::{Error, ErrorCode, Metadata, Result};
 use jsonrpc_derive::rpc;
-use solana_client::rpc_request::{
-    Response, RpcConfirmedBlock, RpcContactInfo, RpcEpochInfo, RpcLeaderSchedule,
-    RpcResponseContext, RpcVersionInfo, RpcVoteAccountInfo, RpcVoteAccountStatus,
+use serde_json::json;
+use solana_client::{
+    rpc_filter::{RpcFilterType, MAX_FILTERS},
+    rpc_request::{
+        Response, RpcAccountBalance, RpcAccountInfoConfig, RpcBlockProduction,
+        RpcBlockProductionConfig, RpcBlockProductionRange, RpcConfirmedSignature,
+        RpcConfirmedTransaction, RpcContactInfo, RpcDataSliceConfig, RpcEncodedConfirmedBlock,
+        RpcEpochInfo, RpcInflationReward, RpcInflationRewardConfig, RpcLargestAccountsConfig,
+        RpcLargestAccountsFilter, RpcLeaderSchedule, RpcPerfSample, RpcProgramAccountsConfig,
+        RpcResponseContext, RpcSimulateTransactionConfig, RpcSimulateTransactionResult,
+        RpcStakeActivation, RpcStakeActivationConfig, RpcSupply, RpcVersionInfo,
+        RpcVoteAccountInfo, RpcVoteAccountStatus, StakeActivationState,
+    },
 };
+use solana_decoder::{UiAccount, UiEncoding};
 use solana_faucet::faucet::request_airdrop_transaction;
 use solana_ledger::{
     bank_forks::BankForks, blocktree::Blocktree, rooted_slot_iterator::RootedSlotIterator,
 };
-use solana_runtime::bank::Bank;
+use solana_runtime::{accounts::AccountAddressFilter, bank::Bank};
 use solana_sdk::{
     account::Account,
-    clock::{Slot, UnixTimestamp},
+    clock::{Epoch, Slot, UnixTimestamp},
     commitment_config::{CommitmentConfig, CommitmentLevel},
     epoch_schedule::EpochSchedule,
     fee_calculator::FeeCalculator,
     hash::Hash,
     inflation::Inflation,
     pubkey::Pubkey,
+    reward_type::RewardType,
     signature::Signature,
+    slot_history::Check,
+    sysvar::{self, slot_history::SlotHistory, stake_history::StakeHistory, Sysvar},
     timing::slot_duration_from_slots_per_year,
     transaction::{self, Transaction},
 };
+use solana_stake_program::stake_state::StakeState;
 use solana_vote_program::vote_state::{VoteState, MAX_LOCKOUT_HISTORY};
 use std::{
-    collections::HashMap,
-    net::{SocketAddr, UdpSocket},
+    collections::{HashMap, HashSet},
+    net::{IpAddr, SocketAddr, UdpSocket},
     sync::{Arc, RwLock},
     thread::sleep,
     time::{Duration, Instant},
@@ -44,6 +63,15 @@ use std::{
 
 type RpcResponse<T> = Result<Response<T>>;
 
+pub const MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE: u64 = 10_000;
+pub const MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_LIMIT: usize = 1_000;
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+pub const NUM_LARGEST_ACCOUNTS: usize = 20;
+pub const DEFAULT_HEALTH_CHECK_SLOT_DISTANCE: u64 = 150;
+
+// JSON-RPC server error returned by 'getHealth' when the node is not healthy
+const NODE_UNHEALTHY_ERROR_CODE: i64 = -32_005;
+
 fn new_response<T>(bank: &Bank, value: T) -> RpcResponse<T> {
     let context = RpcResponseContext { slot: bank.slot() };
     Ok(Response { context, value })
@@ -53,6 +81,11 @@ fn new_response<T>(bank: &Bank, value: T) -> RpcResponse<T> {
 pub struct JsonRpcConfig {
     pub enable_validator_exit: bool, // Enable the 'validatorExit' command
     pub faucet_addr: Option<SocketAddr>,
+    pub max_multiple_accounts: usize, // Maximum number of keys accepted by 'getMultipleAccounts'
+    pub non_circulating_accounts: HashSet<Pubkey>, // Accounts excluded from the circulating supply
+    pub trusted_validators: HashSet<Pubkey>, // Validators whose gossiped slots determine the node's health
+    pub health_check_slot_distance: u64, // Slots the node may lag the trusted validators while healthy
+    pub rate_limit_config: RpcRateLimitConfig, // Per-client request limits and method allow/deny lists
 }
 
 impl Default for JsonRpcConfig {
@@ -60,10 +93,59 @@ impl Default for JsonRpcConfig {
         Self {
             enable_validator_exit: false,
             faucet_addr: None,
+            max_multiple_accounts: MAX_MULTIPLE_ACCOUNTS,
+            non_circulating_accounts: HashSet::new(),
+            trusted_validators: HashSet::new(),
+            health_check_slot_distance: DEFAULT_HEALTH_CHECK_SLOT_DISTANCE,
+            rate_limit_config: RpcRateLimitConfig::default(),
         }
     }
 }
 
+/// The health of the node, as reported by 'getHealth' and the `/health` HTTP path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RpcHealthStatus {
+    Ok,
+    /// The node's latest slot trails the trusted validators by `num_slots`
+    Behind {
+        num_slots: Slot,
+    },
+    /// None of the trusted validators have advertised their slots yet
+    Unknown,
+}
+
+type LargestAccountsCacheKey = (Option<RpcLargestAccountsFilter>, CommitmentLevel);
+
+/// `getLargestAccounts` results, which are only recomputed once per epoch for each filter and
+/// commitment, along with the slot they were computed at
+#[derive(Default)]
+struct LargestAccountsCache {
+    accounts: HashMap<LargestAccountsCacheKey, (Epoch, Slot, Vec<RpcAccountBalance>)>,
+}
+
+impl LargestAccountsCache {
+    fn get(
+        &self,
+        epoch: Epoch,
+        key: &LargestAccountsCacheKey,
+    ) -> Option<(Slot, Vec<RpcAccountBalance>)> {
+        self.accounts
+            .get(key)
+            .filter(|(cached_epoch, _, _)| *cached_epoch == epoch)
+            .map(|(_, slot, accounts)| (*slot, accounts.clone()))
+    }
+
+    fn set(
+        &mut self,
+        epoch: Epoch,
+        slot: Slot,
+        key: LargestAccountsCacheKey,
+        accounts: Vec<RpcAccountBalance>,
+    ) {
+        self.accounts.insert(key, (epoch, slot, accounts));
+    }
+}
+
 #[derive(Clone)]
 pub struct JsonRpcRequestProcessor {
     bank_forks: Arc<RwLock<BankForks>>,
@@ -72,6 +154,8 @@ pub struct JsonRpcRequestProcessor {
     config: JsonRpcConfig,
     storage_state: StorageState,
     validator_exit: Arc<RwLock<Option<ValidatorExit>>>,
+    largest_accounts_cache: Arc<RwLock<LargestAccountsCache>>,
+    perf_samples: PerfSamples,
 }
 
 impl JsonRpcRequestProcessor {
@@ -96,6 +180,7 @@ impl JsonRpcRequestProcessor {
         blocktree: Arc<Blocktree>,
         storage_state: StorageState,
         validator_exit: Arc<RwLock<Option<ValidatorExit>>>,
+        perf_samples: PerfSamples,
     ) -> Self {
         JsonRpcRequestProcessor {
             config,
@@ -104,21 +189,78 @@ impl JsonRpcRequestProcessor {
             blocktree,
             storage_state,
             validator_exit,
+            perf_samples,
+            largest_accounts_cache: Arc::new(RwLock::new(LargestAccountsCache::default())),
         }
     }
 
     pub fn get_account_info(
         &self,
         pubkey: Result<Pubkey>,
-        commitment: Option<CommitmentConfig>,
-    ) -> RpcResponse<Option<Account>> {
-        let bank = &*self.bank(commitment);
+        config: RpcAccountInfoConfig,
+    ) -> RpcResponse<Option<UiAccount>> {
+        let bank = &*self.bank(config.commitment);
+        let encoding = config.encoding.unwrap_or_default();
         match pubkey {
-            Ok(key) => new_response(bank, bank.get_account(&key)),
+            Ok(key) => new_response(
+                bank,
+                bank.get_account(&key)
+                    .map(|account| UiAccount::encode(&key, account, encoding)),
+            ),
             Err(e) => Err(e),
         }
     }
 
+    pub fn get_multiple_accounts(
+        &self,
+        pubkeys: Vec<Pubkey>,
+        config: RpcAccountInfoConfig,
+    ) -> RpcResponse<Vec<Option<UiAccount>>> {
+        if pubkeys.len() > self.config.max_multiple_accounts {
+            return Err(Error::invalid_params(format!(
+                "Too many inputs provided; max {}",
+                self.config.max_multiple_accounts
+            )));
+        }
+        let bank = self.bank(config.commitment);
+        let encoding = config.encoding.unwrap_or_default();
+        let accounts = pubkeys
+            .iter()
+            .map(|pubkey| {
+                bank.get_account(pubkey)
+                    .map(|account| UiAccount::encode(pubkey, account, encoding))
+            })
+            .collect();
+        new_response(&bank, accounts)
+    }
+
+    pub fn simulate_transaction(
+        &self,
+        transaction: Transaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> RpcResponse<RpcSimulateTransactionResult> {
+        let bank = self.bank(config.commitment);
+        let verify_result = if config.sig_verify {
+            transaction.verify()
+        } else {
+            Ok(())
+        };
+        let (result, logs) = match verify_result {
+            Ok(()) => {
+                let (result, logs) = bank.simulate_transaction(transaction);
+                (result, Some(logs))
+            }
+            Err(err) => (Err(err), None),
+        };
+        new_response(
+            &bank,
+            RpcSimulateTransactionResult {
+                err: result.err(),
+                logs,
+            },
+        )
+    }
+
     pub fn get_minimum_balance_for_rent_exemption(
         &self,
         data_len: usize,
@@ -132,13 +274,25 @@ impl JsonRpcRequestProcessor {
     pub fn get_program_accounts(
         &self,
         program_id: &Pubkey,
-        commitment: Option<CommitmentConfig>,
-    ) -> Result<Vec<(String, Account)>> {
+        config: RpcProgramAccountsConfig,
+    ) -> Result<Vec<(String, UiAccount)>> {
+        let filters = config.filters.unwrap_or_default();
+        let data_slice = config.data_slice;
+        let encoding = config.encoding.unwrap_or_default();
         Ok(self
-            .bank(commitment)
+            .bank(config.commitment)
             .get_program_accounts(&program_id)
             .into_iter()
-            .map(|(pubkey, account)| (pubkey.to_string(), account))
+            .filter(|(_, account)| filters.iter().all(|filter| filter.allows(account)))
+            .map(|(pubkey, mut account)| {
+                if let Some(data_slice) = data_slice {
+                    slice_account_data(&mut account, &data_slice);
+                }
+                (
+                    pubkey.to_string(),
+                    UiAccount::encode(&pubkey, account, encoding),
+                )
+            })
             .collect())
     }
 
@@ -146,6 +300,66 @@ impl JsonRpcRequestProcessor {
         Ok(self.bank(commitment).inflation())
     }
 
+    pub fn get_inflation_reward(
+        &self,
+        addresses: Vec<Pubkey>,
+        config: RpcInflationRewardConfig,
+    ) -> Result<Vec<Option<RpcInflationReward>>> {
+        if addresses.len() > self.config.max_multiple_accounts {
+            return Err(Error::invalid_params(format!(
+                "Too many inputs provided; max {}",
+                self.config.max_multiple_accounts
+            )));
+        }
+        let bank = self.bank(config.commitment);
+        let epoch = match config.epoch {
+            Some(epoch) => epoch,
+            None => bank
+                .epoch()
+                .checked_sub(1)
+                .ok_or_else(|| Error::invalid_params("No completed epoch"))?,
+        };
+        if epoch >= bank.epoch() {
+            return Err(Error::invalid_params(format!(
+                "Epoch {} has not completed",
+                epoch
+            )));
+        }
+
+        // Rewards for an epoch are looked up in the first rooted block of the following epoch
+        let first_slot_in_next_epoch = bank.epoch_schedule().get_first_slot_in_epoch(epoch + 1);
+        let effective_slot = (first_slot_in_next_epoch..=bank.slot())
+            .find(|slot| self.blocktree.is_root(*slot))
+            .ok_or_else(|| {
+                Error::invalid_params(format!("Rewards for epoch {} are unavailable", epoch))
+            })?;
+        let rewards = self
+            .blocktree
+            .read_rewards(effective_slot)
+            .map_err(|_| Error::internal_error())?
+            .unwrap_or_default();
+
+        Ok(addresses
+            .iter()
+            .map(|address| {
+                let address = address.to_string();
+                rewards
+                    .iter()
+                    .find(|reward| {
+                        reward.pubkey == address
+                            && (reward.reward_type == Some(RewardType::Staking)
+                                || reward.reward_type == Some(RewardType::Voting))
+                    })
+                    .map(|reward| RpcInflationReward {
+                        epoch,
+                        effective_slot,
+                        amount: reward.lamports as u64,
+                        post_balance: reward.post_balance,
+                    })
+            })
+            .collect())
+    }
+
     pub fn get_epoch_schedule(&self) -> Result<EpochSchedule> {
         // Since epoch schedule data comes from the genesis config, any commitment level should be
         // fine
@@ -224,6 +438,299 @@ impl JsonRpcRequestProcessor {
         Ok(self.bank(commitment).capitalization())
     }
 
+    fn get_supply(&self, commitment: Option<CommitmentConfig>) -> RpcResponse<RpcSupply> {
+        let bank = self.bank(commitment);
+        let non_circulating_supply =
+            calculate_non_circulating_supply(&bank, &self.config.non_circulating_accounts);
+        let total_supply = bank.capitalization();
+        new_response(
+            &bank,
+            RpcSupply {
+                total: total_supply,
+                circulating: total_supply.saturating_sub(non_circulating_supply.lamports),
+                non_circulating: non_circulating_supply.lamports,
+                non_circulating_accounts: non_circulating_supply
+                    .accounts
+                    .iter()
+                    .map(|pubkey| pubkey.to_string())
+                    .collect(),
+            },
+        )
+    }
+
+    fn get_largest_accounts(
+        &self,
+        config: RpcLargestAccountsConfig,
+    ) -> RpcResponse<Vec<RpcAccountBalance>> {
+        let cache_key = (
+            config.filter,
+            config.commitment.clone().unwrap_or_default().commitment,
+        );
+        let bank = self.bank(config.commitment);
+        if let Some((slot, accounts)) = self
+            .largest_accounts_cache
+            .read()
+            .unwrap()
+            .get(bank.epoch(), &cache_key)
+        {
+            return Ok(Response {
+                context: RpcResponseContext { slot },
+                value: accounts,
+            });
+        }
+
+        let (addresses, address_filter) = match config.filter {
+            Some(filter) => {
+                let non_circulating_accounts =
+                    calculate_non_circulating_supply(&bank, &self.config.non_circulating_accounts)
+                        .accounts
+                        .into_iter()
+                        .collect();
+                let address_filter = match filter {
+                    RpcLargestAccountsFilter::Circulating => AccountAddressFilter::Exclude,
+                    RpcLargestAccountsFilter::NonCirculating => AccountAddressFilter::Include,
+                };
+                (non_circulating_accounts, address_filter)
+            }
+            None => (HashSet::new(), AccountAddressFilter::Exclude),
+        };
+        let accounts: Vec<_> = bank
+            .get_largest_accounts(NUM_LARGEST_ACCOUNTS, &addresses, address_filter)
+            .into_iter()
+            .map(|(address, lamports)| RpcAccountBalance {
+                address: address.to_string(),
+                lamports,
+            })
+            .collect();
+        self.largest_accounts_cache.write().unwrap().set(
+            bank.epoch(),
+            bank.slot(),
+            cache_key,
+            accounts.clone(),
+        );
+        new_response(&bank, accounts)
+    }
+
+    pub fn get_health(&self, cluster_info: &ClusterInfo) -> RpcHealthStatus {
+        // Without trusted validators there is nothing to compare against, so the node is always
+        // reported as healthy
+        if self.config.trusted_validators.is_empty() {
+            return RpcHealthStatus::Ok;
+        }
+
+        let latest_trusted_slot = self
+            .config
+            .trusted_validators
+            .iter()
+            .filter_map(|trusted_validator| {
+                cluster_info
+                    .get_epoch_state_for_node(trusted_validator, None)
+                    .map(|(epoch_slots, _)| {
+                        epoch_slots
+                            .slots
+                            .iter()
+                            .next_back()
+                            .map_or(epoch_slots.root, |slot| epoch_slots.root.max(*slot))
+                    })
+            })
+            .max();
+        let latest_trusted_slot = match latest_trusted_slot {
+            Some(slot) => slot,
+            None => return RpcHealthStatus::Unknown,
+        };
+
+        let root = self.bank_forks.read().unwrap().root();
+        let optimistic_slot = self
+            .block_commitment_cache
+            .read()
+            .unwrap()
+            .get_block_with_depth_commitment(0, VOTE_THRESHOLD_SIZE)
+            .unwrap_or(0);
+        let latest_slot = root.max(optimistic_slot);
+        if latest_slot.saturating_add(self.config.health_check_slot_distance) >= latest_trusted_slot
+        {
+            RpcHealthStatus::Ok
+        } else {
+            RpcHealthStatus::Behind {
+                num_slots: latest_trusted_slot - latest_slot,
+            }
+        }
+    }
+
+    fn get_stake_activation(
+        &self,
+        pubkey: &Pubkey,
+        config: RpcStakeActivationConfig,
+    ) -> Result<RpcStakeActivation> {
+        let bank = self.bank(config.commitment);
+        let epoch = config.epoch.unwrap_or_else(|| bank.epoch());
+        if epoch > bank.epoch() {
+            return Err(Error::invalid_params(format!(
+                "Invalid param: epoch {}; current epoch is {}",
+                epoch,
+                bank.epoch()
+            )));
+        }
+
+        let stake_account = bank
+            .get_account(pubkey)
+            .ok_or_else(|| Error::invalid_params("Invalid param: account not found".to_string()))?;
+        if stake_account.owner != solana_stake_program::id() {
+            return Err(Error::invalid_params(
+                "Invalid param: not a stake account".to_string(),
+            ));
+        }
+        let (meta, delegation) = match StakeState::from(&stake_account) {
+            Some(StakeState::Initialized(meta)) => (meta, None),
+            Some(StakeState::Stake(meta, stake)) => (meta, Some(stake.delegation)),
+            _ => {
+                return Err(Error::invalid_params(
+                    "Invalid param: stake account not initialized".to_string(),
+                ))
+            }
+        };
+
+        let (active, activating, deactivating) = match delegation {
+            Some(delegation) => {
+                let stake_history = bank
+                    .get_account(&sysvar::stake_history::id())
+                    .and_then(|account| StakeHistory::from_account(&account))
+                    .ok_or_else(Error::internal_error)?;
+                delegation.stake_activating_and_deactivating(epoch, Some(&stake_history))
+            }
+            None => (0, 0, 0),
+        };
+        let state = if deactivating > 0 {
+            StakeActivationState::Deactivating
+        } else if activating > 0 {
+            StakeActivationState::Activating
+        } else if active > 0 {
+            StakeActivationState::Active
+        } else {
+            StakeActivationState::Inactive
+        };
+        let inactive = stake_account
+            .lamports
+            .saturating_sub(meta.rent_exempt_reserve)
+            .saturating_sub(active);
+        Ok(RpcStakeActivation {
+            state,
+            active,
+            inactive,
+        })
+    }
+
+    fn get_block_production(
+        &self,
+        config: RpcBlockProductionConfig,
+    ) -> RpcResponse<RpcBlockProduction> {
+        let bank = self.bank(config.commitment);
+        let identity = config.identity.map(verify_pubkey).transpose()?;
+        let (first_slot, last_slot) = match config.range {
+            Some(range) => (
+                range.first_slot,
+                range.last_slot.unwrap_or_else(|| bank.slot()),
+            ),
+            None => (
+                bank.epoch_schedule().get_first_slot_in_epoch(bank.epoch()),
+                bank.slot(),
+            ),
+        };
+        if last_slot < first_slot {
+            return Err(Error::invalid_params(format!(
+                "lastSlot, {}, cannot be less than firstSlot, {}",
+                last_slot, first_slot
+            )));
+        }
+        if last_slot > bank.slot() {
+            return Err(Error::invalid_params(format!(
+                "lastSlot, {}, cannot be greater than the current slot, {}",
+                last_slot,
+                bank.slot()
+            )));
+        }
+
+        let slot_history = bank
+            .get_account(&sysvar::slot_history::id())
+            .and_then(|account| SlotHistory::from_account(&account))
+            .ok_or_else(Error::internal_error)?;
+        if slot_history.check(first_slot) == Check::TooOld {
+            return Err(Error::invalid_params(format!(
+                "firstSlot, {}, is older than the available slot history",
+                first_slot
+            )));
+        }
+
+        let epoch_schedule = bank.epoch_schedule();
+        let mut leader_schedule = None;
+        let mut by_identity: HashMap<Pubkey, (usize, usize)> = HashMap::new();
+        for slot in first_slot..=last_slot {
+            let (epoch, slot_index) = epoch_schedule.get_epoch_and_slot_index(slot);
+            let leader_schedule = match leader_schedule {
+                Some((schedule_epoch, ref leader_schedule)) if schedule_epoch == epoch => {
+                    leader_schedule
+                }
+                _ => {
+                    let schedule =
+                        solana_ledger::leader_schedule_utils::leader_schedule(epoch, &bank)
+                            .ok_or_else(|| {
+                                Error::invalid_params(format!(
+                                    "Invalid param: no leader schedule for epoch {}",
+                                    epoch
+                                ))
+                            })?;
+                    &leader_schedule.get_or_insert((epoch, schedule)).1
+                }
+            };
+            let leader = leader_schedule[slot_index];
+            if identity.map_or(false, |identity| identity != leader) {
+                continue;
+            }
+            let entry = by_identity.entry(leader).or_default();
+            entry.0 += 1;
+            if slot_history.check(slot) == Check::Found {
+                entry.1 += 1;
+            }
+        }
+
+        new_response(
+            &bank,
+            RpcBlockProduction {
+                by_identity: by_identity
+                    .into_iter()
+                    .map(|(pubkey, counts)| (pubkey.to_string(), counts))
+                    .collect(),
+                range: RpcBlockProductionRange {
+                    first_slot,
+                    last_slot,
+                },
+            },
+        )
+    }
+
+    fn get_recent_performance_samples(&self, limit: Option<usize>) -> Result<Vec<RpcPerfSample>> {
+        let limit = limit.unwrap_or(MAX_PERF_SAMPLES);
+        if limit > MAX_PERF_SAMPLES {
+            return Err(Error::invalid_params(format!(
+                "Invalid limit; max {}",
+                MAX_PERF_SAMPLES
+            )));
+        }
+        Ok(self
+            .perf_samples
+            .read()
+            .unwrap()
+            .iter()
+            .take(limit)
+            .map(|sample| RpcPerfSample {
+                slot: sample.slot,
+                num_transactions: sample.num_transactions,
+                num_slots: sample.num_slots,
+                sample_period_secs: sample.sample_period_secs,
+            })
+            .collect())
+    }
+
     fn get_vote_accounts(
         &self,
         commitment: Option<CommitmentConfig>,
@@ -312,8 +819,16 @@ impl JsonRpcRequestProcessor {
         }
     }
 
-    pub fn get_confirmed_block(&self, slot: Slot) -> Result<Option<RpcConfirmedBlock>> {
-        Ok(self.blocktree.get_confirmed_block(slot).ok())
+    pub fn get_confirmed_block(
+        &self,
+        slot: Slot,
+        encoding: Option<UiEncoding>,
+    ) -> Result<Option<RpcEncodedConfirmedBlock>> {
+        Ok(self
+            .blocktree
+            .get_confirmed_block(slot)
+            .ok()
+            .map(|block| block.encode(encoding.unwrap_or_default())))
     }
 
     pub fn get_confirmed_blocks(
@@ -339,6 +854,75 @@ impl JsonRpcRequestProcessor {
         }
     }
 
+    pub fn get_confirmed_signatures_for_address(
+        &self,
+        address: Pubkey,
+        start_slot: Slot,
+        end_slot: Slot,
+        limit: Option<usize>,
+        before: Option<Signature>,
+    ) -> Result<Vec<RpcConfirmedSignature>> {
+        if end_slot < start_slot {
+            return Ok(vec![]);
+        }
+        if end_slot - start_slot > MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE {
+            return Err(Error::invalid_params(format!(
+                "Slot range too large; max {}",
+                MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE
+            )));
+        }
+        let limit = limit.unwrap_or(MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_LIMIT);
+        if limit > MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_LIMIT {
+            return Err(Error::invalid_params(format!(
+                "Limit too large; max {}",
+                MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_LIMIT
+            )));
+        }
+
+        let signatures = self
+            .blocktree
+            .get_confirmed_signatures_for_address(address, start_slot, end_slot, before, limit)
+            .map_err(|err| {
+                info!("get_confirmed_signatures_for_address failed: {:?}", err);
+                Error::internal_error()
+            })?;
+        Ok(signatures
+            .into_iter()
+            .map(|(slot, signature)| {
+                let err = self
+                    .blocktree
+                    .get_transaction_status((slot, signature))
+                    .ok()
+                    .and_then(|status| status)
+                    .and_then(|status| status.status.err());
+                RpcConfirmedSignature {
+                    signature: signature.to_string(),
+                    slot,
+                    err,
+                }
+            })
+            .collect())
+    }
+
+    pub fn get_confirmed_transaction(
+        &self,
+        signature: Signature,
+    ) -> Result<Option<RpcConfirmedTransaction>> {
+        let confirmed_transaction = self
+            .blocktree
+            .get_confirmed_transaction(signature)
+            .map_err(|err| {
+                info!("get_confirmed_transaction failed: {:?}", err);
+                Error::internal_error()
+            })?;
+        Ok(confirmed_transaction.map(|mut confirmed_transaction| {
+            confirmed_transaction.block_time = self
+                .get_block_time(confirmed_transaction.slot)
+                .unwrap_or(None);
+            confirmed_transaction
+        }))
+    }
+
     pub fn get_block_time(&self, slot: Slot) -> Result<Option<UnixTimestamp>> {
         // This calculation currently assumes that bank.slots_per_year will remain unchanged after
         // genesis (ie. that this bank's slot_per_year will be applicable to any rooted slot being
@@ -368,11 +952,51 @@ fn verify_signature(input: &str) -> Result<Signature> {
     input.parse().map_err(|_e| Error::invalid_request())
 }
 
+// Memcmp bytes are decoded here, once, rather than for each account the filter is applied to
+fn verify_filter(input: &mut RpcFilterType) -> Result<()> {
+    input
+        .verify()
+        .map_err(|e| Error::invalid_params(format!("Invalid param: {}", e)))?;
+    if let RpcFilterType::Memcmp(memcmp) = input {
+        memcmp.decode();
+    }
+    Ok(())
+}
+
+fn deserialize_transaction(data: &[u8]) -> Result<Transaction> {
+    if data.len() >= PACKET_DATA_SIZE {
+        info!(
+            "transaction too large: {} bytes (max: {} bytes)",
+            data.len(),
+            PACKET_DATA_SIZE
+        );
+        return Err(Error::invalid_request());
+    }
+    bincode::config()
+        .limit(PACKET_DATA_SIZE as u64)
+        .deserialize(data)
+        .map_err(|err| {
+            info!("transaction deserialize error: {:?}", err);
+            Error::invalid_request()
+        })
+}
+
+fn slice_account_data(account: &mut Account, data_slice: &RpcDataSliceConfig) {
+    let data_len = account.data.len();
+    let start = data_slice.offset.min(data_len);
+    let end = data_slice
+        .offset
+        .saturating_add(data_slice.length)
+        .min(data_len);
+    account.data = account.data[start..end].to_vec();
+}
+
 #[derive(Clone)]
 pub struct Meta {
     pub request_processor: Arc<RwLock<JsonRpcRequestProcessor>>,
     pub cluster_info: Arc<RwLock<ClusterInfo>>,
     pub genesis_hash: Hash,
+    pub client_ip: Option<IpAddr>,
 }
 impl Metadata for Meta {}
 
@@ -393,16 +1017,24 @@ pub trait RpcSol {
         &self,
         meta: Self::Metadata,
         pubkey_str: String,
-        commitment: Option<CommitmentConfig>,
-    ) -> RpcResponse<Option<Account>>;
+        config: Option<RpcAccountInfoConfig>,
+    ) -> RpcResponse<Option<UiAccount>>;
+
+    #[rpc(meta, name = "getMultipleAccounts")]
+    fn get_multiple_accounts(
+        &self,
+        meta: Self::Metadata,
+        pubkey_strs: Vec<String>,
+        config: Option<RpcAccountInfoConfig>,
+    ) -> RpcResponse<Vec<Option<UiAccount>>>;
 
     #[rpc(meta, name = "getProgramAccounts")]
     fn get_program_accounts(
         &self,
         meta: Self::Metadata,
         program_id_str: String,
-        commitment: Option<CommitmentConfig>,
-    ) -> Result<Vec<(String, Account)>>;
+        config: Option<RpcProgramAccountsConfig>,
+    ) -> Result<Vec<(String, UiAccount)>>;
 
     #[rpc(meta, name = "getMinimumBalanceForRentExemption")]
     fn get_minimum_balance_for_rent_exemption(
@@ -419,6 +1051,14 @@ pub trait RpcSol {
         commitment: Option<CommitmentConfig>,
     ) -> Result<Inflation>;
 
+    #[rpc(meta, name = "getInflationReward")]
+    fn get_inflation_reward(
+        &self,
+        meta: Self::Metadata,
+        address_strs: Vec<String>,
+        config: Option<RpcInflationRewardConfig>,
+    ) -> Result<Vec<Option<RpcInflationReward>>>;
+
     #[rpc(meta, name = "getEpochSchedule")]
     fn get_epoch_schedule(&self, meta: Self::Metadata) -> Result<EpochSchedule>;
 
@@ -490,6 +1130,45 @@ pub trait RpcSol {
         commitment: Option<CommitmentConfig>,
     ) -> Result<u64>;
 
+    #[rpc(meta, name = "getSupply")]
+    fn get_supply(
+        &self,
+        meta: Self::Metadata,
+        commitment: Option<CommitmentConfig>,
+    ) -> RpcResponse<RpcSupply>;
+
+    #[rpc(meta, name = "getLargestAccounts")]
+    fn get_largest_accounts(
+        &self,
+        meta: Self::Metadata,
+        config: Option<RpcLargestAccountsConfig>,
+    ) -> RpcResponse<Vec<RpcAccountBalance>>;
+
+    #[rpc(meta, name = "getHealth")]
+    fn get_health(&self, meta: Self::Metadata) -> Result<String>;
+
+    #[rpc(meta, name = "getStakeActivation")]
+    fn get_stake_activation(
+        &self,
+        meta: Self::Metadata,
+        pubkey_str: String,
+        config: Option<RpcStakeActivationConfig>,
+    ) -> Result<RpcStakeActivation>;
+
+    #[rpc(meta, name = "getBlockProduction")]
+    fn get_block_production(
+        &self,
+        meta: Self::Metadata,
+        config: Option<RpcBlockProductionConfig>,
+    ) -> RpcResponse<RpcBlockProduction>;
+
+    #[rpc(meta, name = "getRecentPerformanceSamples")]
+    fn get_recent_performance_samples(
+        &self,
+        meta: Self::Metadata,
+        limit: Option<usize>,
+    ) -> Result<Vec<RpcPerfSample>>;
+
     #[rpc(meta, name = "requestAirdrop")]
     fn request_airdrop(
         &self,
@@ -502,6 +1181,14 @@ pub trait RpcSol {
     #[rpc(meta, name = "sendTransaction")]
     fn send_transaction(&self, meta: Self::Metadata, data: Vec<u8>) -> Result<String>;
 
+    #[rpc(meta, name = "simulateTransaction")]
+    fn simulate_transaction(
+        &self,
+        meta: Self::Metadata,
+        data: Vec<u8>,
+        config: Option<RpcSimulateTransactionConfig>,
+    ) -> RpcResponse<RpcSimulateTransactionResult>;
+
     #[rpc(meta, name = "getSlotLeader")]
     fn get_slot_leader(
         &self,
@@ -562,7 +1249,8 @@ pub trait RpcSol {
         &self,
         meta: Self::Metadata,
         slot: Slot,
-    ) -> Result<Option<RpcConfirmedBlock>>;
+        encoding: Option<UiEncoding>,
+    ) -> Result<Option<RpcEncodedConfirmedBlock>>;
 
     #[rpc(meta, name = "getBlockTime")]
     fn get_block_time(&self, meta: Self::Metadata, slot: Slot) -> Result<Option<UnixTimestamp>>;
@@ -574,6 +1262,24 @@ pub trait RpcSol {
         start_slot: Slot,
         end_slot: Option<Slot>,
     ) -> Result<Vec<Slot>>;
+
+    #[rpc(meta, name = "getConfirmedTransaction")]
+    fn get_confirmed_transaction(
+        &self,
+        meta: Self::Metadata,
+        signature_str: String,
+    ) -> Result<Option<RpcConfirmedTransaction>>;
+
+    #[rpc(meta, name = "getConfirmedSignaturesForAddress")]
+    fn get_confirmed_signatures_for_address(
+        &self,
+        meta: Self::Metadata,
+        pubkey_str: String,
+        start_slot: Slot,
+        end_slot: Slot,
+        limit: Option<usize>,
+        before: Option<String>,
+    ) -> Result<Vec<RpcConfirmedSignature>>;
 }
 
 pub struct RpcSolImpl;
@@ -598,14 +1304,34 @@ impl RpcSol for RpcSolImpl {
         &self,
         meta: Self::Metadata,
         pubkey_str: String,
-        commitment: Option<CommitmentConfig>,
-    ) -> RpcResponse<Option<Account>> {
+        config: Option<RpcAccountInfoConfig>,
+    ) -> RpcResponse<Option<UiAccount>> {
         debug!("get_account_info rpc request received: {:?}", pubkey_str);
         let pubkey = verify_pubkey(pubkey_str);
         meta.request_processor
             .read()
             .unwrap()
-            .get_account_info(pubkey, commitment)
+            .get_account_info(pubkey, config.unwrap_or_default())
+    }
+
+    fn get_multiple_accounts(
+        &self,
+        meta: Self::Metadata,
+        pubkey_strs: Vec<String>,
+        config: Option<RpcAccountInfoConfig>,
+    ) -> RpcResponse<Vec<Option<UiAccount>>> {
+        debug!(
+            "get_multiple_accounts rpc request received: {:?}",
+            pubkey_strs.len()
+        );
+        let pubkeys = pubkey_strs
+            .into_iter()
+            .map(verify_pubkey)
+            .collect::<Result<Vec<_>>>()?;
+        meta.request_processor
+            .read()
+            .unwrap()
+            .get_multiple_accounts(pubkeys, config.unwrap_or_default())
     }
 
     fn get_minimum_balance_for_rent_exemption(
@@ -628,17 +1354,29 @@ impl RpcSol for RpcSolImpl {
         &self,
         meta: Self::Metadata,
         program_id_str: String,
-        commitment: Option<CommitmentConfig>,
-    ) -> Result<Vec<(String, Account)>> {
+        config: Option<RpcProgramAccountsConfig>,
+    ) -> Result<Vec<(String, UiAccount)>> {
         debug!(
             "get_program_accounts rpc request received: {:?}",
             program_id_str
         );
         let program_id = verify_pubkey(program_id_str)?;
+        let mut config = config.unwrap_or_default();
+        if let Some(filters) = &mut config.filters {
+            if filters.len() > MAX_FILTERS {
+                return Err(Error::invalid_params(format!(
+                    "Too many filters provided; max {}",
+                    MAX_FILTERS
+                )));
+            }
+            for filter in filters.iter_mut() {
+                verify_filter(filter)?;
+            }
+        }
         meta.request_processor
             .read()
             .unwrap()
-            .get_program_accounts(&program_id, commitment)
+            .get_program_accounts(&program_id, config)
     }
 
     fn get_inflation(
@@ -655,6 +1393,26 @@ impl RpcSol for RpcSolImpl {
             .unwrap())
     }
 
+    fn get_inflation_reward(
+        &self,
+        meta: Self::Metadata,
+        address_strs: Vec<String>,
+        config: Option<RpcInflationRewardConfig>,
+    ) -> Result<Vec<Option<RpcInflationReward>>> {
+        debug!(
+            "get_inflation_reward rpc request received: {:?}",
+            address_strs.len()
+        );
+        let addresses = address_strs
+            .into_iter()
+            .map(verify_pubkey)
+            .collect::<Result<Vec<_>>>()?;
+        meta.request_processor
+            .read()
+            .unwrap()
+            .get_inflation_reward(addresses, config.unwrap_or_default())
+    }
+
     fn get_epoch_schedule(&self, meta: Self::Metadata) -> Result<EpochSchedule> {
         debug!("get_epoch_schedule rpc request received");
         Ok(meta
@@ -846,28 +1604,115 @@ impl RpcSol for RpcSolImpl {
             .get_total_supply(commitment)
     }
 
-    fn request_airdrop(
+    fn get_supply(
         &self,
         meta: Self::Metadata,
-        pubkey_str: String,
-        lamports: u64,
         commitment: Option<CommitmentConfig>,
-    ) -> Result<String> {
-        trace!(
-            "request_airdrop id={} lamports={} commitment: {:?}",
-            pubkey_str,
-            lamports,
-            &commitment
-        );
-
-        let faucet_addr = meta
-            .request_processor
+    ) -> RpcResponse<RpcSupply> {
+        debug!("get_supply rpc request received");
+        meta.request_processor
             .read()
             .unwrap()
-            .config
-            .faucet_addr
-            .ok_or_else(Error::invalid_request)?;
-        let pubkey = verify_pubkey(pubkey_str)?;
+            .get_supply(commitment)
+    }
+
+    fn get_largest_accounts(
+        &self,
+        meta: Self::Metadata,
+        config: Option<RpcLargestAccountsConfig>,
+    ) -> RpcResponse<Vec<RpcAccountBalance>> {
+        debug!("get_largest_accounts rpc request received");
+        meta.request_processor
+            .read()
+            .unwrap()
+            .get_largest_accounts(config.unwrap_or_default())
+    }
+
+    fn get_health(&self, meta: Self::Metadata) -> Result<String> {
+        debug!("get_health rpc request received");
+        let health = meta
+            .request_processor
+            .read()
+            .unwrap()
+            .get_health(&meta.cluster_info.read().unwrap());
+        match health {
+            RpcHealthStatus::Ok => Ok("ok".to_string()),
+            RpcHealthStatus::Behind { num_slots } => Err(Error {
+                code: ErrorCode::ServerError(NODE_UNHEALTHY_ERROR_CODE),
+                message: format!("Node is behind by {} slots", num_slots),
+                data: Some(json!({ "numSlotsBehind": num_slots })),
+            }),
+            RpcHealthStatus::Unknown => Err(Error {
+                code: ErrorCode::ServerError(NODE_UNHEALTHY_ERROR_CODE),
+                message: "Node health is unknown".to_string(),
+                data: Some(json!({ "numSlotsBehind": null })),
+            }),
+        }
+    }
+
+    fn get_stake_activation(
+        &self,
+        meta: Self::Metadata,
+        pubkey_str: String,
+        config: Option<RpcStakeActivationConfig>,
+    ) -> Result<RpcStakeActivation> {
+        debug!(
+            "get_stake_activation rpc request received: {:?}",
+            pubkey_str
+        );
+        let pubkey = verify_pubkey(pubkey_str)?;
+        meta.request_processor
+            .read()
+            .unwrap()
+            .get_stake_activation(&pubkey, config.unwrap_or_default())
+    }
+
+    fn get_block_production(
+        &self,
+        meta: Self::Metadata,
+        config: Option<RpcBlockProductionConfig>,
+    ) -> RpcResponse<RpcBlockProduction> {
+        debug!("get_block_production rpc request received");
+        meta.request_processor
+            .read()
+            .unwrap()
+            .get_block_production(config.unwrap_or_default())
+    }
+
+    fn get_recent_performance_samples(
+        &self,
+        meta: Self::Metadata,
+        limit: Option<usize>,
+    ) -> Result<Vec<RpcPerfSample>> {
+        debug!("get_recent_performance_samples rpc request received");
+        meta.request_processor
+            .read()
+            .unwrap()
+            .get_recent_performance_samples(limit)
+    }
+
+    fn request_airdrop(
+        &self,
+        meta: Self::Metadata,
+        pubkey_str: String,
+        lamports: u64,
+        commitment: Option<CommitmentConfig>,
+    ) -> Result<String> {
+        trace!(
+            "request_airdrop id={} lamports={} commitment: {:?}",
+            pubkey_str,
+            lamports,
+            &commitment
+        );
+
+        let faucet_addr = meta
+            .request_processor
+            .read()
+            .unwrap()
+            .config
+            .faucet_addr
+            .ok_or_else(Error::invalid_request)?;
+        let pubkey = verify_pubkey(pubkey_str)?;
 
         let blockhash = meta
             .request_processor
@@ -923,21 +1768,7 @@ impl RpcSol for RpcSolImpl {
     }
 
     fn send_transaction(&self, meta: Self::Metadata, data: Vec<u8>) -> Result<String> {
-        if data.len() >= PACKET_DATA_SIZE {
-            info!(
-                "send_transaction: transaction too large: {} bytes (max: {} bytes)",
-                data.len(),
-                PACKET_DATA_SIZE
-            );
-            return Err(Error::invalid_request());
-        }
-        let tx: Transaction = bincode::config()
-            .limit(PACKET_DATA_SIZE as u64)
-            .deserialize(&data)
-            .map_err(|err| {
-                info!("send_transaction: deserialize error: {:?}", err);
-                Error::invalid_request()
-            })?;
+        let tx = deserialize_transaction(&data)?;
 
         let transactions_socket = UdpSocket::bind("0.0.0.0:0").unwrap();
         let tpu_addr = get_tpu_addr(&meta.cluster_info)?;
@@ -957,6 +1788,23 @@ impl RpcSol for RpcSolImpl {
         Ok(signature)
     }
 
+    fn simulate_transaction(
+        &self,
+        meta: Self::Metadata,
+        data: Vec<u8>,
+        config: Option<RpcSimulateTransactionConfig>,
+    ) -> RpcResponse<RpcSimulateTransactionResult> {
+        let transaction = deserialize_transaction(&data)?;
+        debug!(
+            "simulate_transaction rpc request received: {:?}",
+            transaction.signatures.get(0)
+        );
+        meta.request_processor
+            .read()
+            .unwrap()
+            .simulate_transaction(transaction, config.unwrap_or_default())
+    }
+
     fn get_slot_leader(
         &self,
         meta: Self::Metadata,
@@ -1031,11 +1879,12 @@ impl RpcSol for RpcSolImpl {
         &self,
         meta: Self::Metadata,
         slot: Slot,
-    ) -> Result<Option<RpcConfirmedBlock>> {
+        encoding: Option<UiEncoding>,
+    ) -> Result<Option<RpcEncodedConfirmedBlock>> {
         meta.request_processor
             .read()
             .unwrap()
-            .get_confirmed_block(slot)
+            .get_confirmed_block(slot, encoding)
     }
 
     fn get_confirmed_blocks(
@@ -1053,6 +1902,45 @@ impl RpcSol for RpcSolImpl {
     fn get_block_time(&self, meta: Self::Metadata, slot: Slot) -> Result<Option<UnixTimestamp>> {
         meta.request_processor.read().unwrap().get_block_time(slot)
     }
+
+    fn get_confirmed_transaction(
+        &self,
+        meta: Self::Metadata,
+        signature_str: String,
+    ) -> Result<Option<RpcConfirmedTransaction>> {
+        debug!(
+            "get_confirmed_transaction rpc request received: {:?}",
+            signature_str
+        );
+        let signature = verify_signature(&signature_str)?;
+        meta.request_processor
+            .read()
+            .unwrap()
+            .get_confirmed_transaction(signature)
+    }
+
+    fn get_confirmed_signatures_for_address(
+        &self,
+        meta: Self::Metadata,
+        pubkey_str: String,
+        start_slot: Slot,
+        end_slot: Slot,
+        limit: Option<usize>,
+        before: Option<String>,
+    ) -> Result<Vec<RpcConfirmedSignature>> {
+        debug!(
+            "get_confirmed_signatures_for_address rpc request received: {:?}",
+            pubkey_str
+        );
+        let pubkey = verify_pubkey(pubkey_str)?;
+        let before = before
+            .map(|signature_str| verify_signature(&signature_str))
+            .transpose()?;
+        meta.request_processor
+            .read()
+            .unwrap()
+            .get_confirmed_signatures_for_address(pubkey, start_slot, end_slot, limit, before)
+    }
 }
 
 #[cfg(test)]
@@ -1060,20 +1948,27 @@ pub mod tests {
     use super::*;
     use crate::{
         contact_info::ContactInfo,
+        crds_value::{CrdsData, CrdsValue, EpochSlots},
         genesis_utils::{create_genesis_config, GenesisConfigInfo},
         replay_stage::tests::create_test_transactions_and_populate_blocktree,
+        sample_performance_service::PerfSample,
     };
     use jsonrpc_core::{MetaIoHandler, Output, Response, Value};
+    use solana_client::rpc_request::{RpcConfirmedBlock, RpcReward};
     use solana_ledger::{
         blocktree::entries_to_test_shreds, blocktree_processor::fill_blocktree_slot_with_ticks,
         entry::next_entry_mut, get_tmp_ledger_path,
     };
     use solana_sdk::{
+        account_utils::State,
         fee_calculator::DEFAULT_BURN_PERCENT,
         hash::{hash, Hash},
         instruction::InstructionError,
+        nonce_program,
+        nonce_state::{Meta as NonceMeta, NonceState},
         signature::{Keypair, KeypairUtil},
         system_transaction,
+        timing::timestamp,
         transaction::TransactionError,
     };
     use solana_vote_program::{
@@ -1201,6 +2096,14 @@ pub mod tests {
         let tx = system_transaction::transfer(&alice, &alice.pubkey(), 20, blockhash);
         let _ = bank.process_transaction(&tx);
 
+        let perf_samples = PerfSamples::default();
+        perf_samples.write().unwrap().push_front(PerfSample {
+            slot: bank.slot(),
+            num_transactions: 4,
+            num_slots: 2,
+            sample_period_secs: 60,
+        });
+
         let request_processor = Arc::new(RwLock::new(JsonRpcRequestProcessor::new(
             JsonRpcConfig::default(),
             bank_forks.clone(),
@@ -1208,6 +2111,7 @@ pub mod tests {
             blocktree,
             StorageState::default(),
             validator_exit,
+            perf_samples,
         )));
         let cluster_info = Arc::new(RwLock::new(ClusterInfo::new_with_invalid_keypair(
             ContactInfo::default(),
@@ -1228,6 +2132,7 @@ pub mod tests {
             request_processor,
             cluster_info,
             genesis_hash: Hash::default(),
+            client_ip: None,
         };
         RpcHandler {
             io,
@@ -1260,6 +2165,7 @@ pub mod tests {
             Arc::new(blocktree),
             StorageState::default(),
             validator_exit,
+            PerfSamples::default(),
         );
         thread::spawn(move || {
             let blockhash = bank.confirmed_last_blockhash().0;
@@ -1379,6 +2285,289 @@ pub mod tests {
         assert!(supply >= TEST_MINT_LAMPORTS);
     }
 
+    #[test]
+    fn test_get_supply() {
+        let bob_pubkey = Pubkey::new_rand();
+        let RpcHandler { io, meta, bank, .. } = start_rpc_handler_with_tx(&bob_pubkey);
+        let req = format!(r#"{{"jsonrpc":"2.0","id":1,"method":"getSupply"}}"#);
+        let res = io.handle_request_sync(&req, meta);
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let supply: RpcSupply = serde_json::from_value(json["result"]["value"].clone())
+            .expect("actual response deserialization");
+        assert_eq!(supply.non_circulating, 0);
+        assert!(supply.non_circulating_accounts.is_empty());
+        assert_eq!(supply.total, bank.capitalization());
+        assert_eq!(supply.circulating, supply.total);
+    }
+
+    #[test]
+    fn test_get_largest_accounts() {
+        let bob_pubkey = Pubkey::new_rand();
+        let RpcHandler { io, meta, .. } = start_rpc_handler_with_tx(&bob_pubkey);
+        let req = format!(r#"{{"jsonrpc":"2.0","id":1,"method":"getLargestAccounts"}}"#);
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let largest_accounts: Vec<RpcAccountBalance> =
+            serde_json::from_value(json["result"]["value"].clone())
+                .expect("actual response deserialization");
+        assert_eq!(largest_accounts.len(), NUM_LARGEST_ACCOUNTS);
+        assert!(largest_accounts
+            .windows(2)
+            .all(|pair| pair[0].lamports >= pair[1].lamports));
+
+        // Results are cached for the rest of the epoch
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let cached_accounts: Vec<RpcAccountBalance> =
+            serde_json::from_value(json["result"]["value"].clone()).unwrap();
+        assert_eq!(cached_accounts, largest_accounts);
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getLargestAccounts","params":[{{"filter":"circulating"}}]}}"#
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let circulating_accounts: Vec<RpcAccountBalance> =
+            serde_json::from_value(json["result"]["value"].clone()).unwrap();
+        assert_eq!(circulating_accounts, largest_accounts);
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getLargestAccounts","params":[{{"filter":"nonCirculating"}}]}}"#
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let non_circulating_accounts: Vec<RpcAccountBalance> =
+            serde_json::from_value(json["result"]["value"].clone()).unwrap();
+        assert!(non_circulating_accounts.is_empty());
+    }
+
+    #[test]
+    fn test_largest_accounts_cache() {
+        let mut cache = LargestAccountsCache::default();
+        let accounts = vec![RpcAccountBalance {
+            address: Pubkey::new_rand().to_string(),
+            lamports: 42,
+        }];
+        cache.set(1, 40, (None, CommitmentLevel::Max), accounts.clone());
+        assert_eq!(
+            cache.get(1, &(None, CommitmentLevel::Max)),
+            Some((40, accounts.clone()))
+        );
+        // Results are kept apart by commitment and filter, and expire with the epoch
+        assert_eq!(cache.get(1, &(None, CommitmentLevel::Recent)), None);
+        assert_eq!(
+            cache.get(
+                1,
+                &(
+                    Some(RpcLargestAccountsFilter::Circulating),
+                    CommitmentLevel::Max
+                )
+            ),
+            None
+        );
+        assert_eq!(cache.get(2, &(None, CommitmentLevel::Max)), None);
+
+        cache.set(1, 45, (None, CommitmentLevel::Recent), vec![]);
+        assert_eq!(
+            cache.get(1, &(None, CommitmentLevel::Recent)),
+            Some((45, vec![]))
+        );
+        assert_eq!(
+            cache.get(1, &(None, CommitmentLevel::Max)),
+            Some((40, accounts))
+        );
+    }
+
+    #[test]
+    fn test_get_health() {
+        let exit = Arc::new(AtomicBool::new(false));
+        let validator_exit = create_validator_exit(&exit);
+        let block_commitment_cache = Arc::new(RwLock::new(BlockCommitmentCache::default()));
+        let ledger_path = get_tmp_ledger_path!();
+        let blocktree = Blocktree::open(&ledger_path).unwrap();
+        let trusted_validator = Pubkey::new_rand();
+        let mut config = JsonRpcConfig::default();
+        config.trusted_validators.insert(trusted_validator);
+        let request_processor = JsonRpcRequestProcessor::new(
+            config,
+            new_bank_forks().0,
+            block_commitment_cache,
+            Arc::new(blocktree),
+            StorageState::default(),
+            validator_exit,
+            PerfSamples::default(),
+        );
+        let mut cluster_info = ClusterInfo::new_with_invalid_keypair(ContactInfo::default());
+
+        // Nothing has been heard from the trusted validator yet
+        assert_eq!(
+            request_processor.get_health(&cluster_info),
+            RpcHealthStatus::Unknown
+        );
+
+        let advertise_slot = |cluster_info: &mut ClusterInfo, slot| {
+            let epoch_slots = EpochSlots::new(
+                trusted_validator,
+                0,
+                0,
+                vec![slot].into_iter().collect(),
+                slot,
+            );
+            cluster_info
+                .gossip
+                .crds
+                .insert(
+                    CrdsValue::new_unsigned(CrdsData::EpochSlots(epoch_slots)),
+                    timestamp(),
+                )
+                .unwrap();
+        };
+        advertise_slot(&mut cluster_info, DEFAULT_HEALTH_CHECK_SLOT_DISTANCE);
+        assert_eq!(
+            request_processor.get_health(&cluster_info),
+            RpcHealthStatus::Ok
+        );
+        advertise_slot(&mut cluster_info, DEFAULT_HEALTH_CHECK_SLOT_DISTANCE + 1);
+        assert_eq!(
+            request_processor.get_health(&cluster_info),
+            RpcHealthStatus::Behind {
+                num_slots: DEFAULT_HEALTH_CHECK_SLOT_DISTANCE + 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_get_stake_activation() {
+        let bob_pubkey = Pubkey::new_rand();
+        let RpcHandler { io, meta, bank, .. } = start_rpc_handler_with_tx(&bob_pubkey);
+        let (stake_pubkey, stake_account) = bank
+            .get_program_accounts(&solana_stake_program::id())
+            .into_iter()
+            .find(|(_, account)| StakeState::stake_from(account).is_some())
+            .unwrap();
+        let stake = StakeState::stake_from(&stake_account).unwrap();
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getStakeActivation","params":["{}"]}}"#,
+            stake_pubkey
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let activation: RpcStakeActivation =
+            serde_json::from_value(json["result"].clone()).unwrap();
+        assert_eq!(activation.state, StakeActivationState::Active);
+        assert_eq!(activation.active, stake.delegation.stake);
+
+        // Epochs that have not begun yet are rejected
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getStakeActivation","params":["{}", {{"epoch":{}}}]}}"#,
+            stake_pubkey,
+            bank.epoch() + 1
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert!(json["error"].is_object());
+
+        // Accounts not owned by the stake program are rejected
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getStakeActivation","params":["{}"]}}"#,
+            bob_pubkey
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert!(json["error"].is_object());
+    }
+
+    #[test]
+    fn test_get_block_production() {
+        let bob_pubkey = Pubkey::new_rand();
+        let RpcHandler {
+            io,
+            meta,
+            leader_pubkey,
+            ..
+        } = start_rpc_handler_with_tx_and_blocktree(&bob_pubkey, vec![1, 2, 4], 0);
+
+        // Slots 1 and 3 were skipped
+        let req = format!(r#"{{"jsonrpc":"2.0","id":1,"method":"getBlockProduction"}}"#);
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let block_production: RpcBlockProduction =
+            serde_json::from_value(json["result"]["value"].clone())
+                .expect("actual response deserialization");
+        assert_eq!(
+            block_production.range,
+            RpcBlockProductionRange {
+                first_slot: 0,
+                last_slot: 4,
+            }
+        );
+        let mut expected_by_identity = HashMap::new();
+        expected_by_identity.insert(leader_pubkey.to_string(), (5, 3));
+        assert_eq!(block_production.by_identity, expected_by_identity);
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getBlockProduction","params":[{{"identity":"{}","range":{{"firstSlot":2}}}}]}}"#,
+            bob_pubkey
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let block_production: RpcBlockProduction =
+            serde_json::from_value(json["result"]["value"].clone()).unwrap();
+        assert_eq!(block_production.range.first_slot, 2);
+        assert!(block_production.by_identity.is_empty());
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getBlockProduction","params":[{{"range":{{"firstSlot":3,"lastSlot":2}}}}]}}"#
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert!(json["error"].is_object());
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getBlockProduction","params":[{{"range":{{"firstSlot":0,"lastSlot":5}}}}]}}"#
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert!(json["error"].is_object());
+    }
+
+    #[test]
+    fn test_get_recent_performance_samples() {
+        let bob_pubkey = Pubkey::new_rand();
+        let RpcHandler { io, meta, bank, .. } = start_rpc_handler_with_tx(&bob_pubkey);
+
+        let req = format!(r#"{{"jsonrpc":"2.0","id":1,"method":"getRecentPerformanceSamples"}}"#);
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let perf_samples: Vec<RpcPerfSample> = serde_json::from_value(json["result"].clone())
+            .expect("actual response deserialization");
+        assert_eq!(
+            perf_samples,
+            vec![RpcPerfSample {
+                slot: bank.slot(),
+                num_transactions: 4,
+                num_slots: 2,
+                sample_period_secs: 60,
+            }]
+        );
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getRecentPerformanceSamples","params":[0]}}"#
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(json["result"], json!([]));
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getRecentPerformanceSamples","params":[{}]}}"#,
+            MAX_PERF_SAMPLES + 1
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert!(json["error"].is_object());
+    }
+
     #[test]
     fn test_rpc_get_minimum_balance_for_rent_exemption() {
         let bob_pubkey = Pubkey::new_rand();
@@ -1432,6 +2621,71 @@ pub mod tests {
         assert_eq!(inflation, bank.inflation());
     }
 
+    #[test]
+    fn test_rpc_get_inflation_reward() {
+        let bob_pubkey = Pubkey::new_rand();
+        let RpcHandler {
+            io,
+            meta,
+            leader_vote_keypair,
+            ..
+        } = start_rpc_handler_with_tx_and_blocktree(&bob_pubkey, vec![TEST_SLOTS_PER_EPOCH + 1], 0);
+
+        let effective_slot = TEST_SLOTS_PER_EPOCH + 1;
+        let rewards = vec![
+            RpcReward {
+                pubkey: bob_pubkey.to_string(),
+                lamports: 42,
+                post_balance: 62,
+                reward_type: Some(RewardType::Staking),
+            },
+            RpcReward {
+                pubkey: leader_vote_keypair.pubkey().to_string(),
+                lamports: 7,
+                post_balance: 7,
+                reward_type: Some(RewardType::Fee),
+            },
+        ];
+        meta.request_processor
+            .read()
+            .unwrap()
+            .blocktree
+            .write_rewards(effective_slot, rewards)
+            .unwrap();
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getInflationReward","params":[["{}","{}"]]}}"#,
+            bob_pubkey,
+            leader_vote_keypair.pubkey()
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let inflation_rewards: Vec<Option<RpcInflationReward>> =
+            serde_json::from_value(json["result"].clone())
+                .expect("actual response deserialization");
+        assert_eq!(
+            inflation_rewards,
+            vec![
+                Some(RpcInflationReward {
+                    epoch: 0,
+                    effective_slot,
+                    amount: 42,
+                    post_balance: 62,
+                }),
+                None,
+            ]
+        );
+
+        // The current epoch has not completed yet
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getInflationReward","params":[["{}"],{{"epoch":1}}]}}"#,
+            bob_pubkey
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert!(json["error"].is_object());
+    }
+
     #[test]
     fn test_rpc_get_epoch_schedule() {
         let bob_pubkey = Pubkey::new_rand();
@@ -1513,13 +2767,13 @@ pub mod tests {
     #[test]
     fn test_rpc_get_account_info() {
         let bob_pubkey = Pubkey::new_rand();
-        let RpcHandler { io, meta, .. } = start_rpc_handler_with_tx(&bob_pubkey);
+        let RpcHandler { io, meta, bank, .. } = start_rpc_handler_with_tx(&bob_pubkey);
 
         let req = format!(
             r#"{{"jsonrpc":"2.0","id":1,"method":"getAccountInfo","params":["{}"]}}"#,
             bob_pubkey
         );
-        let res = io.handle_request_sync(&req, meta);
+        let res = io.handle_request_sync(&req, meta.clone());
         let expected = json!({
             "jsonrpc": "2.0",
             "result": {
@@ -1539,6 +2793,73 @@ pub mod tests {
         let result: Response = serde_json::from_str(&res.expect("actual response"))
             .expect("actual response deserialization");
         assert_eq!(expected, result);
+
+        let nonce_pubkey = Pubkey::new_rand();
+        let nonce_authority = Pubkey::new_rand();
+        let mut nonce_account = Account::new(42, NonceState::size(), &nonce_program::id());
+        nonce_account
+            .set_state(&NonceState::Initialized(
+                NonceMeta::new(&nonce_authority),
+                Hash::default(),
+            ))
+            .unwrap();
+        bank.store_account(&nonce_pubkey, &nonce_account);
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getAccountInfo","params":["{}", {{"encoding":"jsonParsed"}}]}}"#,
+            nonce_pubkey
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let value = &result["result"]["value"];
+        assert_eq!(value["owner"], json!(nonce_program::id().to_string()));
+        assert_eq!(value["data"]["program"], json!("nonce"));
+        assert_eq!(
+            value["data"]["parsed"],
+            json!({
+                "type": "initialized",
+                "info": {
+                    "authority": nonce_authority.to_string(),
+                    "blockhash": Hash::default().to_string(),
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_rpc_get_multiple_accounts() {
+        let bob_pubkey = Pubkey::new_rand();
+        let RpcHandler { io, meta, bank, .. } = start_rpc_handler_with_tx(&bob_pubkey);
+
+        let missing_pubkey = Pubkey::new_rand();
+        let req = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getMultipleAccounts",
+            "params": [[bob_pubkey.to_string(), missing_pubkey.to_string()]],
+        });
+        let res = io.handle_request_sync(&req.to_string(), meta.clone());
+        let result: serde_json::Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(result["result"]["context"]["slot"], json!(bank.slot()));
+        let accounts: Vec<Option<Account>> =
+            serde_json::from_value(result["result"]["value"].clone()).unwrap();
+        assert_eq!(accounts, vec![bank.get_account(&bob_pubkey), None]);
+
+        // Requests over the configured key limit are rejected
+        let pubkeys: Vec<_> = (0..=MAX_MULTIPLE_ACCOUNTS)
+            .map(|_| Pubkey::new_rand().to_string())
+            .collect();
+        let req = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getMultipleAccounts",
+            "params": [pubkeys],
+        });
+        let res = io.handle_request_sync(&req.to_string(), meta);
+        let result: serde_json::Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(result["error"]["code"], json!(-32602));
     }
 
     #[test]
@@ -1559,7 +2880,7 @@ pub mod tests {
             r#"{{"jsonrpc":"2.0","id":1,"method":"getProgramAccounts","params":["{}"]}}"#,
             new_program_id
         );
-        let res = io.handle_request_sync(&req, meta);
+        let res = io.handle_request_sync(&req, meta.clone());
         let expected = format!(
             r#"{{
                 "jsonrpc":"2.0",
@@ -1580,6 +2901,65 @@ pub mod tests {
         let result: Response = serde_json::from_str(&res.expect("actual response"))
             .expect("actual response deserialization");
         assert_eq!(expected, result);
+
+        // Add a program account with data to exercise filters and data slicing
+        let data_pubkey = Pubkey::new_rand();
+        let data_account = Account {
+            lamports: 42,
+            data: vec![1, 2, 3, 4, 5],
+            owner: new_program_id,
+            ..Account::default()
+        };
+        bank.store_account(&data_pubkey, &data_account);
+
+        let get_program_accounts = |config: serde_json::Value| {
+            let req = json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "getProgramAccounts",
+                "params": [new_program_id.to_string(), config],
+            });
+            let res = io.handle_request_sync(&req.to_string(), meta.clone());
+            let result: serde_json::Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            result
+        };
+
+        let result = get_program_accounts(json!({}));
+        assert_eq!(result["result"].as_array().unwrap().len(), 2);
+
+        let result = get_program_accounts(json!({ "filters": [{ "dataSize": 5 }] }));
+        let accounts: Vec<(String, Account)> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(
+            accounts,
+            vec![(data_pubkey.to_string(), data_account.clone())]
+        );
+
+        let result = get_program_accounts(json!({
+            "filters": [
+                { "dataSize": 5 },
+                { "memcmp": { "offset": 1, "bytes": bs58::encode([2, 3]).into_string() } },
+            ],
+            "dataSlice": { "offset": 3, "length": 10 },
+        }));
+        let accounts: Vec<(String, Account)> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].1.data, vec![4, 5]);
+
+        let result = get_program_accounts(json!({
+            "filters": [{ "memcmp": { "offset": 0, "bytes": bs58::encode([2]).into_string() } }],
+        }));
+        assert!(result["result"].as_array().unwrap().is_empty());
+
+        let result = get_program_accounts(json!({ "filters": vec![json!({ "dataSize": 5 }); 5] }));
+        assert_eq!(result["error"]["code"], json!(-32602));
+
+        let result = get_program_accounts(json!({
+            "filters": [{ "memcmp": { "offset": 0, "bytes": "0OIl" } }],
+        }));
+        assert_eq!(result["error"]["code"], json!(-32602));
     }
 
     #[test]
@@ -1614,6 +2994,63 @@ pub mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn test_rpc_simulate_transaction() {
+        let bob_pubkey = Pubkey::new_rand();
+        let RpcHandler {
+            io,
+            meta,
+            bank,
+            blockhash,
+            alice,
+            ..
+        } = start_rpc_handler_with_tx(&bob_pubkey);
+
+        let simulate_transaction = |tx: &Transaction, config: serde_json::Value| {
+            let req = json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "simulateTransaction",
+                "params": [serialize(tx).unwrap(), config],
+            });
+            let res = io.handle_request_sync(&req.to_string(), meta.clone());
+            let result: serde_json::Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            result
+        };
+
+        let balance = bank.get_balance(&bob_pubkey);
+        let tx = system_transaction::transfer(&alice, &bob_pubkey, 1234, blockhash);
+        let result = simulate_transaction(&tx, json!({ "sigVerify": true }));
+        assert_eq!(
+            result["result"]["value"],
+            json!({ "err": null, "logs": [] })
+        );
+        assert_eq!(bank.get_balance(&bob_pubkey), balance);
+
+        let tx = system_transaction::transfer(&alice, &bob_pubkey, std::u64::MAX, blockhash);
+        let result = simulate_transaction(&tx, json!({}));
+        let err: Option<TransactionError> =
+            serde_json::from_value(result["result"]["value"]["err"].clone()).unwrap();
+        assert_eq!(
+            err,
+            Some(TransactionError::InstructionError(
+                0,
+                InstructionError::new_result_with_negative_lamports(),
+            ))
+        );
+
+        // Signatures are only checked when requested
+        let mut tx = system_transaction::transfer(&alice, &bob_pubkey, 1234, blockhash);
+        tx.signatures[0] = Signature::default();
+        let result = simulate_transaction(&tx, json!({ "sigVerify": false }));
+        assert_eq!(result["result"]["value"]["err"], json!(null));
+        let result = simulate_transaction(&tx, json!({ "sigVerify": true }));
+        let err: Option<TransactionError> =
+            serde_json::from_value(result["result"]["value"]["err"].clone()).unwrap();
+        assert_eq!(err, Some(TransactionError::SignatureFailure));
+    }
+
     #[test]
     fn test_rpc_get_signature_status() {
         let bob_pubkey = Pubkey::new_rand();
@@ -1757,6 +3194,7 @@ pub mod tests {
                     Arc::new(blocktree),
                     StorageState::default(),
                     validator_exit,
+                    PerfSamples::default(),
                 );
                 Arc::new(RwLock::new(request_processor))
             },
@@ -1764,6 +3202,7 @@ pub mod tests {
                 ContactInfo::default(),
             ))),
             genesis_hash: Hash::default(),
+            client_ip: None,
         };
 
         let req =
@@ -1855,6 +3294,7 @@ pub mod tests {
             Arc::new(blocktree),
             StorageState::default(),
             validator_exit,
+            PerfSamples::default(),
         );
         assert_eq!(request_processor.validator_exit(), Ok(false));
         assert_eq!(exit.load(Ordering::Relaxed), false);
@@ -1876,6 +3316,7 @@ pub mod tests {
             Arc::new(blocktree),
             StorageState::default(),
             validator_exit,
+            PerfSamples::default(),
         );
         assert_eq!(request_processor.validator_exit(), Ok(true));
         assert_eq!(exit.load(Ordering::Relaxed), true);
@@ -1929,6 +3370,7 @@ pub mod tests {
             Arc::new(blocktree),
             StorageState::default(),
             validator_exit,
+            PerfSamples::default(),
         );
         assert_eq!(
             request_processor.get_block_commitment(0),
@@ -2008,7 +3450,7 @@ pub mod tests {
 
         let req =
             format!(r#"{{"jsonrpc":"2.0","id":1,"method":"getConfirmedBlock","params":[0]}}"#);
-        let res = io.handle_request_sync(&req, meta);
+        let res = io.handle_request_sync(&req, meta.clone());
         let result: Value = serde_json::from_str(&res.expect("actual response"))
             .expect("actual response deserialization");
         let confirmed_block: Option<RpcConfirmedBlock> =
@@ -2032,6 +3474,23 @@ pub mod tests {
                 assert_eq!(result, None);
             }
         }
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getConfirmedBlock","params":[0, "jsonParsed"]}}"#
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let transactions = result["result"]["transactions"].as_array().unwrap();
+        assert_eq!(transactions.len(), 3);
+        for transaction in transactions {
+            let message = &transaction[0]["message"];
+            assert_eq!(message["instructions"][0]["program"], json!("system"));
+            assert_eq!(
+                message["instructions"][0]["parsed"]["type"],
+                json!("transfer")
+            );
+        }
     }
 
     #[test]
@@ -2082,6 +3541,119 @@ pub mod tests {
         assert_eq!(confirmed_blocks, Vec::<Slot>::new());
     }
 
+    #[test]
+    fn test_get_confirmed_signatures_for_address() {
+        let bob_pubkey = Pubkey::new_rand();
+        let RpcHandler {
+            io,
+            meta,
+            alice,
+            confirmed_block_signatures,
+            ..
+        } = start_rpc_handler_with_tx(&bob_pubkey);
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getConfirmedSignaturesForAddress","params":["{}", 0, 1]}}"#,
+            alice.pubkey()
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let signatures: Vec<RpcConfirmedSignature> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(
+            signatures,
+            vec![RpcConfirmedSignature {
+                signature: confirmed_block_signatures[0].to_string(),
+                slot: 0,
+                err: None,
+            }]
+        );
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getConfirmedSignaturesForAddress","params":["{}", 0, 1, 10, "{}"]}}"#,
+            alice.pubkey(),
+            confirmed_block_signatures[0]
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let signatures: Vec<RpcConfirmedSignature> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert!(signatures.is_empty());
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getConfirmedSignaturesForAddress","params":["{}", 0, {}]}}"#,
+            alice.pubkey(),
+            MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE + 1
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert!(result["error"].is_object());
+    }
+
+    #[test]
+    fn test_get_confirmed_transaction() {
+        let bob_pubkey = Pubkey::new_rand();
+        let RpcHandler {
+            io,
+            meta,
+            confirmed_block_signatures,
+            blockhash,
+            ..
+        } = start_rpc_handler_with_tx(&bob_pubkey);
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getConfirmedTransaction","params":["{}"]}}"#,
+            confirmed_block_signatures[0]
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let confirmed_transaction: Option<RpcConfirmedTransaction> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        let confirmed_transaction = confirmed_transaction.unwrap();
+        assert_eq!(confirmed_transaction.slot, 0);
+        assert_eq!(
+            confirmed_transaction.transaction.signatures[0],
+            confirmed_block_signatures[0]
+        );
+        assert_eq!(
+            confirmed_transaction.transaction.message.recent_blockhash,
+            blockhash
+        );
+        assert_eq!(confirmed_transaction.meta.unwrap().status, Ok(()));
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getConfirmedTransaction","params":["{}"]}}"#,
+            confirmed_block_signatures[1]
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let confirmed_transaction: Option<RpcConfirmedTransaction> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(
+            confirmed_transaction.unwrap().meta.unwrap().status,
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::CustomError(1)
+            ))
+        );
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getConfirmedTransaction","params":["{}"]}}"#,
+            Signature::default()
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let confirmed_transaction: Option<RpcConfirmedTransaction> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(confirmed_transaction, None);
+    }
+
     #[test]
     fn test_get_block_time() {
         let bob_pubkey = Pubkey::new_rand();
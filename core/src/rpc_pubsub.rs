@@ -1,16 +1,70 @@
 //! The `pubsub` module implements a threaded subscription service on client RPC request
 
-use crate::rpc_subscriptions::{Confirmations, RpcSubscriptions, SlotInfo};
-use jsonrpc_core::{Error, ErrorCode, Result};
+use crate::rpc_subscriptions::{Confirmations, LogsSubscriptionKey, RpcSubscriptions};
+use jsonrpc_core::{Error, ErrorCode, Metadata, Result};
 use jsonrpc_derive::rpc;
 use jsonrpc_pubsub::typed::Subscriber;
-use jsonrpc_pubsub::{Session, SubscriptionId};
+use jsonrpc_pubsub::{PubSubMetadata, Session, SubscriptionId};
+use solana_client::rpc_request::{
+    RpcBlockSubscribeFilter, RpcBlockUpdate, RpcLogsResponse, RpcTransactionLogsFilter, RpcVote,
+    SlotInfo,
+};
 use solana_sdk::account::Account;
+use solana_sdk::clock::Slot;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Signature;
 use solana_sdk::transaction;
 use std::sync::{atomic, Arc};
 
+pub const MAX_ACTIVE_SUBSCRIPTIONS: usize = 1_000_000;
+pub const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = 1_000;
+pub const QUEUE_CAPACITY_ITEMS: usize = 10_000;
+
+// JSON-RPC server error returned when a subscription would exceed the configured limits
+const SUBSCRIPTION_LIMIT_ERROR_CODE: i64 = -32_001;
+
+#[derive(Debug, Clone)]
+pub struct PubSubConfig {
+    pub max_active_subscriptions: usize, // Maximum subscriptions across all connections
+    pub max_subscriptions_per_connection: usize,
+    pub queue_capacity_items: usize, // Maximum notifications queued for a single connection
+}
+
+impl Default for PubSubConfig {
+    fn default() -> Self {
+        Self {
+            max_active_subscriptions: MAX_ACTIVE_SUBSCRIPTIONS,
+            max_subscriptions_per_connection: MAX_SUBSCRIPTIONS_PER_CONNECTION,
+            queue_capacity_items: QUEUE_CAPACITY_ITEMS,
+        }
+    }
+}
+
+/// Metadata of a pubsub connection: its session, and the id the server assigned the connection
+/// when it was opened, which subscription limits are counted against
+#[derive(Clone)]
+pub struct PubSubSession {
+    session: Arc<Session>,
+    connection_id: u64,
+}
+
+impl PubSubSession {
+    pub fn new(session: Arc<Session>, connection_id: u64) -> Self {
+        Self {
+            session,
+            connection_id,
+        }
+    }
+}
+
+impl Metadata for PubSubSession {}
+
+impl PubSubMetadata for PubSubSession {
+    fn session(&self) -> Option<Arc<Session>> {
+        Some(self.session.clone())
+    }
+}
+
 // Suppress needless_return due to
 //   https://github.com/paritytech/jsonrpc/blob/2d38e6424d8461cdf72e78425ce67d51af9c6586/derive/src/lib.rs#L204
 // Once https://github.com/paritytech/jsonrpc/issues/418 is resolved, try to remove this clippy allow
@@ -88,6 +142,25 @@ pub trait RpcSolPubSub {
     )]
     fn signature_unsubscribe(&self, _: Option<Self::Metadata>, _: SubscriptionId) -> Result<bool>;
 
+    // Get notification with the log messages of each transaction that matches the filter
+    // Accepts "all", "allWithVotes" or { "mentions": [pubkey] } with a base-58 encoded pubkey
+    #[pubsub(subscription = "logsNotification", subscribe, name = "logsSubscribe")]
+    fn logs_subscribe(
+        &self,
+        _: Self::Metadata,
+        _: Subscriber<RpcLogsResponse>,
+        _: RpcTransactionLogsFilter,
+        _: Option<Confirmations>,
+    );
+
+    // Unsubscribe from logs notification subscription.
+    #[pubsub(
+        subscription = "logsNotification",
+        unsubscribe,
+        name = "logsUnsubscribe"
+    )]
+    fn logs_unsubscribe(&self, _: Option<Self::Metadata>, _: SubscriptionId) -> Result<bool>;
+
     // Get notification when slot is encountered
     #[pubsub(subscription = "slotNotification", subscribe, name = "slotSubscribe")]
     fn slot_subscribe(&self, _: Self::Metadata, _: Subscriber<SlotInfo>);
@@ -99,18 +172,120 @@ pub trait RpcSolPubSub {
         name = "slotUnsubscribe"
     )]
     fn slot_unsubscribe(&self, _: Option<Self::Metadata>, _: SubscriptionId) -> Result<bool>;
+
+    // Get notification when a new root is set
+    #[pubsub(subscription = "rootNotification", subscribe, name = "rootSubscribe")]
+    fn root_subscribe(&self, _: Self::Metadata, _: Subscriber<Slot>);
+
+    // Unsubscribe from root notification subscription.
+    #[pubsub(
+        subscription = "rootNotification",
+        unsubscribe,
+        name = "rootUnsubscribe"
+    )]
+    fn root_unsubscribe(&self, _: Option<Self::Metadata>, _: SubscriptionId) -> Result<bool>;
+
+    // Get notification when a vote is observed in gossip
+    #[pubsub(subscription = "voteNotification", subscribe, name = "voteSubscribe")]
+    fn vote_subscribe(&self, _: Self::Metadata, _: Subscriber<RpcVote>);
+
+    // Unsubscribe from vote notification subscription.
+    #[pubsub(
+        subscription = "voteNotification",
+        unsubscribe,
+        name = "voteUnsubscribe"
+    )]
+    fn vote_unsubscribe(&self, _: Option<Self::Metadata>, _: SubscriptionId) -> Result<bool>;
+
+    // Get notification with the full block each time a slot is rooted
+    // Accepts an optional filter, "all" or { "mentionsAccountOrProgram": pubkey } with a base-58
+    // encoded pubkey
+    #[pubsub(subscription = "blockNotification", subscribe, name = "blockSubscribe")]
+    fn block_subscribe(
+        &self,
+        _: Self::Metadata,
+        _: Subscriber<RpcBlockUpdate>,
+        _: Option<RpcBlockSubscribeFilter>,
+    );
+
+    // Unsubscribe from block notification subscription.
+    #[pubsub(
+        subscription = "blockNotification",
+        unsubscribe,
+        name = "blockUnsubscribe"
+    )]
+    fn block_unsubscribe(&self, _: Option<Self::Metadata>, _: SubscriptionId) -> Result<bool>;
 }
 
 #[derive(Default)]
 pub struct RpcSolPubSubImpl {
     uid: Arc<atomic::AtomicUsize>,
     subscriptions: Arc<RpcSubscriptions>,
+    config: PubSubConfig,
 }
 
 impl RpcSolPubSubImpl {
-    pub fn new(subscriptions: Arc<RpcSubscriptions>) -> Self {
+    pub fn new(subscriptions: Arc<RpcSubscriptions>, config: PubSubConfig) -> Self {
         let uid = Arc::new(atomic::AtomicUsize::default());
-        Self { uid, subscriptions }
+        Self {
+            uid,
+            subscriptions,
+            config,
+        }
+    }
+
+    // Assign an id to a new subscription made over `session`, unless the subscription limits
+    // have been reached
+    fn new_subscription_id(&self, session: &PubSubSession) -> Result<SubscriptionId> {
+        let connection = session.connection_id;
+        let mut counts = self.subscriptions.subscription_counts().lock().unwrap();
+        if counts.active() >= self.config.max_active_subscriptions {
+            inc_new_counter_info!("rpc-pubsub-rejected-subscriptions", 1);
+            return Err(Error {
+                code: ErrorCode::ServerError(SUBSCRIPTION_LIMIT_ERROR_CODE),
+                message: format!(
+                    "Too many active subscriptions, the limit is {}",
+                    self.config.max_active_subscriptions
+                ),
+                data: None,
+            });
+        }
+        if counts.per_connection(connection) >= self.config.max_subscriptions_per_connection {
+            inc_new_counter_info!("rpc-pubsub-rejected-subscriptions", 1);
+            return Err(Error {
+                code: ErrorCode::ServerError(SUBSCRIPTION_LIMIT_ERROR_CODE),
+                message: format!(
+                    "Too many subscriptions on this connection, the limit is {}",
+                    self.config.max_subscriptions_per_connection
+                ),
+                data: None,
+            });
+        }
+
+        let id = self.uid.fetch_add(1, atomic::Ordering::Relaxed);
+        let sub_id = SubscriptionId::Number(id as u64);
+        counts.add(sub_id.clone(), connection);
+        Ok(sub_id)
+    }
+
+    // Release the limits held by a subscription once it has been cancelled. Connections that
+    // close cancel each of their subscriptions, and subscriptions dropped for falling behind are
+    // released as they are dropped.
+    fn release_subscription(&self, id: &SubscriptionId, removed: bool) -> Result<bool> {
+        if removed {
+            self.subscriptions
+                .subscription_counts()
+                .lock()
+                .unwrap()
+                .release(id);
+            Ok(true)
+        } else {
+            Err(Error {
+                code: ErrorCode::InvalidParams,
+                message: "Invalid Request: Subscription id does not exist".into(),
+                data: None,
+            })
+        }
     }
 }
 
@@ -125,19 +300,21 @@ fn param<T: FromStr>(param_str: &str, thing: &str) -> Result<T> {
 }
 
 impl RpcSolPubSub for RpcSolPubSubImpl {
-    type Metadata = Arc<Session>;
+    type Metadata = PubSubSession;
 
     fn account_subscribe(
         &self,
-        _meta: Self::Metadata,
+        meta: Self::Metadata,
         subscriber: Subscriber<Account>,
         pubkey_str: String,
         confirmations: Option<Confirmations>,
     ) {
         match param::<Pubkey>(&pubkey_str, "pubkey") {
             Ok(pubkey) => {
-                let id = self.uid.fetch_add(1, atomic::Ordering::Relaxed);
-                let sub_id = SubscriptionId::Number(id as u64);
+                let sub_id = match self.new_subscription_id(&meta) {
+                    Ok(sub_id) => sub_id,
+                    Err(e) => return subscriber.reject(e).unwrap(),
+                };
                 info!("account_subscribe: account={:?} id={:?}", pubkey, sub_id);
                 let sink = subscriber.assign_id(sub_id.clone()).unwrap();
 
@@ -154,28 +331,23 @@ impl RpcSolPubSub for RpcSolPubSubImpl {
         id: SubscriptionId,
     ) -> Result<bool> {
         info!("account_unsubscribe: id={:?}", id);
-        if self.subscriptions.remove_account_subscription(&id) {
-            Ok(true)
-        } else {
-            Err(Error {
-                code: ErrorCode::InvalidParams,
-                message: "Invalid Request: Subscription id does not exist".into(),
-                data: None,
-            })
-        }
+        let removed = self.subscriptions.remove_account_subscription(&id);
+        self.release_subscription(&id, removed)
     }
 
     fn program_subscribe(
         &self,
-        _meta: Self::Metadata,
+        meta: Self::Metadata,
         subscriber: Subscriber<(String, Account)>,
         pubkey_str: String,
         confirmations: Option<Confirmations>,
     ) {
         match param::<Pubkey>(&pubkey_str, "pubkey") {
             Ok(pubkey) => {
-                let id = self.uid.fetch_add(1, atomic::Ordering::Relaxed);
-                let sub_id = SubscriptionId::Number(id as u64);
+                let sub_id = match self.new_subscription_id(&meta) {
+                    Ok(sub_id) => sub_id,
+                    Err(e) => return subscriber.reject(e).unwrap(),
+                };
                 info!("program_subscribe: account={:?} id={:?}", pubkey, sub_id);
                 let sink = subscriber.assign_id(sub_id.clone()).unwrap();
 
@@ -192,20 +364,13 @@ impl RpcSolPubSub for RpcSolPubSubImpl {
         id: SubscriptionId,
     ) -> Result<bool> {
         info!("program_unsubscribe: id={:?}", id);
-        if self.subscriptions.remove_program_subscription(&id) {
-            Ok(true)
-        } else {
-            Err(Error {
-                code: ErrorCode::InvalidParams,
-                message: "Invalid Request: Subscription id does not exist".into(),
-                data: None,
-            })
-        }
+        let removed = self.subscriptions.remove_program_subscription(&id);
+        self.release_subscription(&id, removed)
     }
 
     fn signature_subscribe(
         &self,
-        _meta: Self::Metadata,
+        meta: Self::Metadata,
         subscriber: Subscriber<transaction::Result<()>>,
         signature_str: String,
         confirmations: Option<Confirmations>,
@@ -213,8 +378,10 @@ impl RpcSolPubSub for RpcSolPubSubImpl {
         info!("signature_subscribe");
         match param::<Signature>(&signature_str, "signature") {
             Ok(signature) => {
-                let id = self.uid.fetch_add(1, atomic::Ordering::Relaxed);
-                let sub_id = SubscriptionId::Number(id as u64);
+                let sub_id = match self.new_subscription_id(&meta) {
+                    Ok(sub_id) => sub_id,
+                    Err(e) => return subscriber.reject(e).unwrap(),
+                };
                 info!(
                     "signature_subscribe: signature={:?} id={:?}",
                     signature, sub_id
@@ -238,21 +405,61 @@ impl RpcSolPubSub for RpcSolPubSubImpl {
         id: SubscriptionId,
     ) -> Result<bool> {
         info!("signature_unsubscribe");
-        if self.subscriptions.remove_signature_subscription(&id) {
-            Ok(true)
-        } else {
-            Err(Error {
-                code: ErrorCode::InvalidParams,
-                message: "Invalid Request: Subscription id does not exist".into(),
-                data: None,
-            })
+        let removed = self.subscriptions.remove_signature_subscription(&id);
+        self.release_subscription(&id, removed)
+    }
+
+    fn logs_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<RpcLogsResponse>,
+        filter: RpcTransactionLogsFilter,
+        confirmations: Option<Confirmations>,
+    ) {
+        info!("logs_subscribe");
+        let logs_key = match filter {
+            RpcTransactionLogsFilter::All => Ok(LogsSubscriptionKey::All),
+            RpcTransactionLogsFilter::AllWithVotes => Ok(LogsSubscriptionKey::AllWithVotes),
+            RpcTransactionLogsFilter::Mentions(addresses) => {
+                if addresses.len() == 1 {
+                    param::<Pubkey>(&addresses[0], "mentions").map(LogsSubscriptionKey::Account)
+                } else {
+                    Err(Error {
+                        code: ErrorCode::InvalidParams,
+                        message: "Invalid Request: Only 1 address supported".into(),
+                        data: None,
+                    })
+                }
+            }
+        };
+        match logs_key {
+            Ok(logs_key) => {
+                let sub_id = match self.new_subscription_id(&meta) {
+                    Ok(sub_id) => sub_id,
+                    Err(e) => return subscriber.reject(e).unwrap(),
+                };
+                info!("logs_subscribe: filter={:?} id={:?}", logs_key, sub_id);
+                let sink = subscriber.assign_id(sub_id.clone()).unwrap();
+
+                self.subscriptions
+                    .add_logs_subscription(&logs_key, confirmations, &sub_id, &sink)
+            }
+            Err(e) => subscriber.reject(e).unwrap(),
         }
     }
 
-    fn slot_subscribe(&self, _meta: Self::Metadata, subscriber: Subscriber<SlotInfo>) {
+    fn logs_unsubscribe(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+        info!("logs_unsubscribe: id={:?}", id);
+        let removed = self.subscriptions.remove_logs_subscription(&id);
+        self.release_subscription(&id, removed)
+    }
+
+    fn slot_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<SlotInfo>) {
         info!("slot_subscribe");
-        let id = self.uid.fetch_add(1, atomic::Ordering::Relaxed);
-        let sub_id = SubscriptionId::Number(id as u64);
+        let sub_id = match self.new_subscription_id(&meta) {
+            Ok(sub_id) => sub_id,
+            Err(e) => return subscriber.reject(e).unwrap(),
+        };
         info!("slot_subscribe: id={:?}", sub_id);
         let sink = subscriber.assign_id(sub_id.clone()).unwrap();
 
@@ -261,16 +468,80 @@ impl RpcSolPubSub for RpcSolPubSubImpl {
 
     fn slot_unsubscribe(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
         info!("slot_unsubscribe");
-        if self.subscriptions.remove_slot_subscription(&id) {
-            Ok(true)
-        } else {
-            Err(Error {
-                code: ErrorCode::InvalidParams,
-                message: "Invalid Request: Subscription id does not exist".into(),
-                data: None,
-            })
+        let removed = self.subscriptions.remove_slot_subscription(&id);
+        self.release_subscription(&id, removed)
+    }
+
+    fn root_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<Slot>) {
+        info!("root_subscribe");
+        let sub_id = match self.new_subscription_id(&meta) {
+            Ok(sub_id) => sub_id,
+            Err(e) => return subscriber.reject(e).unwrap(),
+        };
+        info!("root_subscribe: id={:?}", sub_id);
+        let sink = subscriber.assign_id(sub_id.clone()).unwrap();
+
+        self.subscriptions.add_root_subscription(&sub_id, &sink);
+    }
+
+    fn root_unsubscribe(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+        info!("root_unsubscribe");
+        let removed = self.subscriptions.remove_root_subscription(&id);
+        self.release_subscription(&id, removed)
+    }
+
+    fn vote_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<RpcVote>) {
+        info!("vote_subscribe");
+        let sub_id = match self.new_subscription_id(&meta) {
+            Ok(sub_id) => sub_id,
+            Err(e) => return subscriber.reject(e).unwrap(),
+        };
+        info!("vote_subscribe: id={:?}", sub_id);
+        let sink = subscriber.assign_id(sub_id.clone()).unwrap();
+
+        self.subscriptions.add_vote_subscription(&sub_id, &sink);
+    }
+
+    fn vote_unsubscribe(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+        info!("vote_unsubscribe");
+        let removed = self.subscriptions.remove_vote_subscription(&id);
+        self.release_subscription(&id, removed)
+    }
+
+    fn block_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<RpcBlockUpdate>,
+        filter: Option<RpcBlockSubscribeFilter>,
+    ) {
+        info!("block_subscribe");
+        let mentions = match filter.unwrap_or(RpcBlockSubscribeFilter::All) {
+            RpcBlockSubscribeFilter::All => Ok(None),
+            RpcBlockSubscribeFilter::MentionsAccountOrProgram(pubkey_str) => {
+                param::<Pubkey>(&pubkey_str, "mentionsAccountOrProgram").map(Some)
+            }
+        };
+        match mentions {
+            Ok(mentions) => {
+                let sub_id = match self.new_subscription_id(&meta) {
+                    Ok(sub_id) => sub_id,
+                    Err(e) => return subscriber.reject(e).unwrap(),
+                };
+                info!("block_subscribe: mentions={:?} id={:?}", mentions, sub_id);
+                let sink = subscriber.assign_id(sub_id.clone()).unwrap();
+
+                self.subscriptions
+                    .add_block_subscription(&sub_id, mentions, &sink)
+            }
+            Err(e) => subscriber.reject(e).unwrap(),
         }
     }
+
+    fn block_unsubscribe(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+        info!("block_unsubscribe");
+        let removed = self.subscriptions.remove_block_subscription(&id);
+        self.release_subscription(&id, removed)
+    }
 }
 
 #[cfg(test)]
@@ -309,8 +580,12 @@ mod tests {
         Ok(())
     }
 
-    fn create_session() -> Arc<Session> {
-        Arc::new(Session::new(mpsc::channel(1).0))
+    fn create_session() -> PubSubSession {
+        static NEXT_CONNECTION_ID: atomic::AtomicU64 = atomic::AtomicU64::new(0);
+        PubSubSession::new(
+            Arc::new(Session::new(mpsc::channel(1).0)),
+            NEXT_CONNECTION_ID.fetch_add(1, atomic::Ordering::Relaxed),
+        )
     }
 
     #[test]
@@ -628,6 +903,95 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_logs_subscribe() {
+        let bob_pubkey = Pubkey::new_rand();
+        let session = create_session();
+
+        let mut io = PubSubHandler::default();
+        let rpc = RpcSolPubSubImpl::default();
+        let subscriptions = rpc.subscriptions.clone();
+        io.extend_with(rpc.to_delegate());
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"logsSubscribe","params":[{{"mentions":["{}"]}}]}}"#,
+            bob_pubkey
+        );
+        let res = io.handle_request_sync(&req, session.clone());
+        let expected = r#"{"jsonrpc":"2.0","result":0,"id":1}"#;
+        let expected: Response = serde_json::from_str(&expected).unwrap();
+        let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(expected, result);
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"logsSubscribe","params":["all"]}"#;
+        let res = io.handle_request_sync(&req, session.clone());
+        let expected = r#"{"jsonrpc":"2.0","result":1,"id":1}"#;
+        let expected: Response = serde_json::from_str(&expected).unwrap();
+        let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(expected, result);
+
+        // Only a single address may be mentioned
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"logsSubscribe","params":[{{"mentions":["{}","{}"]}}]}}"#,
+            bob_pubkey,
+            Pubkey::new_rand()
+        );
+        let res = io.handle_request_sync(&req, session.clone());
+        let expected = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid Request: Only 1 address supported"},"id":1}"#;
+        let expected: Response = serde_json::from_str(&expected).unwrap();
+        let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(expected, result);
+
+        for id in 0..2 {
+            let req = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"logsUnsubscribe","params":[{}]}}"#,
+                id
+            );
+            let res = io.handle_request_sync(&req, session.clone());
+            let expected = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+            let expected: Response = serde_json::from_str(&expected).unwrap();
+            let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+            assert_eq!(expected, result);
+        }
+        assert!(!subscriptions.remove_logs_subscription(&SubscriptionId::Number(0)));
+    }
+
+    #[test]
+    fn test_root_and_vote_subscribe() {
+        let session = create_session();
+        let mut io = PubSubHandler::default();
+        let rpc = RpcSolPubSubImpl::default();
+        io.extend_with(rpc.to_delegate());
+
+        for (id, method) in ["rootSubscribe", "voteSubscribe"].iter().enumerate() {
+            let req = format!(r#"{{"jsonrpc":"2.0","id":1,"method":"{}"}}"#, method);
+            let res = io.handle_request_sync(&req, session.clone());
+            let expected = format!(r#"{{"jsonrpc":"2.0","result":{},"id":1}}"#, id);
+            let expected: Response = serde_json::from_str(&expected).unwrap();
+            let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+            assert_eq!(expected, result);
+        }
+
+        for (id, method) in ["rootUnsubscribe", "voteUnsubscribe"].iter().enumerate() {
+            let req = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"{}","params":[{}]}}"#,
+                method, id
+            );
+            let res = io.handle_request_sync(&req, session.clone());
+            let expected = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+            let expected: Response = serde_json::from_str(&expected).unwrap();
+            let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+            assert_eq!(expected, result);
+
+            // Each subscription can only be cancelled once
+            let res = io.handle_request_sync(&req, session.clone());
+            let expected = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid Request: Subscription id does not exist"},"id":1}"#;
+            let expected: Response = serde_json::from_str(&expected).unwrap();
+            let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+            assert_eq!(expected, result);
+        }
+    }
+
     #[test]
     fn test_slot_subscribe() {
         let rpc = RpcSolPubSubImpl::default();
@@ -689,4 +1053,68 @@ mod tests {
             .slot_unsubscribe(Some(session), SubscriptionId::Number(0))
             .is_ok());
     }
+
+    #[test]
+    fn test_subscription_limits() {
+        let mut io = PubSubHandler::default();
+        let rpc = RpcSolPubSubImpl::new(
+            Arc::new(RpcSubscriptions::default()),
+            PubSubConfig {
+                max_active_subscriptions: 2,
+                max_subscriptions_per_connection: 1,
+                ..PubSubConfig::default()
+            },
+        );
+        io.extend_with(rpc.to_delegate());
+        let session0 = create_session();
+        let session1 = create_session();
+        let session2 = create_session();
+        let subscribe = r#"{"jsonrpc":"2.0","id":1,"method":"slotSubscribe"}"#;
+        let handle = |req: &str, session: &PubSubSession| -> Response {
+            let res = io.handle_request_sync(req, session.clone());
+            serde_json::from_str(&res.unwrap()).unwrap()
+        };
+        let expected = |res: &str| -> Response { serde_json::from_str(res).unwrap() };
+
+        assert_eq!(
+            handle(subscribe, &session0),
+            expected(r#"{"jsonrpc":"2.0","result":0,"id":1}"#)
+        );
+        // Over the per-connection limit
+        assert_eq!(
+            handle(subscribe, &session0),
+            expected(
+                r#"{"jsonrpc":"2.0","error":{"code":-32001,"message":"Too many subscriptions on this connection, the limit is 1"},"id":1}"#
+            )
+        );
+        assert_eq!(
+            handle(subscribe, &session1),
+            expected(r#"{"jsonrpc":"2.0","result":1,"id":1}"#)
+        );
+        // Over the limit across all connections
+        assert_eq!(
+            handle(subscribe, &session2),
+            expected(
+                r#"{"jsonrpc":"2.0","error":{"code":-32001,"message":"Too many active subscriptions, the limit is 2"},"id":1}"#
+            )
+        );
+
+        // Cancelling a subscription frees up its share of both limits
+        let unsubscribe = r#"{"jsonrpc":"2.0","id":1,"method":"slotUnsubscribe","params":[0]}"#;
+        assert_eq!(
+            handle(unsubscribe, &session0),
+            expected(r#"{"jsonrpc":"2.0","result":true,"id":1}"#)
+        );
+        assert_eq!(
+            handle(subscribe, &session2),
+            expected(r#"{"jsonrpc":"2.0","result":2,"id":1}"#)
+        );
+
+        // So does closing the connection
+        drop(session1);
+        assert_eq!(
+            handle(subscribe, &session0),
+            expected(r#"{"jsonrpc":"2.0","result":3,"id":1}"#)
+        );
+    }
 }
@@ -1,7 +1,8 @@
 //! The `pubsub` module implements a threaded subscription service on client RPC request
 
-use crate::rpc_pubsub::{RpcSolPubSub, RpcSolPubSubImpl};
+use crate::rpc_pubsub::{PubSubConfig, PubSubSession, RpcSolPubSub, RpcSolPubSubImpl};
 use crate::rpc_subscriptions::RpcSubscriptions;
+use jsonrpc_core::futures::{sync::mpsc, Stream};
 use jsonrpc_pubsub::{PubSubHandler, Session};
 use jsonrpc_ws_server::{RequestContext, ServerBuilder};
 use std::net::SocketAddr;
@@ -18,10 +19,12 @@ impl PubSubService {
     pub fn new(
         subscriptions: &Arc<RpcSubscriptions>,
         pubsub_addr: SocketAddr,
+        pubsub_config: PubSubConfig,
         exit: &Arc<AtomicBool>,
     ) -> Self {
         info!("rpc_pubsub bound to {:?}", pubsub_addr);
-        let rpc = RpcSolPubSubImpl::new(subscriptions.clone());
+        let queue_capacity_items = pubsub_config.queue_capacity_items;
+        let rpc = RpcSolPubSubImpl::new(subscriptions.clone(), pubsub_config);
         let exit_ = exit.clone();
         let thread_hdl = Builder::new()
             .name("solana-pubsub".to_string())
@@ -29,13 +32,13 @@ impl PubSubService {
                 let mut io = PubSubHandler::default();
                 io.extend_with(rpc.to_delegate());
 
-                let server = ServerBuilder::with_meta_extractor(io, |context: &RequestContext| {
+                let server = ServerBuilder::with_meta_extractor(io, move |context: &RequestContext| {
                         info!("New pubsub connection");
-                        let session = Arc::new(Session::new(context.sender()));
+                        let session = Arc::new(Session::new(session_sender(context, queue_capacity_items)));
                         session.on_drop(|| {
                             info!("Pubsub connection dropped");
                         });
-                        session
+                        PubSubSession::new(session, context.session_id)
                 })
                 .start(&pubsub_addr);
 
@@ -61,6 +64,18 @@ impl PubSubService {
     }
 }
 
+// Like `RequestContext::sender`, but queues up to `capacity` notifications for the connection
+// before notifiers have to wait on it
+fn session_sender(context: &RequestContext, capacity: usize) -> mpsc::Sender<String> {
+    let out = context.out.clone();
+    let (sender, receiver) = mpsc::channel(capacity);
+    context.executor.spawn(receiver.for_each(move |message| {
+        out.send(message)
+            .map_err(|err| warn!("Error sending a subscription update: {:?}", err))
+    }));
+    sender
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -71,7 +86,8 @@ mod tests {
         let subscriptions = Arc::new(RpcSubscriptions::default());
         let pubsub_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 0);
         let exit = Arc::new(AtomicBool::new(false));
-        let pubsub_service = PubSubService::new(&subscriptions, pubsub_addr, &exit);
+        let pubsub_service =
+            PubSubService::new(&subscriptions, pubsub_addr, PubSubConfig::default(), &exit);
         let thread = pubsub_service.thread_hdl.thread();
         assert_eq!(thread.name().unwrap(), "solana-pubsub");
     }
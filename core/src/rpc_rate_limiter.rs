@@ -0,0 +1,367 @@
+//! The `rpc_rate_limiter` module implements a JSON RPC middleware that throttles clients with
+//! per-client token buckets and enforces the method allow and deny lists.
+
+use crate::rpc::Meta;
+use jsonrpc_core::{
+    futures::future::{self, Either},
+    middleware::{NoopCallFuture, NoopFuture},
+    Call, Error, ErrorCode, Middleware, Output,
+};
+use jsonrpc_http_server::hyper;
+use linked_hash_map::LinkedHashMap;
+use solana_metrics::inc_new_counter_info;
+use std::{
+    collections::HashSet,
+    net::{IpAddr, SocketAddr},
+    sync::Mutex,
+    time::Instant,
+};
+
+// JSON-RPC server error returned when a client exceeds its request rate
+const RATE_LIMIT_ERROR_CODE: i64 = -32_007;
+
+// The least recently seen client's buckets are dropped once this many are being tracked
+const MAX_TRACKED_BUCKETS: usize = 10_000;
+
+/// Methods that scan large portions of the ledger or the accounts, which are metered separately
+/// so they cannot starve cheaper requests
+const EXPENSIVE_METHODS: &[&str] = &[
+    "getConfirmedBlock",
+    "getConfirmedBlocks",
+    "getConfirmedSignaturesForAddress",
+    "getLargestAccounts",
+    "getMultipleAccounts",
+    "getProgramAccounts",
+    "getSupply",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RpcMethodClass {
+    Standard,
+    Expensive,
+}
+
+impl RpcMethodClass {
+    pub fn of(method: &str) -> Self {
+        if EXPENSIVE_METHODS.contains(&method) {
+            RpcMethodClass::Expensive
+        } else {
+            RpcMethodClass::Standard
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RpcRateLimitConfig {
+    /// Requests per second each client may make to standard methods, unlimited if `None`
+    pub requests_per_second: Option<u64>,
+
+    /// Requests per second each client may make to expensive methods, unlimited if `None`
+    pub expensive_requests_per_second: Option<u64>,
+
+    /// If set, only these methods are served
+    pub allowed_methods: Option<HashSet<String>>,
+
+    /// Methods that are never served
+    pub denied_methods: HashSet<String>,
+
+    /// Proxies whose forwarding headers identify the client, instead of the proxy itself
+    pub trusted_proxies: HashSet<IpAddr>,
+}
+
+impl RpcRateLimitConfig {
+    fn requests_per_second(&self, class: RpcMethodClass) -> Option<u64> {
+        match class {
+            RpcMethodClass::Standard => self.requests_per_second,
+            RpcMethodClass::Expensive => self.expensive_requests_per_second,
+        }
+    }
+
+    fn is_method_allowed(&self, method: &str) -> bool {
+        !self.denied_methods.contains(method)
+            && self
+                .allowed_methods
+                .as_ref()
+                .map_or(true, |allowed_methods| allowed_methods.contains(method))
+    }
+
+    /// Identifies the client by the peer address the RPC service attaches to each request.
+    /// Forwarding headers are only honored on connections from a trusted proxy, which appends
+    /// the address it sees to `x-forwarded-for`
+    pub fn client_ip(&self, request: &hyper::Request<hyper::Body>) -> Option<IpAddr> {
+        let peer_ip = request.extensions().get::<SocketAddr>()?.ip();
+        if !self.trusted_proxies.contains(&peer_ip) {
+            return Some(peer_ip);
+        }
+        let headers = request.headers();
+        let forwarded_ip = headers
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| {
+                value
+                    .rsplit(',')
+                    .filter_map(|ip| ip.trim().parse().ok())
+                    .find(|ip| !self.trusted_proxies.contains(ip))
+            })
+            .or_else(|| {
+                headers
+                    .get("x-real-ip")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.trim().parse().ok())
+            });
+        Some(forwarded_ip.unwrap_or(peer_ip))
+    }
+}
+
+/// Holds up to one second's worth of requests, refilled continuously at the configured rate
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: f64, now: Instant) -> Self {
+        Self {
+            tokens: capacity,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, rate: f64, now: Instant) {
+        let elapsed = now.duration_since(self.last_refill);
+        let elapsed_secs = elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) / 1e9;
+        self.tokens = (self.tokens + elapsed_secs * rate).min(rate);
+        self.last_refill = now;
+    }
+
+    fn try_take(&mut self) -> bool {
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+pub struct RpcRateLimiter {
+    config: RpcRateLimitConfig,
+    buckets: Mutex<LinkedHashMap<(Option<IpAddr>, RpcMethodClass), TokenBucket>>,
+}
+
+impl RpcRateLimiter {
+    pub fn new(config: RpcRateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(LinkedHashMap::new()),
+        }
+    }
+
+    pub fn check(&self, method: &str, client_ip: Option<IpAddr>) -> Result<(), Error> {
+        if !self.config.is_method_allowed(method) {
+            inc_new_counter_info!("rpc-rate-limiter-denied-method", 1);
+            return Err(Error::method_not_found());
+        }
+
+        let class = RpcMethodClass::of(method);
+        let rate = match self.config.requests_per_second(class) {
+            Some(rate) => rate as f64,
+            None => return Ok(()),
+        };
+        let now = Instant::now();
+        let key = (client_ip, class);
+        let mut buckets = self.buckets.lock().unwrap();
+        if !buckets.contains_key(&key) {
+            if buckets.len() >= MAX_TRACKED_BUCKETS {
+                buckets.pop_front();
+            }
+            buckets.insert(key, TokenBucket::new(rate, now));
+        }
+        // Moves the bucket to the back, so the least recently seen one is evicted first
+        let bucket = buckets.get_refresh(&key).unwrap();
+        bucket.refill(rate, now);
+        if bucket.try_take() {
+            Ok(())
+        } else {
+            inc_new_counter_info!("rpc-rate-limiter-throttled", 1);
+            Err(Error {
+                code: ErrorCode::ServerError(RATE_LIMIT_ERROR_CODE),
+                message: format!("Too many requests for {}, try again later", method),
+                data: None,
+            })
+        }
+    }
+}
+
+impl Middleware<Meta> for RpcRateLimiter {
+    type Future = NoopFuture;
+    type CallFuture = NoopCallFuture;
+
+    fn on_call<F, X>(&self, call: Call, meta: Meta, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, Meta) -> X + Send + Sync,
+        X: future::Future<Item = Option<Output>, Error = ()> + Send + 'static,
+    {
+        let result = match &call {
+            Call::MethodCall(method_call) => self
+                .check(&method_call.method, meta.client_ip)
+                .map_err(|err| {
+                    Some(Output::from(
+                        Err(err),
+                        method_call.id.clone(),
+                        method_call.jsonrpc,
+                    ))
+                }),
+            Call::Notification(notification) => self
+                .check(&notification.method, meta.client_ip)
+                .map_err(|_| None),
+            Call::Invalid { .. } => Ok(()),
+        };
+        match result {
+            Ok(()) => Either::B(next(call, meta)),
+            Err(output) => Either::A(Box::new(future::ok(output))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_method_lists() {
+        let limiter = RpcRateLimiter::new(RpcRateLimitConfig {
+            denied_methods: vec!["getProgramAccounts".to_string()].into_iter().collect(),
+            ..RpcRateLimitConfig::default()
+        });
+        assert!(limiter.check("getBalance", None).is_ok());
+        assert_eq!(
+            limiter.check("getProgramAccounts", None).unwrap_err().code,
+            ErrorCode::MethodNotFound
+        );
+
+        let limiter = RpcRateLimiter::new(RpcRateLimitConfig {
+            allowed_methods: Some(vec!["getBalance".to_string()].into_iter().collect()),
+            ..RpcRateLimitConfig::default()
+        });
+        assert!(limiter.check("getBalance", None).is_ok());
+        assert!(limiter.check("getSlot", None).is_err());
+    }
+
+    #[test]
+    fn test_rate_limits() {
+        let limiter = RpcRateLimiter::new(RpcRateLimitConfig {
+            requests_per_second: Some(2),
+            expensive_requests_per_second: Some(1),
+            ..RpcRateLimitConfig::default()
+        });
+        let client = Some("127.0.0.1".parse().unwrap());
+        let other_client = Some("127.0.0.2".parse().unwrap());
+
+        assert!(limiter.check("getProgramAccounts", client).is_ok());
+        assert_eq!(
+            limiter
+                .check("getProgramAccounts", client)
+                .unwrap_err()
+                .code,
+            ErrorCode::ServerError(RATE_LIMIT_ERROR_CODE)
+        );
+
+        // Method classes and clients are metered independently
+        assert!(limiter.check("getBalance", client).is_ok());
+        assert!(limiter.check("getBalance", client).is_ok());
+        assert!(limiter.check("getBalance", client).is_err());
+        assert!(limiter.check("getProgramAccounts", other_client).is_ok());
+    }
+
+    #[test]
+    fn test_token_bucket_refill() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(2.0, now);
+        assert!(bucket.try_take());
+        assert!(bucket.try_take());
+        assert!(!bucket.try_take());
+
+        bucket.refill(2.0, now + Duration::from_millis(500));
+        assert!(bucket.try_take());
+        assert!(!bucket.try_take());
+
+        // Refills never exceed one second's worth of requests
+        bucket.refill(2.0, now + Duration::from_secs(60));
+        assert_eq!(bucket.tokens, 2.0);
+    }
+
+    #[test]
+    fn test_bucket_eviction() {
+        let limiter = RpcRateLimiter::new(RpcRateLimitConfig {
+            requests_per_second: Some(1),
+            ..RpcRateLimitConfig::default()
+        });
+        let client = Some("127.0.0.1".parse().unwrap());
+        assert!(limiter.check("getBalance", client).is_ok());
+        assert!(limiter.check("getBalance", client).is_err());
+
+        // The throttled client is seen again last, so others are evicted before it
+        for i in 0..MAX_TRACKED_BUCKETS as u32 - 1 {
+            let other_client = Some(IpAddr::from(std::net::Ipv4Addr::from(i + (10 << 24))));
+            assert!(limiter.check("getBalance", other_client).is_ok());
+        }
+        assert!(limiter.check("getBalance", client).is_err());
+        let other_client = Some("192.168.0.1".parse().unwrap());
+        assert!(limiter.check("getBalance", other_client).is_ok());
+        assert!(limiter.check("getBalance", client).is_err());
+        assert_eq!(limiter.buckets.lock().unwrap().len(), MAX_TRACKED_BUCKETS);
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let peer_addr: SocketAddr = "10.0.0.9:8899".parse().unwrap();
+        let proxy_addr: SocketAddr = "10.0.0.10:8899".parse().unwrap();
+        let request = |peer_addr: &SocketAddr, header: Option<(&str, &str)>| {
+            let mut builder = hyper::Request::builder();
+            if let Some((name, value)) = header {
+                builder.header(name, value);
+            }
+            let mut request = builder.body(hyper::Body::empty()).unwrap();
+            request.extensions_mut().insert(*peer_addr);
+            request
+        };
+        let config = RpcRateLimitConfig {
+            trusted_proxies: vec![proxy_addr.ip()].into_iter().collect(),
+            ..RpcRateLimitConfig::default()
+        };
+
+        // Headers from untrusted peers are ignored
+        assert_eq!(
+            config.client_ip(&request(&peer_addr, Some(("x-forwarded-for", "10.0.0.1")))),
+            Some(peer_addr.ip())
+        );
+        assert_eq!(
+            config.client_ip(&request(&peer_addr, Some(("x-real-ip", "10.0.0.1")))),
+            Some(peer_addr.ip())
+        );
+
+        // Trusted proxies forward the address they see last
+        assert_eq!(
+            config.client_ip(&request(
+                &proxy_addr,
+                Some(("x-forwarded-for", "10.0.0.1, 10.0.0.2, 10.0.0.10"))
+            )),
+            Some("10.0.0.2".parse().unwrap())
+        );
+        assert_eq!(
+            config.client_ip(&request(&proxy_addr, Some(("x-real-ip", "10.0.0.3")))),
+            Some("10.0.0.3".parse().unwrap())
+        );
+        assert_eq!(
+            config.client_ip(&request(&proxy_addr, None)),
+            Some(proxy_addr.ip())
+        );
+
+        let request = hyper::Request::builder()
+            .body(hyper::Body::empty())
+            .unwrap();
+        assert_eq!(config.client_ip(&request), None);
+    }
+}
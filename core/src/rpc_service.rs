@@ -2,22 +2,35 @@
 
 use crate::{
     cluster_info::ClusterInfo, commitment::BlockCommitmentCache, rpc::*,
+    rpc_rate_limiter::RpcRateLimiter, sample_performance_service::PerfSamples,
     storage_stage::StorageState, validator::ValidatorExit,
 };
-use jsonrpc_core::MetaIoHandler;
+use jsonrpc_core::{futures::sync::oneshot, MetaIoHandler};
 use jsonrpc_http_server::{
-    hyper, AccessControlAllowOrigin, CloseHandle, DomainsValidation, RequestMiddleware,
-    RequestMiddlewareAction, ServerBuilder,
+    cors::AccessControlAllowHeaders,
+    hyper::{
+        self,
+        server::conn::AddrStream,
+        service::{make_service_fn, Service},
+    },
+    AccessControlAllowOrigin, RequestMiddleware, RequestMiddlewareAction, RestApi, Rpc,
+    ServerHandler,
 };
 use solana_ledger::{bank_forks::BankForks, blocktree::Blocktree};
 use solana_sdk::hash::Hash;
 use std::{
     net::SocketAddr,
     path::{Path, PathBuf},
-    sync::{mpsc::channel, Arc, RwLock},
+    sync::{mpsc::channel, Arc, Mutex, RwLock},
     thread::{self, Builder, JoinHandle},
 };
-use tokio::prelude::Future;
+use tokio::{
+    prelude::{future, Future},
+    runtime::Runtime,
+};
+
+// Largest JSON RPC request body served, as in the default HTTP server configuration
+const MAX_REQUEST_BODY_SIZE: usize = 5 * 1024 * 1024;
 
 pub struct JsonRpcService {
     thread_hdl: JoinHandle<()>,
@@ -28,13 +41,51 @@ pub struct JsonRpcService {
     close_handle: Option<CloseHandle>,
 }
 
-#[derive(Default)]
+/// Stops the JSON RPC server, dropping its open connections
+#[derive(Clone)]
+struct CloseHandle(Arc<Mutex<Option<oneshot::Sender<()>>>>);
+impl CloseHandle {
+    fn close(self) {
+        if let Some(sender) = self.0.lock().unwrap().take() {
+            let _ = sender.send(());
+        }
+    }
+}
+
+/// Attaches the peer address of a connection to each request on it, where the rate limiter
+/// finds it
+struct PeerAddrService<S> {
+    service: S,
+    peer_addr: SocketAddr,
+}
+impl<S: Service<ReqBody = hyper::Body>> Service for PeerAddrService<S> {
+    type ReqBody = hyper::Body;
+    type ResBody = S::ResBody;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn call(&mut self, mut request: hyper::Request<hyper::Body>) -> Self::Future {
+        request.extensions_mut().insert(self.peer_addr);
+        self.service.call(request)
+    }
+}
+
 struct RpcRequestMiddleware {
     ledger_path: PathBuf,
+    request_processor: Arc<RwLock<JsonRpcRequestProcessor>>,
+    cluster_info: Arc<RwLock<ClusterInfo>>,
 }
 impl RpcRequestMiddleware {
-    pub fn new(ledger_path: PathBuf) -> Self {
-        Self { ledger_path }
+    pub fn new(
+        ledger_path: PathBuf,
+        request_processor: Arc<RwLock<JsonRpcRequestProcessor>>,
+        cluster_info: Arc<RwLock<ClusterInfo>>,
+    ) -> Self {
+        Self {
+            ledger_path,
+            request_processor,
+            cluster_info,
+        }
     }
 
     fn not_found() -> hyper::Response<hyper::Body> {
@@ -68,6 +119,39 @@ impl RpcRequestMiddleware {
             ),
         }
     }
+
+    fn health_check(&self) -> (hyper::StatusCode, String) {
+        let health = self
+            .request_processor
+            .read()
+            .unwrap()
+            .get_health(&self.cluster_info.read().unwrap());
+        match health {
+            RpcHealthStatus::Ok => (hyper::StatusCode::OK, "ok".to_string()),
+            RpcHealthStatus::Behind { num_slots } => (
+                hyper::StatusCode::SERVICE_UNAVAILABLE,
+                format!("behind by {} slots", num_slots),
+            ),
+            RpcHealthStatus::Unknown => (
+                hyper::StatusCode::SERVICE_UNAVAILABLE,
+                "unknown".to_string(),
+            ),
+        }
+    }
+
+    fn health(&self) -> RequestMiddlewareAction {
+        let (status, body) = self.health_check();
+        debug!("health check: {} {}", status, body);
+        RequestMiddlewareAction::Respond {
+            should_validate_hosts: true,
+            response: Box::new(future::ok(
+                hyper::Response::builder()
+                    .status(status)
+                    .body(hyper::Body::from(body))
+                    .unwrap(),
+            )),
+        }
+    }
 }
 
 impl RequestMiddleware for RpcRequestMiddleware {
@@ -76,6 +160,7 @@ impl RequestMiddleware for RpcRequestMiddleware {
         match request.uri().path() {
             "/snapshot.tar.bz2" => self.get("snapshot.tar.bz2"),
             "/genesis.tar.bz2" => self.get("genesis.tar.bz2"),
+            "/health" => self.health(),
             _ => RequestMiddlewareAction::Proceed {
                 should_continue_on_invalid_cors: false,
                 request,
@@ -97,9 +182,12 @@ impl JsonRpcService {
         ledger_path: &Path,
         storage_state: StorageState,
         validator_exit: Arc<RwLock<Option<ValidatorExit>>>,
+        perf_samples: PerfSamples,
     ) -> Self {
         info!("rpc bound to {:?}", rpc_addr);
         info!("rpc configuration: {:?}", config);
+        let rate_limit_config = config.rate_limit_config.clone();
+        let rate_limiter = RpcRateLimiter::new(config.rate_limit_config.clone());
         let request_processor = Arc::new(RwLock::new(JsonRpcRequestProcessor::new(
             config,
             bank_forks,
@@ -107,40 +195,76 @@ impl JsonRpcService {
             blocktree,
             storage_state,
             validator_exit.clone(),
+            perf_samples,
         )));
 
         #[cfg(test)]
         let test_request_processor = request_processor.clone();
 
-        let ledger_path = ledger_path.to_path_buf();
+        let request_middleware = RpcRequestMiddleware::new(
+            ledger_path.to_path_buf(),
+            request_processor.clone(),
+            cluster_info.clone(),
+        );
 
         let (close_handle_sender, close_handle_receiver) = channel();
         let thread_hdl = Builder::new()
             .name("solana-jsonrpc".to_string())
             .spawn(move || {
-                let mut io = MetaIoHandler::default();
+                let mut io = MetaIoHandler::with_middleware(rate_limiter);
                 let rpc = RpcSolImpl;
                 io.extend_with(rpc.to_delegate());
 
-                let server =
-                    ServerBuilder::with_meta_extractor(io, move |_req: &hyper::Request<hyper::Body>| Meta {
+                let rpc = Rpc {
+                    handler: Arc::new(io),
+                    extractor: Arc::new(move |req: &hyper::Request<hyper::Body>| Meta {
                         request_processor: request_processor.clone(),
                         cluster_info: cluster_info.clone(),
-                        genesis_hash
-                    }).threads(4)
-                        .cors(DomainsValidation::AllowOnly(vec![
-                            AccessControlAllowOrigin::Any,
-                        ]))
-                        .request_middleware(RpcRequestMiddleware::new(ledger_path))
-                        .start_http(&rpc_addr);
-                if let Err(e) = server {
-                    warn!("JSON RPC service unavailable error: {:?}. \nAlso, check that port {} is not already in use by another application", e, rpc_addr.port());
-                    return;
-                }
-
-                let server = server.unwrap();
-                close_handle_sender.send(server.close_handle()).unwrap();
-                server.wait();
+                        genesis_hash,
+                        client_ip: rate_limit_config.client_ip(req),
+                    }),
+                };
+                let request_middleware: Arc<dyn RequestMiddleware> = Arc::new(request_middleware);
+
+                // Connections are accepted here rather than by the JSON RPC server builder,
+                // which hides their peer addresses from the rate limiter
+                let server = hyper::Server::try_bind(&rpc_addr).map(|builder| {
+                    builder.serve(make_service_fn(move |socket: &AddrStream| {
+                        future::ok::<_, hyper::Error>(PeerAddrService {
+                            service: ServerHandler::new(
+                                rpc.downgrade(),
+                                Some(vec![AccessControlAllowOrigin::Any]),
+                                None,
+                                AccessControlAllowHeaders::Any,
+                                None,
+                                request_middleware.clone(),
+                                RestApi::Disabled,
+                                None,
+                                MAX_REQUEST_BODY_SIZE,
+                                true,
+                            ),
+                            peer_addr: socket.remote_addr(),
+                        })
+                    }))
+                });
+                let server = match server {
+                    Ok(server) => server,
+                    Err(e) => {
+                        warn!("JSON RPC service unavailable error: {:?}. \nAlso, check that port {} is not already in use by another application", e, rpc_addr.port());
+                        return;
+                    }
+                };
+
+                let (exit_sender, exit_receiver) = oneshot::channel();
+                close_handle_sender
+                    .send(CloseHandle(Arc::new(Mutex::new(Some(exit_sender)))))
+                    .unwrap();
+                let mut runtime = Runtime::new().unwrap();
+                let _ = runtime.block_on(
+                    server
+                        .map_err(|e| warn!("JSON RPC server error: {:?}", e))
+                        .select(exit_receiver.map_err(|_| ())),
+                );
             })
             .unwrap();
 
@@ -180,7 +304,7 @@ mod tests {
     };
     use solana_ledger::get_tmp_ledger_path;
     use solana_runtime::bank::Bank;
-    use solana_sdk::signature::KeypairUtil;
+    use solana_sdk::{pubkey::Pubkey, signature::KeypairUtil};
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
     use std::sync::atomic::AtomicBool;
 
@@ -216,6 +340,7 @@ mod tests {
             &PathBuf::from("farf"),
             StorageState::default(),
             validator_exit,
+            PerfSamples::default(),
         );
         let thread = rpc_service.thread_hdl.thread();
         assert_eq!(thread.name().unwrap(), "solana-jsonrpc");
@@ -233,4 +358,52 @@ mod tests {
         rpc_service.exit();
         rpc_service.join().unwrap();
     }
+
+    #[test]
+    fn test_health_check() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let exit = Arc::new(AtomicBool::new(false));
+        let validator_exit = create_validator_exit(&exit);
+        let bank = Bank::new(&genesis_config);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank.slot(), bank)));
+        let ledger_path = get_tmp_ledger_path!();
+        let blocktree = Arc::new(Blocktree::open(&ledger_path).unwrap());
+        let cluster_info = Arc::new(RwLock::new(ClusterInfo::new_with_invalid_keypair(
+            ContactInfo::default(),
+        )));
+        let new_middleware = |config| {
+            let request_processor = JsonRpcRequestProcessor::new(
+                config,
+                bank_forks.clone(),
+                Arc::new(RwLock::new(BlockCommitmentCache::default())),
+                blocktree.clone(),
+                StorageState::default(),
+                validator_exit.clone(),
+                PerfSamples::default(),
+            );
+            RpcRequestMiddleware::new(
+                PathBuf::from("farf"),
+                Arc::new(RwLock::new(request_processor)),
+                cluster_info.clone(),
+            )
+        };
+
+        // Without trusted validators there is nothing to fall behind
+        let rrm = new_middleware(JsonRpcConfig::default());
+        assert_eq!(
+            rrm.health_check(),
+            (hyper::StatusCode::OK, "ok".to_string())
+        );
+
+        let mut config = JsonRpcConfig::default();
+        config.trusted_validators.insert(Pubkey::new_rand());
+        let rrm = new_middleware(config);
+        assert_eq!(
+            rrm.health_check(),
+            (
+                hyper::StatusCode::SERVICE_UNAVAILABLE,
+                "unknown".to_string()
+            )
+        );
+    }
 }
@@ -1,25 +1,36 @@
 //! The `pubsub` module implements a threaded subscription service on client RPC request
 
 use core::hash::Hash;
-use jsonrpc_core::futures::Future;
+use crossbeam_channel::{unbounded, Sender};
+use jsonrpc_core::futures::{
+    executor::{self, Notify},
+    Async,
+};
 use jsonrpc_pubsub::{typed::Sink, SubscriptionId};
 use serde::Serialize;
-use solana_ledger::bank_forks::BankForks;
-use solana_runtime::bank::Bank;
+use solana_client::rpc_request::{
+    RpcBlockUpdate, RpcConfirmedBlock, RpcLogsResponse, RpcVote, SlotInfo,
+};
+use solana_ledger::{bank_forks::BankForks, blocktree::Blocktree};
+use solana_runtime::bank::{
+    Bank, TransactionLogCollectorConfig, TransactionLogCollectorFilter, TransactionLogInfo,
+};
 use solana_sdk::{
     account::Account, clock::Slot, pubkey::Pubkey, signature::Signature, transaction,
 };
-use solana_vote_program::vote_state::MAX_LOCKOUT_HISTORY;
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use solana_vote_program::vote_state::{Vote, MAX_LOCKOUT_HISTORY};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::{Builder, JoinHandle};
 
 pub type Confirmations = usize;
 
-#[derive(Serialize, Clone)]
-pub struct SlotInfo {
-    pub slot: Slot,
-    pub parent: Slot,
-    pub root: Slot,
+/// Transactions whose logs are delivered to a logs subscription
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LogsSubscriptionKey {
+    All,
+    AllWithVotes,
+    Account(Pubkey),
 }
 
 type RpcAccountSubscriptions =
@@ -29,7 +40,62 @@ type RpcProgramSubscriptions =
 type RpcSignatureSubscriptions = RwLock<
     HashMap<Signature, HashMap<SubscriptionId, (Sink<transaction::Result<()>>, Confirmations)>>,
 >;
+type RpcLogsSubscriptions = RwLock<
+    HashMap<LogsSubscriptionKey, HashMap<SubscriptionId, (Sink<RpcLogsResponse>, Confirmations)>>,
+>;
 type RpcSlotSubscriptions = RwLock<HashMap<SubscriptionId, Sink<SlotInfo>>>;
+type RpcRootSubscriptions = RwLock<HashMap<SubscriptionId, Sink<Slot>>>;
+type RpcVoteSubscriptions = RwLock<HashMap<SubscriptionId, Sink<RpcVote>>>;
+type RpcBlockSubscriptions =
+    RwLock<HashMap<SubscriptionId, (Sink<RpcBlockUpdate>, Option<Pubkey>)>>;
+
+/// Active subscriptions by the connection they were made over, which the pubsub service limits
+#[derive(Default)]
+pub struct SubscriptionCounts {
+    // Connection each active subscription was made over
+    connections: HashMap<SubscriptionId, u64>,
+    // Number of active subscriptions made over each connection
+    per_connection: HashMap<u64, usize>,
+}
+
+impl SubscriptionCounts {
+    /// Number of active subscriptions across all connections
+    pub fn active(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Number of active subscriptions made over `connection`
+    pub fn per_connection(&self, connection: u64) -> usize {
+        self.per_connection.get(&connection).cloned().unwrap_or(0)
+    }
+
+    pub fn add(&mut self, sub_id: SubscriptionId, connection: u64) {
+        *self.per_connection.entry(connection).or_insert(0) += 1;
+        self.connections.insert(sub_id, connection);
+        self.report();
+    }
+
+    /// Release the share of the limits held by `sub_id`, once it is no longer active
+    pub fn release(&mut self, sub_id: &SubscriptionId) {
+        if let Some(connection) = self.connections.remove(sub_id) {
+            if let Some(connection_count) = self.per_connection.get_mut(&connection) {
+                *connection_count -= 1;
+                if *connection_count == 0 {
+                    self.per_connection.remove(&connection);
+                }
+            }
+            self.report();
+        }
+    }
+
+    fn report(&self) {
+        datapoint_info!(
+            "rpc-pubsub-subscriptions",
+            ("active", self.connections.len(), i64),
+            ("connections", self.per_connection.len(), i64)
+        );
+    }
+}
 
 fn add_subscription<K, S>(
     subscriptions: &mut HashMap<K, HashMap<SubscriptionId, (Sink<S>, Confirmations)>>,
@@ -67,16 +133,80 @@ where
     let mut found = false;
     subscriptions.retain(|_, v| {
         v.retain(|k, _| {
-            if *k == *sub_id {
+            let is_match = *k == *sub_id;
+            if is_match {
                 found = true;
             }
-            !found
+            !is_match
         });
         !v.is_empty()
     });
     found
 }
 
+// Notifications are only queued, the notifying thread is never woken to retry
+struct NoopNotify;
+
+impl Notify for NoopNotify {
+    fn notify(&self, _id: usize) {}
+}
+
+// Queue a notification for a subscriber without waiting. Returns false if the subscriber's
+// connection is closed or its notification queue is full, in which case the subscriber should
+// be dropped
+fn notify_sink<S>(sink: &Sink<S>, value: S) -> bool
+where
+    S: Serialize,
+{
+    let mut send = executor::spawn(sink.notify(Ok(value)));
+    match send.poll_future_notify(&Arc::new(NoopNotify), 0) {
+        Ok(Async::Ready(_)) => true,
+        Ok(Async::NotReady) | Err(_) => false,
+    }
+}
+
+fn remove_dropped_subscriptions<K, S>(
+    subscriptions: &mut HashMap<K, HashMap<SubscriptionId, (Sink<S>, Confirmations)>>,
+    dropped: &[SubscriptionId],
+    subscription_counts: &Mutex<SubscriptionCounts>,
+) where
+    K: Eq + Hash + Clone + Copy,
+    S: Clone,
+{
+    for sub_id in dropped {
+        remove_subscription(subscriptions, sub_id);
+    }
+    report_dropped_subscriptions(dropped, subscription_counts);
+}
+
+fn report_dropped_subscriptions(
+    dropped: &[SubscriptionId],
+    subscription_counts: &Mutex<SubscriptionCounts>,
+) {
+    if !dropped.is_empty() {
+        warn!("Dropping slow pubsub subscribers: {:?}", dropped);
+        inc_new_counter_info!("rpc-pubsub-dropped-subscriptions", dropped.len());
+        let mut subscription_counts = subscription_counts.lock().unwrap();
+        for sub_id in dropped {
+            subscription_counts.release(sub_id);
+        }
+    }
+}
+
+fn remove_dropped_sinks<V>(
+    subscriptions: &RwLock<HashMap<SubscriptionId, V>>,
+    dropped: &[SubscriptionId],
+    subscription_counts: &Mutex<SubscriptionCounts>,
+) {
+    if !dropped.is_empty() {
+        let mut subscriptions = subscriptions.write().unwrap();
+        for sub_id in dropped {
+            subscriptions.remove(sub_id);
+        }
+        report_dropped_subscriptions(dropped, subscription_counts);
+    }
+}
+
 fn check_confirmations_and_notify<K, S, F, N, X>(
     subscriptions: &HashMap<K, HashMap<SubscriptionId, (Sink<S>, Confirmations)>>,
     hashmap_key: &K,
@@ -84,13 +214,15 @@ fn check_confirmations_and_notify<K, S, F, N, X>(
     bank_forks: &Arc<RwLock<BankForks>>,
     bank_method: F,
     notify: N,
-) where
+) -> Vec<SubscriptionId>
+where
     K: Eq + Hash + Clone + Copy,
     S: Clone + Serialize,
     F: Fn(&Bank, &K) -> X,
-    N: Fn(X, &Sink<S>, u64),
+    N: Fn(X, &Sink<S>, u64) -> bool,
     X: Clone + Serialize,
 {
+    let mut dropped = vec![];
     let current_ancestors = bank_forks
         .read()
         .unwrap()
@@ -99,7 +231,7 @@ fn check_confirmations_and_notify<K, S, F, N, X>(
         .ancestors
         .clone();
     if let Some(hashmap) = subscriptions.get(hashmap_key) {
-        for (_bank_sub_id, (sink, confirmations)) in hashmap.iter() {
+        for (bank_sub_id, (sink, confirmations)) in hashmap.iter() {
             let desired_slot: Vec<u64> = current_ancestors
                 .iter()
                 .filter(|(_, &v)| v == *confirmations)
@@ -121,74 +253,232 @@ fn check_confirmations_and_notify<K, S, F, N, X>(
                     .unwrap()
                     .clone();
                 let result = bank_method(&desired_bank, hashmap_key);
-                notify(result, &sink, root);
+                if !notify(result, sink, root) {
+                    dropped.push(bank_sub_id.clone());
+                }
             }
         }
     }
+    dropped
 }
 
-fn notify_account<S>(result: Option<(S, Slot)>, sink: &Sink<S>, root: Slot)
+fn notify_account<S>(result: Option<(S, Slot)>, sink: &Sink<S>, root: Slot) -> bool
 where
     S: Clone + Serialize,
 {
-    if let Some((account, fork)) = result {
-        if fork >= root {
-            sink.notify(Ok(account)).wait().unwrap();
-        }
+    match result {
+        Some((account, fork)) if fork >= root => notify_sink(sink, account),
+        _ => true,
     }
 }
 
-fn notify_signature<S>(result: Option<S>, sink: &Sink<S>, _root: Slot)
+fn notify_signature<S>(result: Option<S>, sink: &Sink<S>, _root: Slot) -> bool
 where
     S: Clone + Serialize,
 {
-    if let Some(result) = result {
-        sink.notify(Ok(result)).wait().unwrap();
+    match result {
+        Some(result) => notify_sink(sink, result),
+        None => true,
     }
 }
 
-fn notify_program(accounts: Vec<(Pubkey, Account)>, sink: &Sink<(String, Account)>, _root: Slot) {
-    for (pubkey, account) in accounts.iter() {
-        sink.notify(Ok((pubkey.to_string(), account.clone())))
-            .wait()
-            .unwrap();
+fn notify_program(
+    accounts: Vec<(Pubkey, Account)>,
+    sink: &Sink<(String, Account)>,
+    _root: Slot,
+) -> bool {
+    accounts
+        .into_iter()
+        .all(|(pubkey, account)| notify_sink(sink, (pubkey.to_string(), account)))
+}
+
+fn get_transaction_logs(bank: &Bank, key: &LogsSubscriptionKey) -> Option<Vec<TransactionLogInfo>> {
+    match key {
+        LogsSubscriptionKey::All => bank
+            .get_transaction_logs(None)
+            .map(|logs| logs.into_iter().filter(|log| !log.is_vote).collect()),
+        LogsSubscriptionKey::AllWithVotes => bank.get_transaction_logs(None),
+        LogsSubscriptionKey::Account(pubkey) => bank.get_transaction_logs(Some(pubkey)),
     }
 }
 
+fn notify_logs(
+    logs: Option<Vec<TransactionLogInfo>>,
+    sink: &Sink<RpcLogsResponse>,
+    _root: Slot,
+) -> bool {
+    logs.unwrap_or_default().into_iter().all(|log| {
+        notify_sink(
+            sink,
+            RpcLogsResponse {
+                signature: log.signature.to_string(),
+                err: log.result.err(),
+                logs: log.log_messages,
+            },
+        )
+    })
+}
+
+/// Newly rooted slots whose blocks are to be sent to block subscribers
+struct BlocksNotification {
+    rooted_slots: Vec<Slot>,
+    blocktree: Arc<Blocktree>,
+}
+
+/// Notify block subscribers of each newly rooted block, in ascending slot order
+fn notify_blocks(
+    block_subscriptions: &RpcBlockSubscriptions,
+    rooted_slots: &[Slot],
+    blocktree: &Blocktree,
+    subscription_counts: &Mutex<SubscriptionCounts>,
+) {
+    let subscriptions = block_subscriptions.read().unwrap();
+    if subscriptions.is_empty() {
+        return;
+    }
+    let mut rooted_slots = rooted_slots.to_vec();
+    rooted_slots.sort();
+    let mut dropped = vec![];
+    for slot in rooted_slots {
+        let block = match blocktree.get_confirmed_block(slot) {
+            Ok(block) => block,
+            Err(err) => {
+                // Slots without entries have no block to report
+                debug!("notify_blocks: no block for slot {}: {:?}", slot, err);
+                continue;
+            }
+        };
+        for (sub_id, (sink, mentions)) in subscriptions.iter() {
+            if dropped.contains(sub_id) {
+                continue;
+            }
+            let block = match mentions {
+                None => block.clone(),
+                Some(pubkey) => {
+                    let transactions: Vec<_> = block
+                        .transactions
+                        .iter()
+                        .filter(|(transaction, _)| {
+                            transaction.message.account_keys.contains(pubkey)
+                        })
+                        .cloned()
+                        .collect();
+                    if transactions.is_empty() {
+                        continue;
+                    }
+                    RpcConfirmedBlock {
+                        transactions,
+                        ..block.clone()
+                    }
+                }
+            };
+            if !notify_sink(sink, RpcBlockUpdate { slot, block }) {
+                dropped.push(sub_id.clone());
+            }
+        }
+    }
+    drop(subscriptions);
+    remove_dropped_sinks(block_subscriptions, &dropped, subscription_counts);
+}
+
 pub struct RpcSubscriptions {
     account_subscriptions: RpcAccountSubscriptions,
     program_subscriptions: RpcProgramSubscriptions,
     signature_subscriptions: RpcSignatureSubscriptions,
+    logs_subscriptions: RpcLogsSubscriptions,
     slot_subscriptions: RpcSlotSubscriptions,
+    root_subscriptions: RpcRootSubscriptions,
+    vote_subscriptions: RpcVoteSubscriptions,
+    block_subscriptions: Arc<RpcBlockSubscriptions>,
+    transaction_log_collector_config: Arc<RwLock<TransactionLogCollectorConfig>>,
+    subscription_counts: Arc<Mutex<SubscriptionCounts>>,
+    /// Reading blocks from the ledger is left to the block notification thread, off the
+    /// replay stage's critical path
+    blocks_sender: Option<Sender<BlocksNotification>>,
+    t_blocks_notifier: Option<JoinHandle<()>>,
+}
+
+impl Drop for RpcSubscriptions {
+    fn drop(&mut self) {
+        self.blocks_sender.take();
+        if let Some(t_blocks_notifier) = self.t_blocks_notifier.take() {
+            if t_blocks_notifier.join().is_err() {
+                warn!("block notification thread panicked");
+            }
+        }
+    }
 }
 
 impl Default for RpcSubscriptions {
     fn default() -> Self {
+        Self::new(Arc::default())
+    }
+}
+
+impl RpcSubscriptions {
+    /// `transaction_log_collector_config` is shared with the banks, which only record the
+    /// transaction logs that logs subscribers are interested in
+    pub fn new(
+        transaction_log_collector_config: Arc<RwLock<TransactionLogCollectorConfig>>,
+    ) -> Self {
+        let block_subscriptions = Arc::new(RpcBlockSubscriptions::default());
+        let subscription_counts = Arc::new(Mutex::new(SubscriptionCounts::default()));
+        let (blocks_sender, blocks_receiver) = unbounded::<BlocksNotification>();
+        let t_blocks_notifier = {
+            let block_subscriptions = block_subscriptions.clone();
+            let subscription_counts = subscription_counts.clone();
+            Builder::new()
+                .name("solana-rpc-block-notifier".to_string())
+                .spawn(move || {
+                    // Runs until the sender is dropped along with the subscriptions
+                    for notification in blocks_receiver.iter() {
+                        notify_blocks(
+                            &block_subscriptions,
+                            &notification.rooted_slots,
+                            &notification.blocktree,
+                            &subscription_counts,
+                        );
+                    }
+                })
+                .unwrap()
+        };
         RpcSubscriptions {
             account_subscriptions: RpcAccountSubscriptions::default(),
             program_subscriptions: RpcProgramSubscriptions::default(),
             signature_subscriptions: RpcSignatureSubscriptions::default(),
+            logs_subscriptions: RpcLogsSubscriptions::default(),
             slot_subscriptions: RpcSlotSubscriptions::default(),
+            root_subscriptions: RpcRootSubscriptions::default(),
+            vote_subscriptions: RpcVoteSubscriptions::default(),
+            block_subscriptions,
+            transaction_log_collector_config,
+            subscription_counts,
+            blocks_sender: Some(blocks_sender),
+            t_blocks_notifier: Some(t_blocks_notifier),
         }
     }
-}
 
-impl RpcSubscriptions {
     pub fn check_account(
         &self,
         pubkey: &Pubkey,
         current_slot: Slot,
         bank_forks: &Arc<RwLock<BankForks>>,
     ) {
-        let subscriptions = self.account_subscriptions.read().unwrap();
-        check_confirmations_and_notify(
-            &subscriptions,
-            pubkey,
-            current_slot,
-            bank_forks,
-            Bank::get_account_modified_since_parent,
-            notify_account,
-        );
+        let dropped = {
+            let subscriptions = self.account_subscriptions.read().unwrap();
+            check_confirmations_and_notify(
+                &subscriptions,
+                pubkey,
+                current_slot,
+                bank_forks,
+                Bank::get_account_modified_since_parent,
+                notify_account,
+            )
+        };
+        if !dropped.is_empty() {
+            let mut subscriptions = self.account_subscriptions.write().unwrap();
+            remove_dropped_subscriptions(&mut subscriptions, &dropped, &self.subscription_counts);
+        }
     }
 
     pub fn check_program(
@@ -197,8 +487,8 @@ impl RpcSubscriptions {
         current_slot: Slot,
         bank_forks: &Arc<RwLock<BankForks>>,
     ) {
-        let subscriptions = self.program_subscriptions.write().unwrap();
-        check_confirmations_and_notify(
+        let mut subscriptions = self.program_subscriptions.write().unwrap();
+        let dropped = check_confirmations_and_notify(
             &subscriptions,
             program_id,
             current_slot,
@@ -206,6 +496,7 @@ impl RpcSubscriptions {
             Bank::get_program_accounts_modified_since_parent,
             notify_program,
         );
+        remove_dropped_subscriptions(&mut subscriptions, &dropped, &self.subscription_counts);
     }
 
     pub fn check_signature(
@@ -215,7 +506,7 @@ impl RpcSubscriptions {
         bank_forks: &Arc<RwLock<BankForks>>,
     ) {
         let mut subscriptions = self.signature_subscriptions.write().unwrap();
-        check_confirmations_and_notify(
+        let dropped = check_confirmations_and_notify(
             &subscriptions,
             signature,
             current_slot,
@@ -223,7 +514,38 @@ impl RpcSubscriptions {
             Bank::get_signature_status,
             notify_signature,
         );
-        subscriptions.remove(&signature);
+        report_dropped_subscriptions(&dropped, &self.subscription_counts);
+        if let Some(hashmap) = subscriptions.remove(signature) {
+            // Signature subscriptions end once checked, which releases their limits as well
+            let mut subscription_counts = self.subscription_counts.lock().unwrap();
+            for sub_id in hashmap.keys() {
+                subscription_counts.release(sub_id);
+            }
+        }
+    }
+
+    pub fn check_logs(
+        &self,
+        logs_key: &LogsSubscriptionKey,
+        current_slot: Slot,
+        bank_forks: &Arc<RwLock<BankForks>>,
+    ) {
+        let dropped = {
+            let subscriptions = self.logs_subscriptions.read().unwrap();
+            check_confirmations_and_notify(
+                &subscriptions,
+                logs_key,
+                current_slot,
+                bank_forks,
+                get_transaction_logs,
+                notify_logs,
+            )
+        };
+        if !dropped.is_empty() {
+            let mut subscriptions = self.logs_subscriptions.write().unwrap();
+            remove_dropped_subscriptions(&mut subscriptions, &dropped, &self.subscription_counts);
+            self.update_transaction_log_collector_config(&subscriptions);
+        }
     }
 
     pub fn add_account_subscription(
@@ -274,6 +596,81 @@ impl RpcSubscriptions {
         remove_subscription(&mut subscriptions, id)
     }
 
+    pub fn add_logs_subscription(
+        &self,
+        logs_key: &LogsSubscriptionKey,
+        confirmations: Option<Confirmations>,
+        sub_id: &SubscriptionId,
+        sink: &Sink<RpcLogsResponse>,
+    ) {
+        let mut subscriptions = self.logs_subscriptions.write().unwrap();
+        add_subscription(&mut subscriptions, logs_key, confirmations, sub_id, sink);
+        self.update_transaction_log_collector_config(&subscriptions);
+    }
+
+    pub fn remove_logs_subscription(&self, id: &SubscriptionId) -> bool {
+        let mut subscriptions = self.logs_subscriptions.write().unwrap();
+        let found = remove_subscription(&mut subscriptions, id);
+        self.update_transaction_log_collector_config(&subscriptions);
+        found
+    }
+
+    // Have the banks record only the transaction logs the current subscriptions ask for
+    fn update_transaction_log_collector_config<S>(
+        &self,
+        subscriptions: &HashMap<LogsSubscriptionKey, S>,
+    ) {
+        let mentioned_addresses: HashSet<Pubkey> = subscriptions
+            .keys()
+            .filter_map(|logs_key| match logs_key {
+                LogsSubscriptionKey::Account(pubkey) => Some(*pubkey),
+                _ => None,
+            })
+            .collect();
+        let filter = if subscriptions.contains_key(&LogsSubscriptionKey::AllWithVotes) {
+            TransactionLogCollectorFilter::AllWithVotes
+        } else if subscriptions.contains_key(&LogsSubscriptionKey::All) {
+            TransactionLogCollectorFilter::All
+        } else if !mentioned_addresses.is_empty() {
+            TransactionLogCollectorFilter::OnlyMentionedAddresses
+        } else {
+            TransactionLogCollectorFilter::None
+        };
+
+        let mut config = self.transaction_log_collector_config.write().unwrap();
+        config.mentioned_addresses = mentioned_addresses;
+        config.filter = filter;
+    }
+
+    /// Active subscriptions, which subscriptions dropped for falling behind or ended by the
+    /// service are released from
+    pub fn subscription_counts(&self) -> &Mutex<SubscriptionCounts> {
+        &self.subscription_counts
+    }
+
+    /// Whether `id` is still active, i.e. it was neither cancelled nor dropped for falling behind
+    pub fn has_subscription(&self, id: &SubscriptionId) -> bool {
+        fn contains<K, S>(
+            subscriptions: &RwLock<HashMap<K, HashMap<SubscriptionId, S>>>,
+            id: &SubscriptionId,
+        ) -> bool {
+            subscriptions
+                .read()
+                .unwrap()
+                .values()
+                .any(|hashmap| hashmap.contains_key(id))
+        }
+
+        contains(&self.account_subscriptions, id)
+            || contains(&self.program_subscriptions, id)
+            || contains(&self.signature_subscriptions, id)
+            || contains(&self.logs_subscriptions, id)
+            || self.slot_subscriptions.read().unwrap().contains_key(id)
+            || self.root_subscriptions.read().unwrap().contains_key(id)
+            || self.vote_subscriptions.read().unwrap().contains_key(id)
+            || self.block_subscriptions.read().unwrap().contains_key(id)
+    }
+
     /// Notify subscribers of changes to any accounts or new signatures since
     /// the bank's last checkpoint.
     pub fn notify_subscribers(&self, current_slot: Slot, bank_forks: &Arc<RwLock<BankForks>>) {
@@ -300,6 +697,14 @@ impl RpcSubscriptions {
         for signature in &signatures {
             self.check_signature(signature, current_slot, bank_forks);
         }
+
+        let logs_keys: Vec<_> = {
+            let subs = self.logs_subscriptions.read().unwrap();
+            subs.keys().cloned().collect()
+        };
+        for logs_key in &logs_keys {
+            self.check_logs(logs_key, current_slot, bank_forks);
+        }
     }
 
     pub fn add_slot_subscription(&self, sub_id: &SubscriptionId, sink: &Sink<SlotInfo>) {
@@ -313,13 +718,114 @@ impl RpcSubscriptions {
     }
 
     pub fn notify_slot(&self, slot: Slot, parent: Slot, root: Slot) {
-        let subscriptions = self.slot_subscriptions.read().unwrap();
-        for (_, sink) in subscriptions.iter() {
-            sink.notify(Ok(SlotInfo { slot, parent, root }))
-                .wait()
-                .unwrap();
+        let dropped: Vec<_> = {
+            let subscriptions = self.slot_subscriptions.read().unwrap();
+            subscriptions
+                .iter()
+                .filter(|(_, sink)| !notify_sink(sink, SlotInfo { slot, parent, root }))
+                .map(|(sub_id, _)| sub_id.clone())
+                .collect()
+        };
+        remove_dropped_sinks(
+            &self.slot_subscriptions,
+            &dropped,
+            &self.subscription_counts,
+        );
+    }
+
+    pub fn add_root_subscription(&self, sub_id: &SubscriptionId, sink: &Sink<Slot>) {
+        let mut subscriptions = self.root_subscriptions.write().unwrap();
+        subscriptions.insert(sub_id.clone(), sink.clone());
+    }
+
+    pub fn remove_root_subscription(&self, id: &SubscriptionId) -> bool {
+        let mut subscriptions = self.root_subscriptions.write().unwrap();
+        subscriptions.remove(id).is_some()
+    }
+
+    /// Notify root subscribers of each newly rooted slot, in ascending order
+    pub fn notify_roots(&self, mut rooted_slots: Vec<Slot>) {
+        rooted_slots.sort();
+        let dropped: Vec<_> = {
+            let subscriptions = self.root_subscriptions.read().unwrap();
+            subscriptions
+                .iter()
+                .filter(|(_, sink)| !rooted_slots.iter().all(|root| notify_sink(sink, *root)))
+                .map(|(sub_id, _)| sub_id.clone())
+                .collect()
+        };
+        remove_dropped_sinks(
+            &self.root_subscriptions,
+            &dropped,
+            &self.subscription_counts,
+        );
+    }
+
+    pub fn add_vote_subscription(&self, sub_id: &SubscriptionId, sink: &Sink<RpcVote>) {
+        let mut subscriptions = self.vote_subscriptions.write().unwrap();
+        subscriptions.insert(sub_id.clone(), sink.clone());
+    }
+
+    pub fn remove_vote_subscription(&self, id: &SubscriptionId) -> bool {
+        let mut subscriptions = self.vote_subscriptions.write().unwrap();
+        subscriptions.remove(id).is_some()
+    }
+
+    /// `mentions` restricts the notifications to transactions that include that account
+    pub fn add_block_subscription(
+        &self,
+        sub_id: &SubscriptionId,
+        mentions: Option<Pubkey>,
+        sink: &Sink<RpcBlockUpdate>,
+    ) {
+        let mut subscriptions = self.block_subscriptions.write().unwrap();
+        subscriptions.insert(sub_id.clone(), (sink.clone(), mentions));
+    }
+
+    pub fn remove_block_subscription(&self, id: &SubscriptionId) -> bool {
+        let mut subscriptions = self.block_subscriptions.write().unwrap();
+        subscriptions.remove(id).is_some()
+    }
+
+    /// Notify block subscribers of each newly rooted block, in ascending slot order.  The
+    /// blocks are read from `blocktree` and sent on the block notification thread
+    pub fn notify_blocks(&self, rooted_slots: &[Slot], blocktree: &Arc<Blocktree>) {
+        if self.block_subscriptions.read().unwrap().is_empty() {
+            return;
+        }
+        let notification = BlocksNotification {
+            rooted_slots: rooted_slots.to_vec(),
+            blocktree: blocktree.clone(),
+        };
+        if let Err(err) = self.blocks_sender.as_ref().unwrap().send(notification) {
+            warn!("notify_blocks: block notification thread exited: {:?}", err);
         }
     }
+
+    pub fn notify_vote(&self, vote: &Vote) {
+        let dropped: Vec<_> = {
+            let subscriptions = self.vote_subscriptions.read().unwrap();
+            subscriptions
+                .iter()
+                .filter(|(_, sink)| {
+                    !notify_sink(
+                        sink,
+                        RpcVote {
+                            slots: vote.slots.clone(),
+                            hash: vote.hash.to_string(),
+                            timestamp: vote.timestamp,
+                        },
+                    )
+                })
+                .map(|(sub_id, _)| sub_id.clone())
+                .collect()
+        };
+        remove_dropped_sinks(
+            &self.vote_subscriptions,
+            &dropped,
+            &self.subscription_counts,
+        );
+    }
 }
 
 #[cfg(test)]
@@ -328,6 +834,10 @@ mod tests {
     use crate::genesis_utils::{create_genesis_config, GenesisConfigInfo};
     use jsonrpc_pubsub::typed::Subscriber;
     use solana_budget_program;
+    use solana_ledger::{
+        blocktree::entries_to_test_shreds, entry::next_entry_mut, get_tmp_ledger_path,
+    };
+    use solana_sdk::hash::Hash;
     use solana_sdk::signature::{Keypair, KeypairUtil};
     use solana_sdk::system_transaction;
     use tokio::prelude::{Async, Stream};
@@ -500,6 +1010,77 @@ mod tests {
             .unwrap()
             .contains_key(&signature));
     }
+    #[test]
+    fn test_check_logs_subscribe() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(100);
+        let bank = Bank::new(&genesis_config);
+        let blockhash = bank.last_blockhash();
+        let subscriptions = RpcSubscriptions::new(bank.transaction_log_collector_config.clone());
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(0, bank)));
+        let alice = Keypair::new();
+
+        let (subscriber, _id_receiver, mut transport_receiver) =
+            Subscriber::new_test("logsNotification");
+        let sub_id = SubscriptionId::Number(0 as u64);
+        let sink = subscriber.assign_id(sub_id.clone()).unwrap();
+        let logs_key = LogsSubscriptionKey::Account(alice.pubkey());
+        subscriptions.add_logs_subscription(&logs_key, None, &sub_id, &sink);
+
+        assert!(subscriptions
+            .logs_subscriptions
+            .read()
+            .unwrap()
+            .contains_key(&logs_key));
+        assert_eq!(
+            subscriptions
+                .transaction_log_collector_config
+                .read()
+                .unwrap()
+                .filter,
+            TransactionLogCollectorFilter::OnlyMentionedAddresses
+        );
+
+        let tx = system_transaction::transfer(&mint_keypair, &alice.pubkey(), 20, blockhash);
+        bank_forks
+            .write()
+            .unwrap()
+            .get(0)
+            .unwrap()
+            .process_transaction(&tx)
+            .unwrap();
+
+        subscriptions.check_logs(&logs_key, 0, &bank_forks);
+        let string = transport_receiver.poll();
+        if let Async::Ready(Some(response)) = string.unwrap() {
+            let expected = format!(
+                r#"{{"jsonrpc":"2.0","method":"logsNotification","params":{{"result":{{"err":null,"logs":[],"signature":"{}"}},"subscription":0}}}}"#,
+                tx.signatures[0]
+            );
+            assert_eq!(expected, response);
+        } else {
+            panic!("expected a logs notification");
+        }
+
+        subscriptions.remove_logs_subscription(&sub_id);
+        assert!(!subscriptions
+            .logs_subscriptions
+            .read()
+            .unwrap()
+            .contains_key(&logs_key));
+        assert_eq!(
+            subscriptions
+                .transaction_log_collector_config
+                .read()
+                .unwrap()
+                .filter,
+            TransactionLogCollectorFilter::None
+        );
+    }
+
     #[test]
     fn test_check_slot_subscribe() {
         let (subscriber, _id_receiver, mut transport_receiver) =
@@ -539,4 +1120,194 @@ mod tests {
             .unwrap()
             .contains_key(&sub_id));
     }
+
+    #[test]
+    fn test_check_root_subscribe() {
+        let (subscriber, _id_receiver, transport_receiver) =
+            Subscriber::new_test("rootNotification");
+        let sub_id = SubscriptionId::Number(0 as u64);
+        let sink = subscriber.assign_id(sub_id.clone()).unwrap();
+        let subscriptions = RpcSubscriptions::default();
+        subscriptions.add_root_subscription(&sub_id, &sink);
+
+        assert!(subscriptions
+            .root_subscriptions
+            .read()
+            .unwrap()
+            .contains_key(&sub_id));
+
+        // The test transport has room for a single notification, so each is received before
+        // the next is sent
+        let mut transport_receiver = transport_receiver.wait();
+        let responses: Vec<String> = (1..=3)
+            .map(|root| {
+                subscriptions.notify_roots(vec![root]);
+                transport_receiver.next().unwrap().unwrap()
+            })
+            .collect();
+
+        let expected: Vec<String> = (1..=3)
+            .map(|root| {
+                format!(
+                    r#"{{"jsonrpc":"2.0","method":"rootNotification","params":{{"result":{},"subscription":0}}}}"#,
+                    root
+                )
+            })
+            .collect();
+        assert_eq!(expected, responses);
+
+        subscriptions.remove_root_subscription(&sub_id);
+        assert!(!subscriptions
+            .root_subscriptions
+            .read()
+            .unwrap()
+            .contains_key(&sub_id));
+    }
+
+    #[test]
+    fn test_check_vote_subscribe() {
+        let (subscriber, _id_receiver, mut transport_receiver) =
+            Subscriber::new_test("voteNotification");
+        let sub_id = SubscriptionId::Number(0 as u64);
+        let sink = subscriber.assign_id(sub_id.clone()).unwrap();
+        let subscriptions = RpcSubscriptions::default();
+        subscriptions.add_vote_subscription(&sub_id, &sink);
+
+        assert!(subscriptions
+            .vote_subscriptions
+            .read()
+            .unwrap()
+            .contains_key(&sub_id));
+
+        let vote = Vote {
+            slots: vec![1, 2],
+            hash: Hash::default(),
+            timestamp: None,
+        };
+        subscriptions.notify_vote(&vote);
+
+        let string = transport_receiver.poll();
+        if let Async::Ready(Some(response)) = string.unwrap() {
+            let expected = format!(
+                r#"{{"jsonrpc":"2.0","method":"voteNotification","params":{{"result":{{"hash":"{}","slots":[1,2],"timestamp":null}},"subscription":0}}}}"#,
+                Hash::default()
+            );
+            assert_eq!(expected, response);
+        } else {
+            panic!("expected a vote notification");
+        }
+
+        subscriptions.remove_vote_subscription(&sub_id);
+        assert!(!subscriptions
+            .vote_subscriptions
+            .read()
+            .unwrap()
+            .contains_key(&sub_id));
+    }
+
+    #[test]
+    fn test_check_block_subscribe() {
+        let ledger_path = get_tmp_ledger_path!();
+        let blocktree = Arc::new(Blocktree::open(&ledger_path).unwrap());
+        let alice = Keypair::new();
+        let bob = Keypair::new();
+        let tx = system_transaction::transfer(&alice, &bob.pubkey(), 1, Hash::default());
+        let entries = vec![next_entry_mut(&mut Hash::default(), 0, vec![tx])];
+        let shreds = entries_to_test_shreds(entries, 1, 0, true, 0);
+        blocktree.insert_shreds(shreds, None, false).unwrap();
+        blocktree.set_roots(&[1]).unwrap();
+
+        let subscriptions = RpcSubscriptions::default();
+        let (all_subscriber, _id_receiver, mut all_receiver) =
+            Subscriber::new_test("blockNotification");
+        let all_id = SubscriptionId::Number(0 as u64);
+        let all_sink = all_subscriber.assign_id(all_id.clone()).unwrap();
+        subscriptions.add_block_subscription(&all_id, None, &all_sink);
+
+        let (mentions_subscriber, _id_receiver, mut mentions_receiver) =
+            Subscriber::new_test("blockNotification");
+        let mentions_id = SubscriptionId::Number(1 as u64);
+        let mentions_sink = mentions_subscriber.assign_id(mentions_id.clone()).unwrap();
+        subscriptions.add_block_subscription(&mentions_id, Some(bob.pubkey()), &mentions_sink);
+
+        let (other_subscriber, _id_receiver, mut other_receiver) =
+            Subscriber::new_test("blockNotification");
+        let other_id = SubscriptionId::Number(2 as u64);
+        let other_sink = other_subscriber.assign_id(other_id.clone()).unwrap();
+        subscriptions.add_block_subscription(&other_id, Some(Pubkey::new_rand()), &other_sink);
+
+        // Slot 2 is not rooted and produces no notification
+        subscriptions.notify_blocks(&[1, 2], &blocktree);
+
+        for (receiver, sub_id) in vec![(&mut all_receiver, 0), (&mut mentions_receiver, 1)] {
+            // The notifications are sent from the block notification thread
+            if let Some(Ok(response)) = receiver.by_ref().wait().next() {
+                let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+                assert_eq!(response["method"], "blockNotification");
+                assert_eq!(response["params"]["subscription"], sub_id);
+                let result = &response["params"]["result"];
+                assert_eq!(result["slot"], 1);
+                assert_eq!(result["block"]["parentSlot"], 0);
+                assert_eq!(result["block"]["transactions"].as_array().unwrap().len(), 1);
+            } else {
+                panic!("expected a block notification");
+            }
+        }
+
+        assert!(subscriptions.remove_block_subscription(&all_id));
+        assert!(subscriptions.remove_block_subscription(&mentions_id));
+        assert!(subscriptions.remove_block_subscription(&other_id));
+        assert!(subscriptions.block_subscriptions.read().unwrap().is_empty());
+
+        // The subscription without matching transactions ends without a notification
+        drop(other_sink);
+        assert_eq!(other_receiver.poll().unwrap(), Async::Ready(None));
+
+        drop(subscriptions);
+        drop(blocktree);
+        Blocktree::destroy(&ledger_path).unwrap();
+    }
+
+    #[test]
+    fn test_drop_slow_subscriber() {
+        let (subscriber, _id_receiver, transport_receiver) =
+            Subscriber::new_test("rootNotification");
+        let sub_id = SubscriptionId::Number(0 as u64);
+        let sink = subscriber.assign_id(sub_id.clone()).unwrap();
+        let subscriptions = RpcSubscriptions::default();
+        subscriptions.add_root_subscription(&sub_id, &sink);
+        subscriptions
+            .subscription_counts()
+            .lock()
+            .unwrap()
+            .add(sub_id.clone(), 0);
+        assert!(subscriptions.has_subscription(&sub_id));
+
+        // The test transport has room for a single notification, and nothing consumes it
+        subscriptions.notify_roots(vec![3, 1, 2]);
+        assert!(!subscriptions.has_subscription(&sub_id));
+        // The dropped subscription no longer counts against the limits
+        let subscription_counts = subscriptions.subscription_counts().lock().unwrap();
+        assert_eq!(subscription_counts.active(), 0);
+        assert_eq!(subscription_counts.per_connection(0), 0);
+        drop(subscription_counts);
+
+        // Notifications up to the point the subscriber fell behind were still queued, in
+        // ascending order
+        drop(sink);
+        drop(subscriptions);
+        let responses: Vec<String> = transport_receiver
+            .wait()
+            .map(|response| response.unwrap())
+            .collect();
+        let expected: Vec<String> = (1..=2)
+            .map(|root| {
+                format!(
+                    r#"{{"jsonrpc":"2.0","method":"rootNotification","params":{{"result":{},"subscription":0}}}}"#,
+                    root
+                )
+            })
+            .collect();
+        assert_eq!(expected, responses);
+    }
 }
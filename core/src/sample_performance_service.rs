@@ -0,0 +1,127 @@
+//! The `sample_performance_service` periodically records how many slots and transactions the
+//! root bank advanced by, for the `getRecentPerformanceSamples` RPC.
+
+use solana_ledger::bank_forks::BankForks;
+use solana_sdk::clock::Slot;
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    thread::{self, sleep, Builder, JoinHandle},
+    time::{Duration, Instant},
+};
+
+const SAMPLE_INTERVAL: u64 = 60;
+const SLEEP_INTERVAL: u64 = 500;
+
+/// 12 hours of samples
+pub const MAX_PERF_SAMPLES: usize = 720;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PerfSample {
+    pub slot: Slot,
+    pub num_transactions: u64,
+    pub num_slots: u64,
+    pub sample_period_secs: u16,
+}
+
+/// The most recent samples, newest first
+pub type PerfSamples = Arc<RwLock<VecDeque<PerfSample>>>;
+
+struct SampleSnapshot {
+    slot: Slot,
+    transaction_count: u64,
+    time: Instant,
+}
+
+pub struct SamplePerformanceService {
+    thread_hdl: JoinHandle<()>,
+}
+
+impl SamplePerformanceService {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        bank_forks: &Arc<RwLock<BankForks>>,
+        perf_samples: &PerfSamples,
+        exit: &Arc<AtomicBool>,
+    ) -> Self {
+        let bank_forks = bank_forks.clone();
+        let perf_samples = perf_samples.clone();
+        let exit = exit.clone();
+        let thread_hdl = Builder::new()
+            .name("solana-sample-performance".to_string())
+            .spawn(move || Self::run(&bank_forks, &perf_samples, &exit))
+            .unwrap();
+        Self { thread_hdl }
+    }
+
+    fn snapshot(bank_forks: &RwLock<BankForks>) -> SampleSnapshot {
+        let bank_forks = bank_forks.read().unwrap();
+        let bank = bank_forks.get(bank_forks.root()).unwrap();
+        SampleSnapshot {
+            slot: bank.slot(),
+            transaction_count: bank.transaction_count(),
+            time: Instant::now(),
+        }
+    }
+
+    fn run(bank_forks: &RwLock<BankForks>, perf_samples: &PerfSamples, exit: &AtomicBool) {
+        let mut last = Self::snapshot(bank_forks);
+        while !exit.load(Ordering::Relaxed) {
+            if last.time.elapsed().as_secs() >= SAMPLE_INTERVAL {
+                let current = Self::snapshot(bank_forks);
+                let sample = PerfSample {
+                    slot: current.slot,
+                    num_transactions: current
+                        .transaction_count
+                        .saturating_sub(last.transaction_count),
+                    num_slots: current.slot.saturating_sub(last.slot),
+                    sample_period_secs: current.time.duration_since(last.time).as_secs() as u16,
+                };
+                record_sample(perf_samples, sample);
+                last = current;
+            }
+            sleep(Duration::from_millis(SLEEP_INTERVAL));
+        }
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
+
+fn record_sample(perf_samples: &PerfSamples, sample: PerfSample) {
+    let mut perf_samples = perf_samples.write().unwrap();
+    perf_samples.push_front(sample);
+    perf_samples.truncate(MAX_PERF_SAMPLES);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_sample() {
+        let perf_samples = PerfSamples::default();
+        for slot in 0..MAX_PERF_SAMPLES as u64 + 10 {
+            record_sample(
+                &perf_samples,
+                PerfSample {
+                    slot,
+                    num_transactions: 1,
+                    num_slots: 1,
+                    sample_period_secs: 60,
+                },
+            );
+        }
+        let perf_samples = perf_samples.read().unwrap();
+        assert_eq!(perf_samples.len(), MAX_PERF_SAMPLES);
+        assert_eq!(
+            perf_samples.front().unwrap().slot,
+            MAX_PERF_SAMPLES as u64 + 9
+        );
+        assert_eq!(perf_samples.back().unwrap().slot, 10);
+    }
+}
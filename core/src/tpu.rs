@@ -8,6 +8,7 @@ use crate::{
     cluster_info_vote_listener::ClusterInfoVoteListener,
     fetch_stage::FetchStage,
     poh_recorder::{PohRecorder, WorkingBankEntry},
+    rpc_subscriptions::RpcSubscriptions,
     sigverify::TransactionSigVerifier,
     sigverify_stage::{DisabledSigVerifier, SigVerifyStage},
 };
@@ -46,6 +47,7 @@ impl Tpu {
         broadcast_type: &BroadcastStageType,
         exit: &Arc<AtomicBool>,
         shred_version: u16,
+        subscriptions: &Arc<RpcSubscriptions>,
     ) -> Self {
         let (packet_sender, packet_receiver) = channel();
         let fetch_stage = FetchStage::new_with_sender(
@@ -72,6 +74,7 @@ impl Tpu {
             sigverify_disabled,
             verified_vote_sender,
             &poh_recorder,
+            subscriptions,
         );
 
         let banking_stage = BankingStage::new(
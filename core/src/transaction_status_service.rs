@@ -54,14 +54,21 @@ impl TransactionStatusService {
             transactions,
             statuses,
             balances,
+            transaction_logs,
+            transaction_compute_units,
         } = write_transaction_status_receiver.recv_timeout(Duration::from_secs(1))?;
 
         let slot = bank.slot();
-        for (((transaction, (status, hash_age_kind)), pre_balances), post_balances) in transactions
+        for (
+            ((((transaction, (status, hash_age_kind)), pre_balances), post_balances), log_messages),
+            compute_units_consumed,
+        ) in transactions
             .iter()
             .zip(statuses)
             .zip(balances.pre_balances)
             .zip(balances.post_balances)
+            .zip(transaction_logs)
+            .zip(transaction_compute_units)
         {
             if Bank::can_commit(&status) && !transaction.signatures.is_empty() {
                 let fee_hash = if let Some(HashAgeKind::DurableNonce) = hash_age_kind {
@@ -73,14 +80,20 @@ impl TransactionStatusService {
                     .get_fee_calculator(&fee_hash)
                     .expect("FeeCalculator must exist");
                 let fee = fee_calculator.calculate_fee(transaction.message());
+                let (writable_keys, readonly_keys) =
+                    transaction.message.get_account_keys_by_lock_type();
                 blocktree
                     .write_transaction_status(
                         (slot, transaction.signatures[0]),
+                        writable_keys,
+                        readonly_keys,
                         &RpcTransactionStatus {
                             status,
                             fee,
                             pre_balances,
                             post_balances,
+                            log_messages: Some(log_messages),
+                            compute_units_consumed: Some(compute_units_consumed),
                         },
                     )
                     .expect("Expect database write to succeed");
@@ -10,9 +10,11 @@ use crate::{
     poh_recorder::PohRecorder,
     poh_service::PohService,
     rpc::JsonRpcConfig,
+    rpc_pubsub::PubSubConfig,
     rpc_pubsub_service::PubSubService,
     rpc_service::JsonRpcService,
     rpc_subscriptions::RpcSubscriptions,
+    sample_performance_service::{PerfSamples, SamplePerformanceService},
     sigverify,
     storage_stage::StorageState,
     tpu::Tpu,
@@ -62,6 +64,7 @@ pub struct ValidatorConfig {
     pub storage_slots_per_turn: u64,
     pub account_paths: Vec<PathBuf>,
     pub rpc_config: JsonRpcConfig,
+    pub pubsub_config: PubSubConfig,
     pub snapshot_config: Option<SnapshotConfig>,
     pub max_ledger_slots: Option<u64>,
     pub broadcast_stage_type: BroadcastStageType,
@@ -82,6 +85,7 @@ impl Default for ValidatorConfig {
             max_ledger_slots: None,
             account_paths: Vec::new(),
             rpc_config: JsonRpcConfig::default(),
+            pubsub_config: PubSubConfig::default(),
             snapshot_config: None,
             broadcast_stage_type: BroadcastStageType::Standard,
             partition_cfg: None,
@@ -113,6 +117,7 @@ pub struct Validator {
     rpc_service: Option<JsonRpcService>,
     rpc_pubsub_service: Option<PubSubService>,
     transaction_status_service: Option<TransactionStatusService>,
+    sample_performance_service: Option<SamplePerformanceService>,
     gossip_service: GossipService,
     poh_recorder: Arc<Mutex<PohRecorder>>,
     poh_service: PohService,
@@ -216,6 +221,7 @@ impl Validator {
 
         let blocktree = Arc::new(blocktree);
 
+        let perf_samples = PerfSamples::default();
         let rpc_service = if node.info.rpc.port() == 0 {
             None
         } else {
@@ -230,10 +236,13 @@ impl Validator {
                 ledger_path,
                 storage_state.clone(),
                 validator_exit.clone(),
+                perf_samples.clone(),
             ))
         };
 
-        let subscriptions = Arc::new(RpcSubscriptions::default());
+        let subscriptions = Arc::new(RpcSubscriptions::new(
+            bank.transaction_log_collector_config.clone(),
+        ));
         let rpc_pubsub_service = if node.info.rpc_pubsub.port() == 0 {
             None
         } else {
@@ -243,6 +252,7 @@ impl Validator {
                     IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
                     node.info.rpc_pubsub.port(),
                 ),
+                config.pubsub_config.clone(),
                 &exit,
             ))
         };
@@ -262,6 +272,16 @@ impl Validator {
                 (None, None)
             };
 
+        let sample_performance_service = if rpc_service.is_some() {
+            Some(SamplePerformanceService::new(
+                &bank_forks,
+                &perf_samples,
+                &exit,
+            ))
+        } else {
+            None
+        };
+
         info!(
             "Starting PoH: epoch={} slot={} tick_height={} blockhash={} leader={:?}",
             bank.epoch(),
@@ -394,6 +414,7 @@ impl Validator {
             &config.broadcast_stage_type,
             &exit,
             shred_version,
+            &subscriptions,
         );
 
         datapoint_info!("validator-new", ("id", id.to_string(), String));
@@ -403,6 +424,7 @@ impl Validator {
             rpc_service,
             rpc_pubsub_service,
             transaction_status_service,
+            sample_performance_service,
             tpu,
             tvu,
             poh_service,
@@ -461,6 +483,9 @@ impl Validator {
         if let Some(transaction_status_service) = self.transaction_status_service {
             transaction_status_service.join()?;
         }
+        if let Some(sample_performance_service) = self.sample_performance_service {
+            sample_performance_service.join()?;
+        }
 
         self.gossip_service.join()?;
         self.tpu.join()?;
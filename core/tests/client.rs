@@ -1,10 +1,28 @@
-use solana_client::rpc_client::RpcClient;
-use solana_core::validator::new_validator_for_tests;
+use solana_client::{
+    pubsub_client::PubsubClient,
+    rpc_client::RpcClient,
+    rpc_request::SlotInfo,
+    tpu_client::{TpuClient, TpuClientConfig},
+};
+use solana_core::{
+    rpc_pubsub::PubSubConfig, rpc_pubsub_service::PubSubService,
+    rpc_subscriptions::RpcSubscriptions, validator::new_validator_for_tests,
+};
+use solana_ledger::{
+    bank_forks::BankForks,
+    genesis_utils::{create_genesis_config, GenesisConfigInfo},
+};
+use solana_runtime::bank::Bank;
 use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::message::Message;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::KeypairUtil;
+use solana_sdk::system_instruction;
 use solana_sdk::system_transaction;
 use std::fs::remove_dir_all;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
 use std::thread::sleep;
 use std::time::{Duration, Instant};
 
@@ -57,3 +75,157 @@ fn test_rpc_client() {
     server.close().unwrap();
     remove_dir_all(ledger_path).unwrap();
 }
+
+fn start_pubsub_service(
+    subscriptions: &Arc<RpcSubscriptions>,
+    pubsub_addr: SocketAddr,
+) -> (PubSubService, Arc<AtomicBool>) {
+    let exit = Arc::new(AtomicBool::new(false));
+    let pubsub_service =
+        PubSubService::new(subscriptions, pubsub_addr, PubSubConfig::default(), &exit);
+    // Give the server a moment to start listening
+    sleep(Duration::from_millis(200));
+    (pubsub_service, exit)
+}
+
+fn pubsub_addr() -> SocketAddr {
+    let port = solana_net_utils::find_available_port_in_range((10_000, 65_535)).unwrap();
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port)
+}
+
+#[test]
+fn test_account_and_signature_subscription() {
+    solana_logger::setup();
+
+    let GenesisConfigInfo {
+        genesis_config,
+        mint_keypair: alice,
+        ..
+    } = create_genesis_config(10_000);
+    let bank = Bank::new(&genesis_config);
+    let blockhash = bank.last_blockhash();
+    let bank_forks = Arc::new(RwLock::new(BankForks::new(0, bank)));
+    let bob_pubkey = Pubkey::new_rand();
+
+    let subscriptions = Arc::new(RpcSubscriptions::default());
+    let pubsub_addr = pubsub_addr();
+    let (pubsub_service, exit) = start_pubsub_service(&subscriptions, pubsub_addr);
+    let url = format!("ws://{}", pubsub_addr);
+
+    let tx = system_transaction::transfer(&alice, &bob_pubkey, 20, blockhash);
+    let (mut account_subscription, account_receiver) =
+        PubsubClient::account_subscribe(&url, &bob_pubkey).unwrap();
+    let (mut signature_subscription, signature_receiver) =
+        PubsubClient::signature_subscribe(&url, &tx.signatures[0]).unwrap();
+
+    bank_forks
+        .read()
+        .unwrap()
+        .get(0)
+        .unwrap()
+        .process_transaction(&tx)
+        .unwrap();
+    subscriptions.notify_subscribers(0, &bank_forks);
+
+    let account = account_receiver
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap();
+    assert_eq!(account.lamports, 20);
+    assert_eq!(
+        signature_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap(),
+        Ok(())
+    );
+
+    account_subscription.shutdown().unwrap();
+    signature_subscription.shutdown().unwrap();
+    exit.store(true, Ordering::Relaxed);
+    pubsub_service.close().unwrap();
+}
+
+#[test]
+fn test_slot_subscription_reconnect() {
+    solana_logger::setup();
+
+    let subscriptions = Arc::new(RpcSubscriptions::default());
+    let pubsub_addr = pubsub_addr();
+    let (pubsub_service, exit) = start_pubsub_service(&subscriptions, pubsub_addr);
+
+    let (mut slot_subscription, slot_receiver) =
+        PubsubClient::slot_subscribe(&format!("ws://{}", pubsub_addr)).unwrap();
+    subscriptions.notify_slot(1, 0, 0);
+    assert_eq!(
+        slot_receiver.recv_timeout(Duration::from_secs(5)).unwrap(),
+        SlotInfo {
+            slot: 1,
+            parent: 0,
+            root: 0,
+        }
+    );
+
+    // Restart the service, dropping the subscription on the server side
+    exit.store(true, Ordering::Relaxed);
+    pubsub_service.close().unwrap();
+    let subscriptions = Arc::new(RpcSubscriptions::default());
+    let (pubsub_service, exit) = start_pubsub_service(&subscriptions, pubsub_addr);
+
+    // The client resubscribes once it reconnects
+    let now = Instant::now();
+    let mut slot_info = None;
+    while slot_info.is_none() && now.elapsed() < Duration::from_secs(10) {
+        subscriptions.notify_slot(2, 1, 0);
+        slot_info = slot_receiver.recv_timeout(Duration::from_millis(500)).ok();
+    }
+    assert_eq!(
+        slot_info,
+        Some(SlotInfo {
+            slot: 2,
+            parent: 1,
+            root: 0,
+        })
+    );
+
+    slot_subscription.shutdown().unwrap();
+    exit.store(true, Ordering::Relaxed);
+    pubsub_service.close().unwrap();
+}
+
+#[test]
+fn test_tpu_client() {
+    solana_logger::setup();
+
+    let (server, leader_data, alice, ledger_path) = new_validator_for_tests();
+    let bob_pubkey = Pubkey::new_rand();
+
+    let rpc_client = Arc::new(RpcClient::new_socket(leader_data.rpc));
+    let tpu_client = TpuClient::new(
+        rpc_client.clone(),
+        &format!("ws://{}", leader_data.rpc_pubsub),
+        TpuClientConfig::default(),
+    )
+    .unwrap();
+
+    let messages = vec![
+        Message::new(vec![system_instruction::transfer(
+            &alice.pubkey(),
+            &bob_pubkey,
+            20,
+        )]),
+        Message::new(vec![system_instruction::transfer(
+            &alice.pubkey(),
+            &bob_pubkey,
+            1_000_000_000,
+        )]),
+    ];
+    let transaction_errors = tpu_client
+        .send_and_confirm_messages(&messages, &[&alice])
+        .unwrap();
+    assert_eq!(transaction_errors[0], None);
+    assert!(transaction_errors[1].is_some());
+    assert_eq!(rpc_client.get_balance(&bob_pubkey).unwrap(), 20);
+
+    drop(tpu_client);
+    server.close().unwrap();
+    remove_dir_all(ledger_path).unwrap();
+}
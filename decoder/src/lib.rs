@@ -0,0 +1,254 @@
+//! Decodes the account data and instructions of the built-in programs into JSON
+
+#[macro_use]
+extern crate lazy_static;
+#[macro_use]
+extern crate serde_derive;
+
+pub mod parse_account_data;
+pub mod parse_config;
+pub mod parse_instruction;
+mod parse_nonce;
+mod parse_stake;
+mod parse_system;
+mod parse_vote;
+
+use crate::{
+    parse_account_data::{parse_account_data, ParsedAccount},
+    parse_instruction::{parse_instruction, ParsedInstruction},
+};
+use solana_sdk::{
+    account::Account,
+    clock::Epoch,
+    instruction::CompiledInstruction,
+    message::{Message, MessageHeader},
+    pubkey::Pubkey,
+    transaction::Transaction,
+};
+
+pub mod validator_info {
+    solana_sdk::declare_id!("Va1idator1nfo111111111111111111111111111111");
+}
+
+/// How RPC methods should encode accounts and transactions
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum UiEncoding {
+    /// Return accounts and transactions as they are stored
+    Binary,
+    /// Decode the data of the built-in programs into JSON, where possible
+    JsonParsed,
+}
+
+impl Default for UiEncoding {
+    fn default() -> Self {
+        UiEncoding::Binary
+    }
+}
+
+/// An account, as returned by the RPC account methods
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum UiAccount {
+    Binary(Account),
+    JsonParsed(UiParsedAccount),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiParsedAccount {
+    pub lamports: u64,
+    pub data: ParsedAccount,
+    pub owner: String,
+    pub executable: bool,
+    pub rent_epoch: Epoch,
+}
+
+impl UiAccount {
+    /// Accounts that cannot be parsed are returned in binary
+    pub fn encode(pubkey: &Pubkey, account: Account, encoding: UiEncoding) -> Self {
+        if encoding == UiEncoding::JsonParsed {
+            if let Ok(data) = parse_account_data(pubkey, &account.owner, &account.data) {
+                return UiAccount::JsonParsed(UiParsedAccount {
+                    lamports: account.lamports,
+                    data,
+                    owner: account.owner.to_string(),
+                    executable: account.executable,
+                    rent_epoch: account.rent_epoch,
+                });
+            }
+        }
+        UiAccount::Binary(account)
+    }
+}
+
+/// A transaction, as returned by the RPC block methods
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum UiTransaction {
+    Binary(Transaction),
+    JsonParsed(UiParsedTransaction),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiParsedTransaction {
+    pub signatures: Vec<String>,
+    pub message: UiParsedMessage,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiParsedMessage {
+    pub header: MessageHeader,
+    pub account_keys: Vec<String>,
+    pub recent_blockhash: String,
+    pub instructions: Vec<UiInstruction>,
+}
+
+/// An instruction of a JSON parsed transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum UiInstruction {
+    Parsed(ParsedInstruction),
+    Compiled(UiCompiledInstruction),
+}
+
+/// An instruction that could not be parsed, with its data as a base-58 encoded string
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiCompiledInstruction {
+    pub program_id_index: u8,
+    pub accounts: Vec<u8>,
+    pub data: String,
+}
+
+impl UiTransaction {
+    pub fn encode(transaction: Transaction, encoding: UiEncoding) -> Self {
+        match encoding {
+            UiEncoding::Binary => UiTransaction::Binary(transaction),
+            UiEncoding::JsonParsed => UiTransaction::JsonParsed(UiParsedTransaction {
+                signatures: transaction
+                    .signatures
+                    .iter()
+                    .map(|signature| signature.to_string())
+                    .collect(),
+                message: UiParsedMessage::from(&transaction.message),
+            }),
+        }
+    }
+}
+
+impl From<&Message> for UiParsedMessage {
+    fn from(message: &Message) -> Self {
+        Self {
+            header: message.header.clone(),
+            account_keys: message
+                .account_keys
+                .iter()
+                .map(|pubkey| pubkey.to_string())
+                .collect(),
+            recent_blockhash: message.recent_blockhash.to_string(),
+            instructions: message
+                .instructions
+                .iter()
+                .map(|instruction| UiInstruction::parse(instruction, message))
+                .collect(),
+        }
+    }
+}
+
+impl UiInstruction {
+    /// Instructions that cannot be parsed, including those whose program id index is out of
+    /// range, are returned in compiled form
+    pub fn parse(instruction: &CompiledInstruction, message: &Message) -> Self {
+        let parsed = message
+            .account_keys
+            .get(instruction.program_id_index as usize)
+            .and_then(|program_id| {
+                parse_instruction(program_id, instruction, &message.account_keys).ok()
+            });
+        match parsed {
+            Some(parsed) => UiInstruction::Parsed(parsed),
+            None => UiInstruction::Compiled(UiCompiledInstruction {
+                program_id_index: instruction.program_id_index,
+                accounts: instruction.accounts.clone(),
+                data: bs58::encode(&instruction.data).into_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        hash::Hash,
+        signature::{Keypair, KeypairUtil},
+        system_transaction,
+    };
+
+    #[test]
+    fn test_encode_account() {
+        let pubkey = Pubkey::new_rand();
+        let account = Account::new(42, 8, &Pubkey::new_rand());
+        assert_eq!(
+            UiAccount::encode(&pubkey, account.clone(), UiEncoding::Binary),
+            UiAccount::Binary(account.clone())
+        );
+        // Accounts of programs without a parser stay in binary
+        assert_eq!(
+            UiAccount::encode(&pubkey, account.clone(), UiEncoding::JsonParsed),
+            UiAccount::Binary(account)
+        );
+    }
+
+    #[test]
+    fn test_encode_transaction() {
+        let from = Keypair::new();
+        let to = Pubkey::new_rand();
+        let mut transaction = system_transaction::transfer(&from, &to, 42, Hash::default());
+        assert_eq!(
+            UiTransaction::encode(transaction.clone(), UiEncoding::Binary),
+            UiTransaction::Binary(transaction.clone())
+        );
+
+        // Make the second instruction one the system program cannot parse
+        let mut instruction = transaction.message.instructions[0].clone();
+        instruction.data = vec![255];
+        transaction.message.instructions.push(instruction);
+        let encoded = UiTransaction::encode(transaction.clone(), UiEncoding::JsonParsed);
+        let value = serde_json::to_value(&encoded).unwrap();
+        assert_eq!(
+            value["signatures"][0],
+            transaction.signatures[0].to_string()
+        );
+        assert_eq!(value["message"]["accountKeys"][1], to.to_string());
+        assert_eq!(value["message"]["instructions"][0]["program"], "system");
+        assert_eq!(
+            value["message"]["instructions"][0]["parsed"]["type"],
+            "transfer"
+        );
+        assert_eq!(
+            value["message"]["instructions"][0]["parsed"]["info"]["lamports"],
+            42
+        );
+        assert_eq!(value["message"]["instructions"][1]["data"], "5Q");
+        assert_eq!(
+            serde_json::from_value::<UiTransaction>(value).unwrap(),
+            encoded
+        );
+
+        // An instruction whose program id index is out of range stays compiled
+        let mut instruction = transaction.message.instructions[0].clone();
+        instruction.program_id_index = 255;
+        assert_eq!(
+            UiInstruction::parse(&instruction, &transaction.message),
+            UiInstruction::Compiled(UiCompiledInstruction {
+                program_id_index: 255,
+                accounts: instruction.accounts.clone(),
+                data: bs58::encode(&instruction.data).into_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{
+    parse_config::parse_config, parse_nonce::parse_nonce, parse_stake::parse_stake,
+    parse_vote::parse_vote,
+};
+use serde_json::Value;
+use solana_sdk::{nonce_program, pubkey::Pubkey};
+use std::collections::HashMap;
+use thiserror::Error;
+
+lazy_static! {
+    static ref PARSABLE_PROGRAM_IDS: HashMap<Pubkey, ParsableAccount> = {
+        let mut m = HashMap::new();
+        m.insert(solana_config_program::id(), ParsableAccount::Config);
+        m.insert(nonce_program::id(), ParsableAccount::Nonce);
+        m.insert(solana_stake_program::id(), ParsableAccount::Stake);
+        m.insert(solana_vote_program::id(), ParsableAccount::Vote);
+        m
+    };
+}
+
+/// The programs whose account data can be parsed
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ParsableAccount {
+    Config,
+    Nonce,
+    Stake,
+    Vote,
+}
+
+#[derive(Error, Debug)]
+pub enum ParseAccountError {
+    #[error("{0:?} account not parsable")]
+    AccountNotParsable(ParsableAccount),
+
+    #[error("Program not parsable")]
+    ProgramNotParsable,
+
+    #[error("Serde json error")]
+    SerdeJsonError(#[from] serde_json::error::Error),
+}
+
+/// Account data decoded by the parser of its owning program
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedAccount {
+    pub program: ParsableAccount,
+    pub parsed: Value,
+}
+
+pub fn parse_account_data(
+    pubkey: &Pubkey,
+    program_id: &Pubkey,
+    data: &[u8],
+) -> Result<ParsedAccount, ParseAccountError> {
+    let program = *PARSABLE_PROGRAM_IDS
+        .get(program_id)
+        .ok_or(ParseAccountError::ProgramNotParsable)?;
+    let parsed = match program {
+        ParsableAccount::Config => serde_json::to_value(parse_config(data, pubkey)?)?,
+        ParsableAccount::Nonce => serde_json::to_value(parse_nonce(data)?)?,
+        ParsableAccount::Stake => serde_json::to_value(parse_stake(data)?)?,
+        ParsableAccount::Vote => serde_json::to_value(parse_vote(data)?)?,
+    };
+    Ok(ParsedAccount { program, parsed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{hash::Hash, nonce_state::NonceState};
+
+    #[test]
+    fn test_parse_account_data() {
+        let other_program = Pubkey::new_rand();
+        let data = vec![0; 4];
+        assert!(parse_account_data(&Pubkey::new_rand(), &other_program, &data).is_err());
+
+        let nonce_authority = Pubkey::new_rand();
+        let nonce_state = NonceState::Initialized(
+            solana_sdk::nonce_state::Meta::new(&nonce_authority),
+            Hash::default(),
+        );
+        let data = bincode::serialize(&nonce_state).unwrap();
+        let parsed = parse_account_data(&Pubkey::new_rand(), &nonce_program::id(), &data).unwrap();
+        assert_eq!(parsed.program, ParsableAccount::Nonce);
+        assert_eq!(parsed.parsed["type"], "initialized");
+        assert_eq!(
+            parsed.parsed["info"]["authority"],
+            nonce_authority.to_string()
+        );
+
+        // Data that does not match the owning program's state is rejected
+        assert!(parse_account_data(&Pubkey::new_rand(), &nonce_program::id(), &[9; 4]).is_err());
+    }
+}
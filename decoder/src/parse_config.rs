@@ -0,0 +1,132 @@
+use crate::{
+    parse_account_data::{ParsableAccount, ParseAccountError},
+    validator_info,
+};
+use bincode::deserialize;
+use serde_json::Value;
+use solana_config_program::{get_config_data, ConfigKeys};
+use solana_sdk::pubkey::Pubkey;
+use solana_stake_program::config::Config as StakeConfig;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type", content = "info")]
+pub enum ConfigAccountType {
+    StakeConfig(UiConfig<UiStakeConfig>),
+    ValidatorInfo(UiConfig<Value>),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiConfigKey {
+    pub pubkey: String,
+    pub signer: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiConfig<T> {
+    pub keys: Vec<UiConfigKey>,
+    pub config_data: T,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiStakeConfig {
+    pub warmup_cooldown_rate: f64,
+    pub slash_penalty: u8,
+}
+
+pub fn parse_config(data: &[u8], pubkey: &Pubkey) -> Result<ConfigAccountType, ParseAccountError> {
+    let not_parsable = || ParseAccountError::AccountNotParsable(ParsableAccount::Config);
+    let key_list: ConfigKeys = deserialize(data).map_err(|_| not_parsable())?;
+    let config_data = get_config_data(data).map_err(|_| not_parsable())?;
+    let keys = key_list
+        .keys
+        .iter()
+        .map(|(pubkey, signer)| UiConfigKey {
+            pubkey: pubkey.to_string(),
+            signer: *signer,
+        })
+        .collect();
+    if pubkey == &solana_stake_program::config::id() {
+        let stake_config: StakeConfig = deserialize(config_data).map_err(|_| not_parsable())?;
+        Ok(ConfigAccountType::StakeConfig(UiConfig {
+            keys,
+            config_data: UiStakeConfig {
+                warmup_cooldown_rate: stake_config.warmup_cooldown_rate,
+                slash_penalty: stake_config.slash_penalty,
+            },
+        }))
+    } else if key_list.keys.contains(&(validator_info::id(), false)) {
+        let validator_info: String = deserialize(config_data).map_err(|_| not_parsable())?;
+        Ok(ConfigAccountType::ValidatorInfo(UiConfig {
+            keys,
+            config_data: serde_json::from_str(&validator_info)?,
+        }))
+    } else {
+        Err(not_parsable())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use solana_config_program::create_config_account;
+
+    #[test]
+    fn test_parse_config() {
+        let stake_config = StakeConfig {
+            warmup_cooldown_rate: 0.25,
+            slash_penalty: 50,
+        };
+        let account = create_config_account(vec![], &stake_config, 10);
+        assert_eq!(
+            parse_config(&account.data, &solana_stake_program::config::id()).unwrap(),
+            ConfigAccountType::StakeConfig(UiConfig {
+                keys: vec![],
+                config_data: UiStakeConfig {
+                    warmup_cooldown_rate: 0.25,
+                    slash_penalty: 50,
+                },
+            })
+        );
+
+        let validator_pubkey = Pubkey::new_rand();
+        let keys = vec![(validator_info::id(), false), (validator_pubkey, true)];
+        let validator_info = ValidatorInfo {
+            info: json!({ "name": "Solana" }).to_string(),
+        };
+        let account = create_config_account(keys, &validator_info, 10);
+        assert_eq!(
+            parse_config(&account.data, &Pubkey::new_rand()).unwrap(),
+            ConfigAccountType::ValidatorInfo(UiConfig {
+                keys: vec![
+                    UiConfigKey {
+                        pubkey: validator_info::id().to_string(),
+                        signer: false,
+                    },
+                    UiConfigKey {
+                        pubkey: validator_pubkey.to_string(),
+                        signer: true,
+                    }
+                ],
+                config_data: json!({ "name": "Solana" }),
+            })
+        );
+
+        let bad_data = vec![0; 4];
+        assert!(parse_config(&bad_data, &Pubkey::new_rand()).is_err());
+    }
+
+    #[derive(Serialize, Default)]
+    struct ValidatorInfo {
+        info: String,
+    }
+
+    impl solana_config_program::ConfigState for ValidatorInfo {
+        fn max_space() -> u64 {
+            1000
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    parse_nonce::parse_nonce_instruction, parse_stake::parse_stake_instruction,
+    parse_system::parse_system_instruction, parse_vote::parse_vote_instruction,
+};
+use serde_json::Value;
+use solana_sdk::{instruction::CompiledInstruction, nonce_program, pubkey::Pubkey, system_program};
+use std::collections::HashMap;
+use thiserror::Error;
+
+lazy_static! {
+    static ref PARSABLE_PROGRAM_IDS: HashMap<Pubkey, ParsableProgram> = {
+        let mut m = HashMap::new();
+        m.insert(nonce_program::id(), ParsableProgram::Nonce);
+        m.insert(solana_stake_program::id(), ParsableProgram::Stake);
+        m.insert(system_program::id(), ParsableProgram::System);
+        m.insert(solana_vote_program::id(), ParsableProgram::Vote);
+        m
+    };
+}
+
+/// The programs whose instructions can be parsed
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ParsableProgram {
+    Nonce,
+    Stake,
+    System,
+    Vote,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ParseInstructionError {
+    #[error("{0:?} instruction not parsable")]
+    InstructionNotParsable(ParsableProgram),
+
+    #[error("{0:?} instruction key mismatch")]
+    InstructionKeyMismatch(ParsableProgram),
+
+    #[error("Program not parsable")]
+    ProgramNotParsable,
+}
+
+/// An instruction decoded by the parser of its program
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedInstruction {
+    pub program: ParsableProgram,
+    pub program_id: String,
+    pub parsed: Value,
+}
+
+pub fn parse_instruction(
+    program_id: &Pubkey,
+    instruction: &CompiledInstruction,
+    account_keys: &[Pubkey],
+) -> Result<ParsedInstruction, ParseInstructionError> {
+    let program = *PARSABLE_PROGRAM_IDS
+        .get(program_id)
+        .ok_or(ParseInstructionError::ProgramNotParsable)?;
+    if instruction
+        .accounts
+        .iter()
+        .any(|index| *index as usize >= account_keys.len())
+    {
+        return Err(ParseInstructionError::InstructionKeyMismatch(program));
+    }
+    let parsed = match program {
+        ParsableProgram::Nonce => parse_nonce_instruction(instruction, account_keys)?,
+        ParsableProgram::Stake => parse_stake_instruction(instruction, account_keys)?,
+        ParsableProgram::System => parse_system_instruction(instruction, account_keys)?,
+        ParsableProgram::Vote => parse_vote_instruction(instruction, account_keys)?,
+    };
+    Ok(ParsedInstruction {
+        program,
+        program_id: program_id.to_string(),
+        parsed,
+    })
+}
+
+/// Checks that an instruction references at least the number of accounts its parser reads
+pub(crate) fn check_num_accounts(
+    accounts: &[u8],
+    num: usize,
+    program: ParsableProgram,
+) -> Result<(), ParseInstructionError> {
+    if accounts.len() < num {
+        Err(ParseInstructionError::InstructionKeyMismatch(program))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use solana_sdk::{message::Message, system_instruction};
+
+    #[test]
+    fn test_parse_instruction() {
+        let from = Pubkey::new_rand();
+        let to = Pubkey::new_rand();
+        let message = Message::new(vec![system_instruction::transfer(&from, &to, 42)]);
+        let instruction = &message.instructions[0];
+        assert_eq!(
+            parse_instruction(&system_program::id(), instruction, &message.account_keys).unwrap(),
+            ParsedInstruction {
+                program: ParsableProgram::System,
+                program_id: system_program::id().to_string(),
+                parsed: json!({
+                    "type": "transfer",
+                    "info": {
+                        "source": from.to_string(),
+                        "destination": to.to_string(),
+                        "lamports": 42,
+                    },
+                }),
+            }
+        );
+
+        assert_eq!(
+            parse_instruction(&Pubkey::new_rand(), instruction, &message.account_keys),
+            Err(ParseInstructionError::ProgramNotParsable)
+        );
+        assert_eq!(
+            parse_instruction(
+                &system_program::id(),
+                instruction,
+                &message.account_keys[..1]
+            ),
+            Err(ParseInstructionError::InstructionKeyMismatch(
+                ParsableProgram::System
+            ))
+        );
+    }
+}
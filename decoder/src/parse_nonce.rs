@@ -0,0 +1,140 @@
+use crate::{
+    parse_account_data::{ParsableAccount, ParseAccountError},
+    parse_instruction::{check_num_accounts, ParsableProgram, ParseInstructionError},
+};
+use serde_json::{json, Value};
+use solana_sdk::{
+    instruction::CompiledInstruction, instruction_processor_utils::limited_deserialize,
+    nonce_instruction::NonceInstruction, nonce_state::NonceState, pubkey::Pubkey,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type", content = "info")]
+pub enum NonceAccountType {
+    Uninitialized,
+    Initialized(UiNonceData),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiNonceData {
+    pub authority: String,
+    pub blockhash: String,
+}
+
+pub fn parse_nonce(data: &[u8]) -> Result<NonceAccountType, ParseAccountError> {
+    let nonce_state: NonceState = bincode::deserialize(data)
+        .map_err(|_| ParseAccountError::AccountNotParsable(ParsableAccount::Nonce))?;
+    Ok(match nonce_state {
+        NonceState::Uninitialized => NonceAccountType::Uninitialized,
+        NonceState::Initialized(meta, blockhash) => NonceAccountType::Initialized(UiNonceData {
+            authority: meta.nonce_authority.to_string(),
+            blockhash: blockhash.to_string(),
+        }),
+    })
+}
+
+pub fn parse_nonce_instruction(
+    instruction: &CompiledInstruction,
+    account_keys: &[Pubkey],
+) -> Result<Value, ParseInstructionError> {
+    let nonce_instruction: NonceInstruction = limited_deserialize(&instruction.data)
+        .map_err(|_| ParseInstructionError::InstructionNotParsable(ParsableProgram::Nonce))?;
+    let account = |i: usize| account_keys[instruction.accounts[i] as usize].to_string();
+    let parsed = match nonce_instruction {
+        NonceInstruction::Nonce => {
+            check_num_accounts(&instruction.accounts, 2, ParsableProgram::Nonce)?;
+            json!({
+                "type": "nonce",
+                "info": {
+                    "nonceAccount": account(0),
+                    "recentBlockhashesSysvar": account(1),
+                },
+            })
+        }
+        NonceInstruction::Withdraw(lamports) => {
+            check_num_accounts(&instruction.accounts, 4, ParsableProgram::Nonce)?;
+            json!({
+                "type": "withdraw",
+                "info": {
+                    "nonceAccount": account(0),
+                    "destination": account(1),
+                    "recentBlockhashesSysvar": account(2),
+                    "rentSysvar": account(3),
+                    "lamports": lamports,
+                },
+            })
+        }
+        NonceInstruction::Initialize(authority) => {
+            check_num_accounts(&instruction.accounts, 3, ParsableProgram::Nonce)?;
+            json!({
+                "type": "initialize",
+                "info": {
+                    "nonceAccount": account(0),
+                    "recentBlockhashesSysvar": account(1),
+                    "rentSysvar": account(2),
+                    "nonceAuthority": authority.to_string(),
+                },
+            })
+        }
+        NonceInstruction::Authorize(authority) => {
+            check_num_accounts(&instruction.accounts, 1, ParsableProgram::Nonce)?;
+            json!({
+                "type": "authorize",
+                "info": {
+                    "nonceAccount": account(0),
+                    "newAuthority": authority.to_string(),
+                },
+            })
+        }
+    };
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{hash::Hash, message::Message, nonce_instruction, nonce_state::Meta};
+
+    #[test]
+    fn test_parse_nonce() {
+        let data = bincode::serialize(&NonceState::Uninitialized).unwrap();
+        assert_eq!(parse_nonce(&data).unwrap(), NonceAccountType::Uninitialized);
+
+        let authority = Pubkey::new_rand();
+        let blockhash = Hash::new(&[1; 32]);
+        let data =
+            bincode::serialize(&NonceState::Initialized(Meta::new(&authority), blockhash)).unwrap();
+        assert_eq!(
+            parse_nonce(&data).unwrap(),
+            NonceAccountType::Initialized(UiNonceData {
+                authority: authority.to_string(),
+                blockhash: blockhash.to_string(),
+            })
+        );
+
+        assert!(parse_nonce(&[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_nonce_instruction() {
+        let nonce_pubkey = Pubkey::new_rand();
+        let authority = Pubkey::new_rand();
+        let instruction = nonce_instruction::authorize(&nonce_pubkey, &authority, &authority);
+        let message = Message::new(vec![instruction]);
+        assert_eq!(
+            parse_nonce_instruction(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "authorize",
+                "info": {
+                    "nonceAccount": nonce_pubkey.to_string(),
+                    "newAuthority": authority.to_string(),
+                },
+            })
+        );
+
+        let mut instruction = message.instructions[0].clone();
+        instruction.accounts = vec![];
+        assert!(parse_nonce_instruction(&instruction, &message.account_keys).is_err());
+    }
+}
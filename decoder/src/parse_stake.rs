@@ -0,0 +1,342 @@
+use crate::{
+    parse_account_data::{ParsableAccount, ParseAccountError},
+    parse_instruction::{check_num_accounts, ParsableProgram, ParseInstructionError},
+};
+use serde_json::{json, Value};
+use solana_sdk::{
+    clock::{Epoch, UnixTimestamp},
+    instruction::CompiledInstruction,
+    instruction_processor_utils::limited_deserialize,
+    pubkey::Pubkey,
+};
+use solana_stake_program::{
+    stake_instruction::StakeInstruction,
+    stake_state::{Authorized, Delegation, Lockup, Meta, Stake, StakeAuthorize, StakeState},
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type", content = "info")]
+pub enum StakeAccountType {
+    Uninitialized,
+    Initialized(UiStakeAccount),
+    Delegated(UiStakeAccount),
+    RewardsPool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiStakeAccount {
+    pub meta: UiMeta,
+    pub stake: Option<UiStake>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiMeta {
+    pub rent_exempt_reserve: u64,
+    pub authorized: UiAuthorized,
+    pub lockup: UiLockup,
+}
+
+impl From<Meta> for UiMeta {
+    fn from(meta: Meta) -> Self {
+        Self {
+            rent_exempt_reserve: meta.rent_exempt_reserve,
+            authorized: meta.authorized.into(),
+            lockup: meta.lockup.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiLockup {
+    pub unix_timestamp: UnixTimestamp,
+    pub epoch: Epoch,
+    pub custodian: String,
+}
+
+impl From<Lockup> for UiLockup {
+    fn from(lockup: Lockup) -> Self {
+        Self {
+            unix_timestamp: lockup.unix_timestamp,
+            epoch: lockup.epoch,
+            custodian: lockup.custodian.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiAuthorized {
+    pub staker: String,
+    pub withdrawer: String,
+}
+
+impl From<Authorized> for UiAuthorized {
+    fn from(authorized: Authorized) -> Self {
+        Self {
+            staker: authorized.staker.to_string(),
+            withdrawer: authorized.withdrawer.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiStake {
+    pub delegation: UiDelegation,
+    pub credits_observed: u64,
+}
+
+impl From<Stake> for UiStake {
+    fn from(stake: Stake) -> Self {
+        Self {
+            delegation: stake.delegation.into(),
+            credits_observed: stake.credits_observed,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiDelegation {
+    pub voter: String,
+    pub stake: u64,
+    pub activation_epoch: Epoch,
+    pub deactivation_epoch: Epoch,
+    pub warmup_cooldown_rate: f64,
+}
+
+impl From<Delegation> for UiDelegation {
+    fn from(delegation: Delegation) -> Self {
+        Self {
+            voter: delegation.voter_pubkey.to_string(),
+            stake: delegation.stake,
+            activation_epoch: delegation.activation_epoch,
+            deactivation_epoch: delegation.deactivation_epoch,
+            warmup_cooldown_rate: delegation.warmup_cooldown_rate,
+        }
+    }
+}
+
+pub fn parse_stake(data: &[u8]) -> Result<StakeAccountType, ParseAccountError> {
+    let stake_state: StakeState = bincode::deserialize(data)
+        .map_err(|_| ParseAccountError::AccountNotParsable(ParsableAccount::Stake))?;
+    Ok(match stake_state {
+        StakeState::Uninitialized => StakeAccountType::Uninitialized,
+        StakeState::Initialized(meta) => StakeAccountType::Initialized(UiStakeAccount {
+            meta: meta.into(),
+            stake: None,
+        }),
+        StakeState::Stake(meta, stake) => StakeAccountType::Delegated(UiStakeAccount {
+            meta: meta.into(),
+            stake: Some(stake.into()),
+        }),
+        StakeState::RewardsPool => StakeAccountType::RewardsPool,
+    })
+}
+
+pub fn parse_stake_instruction(
+    instruction: &CompiledInstruction,
+    account_keys: &[Pubkey],
+) -> Result<Value, ParseInstructionError> {
+    let stake_instruction: StakeInstruction = limited_deserialize(&instruction.data)
+        .map_err(|_| ParseInstructionError::InstructionNotParsable(ParsableProgram::Stake))?;
+    let account = |i: usize| account_keys[instruction.accounts[i] as usize].to_string();
+    let parsed = match stake_instruction {
+        StakeInstruction::Initialize(authorized, lockup) => {
+            check_num_accounts(&instruction.accounts, 2, ParsableProgram::Stake)?;
+            json!({
+                "type": "initialize",
+                "info": {
+                    "stakeAccount": account(0),
+                    "rentSysvar": account(1),
+                    "authorized": UiAuthorized::from(authorized),
+                    "lockup": UiLockup::from(lockup),
+                },
+            })
+        }
+        StakeInstruction::Authorize(new_authorized, authority_type) => {
+            check_num_accounts(&instruction.accounts, 1, ParsableProgram::Stake)?;
+            let authority_type = match authority_type {
+                StakeAuthorize::Staker => "staker",
+                StakeAuthorize::Withdrawer => "withdrawer",
+            };
+            json!({
+                "type": "authorize",
+                "info": {
+                    "stakeAccount": account(0),
+                    "newAuthority": new_authorized.to_string(),
+                    "authorityType": authority_type,
+                },
+            })
+        }
+        StakeInstruction::DelegateStake => {
+            check_num_accounts(&instruction.accounts, 4, ParsableProgram::Stake)?;
+            json!({
+                "type": "delegate",
+                "info": {
+                    "stakeAccount": account(0),
+                    "voteAccount": account(1),
+                    "clockSysvar": account(2),
+                    "stakeConfigAccount": account(3),
+                },
+            })
+        }
+        StakeInstruction::RedeemVoteCredits => {
+            check_num_accounts(&instruction.accounts, 5, ParsableProgram::Stake)?;
+            json!({
+                "type": "redeemVoteCredits",
+                "info": {
+                    "stakeAccount": account(0),
+                    "voteAccount": account(1),
+                    "rewardsPoolAccount": account(2),
+                    "rewardsSysvar": account(3),
+                    "stakeHistorySysvar": account(4),
+                },
+            })
+        }
+        StakeInstruction::Split(lamports) => {
+            check_num_accounts(&instruction.accounts, 2, ParsableProgram::Stake)?;
+            json!({
+                "type": "split",
+                "info": {
+                    "stakeAccount": account(0),
+                    "newSplitAccount": account(1),
+                    "lamports": lamports,
+                },
+            })
+        }
+        StakeInstruction::Withdraw(lamports) => {
+            check_num_accounts(&instruction.accounts, 4, ParsableProgram::Stake)?;
+            json!({
+                "type": "withdraw",
+                "info": {
+                    "stakeAccount": account(0),
+                    "destination": account(1),
+                    "clockSysvar": account(2),
+                    "stakeHistorySysvar": account(3),
+                    "lamports": lamports,
+                },
+            })
+        }
+        StakeInstruction::Deactivate => {
+            check_num_accounts(&instruction.accounts, 2, ParsableProgram::Stake)?;
+            json!({
+                "type": "deactivate",
+                "info": {
+                    "stakeAccount": account(0),
+                    "clockSysvar": account(1),
+                },
+            })
+        }
+    };
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::message::Message;
+    use solana_stake_program::stake_instruction;
+
+    #[test]
+    fn test_parse_stake() {
+        let data = bincode::serialize(&StakeState::Uninitialized).unwrap();
+        assert_eq!(parse_stake(&data).unwrap(), StakeAccountType::Uninitialized);
+
+        let staker = Pubkey::new_rand();
+        let withdrawer = Pubkey::new_rand();
+        let custodian = Pubkey::new_rand();
+        let meta = Meta {
+            rent_exempt_reserve: 42,
+            authorized: Authorized { staker, withdrawer },
+            lockup: Lockup {
+                unix_timestamp: 0,
+                epoch: 1,
+                custodian,
+            },
+        };
+        let expected_meta = UiMeta {
+            rent_exempt_reserve: 42,
+            authorized: UiAuthorized {
+                staker: staker.to_string(),
+                withdrawer: withdrawer.to_string(),
+            },
+            lockup: UiLockup {
+                unix_timestamp: 0,
+                epoch: 1,
+                custodian: custodian.to_string(),
+            },
+        };
+        let data = bincode::serialize(&StakeState::Initialized(meta)).unwrap();
+        assert_eq!(
+            parse_stake(&data).unwrap(),
+            StakeAccountType::Initialized(UiStakeAccount {
+                meta: expected_meta.clone(),
+                stake: None,
+            })
+        );
+
+        let voter_pubkey = Pubkey::new_rand();
+        let stake = Stake {
+            delegation: Delegation {
+                voter_pubkey,
+                stake: 20,
+                activation_epoch: 2,
+                deactivation_epoch: std::u64::MAX,
+                warmup_cooldown_rate: 0.25,
+            },
+            credits_observed: 10,
+            ..Stake::default()
+        };
+        let data = bincode::serialize(&StakeState::Stake(meta, stake)).unwrap();
+        assert_eq!(
+            parse_stake(&data).unwrap(),
+            StakeAccountType::Delegated(UiStakeAccount {
+                meta: expected_meta,
+                stake: Some(UiStake {
+                    delegation: UiDelegation {
+                        voter: voter_pubkey.to_string(),
+                        stake: 20,
+                        activation_epoch: 2,
+                        deactivation_epoch: std::u64::MAX,
+                        warmup_cooldown_rate: 0.25,
+                    },
+                    credits_observed: 10,
+                }),
+            })
+        );
+
+        assert!(parse_stake(&[1, 2, 3, 4]).is_err());
+    }
+
+    #[test]
+    fn test_parse_stake_instruction() {
+        let stake_pubkey = Pubkey::new_rand();
+        let vote_pubkey = Pubkey::new_rand();
+        let authorized_pubkey = Pubkey::new_rand();
+
+        let instruction =
+            stake_instruction::delegate_stake(&stake_pubkey, &authorized_pubkey, &vote_pubkey);
+        let message = Message::new(vec![instruction]);
+        let parsed =
+            parse_stake_instruction(&message.instructions[0], &message.account_keys).unwrap();
+        assert_eq!(parsed["type"], "delegate");
+        assert_eq!(parsed["info"]["stakeAccount"], stake_pubkey.to_string());
+        assert_eq!(parsed["info"]["voteAccount"], vote_pubkey.to_string());
+
+        let instruction =
+            stake_instruction::split(&stake_pubkey, &authorized_pubkey, 42, &Pubkey::new_rand());
+        let message = Message::new(instruction);
+        let parsed =
+            parse_stake_instruction(&message.instructions[1], &message.account_keys).unwrap();
+        assert_eq!(parsed["type"], "split");
+        assert_eq!(parsed["info"]["lamports"], 42);
+
+        let mut instruction = message.instructions[1].clone();
+        instruction.accounts.truncate(1);
+        assert!(parse_stake_instruction(&instruction, &message.account_keys).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use crate::parse_instruction::{check_num_accounts, ParsableProgram, ParseInstructionError};
+use serde_json::{json, Value};
+use solana_sdk::{
+    instruction::CompiledInstruction, instruction_processor_utils::limited_deserialize,
+    pubkey::Pubkey, system_instruction::SystemInstruction,
+};
+
+pub fn parse_system_instruction(
+    instruction: &CompiledInstruction,
+    account_keys: &[Pubkey],
+) -> Result<Value, ParseInstructionError> {
+    let system_instruction: SystemInstruction = limited_deserialize(&instruction.data)
+        .map_err(|_| ParseInstructionError::InstructionNotParsable(ParsableProgram::System))?;
+    let account = |i: usize| account_keys[instruction.accounts[i] as usize].to_string();
+    let parsed = match system_instruction {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            program_id,
+        } => {
+            check_num_accounts(&instruction.accounts, 2, ParsableProgram::System)?;
+            json!({
+                "type": "createAccount",
+                "info": {
+                    "source": account(0),
+                    "newAccount": account(1),
+                    "lamports": lamports,
+                    "space": space,
+                    "owner": program_id.to_string(),
+                },
+            })
+        }
+        SystemInstruction::Assign { program_id } => {
+            check_num_accounts(&instruction.accounts, 1, ParsableProgram::System)?;
+            json!({
+                "type": "assign",
+                "info": {
+                    "account": account(0),
+                    "owner": program_id.to_string(),
+                },
+            })
+        }
+        SystemInstruction::Transfer { lamports } => {
+            check_num_accounts(&instruction.accounts, 2, ParsableProgram::System)?;
+            json!({
+                "type": "transfer",
+                "info": {
+                    "source": account(0),
+                    "destination": account(1),
+                    "lamports": lamports,
+                },
+            })
+        }
+        SystemInstruction::CreateAccountWithSeed {
+            seed,
+            lamports,
+            space,
+            program_id,
+        } => {
+            check_num_accounts(&instruction.accounts, 2, ParsableProgram::System)?;
+            json!({
+                "type": "createAccountWithSeed",
+                "info": {
+                    "source": account(0),
+                    "newAccount": account(1),
+                    "base": account(0),
+                    "seed": seed,
+                    "lamports": lamports,
+                    "space": space,
+                    "owner": program_id.to_string(),
+                },
+            })
+        }
+    };
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{message::Message, system_instruction};
+
+    #[test]
+    fn test_parse_system_instruction() {
+        let from = Pubkey::new_rand();
+        let to = Pubkey::new_rand();
+        let owner = Pubkey::new_rand();
+
+        let instruction = system_instruction::create_account(&from, &to, 42, 8, &owner);
+        let message = Message::new(vec![instruction]);
+        assert_eq!(
+            parse_system_instruction(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "createAccount",
+                "info": {
+                    "source": from.to_string(),
+                    "newAccount": to.to_string(),
+                    "lamports": 42,
+                    "space": 8,
+                    "owner": owner.to_string(),
+                },
+            })
+        );
+
+        let instruction = system_instruction::assign(&from, &owner);
+        let message = Message::new(vec![instruction]);
+        assert_eq!(
+            parse_system_instruction(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "assign",
+                "info": {
+                    "account": from.to_string(),
+                    "owner": owner.to_string(),
+                },
+            })
+        );
+
+        let instruction =
+            system_instruction::create_account_with_seed(&from, &to, "seed", 42, 8, &owner);
+        let message = Message::new(vec![instruction]);
+        let parsed =
+            parse_system_instruction(&message.instructions[0], &message.account_keys).unwrap();
+        assert_eq!(parsed["type"], "createAccountWithSeed");
+        assert_eq!(parsed["info"]["base"], from.to_string());
+        assert_eq!(parsed["info"]["seed"], "seed");
+
+        let mut instruction = message.instructions[0].clone();
+        instruction.accounts.truncate(1);
+        assert_eq!(
+            parse_system_instruction(&instruction, &message.account_keys),
+            Err(ParseInstructionError::InstructionKeyMismatch(
+                ParsableProgram::System
+            ))
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use crate::{
+    parse_account_data::{ParsableAccount, ParseAccountError},
+    parse_instruction::{check_num_accounts, ParsableProgram, ParseInstructionError},
+};
+use serde_json::{json, Value};
+use solana_sdk::{
+    clock::{Epoch, Slot, UnixTimestamp},
+    instruction::CompiledInstruction,
+    instruction_processor_utils::limited_deserialize,
+    pubkey::Pubkey,
+};
+use solana_vote_program::{
+    vote_instruction::VoteInstruction,
+    vote_state::{VoteAuthorize, VoteState},
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiVoteState {
+    pub node_pubkey: String,
+    pub authorized_voter: String,
+    pub authorized_withdrawer: String,
+    pub commission: u8,
+    pub votes: Vec<UiLockout>,
+    pub root_slot: Option<Slot>,
+    pub epoch_credits: Vec<UiEpochCredits>,
+    pub last_timestamp: UiBlockTimestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiLockout {
+    pub slot: Slot,
+    pub confirmation_count: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiEpochCredits {
+    pub epoch: Epoch,
+    pub credits: u64,
+    pub previous_credits: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiBlockTimestamp {
+    pub slot: Slot,
+    pub timestamp: UnixTimestamp,
+}
+
+pub fn parse_vote(data: &[u8]) -> Result<UiVoteState, ParseAccountError> {
+    let vote_state = VoteState::deserialize(data)
+        .map_err(|_| ParseAccountError::AccountNotParsable(ParsableAccount::Vote))?;
+    Ok(UiVoteState {
+        node_pubkey: vote_state.node_pubkey.to_string(),
+        authorized_voter: vote_state.authorized_voter.to_string(),
+        authorized_withdrawer: vote_state.authorized_withdrawer.to_string(),
+        commission: vote_state.commission,
+        votes: vote_state
+            .votes
+            .iter()
+            .map(|lockout| UiLockout {
+                slot: lockout.slot,
+                confirmation_count: lockout.confirmation_count,
+            })
+            .collect(),
+        root_slot: vote_state.root_slot,
+        epoch_credits: vote_state
+            .epoch_credits()
+            .iter()
+            .map(|(epoch, credits, previous_credits)| UiEpochCredits {
+                epoch: *epoch,
+                credits: *credits,
+                previous_credits: *previous_credits,
+            })
+            .collect(),
+        last_timestamp: UiBlockTimestamp {
+            slot: vote_state.last_timestamp.slot,
+            timestamp: vote_state.last_timestamp.timestamp,
+        },
+    })
+}
+
+pub fn parse_vote_instruction(
+    instruction: &CompiledInstruction,
+    account_keys: &[Pubkey],
+) -> Result<Value, ParseInstructionError> {
+    let vote_instruction: VoteInstruction = limited_deserialize(&instruction.data)
+        .map_err(|_| ParseInstructionError::InstructionNotParsable(ParsableProgram::Vote))?;
+    let account = |i: usize| account_keys[instruction.accounts[i] as usize].to_string();
+    let parsed = match vote_instruction {
+        VoteInstruction::InitializeAccount(vote_init) => {
+            check_num_accounts(&instruction.accounts, 2, ParsableProgram::Vote)?;
+            json!({
+                "type": "initialize",
+                "info": {
+                    "voteAccount": account(0),
+                    "rentSysvar": account(1),
+                    "node": vote_init.node_pubkey.to_string(),
+                    "authorizedVoter": vote_init.authorized_voter.to_string(),
+                    "authorizedWithdrawer": vote_init.authorized_withdrawer.to_string(),
+                    "commission": vote_init.commission,
+                },
+            })
+        }
+        VoteInstruction::Authorize(new_authorized, authority_type) => {
+            check_num_accounts(&instruction.accounts, 1, ParsableProgram::Vote)?;
+            let authority_type = match authority_type {
+                VoteAuthorize::Voter => "voter",
+                VoteAuthorize::Withdrawer => "withdrawer",
+            };
+            json!({
+                "type": "authorize",
+                "info": {
+                    "voteAccount": account(0),
+                    "newAuthority": new_authorized.to_string(),
+                    "authorityType": authority_type,
+                },
+            })
+        }
+        VoteInstruction::Vote(vote) => {
+            check_num_accounts(&instruction.accounts, 3, ParsableProgram::Vote)?;
+            json!({
+                "type": "vote",
+                "info": {
+                    "voteAccount": account(0),
+                    "slotHashesSysvar": account(1),
+                    "clockSysvar": account(2),
+                    "vote": {
+                        "slots": vote.slots,
+                        "hash": vote.hash.to_string(),
+                        "timestamp": vote.timestamp,
+                    },
+                },
+            })
+        }
+        VoteInstruction::Withdraw(lamports) => {
+            check_num_accounts(&instruction.accounts, 2, ParsableProgram::Vote)?;
+            json!({
+                "type": "withdraw",
+                "info": {
+                    "voteAccount": account(0),
+                    "destination": account(1),
+                    "lamports": lamports,
+                },
+            })
+        }
+        VoteInstruction::UpdateNode(node_pubkey) => {
+            check_num_accounts(&instruction.accounts, 1, ParsableProgram::Vote)?;
+            json!({
+                "type": "updateNode",
+                "info": {
+                    "voteAccount": account(0),
+                    "newNode": node_pubkey.to_string(),
+                },
+            })
+        }
+    };
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{hash::Hash, message::Message};
+    use solana_vote_program::{
+        vote_instruction,
+        vote_state::{Vote, VoteInit},
+    };
+
+    #[test]
+    fn test_parse_vote() {
+        let vote_init = VoteInit {
+            node_pubkey: Pubkey::new_rand(),
+            authorized_voter: Pubkey::new_rand(),
+            authorized_withdrawer: Pubkey::new_rand(),
+            commission: 5,
+        };
+        let mut vote_state = VoteState::new(&vote_init);
+        vote_state.process_slot_vote_unchecked(1);
+        let mut data = vec![0; VoteState::size_of()];
+        vote_state.serialize(&mut data).unwrap();
+
+        let parsed = parse_vote(&data).unwrap();
+        assert_eq!(parsed.node_pubkey, vote_init.node_pubkey.to_string());
+        assert_eq!(
+            parsed.authorized_withdrawer,
+            vote_init.authorized_withdrawer.to_string()
+        );
+        assert_eq!(parsed.commission, 5);
+        assert_eq!(
+            parsed.votes,
+            vec![UiLockout {
+                slot: 1,
+                confirmation_count: 1,
+            }]
+        );
+        assert_eq!(parsed.root_slot, None);
+
+        assert!(parse_vote(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_parse_vote_instruction() {
+        let vote_pubkey = Pubkey::new_rand();
+        let authorized_voter = Pubkey::new_rand();
+        let hash = Hash::new(&[1; 32]);
+        let instruction =
+            vote_instruction::vote(&vote_pubkey, &authorized_voter, Vote::new(vec![1, 2], hash));
+        let message = Message::new(vec![instruction]);
+        assert_eq!(
+            parse_vote_instruction(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "vote",
+                "info": {
+                    "voteAccount": vote_pubkey.to_string(),
+                    "slotHashesSysvar": solana_sdk::sysvar::slot_hashes::id().to_string(),
+                    "clockSysvar": solana_sdk::sysvar::clock::id().to_string(),
+                    "vote": {
+                        "slots": [1, 2],
+                        "hash": hash.to_string(),
+                        "timestamp": null,
+                    },
+                },
+            })
+        );
+
+        let mut instruction = message.instructions[0].clone();
+        instruction.accounts.truncate(2);
+        assert!(parse_vote_instruction(&instruction, &message.account_keys).is_err());
+    }
+}
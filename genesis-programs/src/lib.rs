@@ -7,6 +7,8 @@ use solana_sdk::{
 #[macro_use]
 extern crate solana_bpf_loader_program;
 #[macro_use]
+extern crate solana_bpf_loader_upgradeable_program;
+#[macro_use]
 extern crate solana_budget_program;
 #[macro_use]
 extern crate solana_config_program;
@@ -58,6 +60,7 @@ pub fn get_programs(operating_mode: OperatingMode, epoch: Epoch) -> Option<Vec<(
                     // Enable all SoftLaunch programs
                     solana_system_program(),
                     solana_bpf_loader_program!(),
+                    solana_bpf_loader_upgradeable_program!(),
                     solana_config_program!(),
                     solana_nonce_program(),
                     solana_stake_program!(),
@@ -97,7 +100,10 @@ pub fn get_programs(operating_mode: OperatingMode, epoch: Epoch) -> Option<Vec<(
                 //
                 // The epoch of std::u64::MAX is a placeholder and is expected to be reduced in a
                 // future hard fork.
-                Some(vec![solana_bpf_loader_program!()])
+                Some(vec![
+                    solana_bpf_loader_program!(),
+                    solana_bpf_loader_upgradeable_program!(),
+                ])
             } else {
                 None
             }
@@ -150,7 +156,7 @@ mod tests {
     fn test_development_programs() {
         assert_eq!(
             get_programs(OperatingMode::Development, 0).unwrap().len(),
-            11
+            12
         );
         assert_eq!(get_programs(OperatingMode::Development, 1), None);
     }
@@ -3,6 +3,7 @@ use clap::{
 };
 use histogram;
 use serde_json::json;
+use solana_decoder::parse_instruction::parse_instruction;
 use solana_ledger::blocktree_db::Database;
 use solana_ledger::{
     bank_forks::{BankForks, SnapshotConfig},
@@ -14,8 +15,7 @@ use solana_ledger::{
     rooted_slot_iterator::RootedSlotIterator,
 };
 use solana_sdk::{
-    clock::Slot, genesis_config::GenesisConfig, instruction_processor_utils::limited_deserialize,
-    native_token::lamports_to_sol, pubkey::Pubkey,
+    clock::Slot, genesis_config::GenesisConfig, native_token::lamports_to_sol, pubkey::Pubkey,
 };
 use solana_vote_program::vote_state::VoteState;
 use std::{
@@ -81,28 +81,14 @@ fn output_slot(blocktree: &Blocktree, slot: Slot, method: &LedgerOutputMethod) {
                             );
                         }
 
-                        let mut raw = true;
-                        if program_pubkey == solana_vote_program::id() {
-                            if let Ok(vote_instruction) =
-                                limited_deserialize::<
-                                    solana_vote_program::vote_instruction::VoteInstruction,
-                                >(&instruction.data)
-                            {
-                                println!("        {:?}", vote_instruction);
-                                raw = false;
-                            }
-                        } else if program_pubkey == solana_sdk::system_program::id() {
-                            if let Ok(system_instruction) =
-                                limited_deserialize::<
-                                    solana_sdk::system_instruction::SystemInstruction,
-                                >(&instruction.data)
-                            {
-                                println!("        {:?}", system_instruction);
-                                raw = false;
-                            }
-                        }
-
-                        if raw {
+                        if let Ok(parsed) =
+                            parse_instruction(&program_pubkey, instruction, &message.account_keys)
+                        {
+                            println!(
+                                "        {}",
+                                serde_json::to_string(&parsed.parsed).expect("serialize parsed")
+                            );
+                        } else {
                             println!("        Data: {:?}", instruction.data);
                         }
                     }
@@ -21,7 +21,9 @@ use rayon::{
     ThreadPool,
 };
 use rocksdb::DBRawIterator;
-use solana_client::rpc_request::{RpcConfirmedBlock, RpcTransactionStatus};
+use solana_client::rpc_request::{
+    RpcConfirmedBlock, RpcConfirmedTransaction, RpcRewards, RpcTransactionStatus,
+};
 use solana_measure::measure::Measure;
 use solana_metrics::{datapoint_debug, datapoint_error};
 use solana_rayon_threadlimit::get_thread_count;
@@ -76,6 +78,9 @@ pub struct Blocktree {
     data_shred_cf: LedgerColumn<cf::ShredData>,
     code_shred_cf: LedgerColumn<cf::ShredCode>,
     transaction_status_cf: LedgerColumn<cf::TransactionStatus>,
+    address_signatures_cf: LedgerColumn<cf::AddressSignatures>,
+    signature_slots_cf: LedgerColumn<cf::SignatureSlots>,
+    rewards_cf: LedgerColumn<cf::Rewards>,
     last_root: Arc<RwLock<Slot>>,
     insert_shreds_lock: Arc<Mutex<()>>,
     pub new_shreds_signals: Vec<SyncSender<bool>>,
@@ -184,6 +189,9 @@ impl Blocktree {
         let data_shred_cf = db.column();
         let code_shred_cf = db.column();
         let transaction_status_cf = db.column();
+        let address_signatures_cf = db.column();
+        let signature_slots_cf = db.column();
+        let rewards_cf = db.column();
 
         let db = Arc::new(db);
 
@@ -207,6 +215,9 @@ impl Blocktree {
             data_shred_cf,
             code_shred_cf,
             transaction_status_cf,
+            address_signatures_cf,
+            signature_slots_cf,
+            rewards_cf,
             new_shreds_signals: vec![],
             completed_slots_senders: vec![],
             insert_shreds_lock: Arc::new(Mutex::new(())),
@@ -293,6 +304,9 @@ impl Blocktree {
             .expect("Database Error: Failed to get write batch");
         // delete range cf is not inclusive
         let to_slot = to_slot.checked_add(1).unwrap_or_else(|| std::u64::MAX);
+        // The signature slots column is keyed by signature, so its entries are found through the
+        // transaction statuses of the purged slots
+        self.delete_signature_slots(&mut write_batch, from_slot, to_slot)?;
         let columns_empty = self
             .db
             .delete_range_cf::<cf::SlotMeta>(&mut write_batch, from_slot, to_slot)
@@ -328,6 +342,14 @@ impl Blocktree {
             & self
                 .db
                 .delete_range_cf::<cf::TransactionStatus>(&mut write_batch, from_slot, to_slot)
+                .unwrap_or_else(|_| false)
+            & self
+                .db
+                .delete_range_cf::<cf::AddressSignatures>(&mut write_batch, from_slot, to_slot)
+                .unwrap_or_else(|_| false)
+            & self
+                .db
+                .delete_range_cf::<cf::Rewards>(&mut write_batch, from_slot, to_slot)
                 .unwrap_or_else(|_| false);
         if let Err(e) = self.db.write(write_batch) {
             error!(
@@ -339,6 +361,22 @@ impl Blocktree {
         Ok(columns_empty)
     }
 
+    fn delete_signature_slots(
+        &self,
+        write_batch: &mut WriteBatch,
+        from_slot: Slot,
+        to_slot: Slot,
+    ) -> Result<()> {
+        let status_iterator = self.transaction_status_cf.iter(IteratorMode::From(
+            cf::TransactionStatus::as_index(from_slot),
+            IteratorDirection::Forward,
+        ))?;
+        for ((slot, signature), _) in status_iterator.take_while(|((slot, _), _)| *slot < to_slot) {
+            write_batch.delete::<cf::SignatureSlots>((signature, slot))?;
+        }
+        Ok(())
+    }
+
     pub fn compact_storage(&self, from_slot: Slot, to_slot: Slot) -> Result<bool> {
         let result = self
             .meta_cf
@@ -376,6 +414,14 @@ impl Blocktree {
             && self
                 .transaction_status_cf
                 .compact_range(from_slot, to_slot)
+                .unwrap_or(false)
+            && self
+                .address_signatures_cf
+                .compact_range(from_slot, to_slot)
+                .unwrap_or(false)
+            && self
+                .rewards_cf
+                .compact_range(from_slot, to_slot)
                 .unwrap_or(false);
         Ok(result)
     }
@@ -1331,6 +1377,7 @@ impl Blocktree {
                     parent_slot: slot_meta.parent_slot,
                     transactions: self
                         .map_transactions_to_statuses(slot, slot_transaction_iterator),
+                    rewards: self.rewards_cf.get(slot)?.unwrap_or_default(),
                 };
                 return Ok(block);
             }
@@ -1356,12 +1403,139 @@ impl Blocktree {
             .collect()
     }
 
+    pub fn read_rewards(&self, slot: Slot) -> Result<Option<RpcRewards>> {
+        self.rewards_cf.get(slot)
+    }
+
+    pub fn write_rewards(&self, slot: Slot, rewards: RpcRewards) -> Result<()> {
+        self.rewards_cf.put(slot, &rewards)
+    }
+
+    /// Persists the status of a transaction, indexed by signature, along with an entry in the
+    /// address signatures column for every account the transaction references
     pub fn write_transaction_status(
         &self,
         index: (Slot, Signature),
+        writable_keys: Vec<&Pubkey>,
+        readonly_keys: Vec<&Pubkey>,
         status: &RpcTransactionStatus,
     ) -> Result<()> {
-        self.transaction_status_cf.put(index, status)
+        let (slot, signature) = index;
+        let mut write_batch = self.db.batch()?;
+        write_batch.put::<cf::TransactionStatus>(index, status)?;
+        write_batch.put::<cf::SignatureSlots>((signature, slot), &true)?;
+        for address in writable_keys {
+            write_batch.put::<cf::AddressSignatures>(
+                (slot, *address, signature),
+                &AddressSignatureMeta { writeable: true },
+            )?;
+        }
+        for address in readonly_keys {
+            write_batch.put::<cf::AddressSignatures>(
+                (slot, *address, signature),
+                &AddressSignatureMeta { writeable: false },
+            )?;
+        }
+        self.db.write(write_batch)
+    }
+
+    fn get_slot_signatures_for_address(
+        &self,
+        slot: Slot,
+        address: Pubkey,
+    ) -> Result<Vec<Signature>> {
+        let index_iterator = self.db.iter::<cf::AddressSignatures>(IteratorMode::From(
+            (slot, address, Signature::default()),
+            IteratorDirection::Forward,
+        ))?;
+        Ok(index_iterator
+            .take_while(|((key_slot, key_address, _), _)| {
+                *key_slot == slot && *key_address == address
+            })
+            .map(|((_, _, signature), _)| signature)
+            .collect())
+    }
+
+    /// Returns the signatures of rooted transactions that reference `address` in slots
+    /// `start_slot..=end_slot`, newest slot first. If `before` is provided, only signatures that
+    /// follow it in that ordering are returned. At most `limit` signatures are returned.
+    pub fn get_confirmed_signatures_for_address(
+        &self,
+        address: Pubkey,
+        start_slot: Slot,
+        end_slot: Slot,
+        before: Option<Signature>,
+        limit: usize,
+    ) -> Result<Vec<(Slot, Signature)>> {
+        let rooted_slots: Vec<Slot> = self
+            .db
+            .iter::<cf::Root>(IteratorMode::From(start_slot, IteratorDirection::Forward))?
+            .map(|(slot, _)| slot)
+            .take_while(|slot| *slot <= end_slot)
+            .collect();
+
+        let mut found_before = before.is_none();
+        let mut signatures = vec![];
+        for slot in rooted_slots.into_iter().rev() {
+            for signature in self.get_slot_signatures_for_address(slot, address)? {
+                if !found_before {
+                    found_before = Some(signature) == before;
+                    continue;
+                }
+                if signatures.len() >= limit {
+                    return Ok(signatures);
+                }
+                signatures.push((slot, signature));
+            }
+        }
+        Ok(signatures)
+    }
+
+    pub fn get_transaction_status(
+        &self,
+        index: (Slot, Signature),
+    ) -> Result<Option<RpcTransactionStatus>> {
+        self.transaction_status_cf.get(index)
+    }
+
+    /// Returns the rooted slot that contains the transaction with `signature`, if any
+    pub fn get_confirmed_transaction_slot(&self, signature: Signature) -> Result<Option<Slot>> {
+        let slot_iterator = self.signature_slots_cf.iter(IteratorMode::From(
+            (signature, 0),
+            IteratorDirection::Forward,
+        ))?;
+        for ((key_signature, slot), _) in slot_iterator {
+            if key_signature != signature {
+                break;
+            }
+            if self.is_root(slot) {
+                return Ok(Some(slot));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the rooted transaction with `signature`, along with its status, if any
+    pub fn get_confirmed_transaction(
+        &self,
+        signature: Signature,
+    ) -> Result<Option<RpcConfirmedTransaction>> {
+        if let Some(slot) = self.get_confirmed_transaction_slot(signature)? {
+            let transaction = self
+                .get_slot_entries(slot, 0, None)?
+                .into_iter()
+                .flat_map(|entry| entry.transactions)
+                .find(|transaction| transaction.signatures.get(0) == Some(&signature));
+            if let Some(transaction) = transaction {
+                return Ok(Some(RpcConfirmedTransaction {
+                    slot,
+                    transaction,
+                    meta: self.transaction_status_cf.get((slot, signature))?,
+                    block_time: None,
+                }));
+            }
+        }
+        Ok(None)
     }
 
     fn get_block_timestamps(&self, slot: Slot) -> Result<Vec<(Pubkey, UnixTimestamp)>> {
@@ -2303,12 +2477,14 @@ pub mod tests {
     use bincode::serialize;
     use itertools::Itertools;
     use rand::{seq::SliceRandom, thread_rng};
+    use solana_client::rpc_request::RpcReward;
     use solana_runtime::bank::Bank;
     use solana_sdk::{
         hash::{self, hash, Hash},
         instruction::CompiledInstruction,
         packet::PACKET_DATA_SIZE,
         pubkey::Pubkey,
+        reward_type::RewardType,
         signature::Signature,
         transaction::TransactionError,
     };
@@ -2397,6 +2573,13 @@ pub mod tests {
                 .unwrap()
                 .next()
                 .map(|((slot, _), _)| slot >= min_slot)
+                .unwrap_or(true)
+            & blocktree
+                .db
+                .iter::<cf::AddressSignatures>(IteratorMode::Start)
+                .unwrap()
+                .next()
+                .map(|((slot, _, _), _)| slot >= min_slot)
                 .unwrap_or(true);
         assert!(condition_met);
     }
@@ -4579,6 +4762,8 @@ pub mod tests {
                             fee: 42,
                             pre_balances: pre_balances.clone(),
                             post_balances: post_balances.clone(),
+                            log_messages: Some(vec![]),
+                            compute_units_consumed: Some(0),
                         },
                     )
                     .unwrap();
@@ -4591,6 +4776,8 @@ pub mod tests {
                             fee: 42,
                             pre_balances: pre_balances.clone(),
                             post_balances: post_balances.clone(),
+                            log_messages: Some(vec![]),
+                            compute_units_consumed: Some(0),
                         },
                     )
                     .unwrap();
@@ -4601,6 +4788,8 @@ pub mod tests {
                         fee: 42,
                         pre_balances,
                         post_balances,
+                        log_messages: Some(vec![]),
+                        compute_units_consumed: Some(0),
                     }),
                 )
             })
@@ -4621,11 +4810,24 @@ pub mod tests {
         // root, but empty of entries. This is special handling for snapshot root slots.
         assert_eq!(confirmed_block, expected_block);
 
+        let rewards = vec![RpcReward {
+            pubkey: Pubkey::new_rand().to_string(),
+            lamports: 42,
+            post_balance: 42,
+            reward_type: Some(RewardType::Fee),
+        }];
+        ledger.write_rewards(slot + 1, rewards.clone()).unwrap();
+        assert_eq!(
+            ledger.read_rewards(slot + 1).unwrap(),
+            Some(rewards.clone())
+        );
+
         let confirmed_block = ledger.get_confirmed_block(slot + 1).unwrap();
         assert_eq!(confirmed_block.transactions.len(), 100);
 
         let mut expected_block = RpcConfirmedBlock::default();
         expected_block.transactions = expected_transactions;
+        expected_block.rewards = rewards;
         expected_block.parent_slot = slot;
         expected_block.previous_blockhash = blockhash;
         expected_block.blockhash = blockhash;
@@ -4739,6 +4941,8 @@ pub mod tests {
                         fee: 5u64,
                         pre_balances: pre_balances_vec.clone(),
                         post_balances: post_balances_vec.clone(),
+                        log_messages: Some(vec![]),
+                        compute_units_consumed: Some(0),
                     },
                 )
                 .is_ok());
@@ -4749,6 +4953,8 @@ pub mod tests {
                 fee,
                 pre_balances,
                 post_balances,
+                log_messages,
+                compute_units_consumed,
             } = transaction_status_cf
                 .get((0, Signature::default()))
                 .unwrap()
@@ -4757,6 +4963,8 @@ pub mod tests {
             assert_eq!(fee, 5u64);
             assert_eq!(pre_balances, pre_balances_vec);
             assert_eq!(post_balances, post_balances_vec);
+            assert_eq!(log_messages, Some(vec![]));
+            assert_eq!(compute_units_consumed, Some(0));
 
             // insert value
             assert!(transaction_status_cf
@@ -4767,6 +4975,8 @@ pub mod tests {
                         fee: 9u64,
                         pre_balances: pre_balances_vec.clone(),
                         post_balances: post_balances_vec.clone(),
+                        log_messages: Some(vec![]),
+                        compute_units_consumed: Some(0),
                     },
                 )
                 .is_ok());
@@ -4777,6 +4987,8 @@ pub mod tests {
                 fee,
                 pre_balances,
                 post_balances,
+                log_messages,
+                compute_units_consumed,
             } = transaction_status_cf
                 .get((9, Signature::default()))
                 .unwrap()
@@ -4787,10 +4999,235 @@ pub mod tests {
             assert_eq!(fee, 9u64);
             assert_eq!(pre_balances, pre_balances_vec);
             assert_eq!(post_balances, post_balances_vec);
+            assert_eq!(log_messages, Some(vec![]));
+            assert_eq!(compute_units_consumed, Some(0));
+
+            // values written before consumed units were recorded are still found
+            let old_status: (
+                solana_sdk::transaction::Result<()>,
+                u64,
+                Vec<u64>,
+                Vec<u64>,
+                Option<Vec<String>>,
+            ) = (
+                Ok(()),
+                8u64,
+                pre_balances_vec.clone(),
+                post_balances_vec.clone(),
+                Some(vec!["log".to_string()]),
+            );
+            transaction_status_cf
+                .put_bytes((8, Signature::default()), &serialize(&old_status).unwrap())
+                .unwrap();
+            assert_eq!(
+                transaction_status_cf
+                    .get((8, Signature::default()))
+                    .unwrap()
+                    .unwrap(),
+                RpcTransactionStatus {
+                    status: Ok(()),
+                    fee: 8u64,
+                    pre_balances: pre_balances_vec.clone(),
+                    post_balances: post_balances_vec.clone(),
+                    log_messages: Some(vec!["log".to_string()]),
+                    compute_units_consumed: None,
+                }
+            );
+
+            // values written before log messages were recorded are still found
+            let old_status: (solana_sdk::transaction::Result<()>, u64, Vec<u64>, Vec<u64>) = (
+                Ok(()),
+                7u64,
+                pre_balances_vec.clone(),
+                post_balances_vec.clone(),
+            );
+            transaction_status_cf
+                .put_bytes((7, Signature::default()), &serialize(&old_status).unwrap())
+                .unwrap();
+            assert_eq!(
+                transaction_status_cf
+                    .get((7, Signature::default()))
+                    .unwrap()
+                    .unwrap(),
+                RpcTransactionStatus {
+                    status: Ok(()),
+                    fee: 7u64,
+                    pre_balances: pre_balances_vec,
+                    post_balances: post_balances_vec,
+                    log_messages: None,
+                    compute_units_consumed: None,
+                }
+            );
         }
         Blocktree::destroy(&blocktree_path).expect("Expected successful database destruction");
     }
 
+    #[test]
+    fn test_get_confirmed_signatures_for_address() {
+        let blocktree_path = get_tmp_ledger_path!();
+        {
+            let blocktree = Blocktree::open(&blocktree_path).unwrap();
+            let address0 = Pubkey::new_rand();
+            let address1 = Pubkey::new_rand();
+            let status = RpcTransactionStatus {
+                status: Ok(()),
+                fee: 42,
+                pre_balances: vec![],
+                post_balances: vec![],
+                log_messages: Some(vec![]),
+                compute_units_consumed: Some(0),
+            };
+
+            let mut expected_signatures = vec![];
+            for slot in 0..4 {
+                for i in 0..2u8 {
+                    let signature = Signature::new(&[slot as u8 * 2 + i; 64]);
+                    blocktree
+                        .write_transaction_status(
+                            (slot, signature),
+                            vec![&address0],
+                            vec![&address1],
+                            &status,
+                        )
+                        .unwrap();
+                    expected_signatures.push((slot, signature));
+                }
+            }
+            // Slot 2 is not rooted, so its signatures must not be returned
+            blocktree.set_roots(&[0, 1, 3]).unwrap();
+            expected_signatures.retain(|(slot, _)| *slot != 2);
+            expected_signatures.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+            let signatures = blocktree
+                .get_confirmed_signatures_for_address(address0, 0, 3, None, 100)
+                .unwrap();
+            assert_eq!(signatures, expected_signatures);
+            let signatures = blocktree
+                .get_confirmed_signatures_for_address(address1, 0, 3, None, 100)
+                .unwrap();
+            assert_eq!(signatures, expected_signatures);
+
+            // Range and limit are honored
+            let signatures = blocktree
+                .get_confirmed_signatures_for_address(address0, 1, 1, None, 100)
+                .unwrap();
+            assert_eq!(signatures, expected_signatures[2..4].to_vec());
+            let signatures = blocktree
+                .get_confirmed_signatures_for_address(address0, 0, 3, None, 3)
+                .unwrap();
+            assert_eq!(signatures, expected_signatures[..3].to_vec());
+
+            // Pagination resumes after the `before` signature
+            let signatures = blocktree
+                .get_confirmed_signatures_for_address(
+                    address0,
+                    0,
+                    3,
+                    Some(expected_signatures[2].1),
+                    2,
+                )
+                .unwrap();
+            assert_eq!(signatures, expected_signatures[3..5].to_vec());
+
+            // Unknown addresses have no signatures
+            assert!(blocktree
+                .get_confirmed_signatures_for_address(Pubkey::new_rand(), 0, 3, None, 100)
+                .unwrap()
+                .is_empty());
+
+            // Purged slots are removed from the index
+            blocktree.purge_slots(0, Some(1));
+            let signatures = blocktree
+                .get_confirmed_signatures_for_address(address0, 0, 3, None, 100)
+                .unwrap();
+            assert_eq!(signatures, expected_signatures[..2].to_vec());
+        }
+        Blocktree::destroy(&blocktree_path).expect("Expected successful database destruction");
+    }
+
+    #[test]
+    fn test_get_confirmed_transaction() {
+        let slot = 2;
+        let entries = make_slot_entries_with_transactions(5);
+        let shreds = entries_to_test_shreds(entries.clone(), slot, slot - 1, true, 0);
+        let ledger_path = get_tmp_ledger_path!();
+        {
+            let ledger = Blocktree::open(&ledger_path).unwrap();
+            ledger.insert_shreds(shreds, None, false).unwrap();
+
+            let transactions: Vec<Transaction> = entries
+                .into_iter()
+                .flat_map(|entry| entry.transactions)
+                .collect();
+            let status = RpcTransactionStatus {
+                status: Ok(()),
+                fee: 42,
+                pre_balances: vec![],
+                post_balances: vec![],
+                log_messages: Some(vec![]),
+                compute_units_consumed: Some(0),
+            };
+            for transaction in transactions.iter() {
+                let (writable_keys, readonly_keys) =
+                    transaction.message.get_account_keys_by_lock_type();
+                ledger
+                    .write_transaction_status(
+                        (slot, transaction.signatures[0]),
+                        writable_keys,
+                        readonly_keys,
+                        &status,
+                    )
+                    .unwrap();
+            }
+
+            // Unrooted slots are not confirmed
+            let signature = transactions[0].signatures[0];
+            assert_eq!(
+                ledger.get_confirmed_transaction_slot(signature).unwrap(),
+                None
+            );
+            assert_eq!(ledger.get_confirmed_transaction(signature).unwrap(), None);
+
+            ledger.set_roots(&[slot]).unwrap();
+            for transaction in transactions.iter() {
+                let signature = transaction.signatures[0];
+                assert_eq!(
+                    ledger.get_confirmed_transaction_slot(signature).unwrap(),
+                    Some(slot)
+                );
+                assert_eq!(
+                    ledger.get_confirmed_transaction(signature).unwrap(),
+                    Some(RpcConfirmedTransaction {
+                        slot,
+                        transaction: transaction.clone(),
+                        meta: Some(status.clone()),
+                        block_time: None,
+                    })
+                );
+            }
+            assert_eq!(
+                ledger
+                    .get_confirmed_transaction(Signature::default())
+                    .unwrap(),
+                None
+            );
+
+            // Purged slots are no longer indexed
+            ledger.purge_slots(0, Some(slot));
+            assert_eq!(
+                ledger.get_confirmed_transaction_slot(signature).unwrap(),
+                None
+            );
+            assert!(ledger
+                .signature_slots_cf
+                .iter(IteratorMode::Start)
+                .unwrap()
+                .next()
+                .is_none());
+        }
+        Blocktree::destroy(&ledger_path).expect("Expected successful database destruction");
+    }
+
     #[test]
     fn test_get_last_hash() {
         let mut entries: Vec<Entry> = vec![];
@@ -4834,6 +5271,8 @@ pub mod tests {
                             fee: x,
                             pre_balances: vec![],
                             post_balances: vec![],
+                            log_messages: Some(vec![]),
+                            compute_units_consumed: Some(0),
                         },
                     )
                     .unwrap();
@@ -10,8 +10,8 @@ use rocksdb::{
 };
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use solana_client::rpc_request::RpcTransactionStatus;
-use solana_sdk::{clock::Slot, signature::Signature};
+use solana_client::rpc_request::{RpcRewards, RpcTransactionStatus};
+use solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature};
 use std::{collections::HashMap, fs, marker::PhantomData, path::Path, sync::Arc};
 use thiserror::Error;
 
@@ -34,6 +34,12 @@ const DATA_SHRED_CF: &str = "data_shred";
 const CODE_SHRED_CF: &str = "code_shred";
 /// Column family for Transaction Status
 const TRANSACTION_STATUS_CF: &str = "transaction_status";
+/// Column family for Address Signatures
+const ADDRESS_SIGNATURES_CF: &str = "address_signatures";
+/// Column family mapping transaction signatures to the slots that contain them
+const SIGNATURE_SLOTS_CF: &str = "signature_slots";
+/// Column family for the rewards credited by each slot's bank
+const REWARDS_CF: &str = "rewards";
 
 #[derive(Error, Debug)]
 pub enum BlocktreeError {
@@ -95,6 +101,18 @@ pub mod columns {
     #[derive(Debug)]
     /// The transaction status column
     pub struct TransactionStatus;
+
+    #[derive(Debug)]
+    /// The address signatures column
+    pub struct AddressSignatures;
+
+    #[derive(Debug)]
+    /// The signature slots column
+    pub struct SignatureSlots;
+
+    #[derive(Debug)]
+    /// The rewards column
+    pub struct Rewards;
 }
 
 #[derive(Debug)]
@@ -103,8 +121,8 @@ struct Rocks(rocksdb::DB);
 impl Rocks {
     fn open(path: &Path) -> Result<Rocks> {
         use columns::{
-            DeadSlots, ErasureMeta, Index, Orphans, Root, ShredCode, ShredData, SlotMeta,
-            TransactionStatus,
+            AddressSignatures, DeadSlots, ErasureMeta, Index, Orphans, Rewards, Root, ShredCode,
+            ShredData, SignatureSlots, SlotMeta, TransactionStatus,
         };
 
         fs::create_dir_all(&path)?;
@@ -127,6 +145,11 @@ impl Rocks {
             ColumnFamilyDescriptor::new(ShredCode::NAME, get_cf_options());
         let transaction_status_cf_descriptor =
             ColumnFamilyDescriptor::new(TransactionStatus::NAME, get_cf_options());
+        let address_signatures_cf_descriptor =
+            ColumnFamilyDescriptor::new(AddressSignatures::NAME, get_cf_options());
+        let signature_slots_cf_descriptor =
+            ColumnFamilyDescriptor::new(SignatureSlots::NAME, get_cf_options());
+        let rewards_cf_descriptor = ColumnFamilyDescriptor::new(Rewards::NAME, get_cf_options());
 
         let cfs = vec![
             meta_cf_descriptor,
@@ -138,6 +161,9 @@ impl Rocks {
             shred_data_cf_descriptor,
             shred_code_cf_descriptor,
             transaction_status_cf_descriptor,
+            address_signatures_cf_descriptor,
+            signature_slots_cf_descriptor,
+            rewards_cf_descriptor,
         ];
 
         // Open the database
@@ -148,8 +174,8 @@ impl Rocks {
 
     fn columns(&self) -> Vec<&'static str> {
         use columns::{
-            DeadSlots, ErasureMeta, Index, Orphans, Root, ShredCode, ShredData, SlotMeta,
-            TransactionStatus,
+            AddressSignatures, DeadSlots, ErasureMeta, Index, Orphans, Rewards, Root, ShredCode,
+            ShredData, SignatureSlots, SlotMeta, TransactionStatus,
         };
 
         vec![
@@ -162,6 +188,9 @@ impl Rocks {
             ShredData::NAME,
             ShredCode::NAME,
             TransactionStatus::NAME,
+            AddressSignatures::NAME,
+            SignatureSlots::NAME,
+            Rewards::NAME,
         ]
     }
 
@@ -240,10 +269,24 @@ pub trait Column {
 
 pub trait TypedColumn: Column {
     type Type: Serialize + DeserializeOwned;
+
+    /// Deserialize a value of this column, which may have been written in an older layout
+    fn deserialize_value(data: &[u8]) -> Result<Self::Type> {
+        Ok(deserialize(data)?)
+    }
 }
 
 impl TypedColumn for columns::TransactionStatus {
     type Type = RpcTransactionStatus;
+
+    fn deserialize_value(data: &[u8]) -> Result<RpcTransactionStatus> {
+        let status = deserialize(data)
+            .or_else(|_| deserialize::<blocktree_meta::TransactionStatusV1>(data).map(Into::into))
+            .or_else(|_| {
+                deserialize::<blocktree_meta::TransactionStatusV0>(data).map(Into::into)
+            })?;
+        Ok(status)
+    }
 }
 
 impl Column for columns::TransactionStatus {
@@ -272,6 +315,68 @@ impl Column for columns::TransactionStatus {
     }
 }
 
+impl TypedColumn for columns::AddressSignatures {
+    type Type = blocktree_meta::AddressSignatureMeta;
+}
+
+impl Column for columns::AddressSignatures {
+    const NAME: &'static str = ADDRESS_SIGNATURES_CF;
+    type Index = (Slot, Pubkey, Signature);
+
+    fn key((slot, pubkey, signature): (Slot, Pubkey, Signature)) -> Vec<u8> {
+        let mut key = vec![0; 8 + 32 + 64];
+        BigEndian::write_u64(&mut key[..8], slot);
+        key[8..40].clone_from_slice(&pubkey.as_ref()[0..32]);
+        key[40..104].clone_from_slice(&signature.as_ref()[0..64]);
+        key
+    }
+
+    fn index(key: &[u8]) -> (Slot, Pubkey, Signature) {
+        let slot = BigEndian::read_u64(&key[..8]);
+        let pubkey = Pubkey::new(&key[8..40]);
+        let signature = Signature::new(&key[40..104]);
+        (slot, pubkey, signature)
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index.0
+    }
+
+    fn as_index(slot: Slot) -> Self::Index {
+        (slot, Pubkey::default(), Signature::default())
+    }
+}
+
+impl TypedColumn for columns::SignatureSlots {
+    type Type = bool;
+}
+
+impl Column for columns::SignatureSlots {
+    const NAME: &'static str = SIGNATURE_SLOTS_CF;
+    type Index = (Signature, Slot);
+
+    fn key((signature, slot): (Signature, Slot)) -> Vec<u8> {
+        let mut key = vec![0; 64 + 8];
+        key[..64].clone_from_slice(&signature.as_ref()[0..64]);
+        BigEndian::write_u64(&mut key[64..72], slot);
+        key
+    }
+
+    fn index(key: &[u8]) -> (Signature, Slot) {
+        let signature = Signature::new(&key[..64]);
+        let slot = BigEndian::read_u64(&key[64..72]);
+        (signature, slot)
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index.1
+    }
+
+    fn as_index(slot: Slot) -> Self::Index {
+        (Signature::default(), slot)
+    }
+}
+
 impl Column for columns::ShredCode {
     const NAME: &'static str = CODE_SHRED_CF;
     type Index = (u64, u64);
@@ -373,6 +478,33 @@ impl TypedColumn for columns::DeadSlots {
     type Type = bool;
 }
 
+impl Column for columns::Rewards {
+    const NAME: &'static str = REWARDS_CF;
+    type Index = Slot;
+
+    fn key(slot: Slot) -> Vec<u8> {
+        let mut key = vec![0; 8];
+        BigEndian::write_u64(&mut key[..], slot);
+        key
+    }
+
+    fn index(key: &[u8]) -> Slot {
+        BigEndian::read_u64(&key[..8])
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index
+    }
+
+    fn as_index(slot: Slot) -> Self::Index {
+        slot
+    }
+}
+
+impl TypedColumn for columns::Rewards {
+    type Type = RpcRewards;
+}
+
 impl Column for columns::Orphans {
     const NAME: &'static str = ORPHANS_CF;
     type Index = u64;
@@ -526,7 +658,7 @@ impl Database {
         C: TypedColumn,
     {
         if let Some(serialized_value) = self.backend.get_cf(self.cf_handle::<C>(), &C::key(key))? {
-            let value = deserialize(&serialized_value)?;
+            let value = C::deserialize_value(&serialized_value)?;
 
             Ok(Some(value))
         } else {
@@ -694,7 +826,7 @@ where
 {
     pub fn get(&self, key: C::Index) -> Result<Option<C::Type>> {
         if let Some(serialized_value) = self.backend.get_cf(self.handle(), &C::key(key))? {
-            let value = deserialize(&serialized_value)?;
+            let value = C::deserialize_value(&serialized_value)?;
 
             Ok(Some(value))
         } else {
@@ -1,7 +1,8 @@
 use crate::erasure::ErasureConfig;
 use serde::{Deserialize, Serialize};
+use solana_client::rpc_request::RpcTransactionStatus;
 use solana_metrics::datapoint;
-use solana_sdk::clock::Slot;
+use solana_sdk::{clock::Slot, transaction};
 use std::{collections::BTreeSet, ops::RangeBounds};
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
@@ -67,6 +68,58 @@ pub struct ErasureMeta {
     pub config: ErasureConfig,
 }
 
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+/// Information about a transaction signature that references an address
+pub struct AddressSignatureMeta {
+    /// Whether the address was writable in the transaction
+    pub writeable: bool,
+}
+
+#[derive(Deserialize)]
+/// Layout of the transaction status column before log messages were recorded
+pub struct TransactionStatusV0 {
+    pub status: transaction::Result<()>,
+    pub fee: u64,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+}
+
+impl From<TransactionStatusV0> for RpcTransactionStatus {
+    fn from(status: TransactionStatusV0) -> Self {
+        Self {
+            status: status.status,
+            fee: status.fee,
+            pre_balances: status.pre_balances,
+            post_balances: status.post_balances,
+            log_messages: None,
+            compute_units_consumed: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+/// Layout of the transaction status column before consumed compute units were recorded
+pub struct TransactionStatusV1 {
+    pub status: transaction::Result<()>,
+    pub fee: u64,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+    pub log_messages: Option<Vec<String>>,
+}
+
+impl From<TransactionStatusV1> for RpcTransactionStatus {
+    fn from(status: TransactionStatusV1) -> Self {
+        Self {
+            status: status.status,
+            fee: status.fee,
+            pre_balances: status.pre_balances,
+            post_balances: status.post_balances,
+            log_messages: status.log_messages,
+            compute_units_consumed: None,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum ErasureMetaStatus {
     CanRecover,
@@ -14,7 +14,10 @@ use rayon::{prelude::*, ThreadPool};
 use solana_metrics::{datapoint, datapoint_error, inc_new_counter_debug};
 use solana_rayon_threadlimit::get_thread_count;
 use solana_runtime::{
-    bank::{Bank, TransactionBalancesSet, TransactionProcessResult, TransactionResults},
+    bank::{
+        Bank, TransactionBalancesSet, TransactionLogMessages, TransactionProcessResult,
+        TransactionResults,
+    },
     transaction_batch::TransactionBatch,
 };
 use solana_sdk::{
@@ -60,10 +63,14 @@ fn execute_batch(
             processing_results,
         },
         balances,
+        transaction_logs,
+        transaction_compute_units,
     ) = batch.bank().load_execute_and_commit_transactions(
         batch,
         MAX_RECENT_BLOCKHASHES,
         transaction_status_sender.is_some(),
+        transaction_status_sender.is_some(),
+        transaction_status_sender.is_some(),
     );
 
     if let Some(sender) = transaction_status_sender {
@@ -72,6 +79,8 @@ fn execute_batch(
             batch.transactions(),
             processing_results,
             balances,
+            transaction_logs,
+            transaction_compute_units,
             sender,
         );
     }
@@ -567,6 +576,8 @@ pub struct TransactionStatusBatch {
     pub transactions: Vec<Transaction>,
     pub statuses: Vec<TransactionProcessResult>,
     pub balances: TransactionBalancesSet,
+    pub transaction_logs: Vec<TransactionLogMessages>,
+    pub transaction_compute_units: Vec<u64>,
 }
 pub type TransactionStatusSender = Sender<TransactionStatusBatch>;
 
@@ -575,6 +586,8 @@ pub fn send_transaction_status_batch(
     transactions: &[Transaction],
     statuses: Vec<TransactionProcessResult>,
     balances: TransactionBalancesSet,
+    transaction_logs: Vec<TransactionLogMessages>,
+    transaction_compute_units: Vec<u64>,
     transaction_status_sender: TransactionStatusSender,
 ) {
     let slot = bank.slot();
@@ -583,6 +596,8 @@ pub fn send_transaction_status_batch(
         transactions: transactions.to_vec(),
         statuses,
         balances,
+        transaction_logs,
+        transaction_compute_units,
     }) {
         trace!(
             "Slot {} transaction_status send batch failed: {:?}",
@@ -4,6 +4,7 @@ extern crate test;
 
 use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
 use solana_rbpf::EbpfVm;
+use solana_sdk::instruction_processor_utils::{ComputeBudget, MockInvokeContext};
 use std::env;
 use std::fs::File;
 use std::io::Error;
@@ -76,7 +77,12 @@ fn bench_program_alu(bencher: &mut Bencher) {
     inner_iter.write_u64::<LittleEndian>(0).unwrap();
 
     let elf = load_elf().unwrap();
-    let (mut vm, _) = solana_bpf_loader_program::create_vm(&elf).unwrap();
+    let (mut vm, _) = solana_bpf_loader_program::create_vm(
+        &elf,
+        ComputeBudget::default().max_units,
+        &mut MockInvokeContext::default(),
+    )
+    .unwrap();
 
     println!("Interpreted:");
     assert_eq!(
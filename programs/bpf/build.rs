@@ -73,9 +73,12 @@ fn main() {
             "iter",
             "many_args",
             "external_spend",
+            "invoke",
+            "mem",
             "noop",
             "panic",
             "param_passing",
+            "sha",
             "sysval",
         ];
         for program in rust_programs.iter() {
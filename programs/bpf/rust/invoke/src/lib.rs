@@ -0,0 +1,27 @@
+//! @brief Example Rust-based BPF program that has the system program move a lamport from one
+//! account to another
+
+extern crate solana_sdk;
+use solana_sdk::{
+    account_info::AccountInfo, entrypoint, entrypoint::SUCCESS, program::invoke, pubkey::Pubkey,
+    system_instruction,
+};
+
+entrypoint!(process_instruction);
+fn process_instruction(_program_id: &Pubkey, accounts: &mut [AccountInfo], _data: &[u8]) -> u32 {
+    // account 0 is the mint and signed the transaction, which lets this program have the
+    // system program debit it on its behalf
+    let from_lamports = *accounts[0].lamports;
+    let to_lamports = *accounts[1].lamports;
+    invoke(&system_instruction::transfer(
+        accounts[0].key,
+        accounts[1].key,
+        1,
+    ));
+
+    // The system program's changes are visible once the invocation returns
+    assert_eq!(*accounts[0].lamports, from_lamports - 1);
+    assert_eq!(*accounts[1].lamports, to_lamports + 1);
+
+    SUCCESS
+}
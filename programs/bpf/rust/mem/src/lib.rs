@@ -0,0 +1,34 @@
+//! @brief Memory operation Syscall test
+
+extern crate solana_sdk;
+use solana_sdk::{
+    account_info::AccountInfo,
+    entrypoint,
+    entrypoint::SUCCESS,
+    info,
+    program_memory::{sol_memcmp, sol_memcpy, sol_memmove, sol_memset},
+    pubkey::Pubkey,
+};
+
+entrypoint!(process_instruction);
+fn process_instruction(_program_id: &Pubkey, _accounts: &mut [AccountInfo], _data: &[u8]) -> u32 {
+    info!("mem");
+
+    let mut buf = [0u8; 8];
+    sol_memcpy(&mut buf, &[1, 2, 3, 4], 4);
+    assert_eq!(buf, [1, 2, 3, 4, 0, 0, 0, 0]);
+
+    // Overlapping regions must go through memmove
+    unsafe { sol_memmove(buf.as_mut_ptr().add(2), buf.as_ptr(), 4) };
+    assert_eq!(buf, [1, 2, 1, 2, 3, 4, 0, 0]);
+
+    sol_memset(&mut buf[6..], 9, 2);
+    assert_eq!(buf, [1, 2, 1, 2, 3, 4, 9, 9]);
+
+    assert_eq!(sol_memcmp(&buf, &buf, 8), 0);
+    assert_eq!(sol_memcmp(&buf[..2], &buf[2..4], 2), 0);
+    assert!(sol_memcmp(&buf[..4], &buf[4..], 4) < 0);
+    assert!(sol_memcmp(&buf[4..], &buf[..4], 4) > 0);
+
+    SUCCESS
+}
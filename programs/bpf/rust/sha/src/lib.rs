@@ -0,0 +1,26 @@
+//! @brief SHA-256 and public key logging Syscall test
+
+extern crate solana_sdk;
+use solana_sdk::{
+    account_info::AccountInfo,
+    entrypoint,
+    entrypoint::SUCCESS,
+    hash::{hashv, Hash},
+    info,
+    pubkey::Pubkey,
+};
+use std::str::FromStr;
+
+entrypoint!(process_instruction);
+fn process_instruction(program_id: &Pubkey, _accounts: &mut [AccountInfo], _data: &[u8]) -> u32 {
+    info!("sha");
+
+    // SHA-256 of "Gaggablaghblagh!"
+    let expected = Hash::from_str("2CXAb4P6wq24ado2HykNsmTT3E6s6hv39NmZHJaAhRjY").unwrap();
+    assert_eq!(hashv(&[b"Gaggablaghblagh!"]), expected);
+    assert_eq!(hashv(&[b"Gaggabla", b"", b"ghblagh!"]), expected);
+
+    program_id.log();
+
+    SUCCESS
+}
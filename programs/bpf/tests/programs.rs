@@ -96,11 +96,14 @@ mod bpf {
                 ("solana_bpf_rust_alloc", true),
                 ("solana_bpf_rust_dep_crate", true),
                 ("solana_bpf_rust_external_spend", false),
+                ("solana_bpf_rust_invoke", true),
                 ("solana_bpf_rust_iter", true),
                 ("solana_bpf_rust_many_args", true),
+                ("solana_bpf_rust_mem", true),
                 ("solana_bpf_rust_noop", true),
                 ("solana_bpf_rust_panic", false),
                 ("solana_bpf_rust_param_passing", true),
+                ("solana_bpf_rust_sha", true),
                 ("solana_bpf_rust_sysval", true),
             ];
             for program in programs.iter() {
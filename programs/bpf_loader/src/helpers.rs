@@ -1,17 +1,26 @@
-use crate::alloc;
+use crate::{alloc, deserialize_parameters, update_parameters};
 use alloc::Alloc;
 use libc::c_char;
-use log::*;
 use solana_rbpf::{
-    ebpf::{HelperContext, MM_HEAP_START},
+    ebpf::{HelperContext, HelperFunction, MM_HEAP_START, MM_INPUT_START},
     memory_region::{translate_addr, MemoryRegion},
     EbpfVm,
 };
+use solana_sdk::account::KeyedAccount;
+use solana_sdk::hash::{Hasher, HASH_BYTES};
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::instruction_processor_utils::{
+    limited_deserialize, ComputeMeter, InvokeContext, Logger,
+};
+use solana_sdk::pubkey::Pubkey;
 use std::alloc::Layout;
+use std::cell::RefCell;
+use std::convert::TryFrom;
 use std::ffi::CStr;
 use std::io::{Error, ErrorKind};
 use std::mem;
-use std::slice::from_raw_parts;
+use std::rc::Rc;
+use std::slice::{from_raw_parts, from_raw_parts_mut};
 use std::str::from_utf8;
 
 /// Program heap allocators are intended to allocate/free from a given
@@ -26,14 +35,87 @@ use crate::allocator_bump::BPFAllocator;
 /// are expected to enforce this
 const DEFAULT_HEAP_SIZE: usize = 32 * 1024;
 
-pub fn register_helpers(vm: &mut EbpfVm) -> Result<MemoryRegion, Error> {
+/// Context of the helpers that charge a fixed cost to the instruction's compute meter
+struct MeteredHelperContext {
+    compute_meter: Rc<RefCell<dyn ComputeMeter>>,
+    cost: u64,
+    logger: Rc<RefCell<dyn Logger>>,
+}
+
+/// Charge the cost of the helper whose context this is, failing if the instruction's compute
+/// budget is exhausted
+fn charge(context: &mut HelperContext) -> Result<&MeteredHelperContext, Error> {
+    let context = context
+        .as_ref()
+        .and_then(|context| context.downcast_ref::<MeteredHelperContext>())
+        .ok_or_else(|| Error::new(ErrorKind::Other, "Error: Failed to get helper context"))?;
+    context
+        .compute_meter
+        .borrow_mut()
+        .consume(context.cost)
+        .map_err(|_| Error::new(ErrorKind::Other, "Error: Exceeded the compute budget"))?;
+    Ok(context)
+}
+
+pub fn register_helpers(
+    vm: &mut EbpfVm,
+    invoke_context: &mut dyn InvokeContext,
+) -> Result<MemoryRegion, Error> {
     vm.register_helper_ex("abort", helper_abort, None)?;
     vm.register_helper_ex("sol_panic", helper_sol_panic, None)?;
     vm.register_helper_ex("sol_panic_", helper_sol_panic, None)?;
-    vm.register_helper_ex("sol_log", helper_sol_log, None)?;
-    vm.register_helper_ex("sol_log_", helper_sol_log, None)?;
-    vm.register_helper_ex("sol_log_64", helper_sol_log_u64, None)?;
-    vm.register_helper_ex("sol_log_64_", helper_sol_log_u64, None)?;
+
+    // Metered helpers charge their cost to the instruction's compute meter, and log helpers
+    // write to the logger of the current invocation
+    let compute_budget = *invoke_context.get_compute_budget();
+    let metered_helpers: [(&str, HelperFunction, u64); 10] = [
+        ("sol_log", helper_sol_log, compute_budget.log_units),
+        ("sol_log_", helper_sol_log, compute_budget.log_units),
+        (
+            "sol_log_64",
+            helper_sol_log_u64,
+            compute_budget.log_64_units,
+        ),
+        (
+            "sol_log_64_",
+            helper_sol_log_u64,
+            compute_budget.log_64_units,
+        ),
+        (
+            "sol_log_pubkey",
+            helper_sol_log_pubkey,
+            compute_budget.log_pubkey_units,
+        ),
+        ("sol_sha256", helper_sol_sha256, compute_budget.sha256_units),
+        (
+            "sol_memcpy_",
+            helper_sol_memcpy,
+            compute_budget.mem_op_units,
+        ),
+        (
+            "sol_memmove_",
+            helper_sol_memmove,
+            compute_budget.mem_op_units,
+        ),
+        (
+            "sol_memset_",
+            helper_sol_memset,
+            compute_budget.mem_op_units,
+        ),
+        (
+            "sol_memcmp_",
+            helper_sol_memcmp,
+            compute_budget.mem_op_units,
+        ),
+    ];
+    for (name, helper, cost) in metered_helpers.iter() {
+        let context = MeteredHelperContext {
+            compute_meter: invoke_context.get_compute_meter(),
+            cost: *cost,
+            logger: invoke_context.get_logger(),
+        };
+        vm.register_helper_ex(name, *helper, Some(Box::new(context)))?;
+    }
 
     let heap = vec![0_u8; DEFAULT_HEAP_SIZE];
     let heap_region = MemoryRegion::new_from_slice(&heap, MM_HEAP_START);
@@ -43,6 +125,32 @@ pub fn register_helpers(vm: &mut EbpfVm) -> Result<MemoryRegion, Error> {
     Ok(heap_region)
 }
 
+/// Context of the `sol_invoke_` helper.  The loader keeps `invoke_context` and `keyed_accounts`
+/// borrowed for as long as the VM runs, and the helper only runs while the VM does
+struct InvokeHelperContext {
+    invoke_context: *mut (dyn InvokeContext + 'static),
+    keyed_accounts: *mut KeyedAccount<'static>,
+    keyed_accounts_len: usize,
+    error: Rc<RefCell<Option<InstructionError>>>,
+}
+
+/// Register the cross-program invocation helper.  `keyed_accounts` are the accounts serialized
+/// into the VM's input, and `error` receives the error of an invoked instruction that failed
+pub fn register_helper_invoke(
+    vm: &mut EbpfVm,
+    invoke_context: &mut dyn InvokeContext,
+    keyed_accounts: &mut [KeyedAccount],
+    error: Rc<RefCell<Option<InstructionError>>>,
+) -> Result<(), Error> {
+    let context = InvokeHelperContext {
+        invoke_context: unsafe { mem::transmute(invoke_context) },
+        keyed_accounts: keyed_accounts.as_mut_ptr() as *mut KeyedAccount<'static>,
+        keyed_accounts_len: keyed_accounts.len(),
+        error,
+    };
+    vm.register_helper_ex("sol_invoke_", helper_sol_invoke, Some(Box::new(context)))
+}
+
 /// Abort helper functions, called when the BPF program calls `abort()`
 /// The verify function returns an error which will cause the BPF program
 /// to be halted immediately
@@ -91,17 +199,19 @@ pub fn helper_sol_panic(
     Err(Error::new(ErrorKind::Other, "Error: BPF program Panicked"))
 }
 
+/// Log a user's info message
 pub fn helper_sol_log(
     addr: u64,
     len: u64,
     _arg3: u64,
     _arg4: u64,
     _arg5: u64,
-    _context: &mut HelperContext,
+    context: &mut HelperContext,
     ro_regions: &[MemoryRegion],
     _rw_regions: &[MemoryRegion],
 ) -> Result<u64, Error> {
-    if log_enabled!(log::Level::Info) {
+    let logger = &charge(context)?.logger;
+    if logger.borrow().log_enabled() {
         let host_addr = translate_addr(addr, len as usize, "Load", 0, ro_regions)?;
         let c_buf: *const c_char = host_addr as *const c_char;
         unsafe {
@@ -110,7 +220,9 @@ pub fn helper_sol_log(
                 if i == len - 1 || c == 0 {
                     let message =
                         from_utf8(from_raw_parts(host_addr as *const u8, len as usize)).unwrap();
-                    info!("info!: {}", message);
+                    logger
+                        .borrow_mut()
+                        .log(&format!("Program log: {}", message));
                     return Ok(0);
                 }
             }
@@ -124,22 +236,207 @@ pub fn helper_sol_log(
     }
 }
 
+/// Log 5 64-bit values
 pub fn helper_sol_log_u64(
     arg1: u64,
     arg2: u64,
     arg3: u64,
     arg4: u64,
     arg5: u64,
-    _context: &mut HelperContext,
+    context: &mut HelperContext,
     _ro_regions: &[MemoryRegion],
     _rw_regions: &[MemoryRegion],
 ) -> Result<u64, Error> {
-    if log_enabled!(log::Level::Info) {
-        info!(
-            "info!: {:#x}, {:#x}, {:#x}, {:#x}, {:#x}",
+    let mut logger = charge(context)?.logger.borrow_mut();
+    if logger.log_enabled() {
+        logger.log(&format!(
+            "Program log: {:#x}, {:#x}, {:#x}, {:#x}, {:#x}",
             arg1, arg2, arg3, arg4, arg5
-        );
+        ));
+    }
+    Ok(0)
+}
+
+/// Translate a VM address range to a host slice
+fn translate_slice<'a>(
+    vm_addr: u64,
+    len: u64,
+    access_type: &str,
+    regions: &[MemoryRegion],
+) -> Result<&'a [u8], Error> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    let host_addr = translate_addr(vm_addr, len as usize, access_type, 0, regions)?;
+    Ok(unsafe { from_raw_parts(host_addr as *const u8, len as usize) })
+}
+
+/// Translate a VM address range to a mutable host slice
+fn translate_slice_mut<'a>(
+    vm_addr: u64,
+    len: u64,
+    regions: &[MemoryRegion],
+) -> Result<&'a mut [u8], Error> {
+    if len == 0 {
+        return Ok(&mut []);
+    }
+    let host_addr = translate_addr(vm_addr, len as usize, "Store", 0, regions)?;
+    Ok(unsafe { from_raw_parts_mut(host_addr as *mut u8, len as usize) })
+}
+
+/// SHA-256 of the concatenation of `vals_len` slices, each laid out as a pointer followed by
+/// a length, written to the 32 bytes at `result_addr`
+pub fn helper_sol_sha256(
+    vals_addr: u64,
+    vals_len: u64,
+    result_addr: u64,
+    _arg4: u64,
+    _arg5: u64,
+    context: &mut HelperContext,
+    ro_regions: &[MemoryRegion],
+    rw_regions: &[MemoryRegion],
+) -> Result<u64, Error> {
+    charge(context)?;
+
+    let slice_size = 2 * mem::size_of::<u64>() as u64;
+    let vals_size = vals_len.checked_mul(slice_size).ok_or_else(|| {
+        Error::new(
+            ErrorKind::Other,
+            "Error: Too many slices to hash, their size overflows",
+        )
+    })?;
+    let vals = translate_slice(vals_addr, vals_size, "Load", ro_regions)?;
+    let mut hasher = Hasher::default();
+    for val in vals.chunks_exact(slice_size as usize) {
+        let (addr, len) = val.split_at(mem::size_of::<u64>());
+        let addr = u64::from_le_bytes(<[u8; 8]>::try_from(addr).unwrap());
+        let len = u64::from_le_bytes(<[u8; 8]>::try_from(len).unwrap());
+        hasher.hash(translate_slice(addr, len, "Load", ro_regions)?);
     }
+    let result = translate_slice_mut(result_addr, HASH_BYTES as u64, rw_regions)?;
+    result.copy_from_slice(hasher.result().as_ref());
+    Ok(0)
+}
+
+/// Log the base58 representation of the public key at `pubkey_addr`
+pub fn helper_sol_log_pubkey(
+    pubkey_addr: u64,
+    _arg2: u64,
+    _arg3: u64,
+    _arg4: u64,
+    _arg5: u64,
+    context: &mut HelperContext,
+    ro_regions: &[MemoryRegion],
+    _rw_regions: &[MemoryRegion],
+) -> Result<u64, Error> {
+    let logger = &charge(context)?.logger;
+
+    let pubkey = translate_slice(
+        pubkey_addr,
+        mem::size_of::<Pubkey>() as u64,
+        "Load",
+        ro_regions,
+    )?;
+    let mut logger = logger.borrow_mut();
+    if logger.log_enabled() {
+        logger.log(&format!("Program log: {}", Pubkey::new(pubkey)));
+    }
+    Ok(0)
+}
+
+/// Copy `n` bytes from `src_addr` to `dst_addr`, which must not overlap
+pub fn helper_sol_memcpy(
+    dst_addr: u64,
+    src_addr: u64,
+    n: u64,
+    _arg4: u64,
+    _arg5: u64,
+    context: &mut HelperContext,
+    ro_regions: &[MemoryRegion],
+    rw_regions: &[MemoryRegion],
+) -> Result<u64, Error> {
+    charge(context)?;
+
+    if dst_addr < src_addr.saturating_add(n) && src_addr < dst_addr.saturating_add(n) {
+        return Err(Error::new(
+            ErrorKind::Other,
+            "Error: Overlapping memcpy, use memmove",
+        ));
+    }
+    let src = translate_slice(src_addr, n, "Load", ro_regions)?;
+    let dst = translate_slice_mut(dst_addr, n, rw_regions)?;
+    dst.copy_from_slice(src);
+    Ok(0)
+}
+
+/// Copy `n` bytes from `src_addr` to `dst_addr`, which may overlap
+pub fn helper_sol_memmove(
+    dst_addr: u64,
+    src_addr: u64,
+    n: u64,
+    _arg4: u64,
+    _arg5: u64,
+    context: &mut HelperContext,
+    ro_regions: &[MemoryRegion],
+    rw_regions: &[MemoryRegion],
+) -> Result<u64, Error> {
+    charge(context)?;
+
+    if n == 0 {
+        return Ok(0);
+    }
+    // The ranges may overlap, so they are copied between as raw pointers, never as slices
+    let src = translate_addr(src_addr, n as usize, "Load", 0, ro_regions)? as *const u8;
+    let dst = translate_addr(dst_addr, n as usize, "Store", 0, rw_regions)? as *mut u8;
+    unsafe {
+        std::ptr::copy(src, dst, n as usize);
+    }
+    Ok(0)
+}
+
+/// Set `n` bytes at `s_addr` to `c`
+pub fn helper_sol_memset(
+    s_addr: u64,
+    c: u64,
+    n: u64,
+    _arg4: u64,
+    _arg5: u64,
+    context: &mut HelperContext,
+    _ro_regions: &[MemoryRegion],
+    rw_regions: &[MemoryRegion],
+) -> Result<u64, Error> {
+    charge(context)?;
+
+    for byte in translate_slice_mut(s_addr, n, rw_regions)?.iter_mut() {
+        *byte = c as u8;
+    }
+    Ok(0)
+}
+
+/// Compare `n` bytes at `s1_addr` and `s2_addr`, writing the difference of the first pair
+/// of bytes that differ, or 0, as an i32 to `result_addr`
+pub fn helper_sol_memcmp(
+    s1_addr: u64,
+    s2_addr: u64,
+    n: u64,
+    result_addr: u64,
+    _arg5: u64,
+    context: &mut HelperContext,
+    ro_regions: &[MemoryRegion],
+    rw_regions: &[MemoryRegion],
+) -> Result<u64, Error> {
+    charge(context)?;
+
+    let s1 = translate_slice(s1_addr, n, "Load", ro_regions)?;
+    let s2 = translate_slice(s2_addr, n, "Load", ro_regions)?;
+    let difference = s1
+        .iter()
+        .zip(s2)
+        .map(|(a, b)| i32::from(*a) - i32::from(*b))
+        .find(|difference| *difference != 0)
+        .unwrap_or(0);
+    let result = translate_slice_mut(result_addr, mem::size_of::<i32>() as u64, rw_regions)?;
+    result.copy_from_slice(&difference.to_le_bytes());
     Ok(0)
 }
 
@@ -177,3 +474,266 @@ pub fn helper_sol_alloc_free(
     }
     panic!("Failed to get alloc_free context");
 }
+
+/// Cross-program invocation helper called when the BPF program calls `sol_invoke_()`, with
+/// the serialized instruction and the serialized seeds of the program addresses it signs for.
+/// The program's changes to its accounts are copied out of the VM's input before the
+/// instruction is invoked, and the invoked program's changes are copied back in afterwards
+pub fn helper_sol_invoke(
+    instruction_addr: u64,
+    instruction_len: u64,
+    signers_seeds_addr: u64,
+    signers_seeds_len: u64,
+    _arg5: u64,
+    context: &mut HelperContext,
+    ro_regions: &[MemoryRegion],
+    rw_regions: &[MemoryRegion],
+) -> Result<u64, Error> {
+    let context = context
+        .as_mut()
+        .and_then(|context| context.downcast_mut::<InvokeHelperContext>())
+        .ok_or_else(|| Error::new(ErrorKind::Other, "Error: Failed to get invoke context"))?;
+
+    let host_addr = translate_addr(
+        instruction_addr,
+        instruction_len as usize,
+        "Load",
+        0,
+        ro_regions,
+    )?;
+    let instruction: Instruction = limited_deserialize(unsafe {
+        from_raw_parts(host_addr as *const u8, instruction_len as usize)
+    })
+    .map_err(|_| Error::new(ErrorKind::Other, "Error: Invalid instruction"))?;
+    let signers_seeds: Vec<Vec<Vec<u8>>> = if signers_seeds_len > 0 {
+        let host_addr = translate_addr(
+            signers_seeds_addr,
+            signers_seeds_len as usize,
+            "Load",
+            0,
+            ro_regions,
+        )?;
+        limited_deserialize(unsafe {
+            from_raw_parts(host_addr as *const u8, signers_seeds_len as usize)
+        })
+        .map_err(|_| Error::new(ErrorKind::Other, "Error: Invalid signer seeds"))?
+    } else {
+        vec![]
+    };
+    let signers_seeds: Vec<Vec<&[u8]>> = signers_seeds
+        .iter()
+        .map(|seeds| seeds.iter().map(|seed| seed.as_slice()).collect())
+        .collect();
+    let signers_seeds: Vec<&[&[u8]]> = signers_seeds.iter().map(|seeds| seeds.as_slice()).collect();
+
+    let input_region = rw_regions
+        .iter()
+        .find(|region| region.addr_vm == MM_INPUT_START)
+        .ok_or_else(|| Error::new(ErrorKind::Other, "Error: Failed to get input region"))?;
+    let input =
+        unsafe { from_raw_parts_mut(input_region.addr_host as *mut u8, input_region.len as usize) };
+    let keyed_accounts =
+        unsafe { from_raw_parts_mut(context.keyed_accounts, context.keyed_accounts_len) };
+    let invoke_context = unsafe { &mut *context.invoke_context };
+
+    deserialize_parameters(keyed_accounts, input);
+    let result = invoke_context
+        .invoke_signed(&instruction, keyed_accounts, &signers_seeds)
+        .and_then(|_| update_parameters(keyed_accounts, input));
+    match result {
+        Ok(()) => Ok(0),
+        Err(err) => {
+            let message = format!("Error: Invoked instruction failed: {:?}", err);
+            *context.error.borrow_mut() = Some(err);
+            Err(Error::new(ErrorKind::Other, message))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::hashv;
+    use solana_sdk::instruction_processor_utils::{MockComputeMeter, MockLogger};
+
+    fn new_rw_region(buf: &mut [u8], addr_vm: u64) -> MemoryRegion {
+        MemoryRegion {
+            addr_host: buf.as_mut_ptr() as u64,
+            addr_vm,
+            len: buf.len() as u64,
+        }
+    }
+
+    fn new_context(compute_meter: &Rc<RefCell<MockComputeMeter>>, cost: u64) -> HelperContext {
+        Some(Box::new(MeteredHelperContext {
+            compute_meter: compute_meter.clone(),
+            cost,
+            logger: Rc::new(RefCell::new(MockLogger::default())),
+        }))
+    }
+
+    #[test]
+    fn test_helper_sol_sha256() {
+        let bytes1 = "Gaggablaghblagh!".as_bytes();
+        let bytes2 = "flurbos".as_bytes();
+        let vals = [
+            bytes1.as_ptr() as u64,
+            bytes1.len() as u64,
+            bytes2.as_ptr() as u64,
+            bytes2.len() as u64,
+        ];
+        let vals_bytes =
+            unsafe { from_raw_parts(vals.as_ptr() as *const u8, mem::size_of_val(&vals)) };
+        let ro_regions = [
+            MemoryRegion::new_from_slice(vals_bytes, vals_bytes.as_ptr() as u64),
+            MemoryRegion::new_from_slice(bytes1, bytes1.as_ptr() as u64),
+            MemoryRegion::new_from_slice(bytes2, bytes2.as_ptr() as u64),
+        ];
+        let mut result = [0u8; HASH_BYTES];
+        let rw_regions = [MemoryRegion::new_from_slice(
+            &result,
+            result.as_ptr() as u64,
+        )];
+
+        let compute_meter = Rc::new(RefCell::new(MockComputeMeter { remaining: 170 }));
+        let mut context = new_context(&compute_meter, 85);
+        helper_sol_sha256(
+            vals_bytes.as_ptr() as u64,
+            2,
+            result.as_mut_ptr() as u64,
+            0,
+            0,
+            &mut context,
+            &ro_regions,
+            &rw_regions,
+        )
+        .unwrap();
+        assert_eq!(result, hashv(&[bytes1, bytes2]).as_ref());
+
+        // The size of the slices overflows
+        assert!(helper_sol_sha256(
+            vals_bytes.as_ptr() as u64,
+            u64::max_value() / 8,
+            result.as_mut_ptr() as u64,
+            0,
+            0,
+            &mut context,
+            &ro_regions,
+            &rw_regions,
+        )
+        .is_err());
+
+        // Compute budget exhausted
+        assert!(helper_sol_sha256(
+            vals_bytes.as_ptr() as u64,
+            2,
+            result.as_mut_ptr() as u64,
+            0,
+            0,
+            &mut context,
+            &ro_regions,
+            &rw_regions,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_helper_sol_mem_ops() {
+        let src = [1u8, 2, 3, 4];
+        let mut dst = [0u8; 4];
+        let ro_regions = [MemoryRegion::new_from_slice(&src, 0x1000)];
+        let rw_regions = [new_rw_region(&mut dst, 0x2000)];
+        let compute_meter = Rc::new(RefCell::new(MockComputeMeter { remaining: 1_000 }));
+        let mut context = new_context(&compute_meter, 10);
+
+        helper_sol_memcpy(
+            0x2000,
+            0x1000,
+            4,
+            0,
+            0,
+            &mut context,
+            &ro_regions,
+            &rw_regions,
+        )
+        .unwrap();
+        assert_eq!(dst, src);
+
+        // Out of bounds
+        assert!(helper_sol_memcpy(
+            0x2000,
+            0x1001,
+            4,
+            0,
+            0,
+            &mut context,
+            &ro_regions,
+            &rw_regions
+        )
+        .is_err());
+        assert!(
+            helper_sol_memset(0x2002, 0, 3, 0, 0, &mut context, &ro_regions, &rw_regions).is_err()
+        );
+
+        // Overlapping copies are only allowed through memmove
+        let regions = [new_rw_region(&mut dst, 0x2000)];
+        assert!(
+            helper_sol_memcpy(0x2001, 0x2000, 3, 0, 0, &mut context, &regions, &regions).is_err()
+        );
+        helper_sol_memmove(0x2001, 0x2000, 3, 0, 0, &mut context, &regions, &regions).unwrap();
+        assert_eq!(dst, [1, 1, 2, 3]);
+
+        helper_sol_memset(0x2002, 9, 2, 0, 0, &mut context, &ro_regions, &rw_regions).unwrap();
+        assert_eq!(dst, [1, 1, 9, 9]);
+
+        let mut result = 0i32;
+        let regions = [
+            MemoryRegion::new_from_slice(&src, 0x1000),
+            MemoryRegion::new_from_slice(&dst, 0x2000),
+        ];
+        let result_regions = [MemoryRegion {
+            addr_host: &mut result as *mut _ as u64,
+            addr_vm: 0x3000,
+            len: mem::size_of::<i32>() as u64,
+        }];
+        helper_sol_memcmp(
+            0x1000,
+            0x2000,
+            4,
+            0x3000,
+            0,
+            &mut context,
+            &regions,
+            &result_regions,
+        )
+        .unwrap();
+        assert_eq!(result, 1);
+        helper_sol_memcmp(
+            0x2000,
+            0x1000,
+            1,
+            0x3000,
+            0,
+            &mut context,
+            &regions,
+            &result_regions,
+        )
+        .unwrap();
+        assert_eq!(result, 0);
+        result = 5;
+        helper_sol_memcmp(
+            0x2000,
+            0x1000,
+            3,
+            0x3000,
+            0,
+            &mut context,
+            &regions,
+            &result_regions,
+        )
+        .unwrap();
+        assert_eq!(result, -1);
+
+        assert_eq!(compute_meter.borrow().remaining, 1_000 - 9 * 10);
+    }
+}
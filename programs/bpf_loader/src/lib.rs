@@ -8,28 +8,54 @@ use log::*;
 use solana_rbpf::{memory_region::MemoryRegion, EbpfVm};
 use solana_sdk::account::KeyedAccount;
 use solana_sdk::instruction::InstructionError;
-use solana_sdk::instruction_processor_utils::{limited_deserialize, next_keyed_account};
+use solana_sdk::instruction_processor_utils::{
+    limited_deserialize, next_keyed_account, InvokeContext,
+};
 use solana_sdk::loader_instruction::LoaderInstruction;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::sysvar::rent;
+use std::cell::RefCell;
 use std::convert::TryFrom;
 use std::io::prelude::*;
 use std::io::Error;
 use std::mem;
+use std::rc::Rc;
 
-solana_sdk::declare_program!(
+macro_rules! log_message {
+    ($logger:ident, $message:expr) => {
+        if let Ok(mut logger) = $logger.try_borrow_mut() {
+            if logger.log_enabled() {
+                logger.log($message);
+            }
+        }
+    };
+    ($logger:ident, $fmt:expr, $($arg:tt)*) => {
+        if let Ok(mut logger) = $logger.try_borrow_mut() {
+            if logger.log_enabled() {
+                logger.log(&format!($fmt, $($arg)*));
+            }
+        }
+    };
+}
+
+solana_sdk::declare_loader!(
     solana_sdk::bpf_loader::ID,
     solana_bpf_loader_program,
     process_instruction
 );
 
-pub fn create_vm(prog: &[u8]) -> Result<(EbpfVm, MemoryRegion), Error> {
+/// Create a VM for `prog` that may execute up to `max_instruction_count` instructions
+pub fn create_vm<'a>(
+    prog: &'a [u8],
+    max_instruction_count: u64,
+    invoke_context: &mut dyn InvokeContext,
+) -> Result<(EbpfVm<'a>, MemoryRegion), Error> {
     let mut vm = EbpfVm::new(None)?;
     vm.set_verifier(bpf_verifier::check)?;
-    vm.set_max_instruction_count(100_000)?;
+    vm.set_max_instruction_count(max_instruction_count)?;
     vm.set_elf(&prog)?;
 
-    let heap_region = helpers::register_helpers(&mut vm)?;
+    let heap_region = helpers::register_helpers(&mut vm, invoke_context)?;
 
     Ok((vm, heap_region))
 }
@@ -86,10 +112,138 @@ fn deserialize_parameters(keyed_accounts: &mut [KeyedAccount], buffer: &[u8]) {
     }
 }
 
+/// Copy the accounts' lamports, data and owner back into a buffer created by
+/// `serialize_parameters`, after another program changed them
+fn update_parameters(
+    keyed_accounts: &[KeyedAccount],
+    buffer: &mut [u8],
+) -> Result<(), InstructionError> {
+    assert_eq!(32, mem::size_of::<Pubkey>());
+
+    let mut start = mem::size_of::<u64>();
+    for info in keyed_accounts.iter() {
+        start += mem::size_of::<u64>(); // skip signer_key boolean
+        start += mem::size_of::<Pubkey>(); // skip pubkey
+        LittleEndian::write_u64(&mut buffer[start..], info.account.lamports);
+
+        start += mem::size_of::<u64>(); // skip lamports
+        if LittleEndian::read_u64(&buffer[start..]) as usize != info.account.data.len() {
+            // The program's view of the account can't be resized
+            return Err(InstructionError::AccountDataSizeChanged);
+        }
+        start += mem::size_of::<u64>(); // skip length tag
+        let end = start + info.account.data.len();
+        buffer[start..end].copy_from_slice(&info.account.data);
+
+        start += info.account.data.len(); // skip data
+        let end = start + mem::size_of::<Pubkey>();
+        buffer[start..end].copy_from_slice(info.account.owner.as_ref());
+        start = end; // skip owner
+    }
+    Ok(())
+}
+
+/// Run the BPF program `elf`, deployed at `program_id`, with `parameter_accounts` and `data` as
+/// its input
+pub fn execute(
+    elf: &[u8],
+    program_id: &Pubkey,
+    parameter_accounts: &mut [KeyedAccount],
+    data: &[u8],
+    invoke_context: &mut dyn InvokeContext,
+) -> Result<(), InstructionError> {
+    let logger = invoke_context.get_logger();
+    let compute_meter = invoke_context.get_compute_meter();
+    let initial_units = compute_meter.borrow().get_remaining();
+
+    // The VM may execute as many instructions as there are units left.  The helpers the program
+    // calls and the programs it invokes are charged as they run, but the VM only reports how
+    // many instructions it executed once the program returns, so those are charged last and
+    // the program fails if, together, they exceed the budget
+    if initial_units == 0 {
+        // The VM treats a zero instruction limit as no limit
+        return Err(InstructionError::ComputationalBudgetExceeded);
+    }
+    let (mut vm, heap_region) = match create_vm(elf, initial_units, invoke_context) {
+        Ok(info) => info,
+        Err(e) => {
+            warn!("Failed to create BPF VM: {}", e);
+            return Err(InstructionError::GenericError);
+        }
+    };
+    let mut parameter_bytes = serialize_parameters(program_id, parameter_accounts, data);
+    let invoke_error = Rc::new(RefCell::new(None));
+    if let Err(e) = helpers::register_helper_invoke(
+        &mut vm,
+        invoke_context,
+        parameter_accounts,
+        invoke_error.clone(),
+    ) {
+        warn!("Failed to register invoke helper: {}", e);
+        return Err(InstructionError::GenericError);
+    }
+
+    log_message!(logger, "Call BPF program {}", program_id);
+    let result = vm.execute_program(parameter_bytes.as_mut_slice(), &[], &[heap_region]);
+
+    let executed_instructions = vm.get_last_instruction_count();
+    let calls_exceeded_budget = compute_meter.borrow().get_remaining() == 0;
+    let exceeded_budget = compute_meter
+        .borrow_mut()
+        .consume(executed_instructions)
+        .is_err();
+    let remaining_units = compute_meter.borrow().get_remaining();
+    log_message!(
+        logger,
+        "BPF program {} consumed {} of {} units",
+        program_id,
+        initial_units - remaining_units,
+        initial_units
+    );
+    if exceeded_budget {
+        log_message!(
+            logger,
+            "BPF program {} exceeded its compute budget",
+            program_id
+        );
+        return Err(InstructionError::ComputationalBudgetExceeded);
+    }
+    match result {
+        Ok(status) => match u32::try_from(status) {
+            Ok(status) => {
+                if status > 0 {
+                    log_message!(logger, "BPF program {} failed: {}", program_id, status);
+                    return Err(InstructionError::CustomError(status));
+                }
+            }
+            Err(e) => {
+                warn!("BPF VM encountered invalid status: {}", e);
+                return Err(InstructionError::GenericError);
+            }
+        },
+        Err(e) => {
+            warn!("BPF VM failed to run program: {}", e);
+            // Fail with the error of the instruction the program invoked, if any
+            let err = invoke_error.borrow_mut().take();
+            if let Some(err) = err {
+                return Err(err);
+            }
+            if executed_instructions >= initial_units || calls_exceeded_budget {
+                return Err(InstructionError::ComputationalBudgetExceeded);
+            }
+            return Err(InstructionError::GenericError);
+        }
+    }
+    deserialize_parameters(parameter_accounts, &parameter_bytes);
+    log_message!(logger, "BPF program {} success", program_id);
+    Ok(())
+}
+
 pub fn process_instruction(
     program_id: &Pubkey,
     keyed_accounts: &mut [KeyedAccount],
     ix_data: &[u8],
+    invoke_context: &mut dyn InvokeContext,
 ) -> Result<(), InstructionError> {
     solana_logger::setup();
 
@@ -143,38 +297,13 @@ pub fn process_instruction(
                     warn!("BPF program account not executable");
                     return Err(InstructionError::AccountNotExecutable);
                 }
-                let (mut vm, heap_region) = match create_vm(&program.account.data) {
-                    Ok(info) => info,
-                    Err(e) => {
-                        warn!("Failed to create BPF VM: {}", e);
-                        return Err(InstructionError::GenericError);
-                    }
-                };
-                let parameter_accounts = keyed_accounts_iter.into_slice();
-                let mut parameter_bytes =
-                    serialize_parameters(program_id, parameter_accounts, &data);
-
-                info!("Call BPF program");
-                match vm.execute_program(parameter_bytes.as_mut_slice(), &[], &[heap_region]) {
-                    Ok(status) => match u32::try_from(status) {
-                        Ok(status) => {
-                            if status > 0 {
-                                warn!("BPF program failed: {}", status);
-                                return Err(InstructionError::CustomError(status));
-                            }
-                        }
-                        Err(e) => {
-                            warn!("BPF VM encountered invalid status: {}", e);
-                            return Err(InstructionError::GenericError);
-                        }
-                    },
-                    Err(e) => {
-                        warn!("BPF VM failed to run program: {}", e);
-                        return Err(InstructionError::GenericError);
-                    }
-                }
-                deserialize_parameters(parameter_accounts, &parameter_bytes);
-                info!("BPF program success");
+                execute(
+                    &program.account.data,
+                    program_id,
+                    keyed_accounts_iter.into_slice(),
+                    &data,
+                    invoke_context,
+                )?;
             }
         }
     } else {
@@ -187,7 +316,7 @@ pub fn process_instruction(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use solana_sdk::account::Account;
+    use solana_sdk::{account::Account, instruction_processor_utils::MockInvokeContext};
     use std::fs::File;
     use std::io::Read;
 
@@ -224,13 +353,23 @@ mod tests {
         // Case: Empty keyed accounts
         assert_eq!(
             Err(InstructionError::NotEnoughAccountKeys),
-            process_instruction(&program_id, &mut vec![], &ix_data)
+            process_instruction(
+                &program_id,
+                &mut vec![],
+                &ix_data,
+                &mut MockInvokeContext::default()
+            )
         );
 
         // Case: Not signed
         assert_eq!(
             Err(InstructionError::MissingRequiredSignature),
-            process_instruction(&program_id, &mut keyed_accounts, &ix_data)
+            process_instruction(
+                &program_id,
+                &mut keyed_accounts,
+                &ix_data,
+                &mut MockInvokeContext::default()
+            )
         );
 
         // Case: Write bytes to an offset
@@ -238,7 +377,12 @@ mod tests {
         keyed_accounts[0].account.data = vec![0; 6];
         assert_eq!(
             Ok(()),
-            process_instruction(&program_id, &mut keyed_accounts, &ix_data)
+            process_instruction(
+                &program_id,
+                &mut keyed_accounts,
+                &ix_data,
+                &mut MockInvokeContext::default()
+            )
         );
         assert_eq!(vec![0, 0, 0, 1, 2, 3], keyed_accounts[0].account.data);
 
@@ -247,7 +391,12 @@ mod tests {
         keyed_accounts[0].account.data = vec![0; 5];
         assert_eq!(
             Err(InstructionError::AccountDataTooSmall),
-            process_instruction(&program_id, &mut keyed_accounts, &ix_data)
+            process_instruction(
+                &program_id,
+                &mut keyed_accounts,
+                &ix_data,
+                &mut MockInvokeContext::default()
+            )
         );
     }
 
@@ -268,7 +417,12 @@ mod tests {
         // Case: Empty keyed accounts
         assert_eq!(
             Err(InstructionError::NotEnoughAccountKeys),
-            process_instruction(&program_id, &mut vec![], &ix_data)
+            process_instruction(
+                &program_id,
+                &mut vec![],
+                &ix_data,
+                &mut MockInvokeContext::default()
+            )
         );
 
         let mut rent_account = rent::create_account(1, &rent);
@@ -277,7 +431,12 @@ mod tests {
         // Case: Not signed
         assert_eq!(
             Err(InstructionError::MissingRequiredSignature),
-            process_instruction(&program_id, &mut keyed_accounts, &ix_data)
+            process_instruction(
+                &program_id,
+                &mut keyed_accounts,
+                &ix_data,
+                &mut MockInvokeContext::default()
+            )
         );
 
         // Case: Finalize
@@ -287,7 +446,12 @@ mod tests {
         ];
         assert_eq!(
             Ok(()),
-            process_instruction(&program_id, &mut keyed_accounts, &ix_data)
+            process_instruction(
+                &program_id,
+                &mut keyed_accounts,
+                &ix_data,
+                &mut MockInvokeContext::default()
+            )
         );
         assert!(keyed_accounts[0].account.executable);
 
@@ -299,7 +463,12 @@ mod tests {
         ];
         assert_eq!(
             Err(InstructionError::InvalidAccountData),
-            process_instruction(&program_id, &mut keyed_accounts, &ix_data)
+            process_instruction(
+                &program_id,
+                &mut keyed_accounts,
+                &ix_data,
+                &mut MockInvokeContext::default()
+            )
         );
     }
 
@@ -322,20 +491,35 @@ mod tests {
         // Case: Empty keyed accounts
         assert_eq!(
             Err(InstructionError::NotEnoughAccountKeys),
-            process_instruction(&program_id, &mut vec![], &ix_data)
+            process_instruction(
+                &program_id,
+                &mut vec![],
+                &ix_data,
+                &mut MockInvokeContext::default()
+            )
         );
 
         // Case: Only a program account
         assert_eq!(
             Ok(()),
-            process_instruction(&program_id, &mut keyed_accounts, &ix_data)
+            process_instruction(
+                &program_id,
+                &mut keyed_accounts,
+                &ix_data,
+                &mut MockInvokeContext::default()
+            )
         );
 
         // Case: Account not executable
         keyed_accounts[0].account.executable = false;
         assert_eq!(
             Err(InstructionError::AccountNotExecutable),
-            process_instruction(&program_id, &mut keyed_accounts, &ix_data)
+            process_instruction(
+                &program_id,
+                &mut keyed_accounts,
+                &ix_data,
+                &mut MockInvokeContext::default()
+            )
         );
         keyed_accounts[0].account.executable = true;
 
@@ -348,7 +532,85 @@ mod tests {
         ));
         assert_eq!(
             Ok(()),
-            process_instruction(&program_id, &mut keyed_accounts, &ix_data)
+            process_instruction(
+                &program_id,
+                &mut keyed_accounts,
+                &ix_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+
+        // Case: Program uses its whole compute budget
+        let mut invoke_context = MockInvokeContext::default();
+        process_instruction(
+            &program_id,
+            &mut keyed_accounts,
+            &ix_data,
+            &mut invoke_context,
+        )
+        .unwrap();
+        let consumed = invoke_context.compute_budget.max_units
+            - invoke_context.compute_meter.borrow().remaining;
+        assert!(consumed > 0);
+        let mut invoke_context = MockInvokeContext::default();
+        invoke_context.compute_meter.borrow_mut().remaining = consumed;
+        assert_eq!(
+            Ok(()),
+            process_instruction(
+                &program_id,
+                &mut keyed_accounts,
+                &ix_data,
+                &mut invoke_context
+            )
+        );
+        assert_eq!(invoke_context.compute_meter.borrow().remaining, 0);
+
+        // Case: Compute budget exhausted, before and while running
+        for remaining in &[0, 1, 2, consumed - 1] {
+            let mut invoke_context = MockInvokeContext::default();
+            invoke_context.compute_meter.borrow_mut().remaining = *remaining;
+            assert_eq!(
+                Err(InstructionError::ComputationalBudgetExceeded),
+                process_instruction(
+                    &program_id,
+                    &mut keyed_accounts,
+                    &ix_data,
+                    &mut invoke_context
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_bpf_loader_update_parameters() {
+        let program_id = Pubkey::new_rand();
+        let keys = vec![Pubkey::new_rand(), Pubkey::new_rand()];
+        let mut accounts = vec![
+            Account::new(10, 3, &program_id),
+            Account::new(20, 0, &program_id),
+        ];
+        let mut keyed_accounts: Vec<_> = keys
+            .iter()
+            .zip(accounts.iter_mut())
+            .map(|(key, account)| KeyedAccount::new(key, false, account))
+            .collect();
+        let mut buffer = serialize_parameters(&program_id, &mut keyed_accounts, &[4, 5]);
+
+        // Another program's changes end up where the program looks for them
+        keyed_accounts[0].account.lamports = 5;
+        keyed_accounts[0].account.data = vec![3, 2, 1];
+        keyed_accounts[1].account.lamports = 25;
+        keyed_accounts[1].account.owner = Pubkey::new_rand();
+        assert_eq!(update_parameters(&keyed_accounts, &mut buffer), Ok(()));
+        assert_eq!(
+            buffer,
+            serialize_parameters(&program_id, &mut keyed_accounts, &[4, 5])
+        );
+
+        keyed_accounts[0].account.data = vec![1];
+        assert_eq!(
+            update_parameters(&keyed_accounts, &mut buffer),
+            Err(InstructionError::AccountDataSizeChanged)
         );
     }
 }
@@ -0,0 +1,1039 @@
+use log::*;
+use solana_bpf_loader_program::{check_elf, execute};
+use solana_sdk::account::KeyedAccount;
+use solana_sdk::account_utils::State;
+use solana_sdk::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::instruction_processor_utils::{
+    limited_deserialize, next_keyed_account, InvokeContext,
+};
+use solana_sdk::loader_instruction::LoaderInstruction;
+use solana_sdk::loader_upgradeable_instruction::UpgradeableLoaderInstruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_instruction;
+use solana_sdk::sysvar::{rent::Rent, Sysvar};
+
+solana_sdk::declare_loader!(
+    solana_sdk::bpf_loader_upgradeable::ID,
+    solana_bpf_loader_upgradeable_program,
+    process_instruction
+);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    keyed_accounts: &mut [KeyedAccount],
+    ix_data: &[u8],
+    invoke_context: &mut dyn InvokeContext,
+) -> Result<(), InstructionError> {
+    if bpf_loader_upgradeable::check_id(program_id) {
+        process_loader_instruction(keyed_accounts, ix_data, invoke_context)
+    } else {
+        // The runtime wraps the data of instructions to programs the loader deployed
+        match limited_deserialize(ix_data)? {
+            LoaderInstruction::InvokeMain { data } => {
+                invoke_program(program_id, keyed_accounts, &data, invoke_context)
+            }
+            _ => {
+                warn!("Invalid instruction data: {:?}", ix_data);
+                Err(InstructionError::InvalidInstructionData)
+            }
+        }
+    }
+}
+
+/// Run a deployed program.  The runtime loads the program's ProgramData account ahead of the
+/// program, neither is passed to the program
+fn invoke_program(
+    program_id: &Pubkey,
+    keyed_accounts: &mut [KeyedAccount],
+    data: &[u8],
+    invoke_context: &mut dyn InvokeContext,
+) -> Result<(), InstructionError> {
+    let mut keyed_accounts_iter = keyed_accounts.iter_mut();
+    let programdata = next_keyed_account(&mut keyed_accounts_iter)?;
+    let program = next_keyed_account(&mut keyed_accounts_iter)?;
+
+    if !program.account.executable {
+        warn!("BPF program account not executable");
+        return Err(InstructionError::AccountNotExecutable);
+    }
+    check_programdata_address(program, programdata)?;
+    if programdata.account.owner != bpf_loader_upgradeable::id() {
+        warn!("ProgramData account not owned by the loader");
+        return Err(InstructionError::IncorrectProgramId);
+    }
+    let elf = programdata
+        .account
+        .data
+        .get(UpgradeableLoaderState::programdata_data_offset()?..)
+        .ok_or(InstructionError::InvalidAccountData)?;
+    execute(
+        elf,
+        program_id,
+        keyed_accounts_iter.into_slice(),
+        data,
+        invoke_context,
+    )
+}
+
+fn process_loader_instruction(
+    keyed_accounts: &mut [KeyedAccount],
+    ix_data: &[u8],
+    invoke_context: &mut dyn InvokeContext,
+) -> Result<(), InstructionError> {
+    match limited_deserialize(ix_data)? {
+        UpgradeableLoaderInstruction::InitializeBuffer => {
+            let mut keyed_accounts_iter = keyed_accounts.iter_mut();
+            let buffer = next_keyed_account(&mut keyed_accounts_iter)?;
+            let authority = next_keyed_account(&mut keyed_accounts_iter)?;
+            let state: UpgradeableLoaderState = buffer.state()?;
+            if state != UpgradeableLoaderState::Uninitialized {
+                warn!("Buffer account already initialized");
+                return Err(InstructionError::AccountAlreadyInitialized);
+            }
+            buffer.set_state(&UpgradeableLoaderState::Buffer {
+                authority_address: *authority.unsigned_key(),
+            })?;
+        }
+        UpgradeableLoaderInstruction::Write { offset, bytes } => {
+            let mut keyed_accounts_iter = keyed_accounts.iter_mut();
+            let buffer = next_keyed_account(&mut keyed_accounts_iter)?;
+            let authority = next_keyed_account(&mut keyed_accounts_iter)?;
+            check_buffer_authority(buffer, authority)?;
+            let offset = UpgradeableLoaderState::buffer_data_offset()? + offset as usize;
+            let len = bytes.len();
+            trace!("Write: offset={} length={}", offset, len);
+            if buffer.account.data.len() < offset + len {
+                warn!(
+                    "Write overflow: {} < {}",
+                    buffer.account.data.len(),
+                    offset + len
+                );
+                return Err(InstructionError::AccountDataTooSmall);
+            }
+            buffer.account.data[offset..offset + len].copy_from_slice(&bytes);
+        }
+        UpgradeableLoaderInstruction::DeployWithMaxDataLen { max_data_len } => {
+            deploy(keyed_accounts, max_data_len, invoke_context)?;
+        }
+        UpgradeableLoaderInstruction::Upgrade => {
+            let mut keyed_accounts_iter = keyed_accounts.iter_mut();
+            let programdata = next_keyed_account(&mut keyed_accounts_iter)?;
+            let program = next_keyed_account(&mut keyed_accounts_iter)?;
+            let buffer = next_keyed_account(&mut keyed_accounts_iter)?;
+            let spill = next_keyed_account(&mut keyed_accounts_iter)?;
+            let authority = next_keyed_account(&mut keyed_accounts_iter)?;
+
+            if !program.account.executable {
+                warn!("Program account not executable");
+                return Err(InstructionError::AccountNotExecutable);
+            }
+            if program.account.owner != bpf_loader_upgradeable::id() {
+                warn!("Program account not owned by the loader");
+                return Err(InstructionError::IncorrectProgramId);
+            }
+            check_programdata_address(program, programdata)?;
+            check_upgrade_authority(programdata, authority)?;
+            check_buffer_authority(buffer, authority)?;
+
+            let buffer_data_offset = UpgradeableLoaderState::buffer_data_offset()?;
+            let programdata_data_offset = UpgradeableLoaderState::programdata_data_offset()?;
+            let elf = &buffer.account.data[buffer_data_offset..];
+            if programdata.account.data.len() < programdata_data_offset + elf.len() {
+                warn!("ProgramData account too small for the upgrade");
+                return Err(InstructionError::AccountDataTooSmall);
+            }
+            if let Err(e) = check_elf(elf) {
+                warn!("Invalid ELF: {}", e);
+                return Err(InstructionError::InvalidAccountData);
+            }
+
+            // Replace the program data, clearing what is left of the old
+            let data = &mut programdata.account.data[programdata_data_offset..];
+            data[..elf.len()].copy_from_slice(elf);
+            for byte in data[elf.len()..].iter_mut() {
+                *byte = 0;
+            }
+
+            spill.account.lamports += buffer.account.lamports;
+            buffer.account.lamports = 0;
+            info!("Upgraded program {:?}", program.unsigned_key());
+        }
+        UpgradeableLoaderInstruction::SetAuthority => {
+            let mut keyed_accounts_iter = keyed_accounts.iter_mut();
+            let programdata = next_keyed_account(&mut keyed_accounts_iter)?;
+            let authority = next_keyed_account(&mut keyed_accounts_iter)?;
+            let new_authority = keyed_accounts_iter
+                .next()
+                .map(|keyed_account| *keyed_account.unsigned_key());
+
+            check_upgrade_authority(programdata, authority)?;
+            programdata.set_state(&UpgradeableLoaderState::ProgramData {
+                upgrade_authority_address: new_authority,
+            })?;
+            info!("New upgrade authority {:?}", new_authority);
+        }
+    }
+    Ok(())
+}
+
+/// Create the ProgramData account, copy the Buffer's program data into it, and mark the Program
+/// account executable
+fn deploy(
+    keyed_accounts: &mut [KeyedAccount],
+    max_data_len: usize,
+    invoke_context: &mut dyn InvokeContext,
+) -> Result<(), InstructionError> {
+    if keyed_accounts.len() < 7 {
+        return Err(InstructionError::NotEnoughAccountKeys);
+    }
+    let payer_key = *keyed_accounts[0].unsigned_key();
+    let programdata_key = *keyed_accounts[1].unsigned_key();
+    let program_key = *keyed_accounts[2].unsigned_key();
+    let upgrade_authority_address = keyed_accounts
+        .get(7)
+        .map(|keyed_account| *keyed_account.unsigned_key());
+    let rent = Rent::from_keyed_account(&keyed_accounts[4])?;
+
+    let program = &keyed_accounts[2];
+    let state: UpgradeableLoaderState = program.state()?;
+    if state != UpgradeableLoaderState::Uninitialized {
+        warn!("Program account already initialized");
+        return Err(InstructionError::AccountAlreadyInitialized);
+    }
+    if program.account.data.len() < UpgradeableLoaderState::program_len()? {
+        warn!("Program account too small");
+        return Err(InstructionError::AccountDataTooSmall);
+    }
+    if !rent.is_exempt(program.account.lamports, program.account.data.len()) {
+        warn!("Program account not rent-exempt");
+        return Err(InstructionError::InsufficientFunds);
+    }
+
+    let buffer = &keyed_accounts[3];
+    check_buffer_authority(buffer, &keyed_accounts[6])?;
+    let buffer_data_offset = UpgradeableLoaderState::buffer_data_offset()?;
+    let program_len = buffer.account.data.len() - buffer_data_offset;
+    if max_data_len < program_len {
+        warn!("Max data length too small for the program data");
+        return Err(InstructionError::AccountDataTooSmall);
+    }
+    if let Err(e) = check_elf(&buffer.account.data[buffer_data_offset..]) {
+        warn!("Invalid ELF: {}", e);
+        return Err(InstructionError::InvalidAccountData);
+    }
+
+    // Only the loader can sign for the address derived from the Program account's, so that is
+    // where the ProgramData account is created
+    let (derived_address, bump_seed) =
+        Pubkey::find_program_address(&[program_key.as_ref()], &bpf_loader_upgradeable::id());
+    if derived_address != programdata_key {
+        warn!("ProgramData address is not derived from the Program address");
+        return Err(InstructionError::InvalidArgument);
+    }
+    let programdata_len = UpgradeableLoaderState::programdata_len(max_data_len)?;
+    invoke_context.invoke_signed(
+        &system_instruction::create_account(
+            &payer_key,
+            &programdata_key,
+            rent.minimum_balance(programdata_len),
+            programdata_len as u64,
+            &bpf_loader_upgradeable::id(),
+        ),
+        keyed_accounts,
+        &[&[program_key.as_ref(), &[bump_seed]]],
+    )?;
+
+    let (accounts, buffer) = keyed_accounts.split_at_mut(3);
+    let buffer = &mut buffer[0];
+    let programdata = &mut accounts[1];
+    programdata.set_state(&UpgradeableLoaderState::ProgramData {
+        upgrade_authority_address,
+    })?;
+    let programdata_data_offset = UpgradeableLoaderState::programdata_data_offset()?;
+    programdata.account.data[programdata_data_offset..programdata_data_offset + program_len]
+        .copy_from_slice(&buffer.account.data[buffer_data_offset..]);
+
+    let program = &mut accounts[2];
+    program.set_state(&UpgradeableLoaderState::Program {
+        programdata_address: programdata_key,
+    })?;
+    program.account.executable = true;
+
+    // The Buffer is no longer needed
+    accounts[0].account.lamports += buffer.account.lamports;
+    buffer.account.lamports = 0;
+    info!("Deployed program {:?}", program_key);
+    Ok(())
+}
+
+/// Check that `buffer` is a Buffer account, and that `authority` is its authority and signed
+fn check_buffer_authority(
+    buffer: &KeyedAccount,
+    authority: &KeyedAccount,
+) -> Result<(), InstructionError> {
+    if buffer.account.owner != bpf_loader_upgradeable::id() {
+        warn!("Buffer account not owned by the loader");
+        return Err(InstructionError::IncorrectProgramId);
+    }
+    match buffer.state()? {
+        UpgradeableLoaderState::Buffer { authority_address } => {
+            if authority_address != *authority.unsigned_key() {
+                warn!("Incorrect Buffer authority");
+                return Err(InstructionError::IncorrectAuthority);
+            }
+            if authority.signer_key().is_none() {
+                warn!("Buffer authority did not sign");
+                return Err(InstructionError::MissingRequiredSignature);
+            }
+            Ok(())
+        }
+        _ => {
+            warn!("Invalid Buffer account");
+            Err(InstructionError::InvalidAccountData)
+        }
+    }
+}
+
+/// Check that `programdata` is the ProgramData account `program` refers to
+fn check_programdata_address(
+    program: &KeyedAccount,
+    programdata: &KeyedAccount,
+) -> Result<(), InstructionError> {
+    match program.state()? {
+        UpgradeableLoaderState::Program {
+            programdata_address,
+        } => {
+            if programdata_address != *programdata.unsigned_key() {
+                warn!("Wrong ProgramData account");
+                return Err(InstructionError::InvalidArgument);
+            }
+            Ok(())
+        }
+        _ => {
+            warn!("Invalid Program account");
+            Err(InstructionError::InvalidAccountData)
+        }
+    }
+}
+
+/// Check that `authority` is the upgrade authority of `programdata`, and signed
+fn check_upgrade_authority(
+    programdata: &KeyedAccount,
+    authority: &KeyedAccount,
+) -> Result<(), InstructionError> {
+    if programdata.account.owner != bpf_loader_upgradeable::id() {
+        warn!("ProgramData account not owned by the loader");
+        return Err(InstructionError::IncorrectProgramId);
+    }
+    match programdata.state()? {
+        UpgradeableLoaderState::ProgramData {
+            upgrade_authority_address: None,
+        } => {
+            warn!("Program is immutable");
+            Err(InstructionError::Immutable)
+        }
+        UpgradeableLoaderState::ProgramData {
+            upgrade_authority_address: Some(upgrade_authority_address),
+        } => {
+            if upgrade_authority_address != *authority.unsigned_key() {
+                warn!("Incorrect upgrade authority");
+                return Err(InstructionError::IncorrectAuthority);
+            }
+            if authority.signer_key().is_none() {
+                warn!("Upgrade authority did not sign");
+                return Err(InstructionError::MissingRequiredSignature);
+            }
+            Ok(())
+        }
+        _ => {
+            warn!("Invalid ProgramData account");
+            Err(InstructionError::InvalidAccountData)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        account::Account, bpf_loader_upgradeable::get_program_data_address,
+        instruction_processor_utils::MockInvokeContext, system_program, sysvar::rent,
+    };
+    use std::{fs::File, io::Read};
+
+    fn load_elf() -> Vec<u8> {
+        let mut file = File::open("../bpf_loader/test_elfs/noop.so").expect("file open failed");
+        let mut elf = Vec::new();
+        file.read_to_end(&mut elf).unwrap();
+        elf
+    }
+
+    fn process_loader_instruction(
+        keyed_accounts: &mut [KeyedAccount],
+        instruction: &UpgradeableLoaderInstruction,
+        invoke_context: &mut MockInvokeContext,
+    ) -> Result<(), InstructionError> {
+        process_instruction(
+            &bpf_loader_upgradeable::id(),
+            keyed_accounts,
+            &bincode::serialize(instruction).unwrap(),
+            invoke_context,
+        )
+    }
+
+    fn new_buffer_account(elf: &[u8], authority_address: &Pubkey) -> Account {
+        let mut account = Account::new(
+            1,
+            UpgradeableLoaderState::buffer_len(elf.len()).unwrap(),
+            &bpf_loader_upgradeable::id(),
+        );
+        account
+            .set_state(&UpgradeableLoaderState::Buffer {
+                authority_address: *authority_address,
+            })
+            .unwrap();
+        account.data[UpgradeableLoaderState::buffer_data_offset().unwrap()..].copy_from_slice(elf);
+        account
+    }
+
+    fn new_programdata_account(
+        elf: &[u8],
+        max_data_len: usize,
+        upgrade_authority_address: Option<Pubkey>,
+    ) -> Account {
+        let mut account = Account::new(
+            1,
+            UpgradeableLoaderState::programdata_len(max_data_len).unwrap(),
+            &bpf_loader_upgradeable::id(),
+        );
+        account
+            .set_state(&UpgradeableLoaderState::ProgramData {
+                upgrade_authority_address,
+            })
+            .unwrap();
+        let offset = UpgradeableLoaderState::programdata_data_offset().unwrap();
+        account.data[offset..offset + elf.len()].copy_from_slice(elf);
+        account
+    }
+
+    fn new_program_account(programdata_address: &Pubkey) -> Account {
+        let mut account = Account::new(
+            1,
+            UpgradeableLoaderState::program_len().unwrap(),
+            &bpf_loader_upgradeable::id(),
+        );
+        account
+            .set_state(&UpgradeableLoaderState::Program {
+                programdata_address: *programdata_address,
+            })
+            .unwrap();
+        account.executable = true;
+        account
+    }
+
+    #[test]
+    fn test_initialize_buffer_and_write() {
+        let buffer_address = Pubkey::new_rand();
+        let authority_address = Pubkey::new_rand();
+        let mut buffer_account = Account::new(
+            1,
+            UpgradeableLoaderState::buffer_len(4).unwrap(),
+            &bpf_loader_upgradeable::id(),
+        );
+        let mut authority_account = Account::default();
+        let mut write = |buffer_account: &mut Account,
+                         authority_address: &Pubkey,
+                         authority_signed: bool,
+                         offset: u32| {
+            process_loader_instruction(
+                &mut [
+                    KeyedAccount::new(&buffer_address, false, buffer_account),
+                    KeyedAccount::new_readonly(
+                        authority_address,
+                        authority_signed,
+                        &mut authority_account,
+                    ),
+                ],
+                &UpgradeableLoaderInstruction::Write {
+                    offset,
+                    bytes: vec![7, 8],
+                },
+                &mut MockInvokeContext::default(),
+            )
+        };
+
+        // Case: Not initialized
+        assert_eq!(
+            Err(InstructionError::InvalidAccountData),
+            write(&mut buffer_account, &authority_address, true, 1)
+        );
+
+        let initialize = |buffer_account: &mut Account| {
+            process_loader_instruction(
+                &mut [
+                    KeyedAccount::new(&buffer_address, false, buffer_account),
+                    KeyedAccount::new_readonly(&authority_address, false, &mut Account::default()),
+                ],
+                &UpgradeableLoaderInstruction::InitializeBuffer,
+                &mut MockInvokeContext::default(),
+            )
+        };
+        assert_eq!(Ok(()), initialize(&mut buffer_account));
+        assert_eq!(
+            buffer_account.state(),
+            Ok(UpgradeableLoaderState::Buffer { authority_address })
+        );
+        assert_eq!(
+            Err(InstructionError::AccountAlreadyInitialized),
+            initialize(&mut buffer_account)
+        );
+
+        // Case: Not signed by the authority
+        assert_eq!(
+            Err(InstructionError::MissingRequiredSignature),
+            write(&mut buffer_account, &authority_address, false, 1)
+        );
+
+        // Case: Wrong authority
+        assert_eq!(
+            Err(InstructionError::IncorrectAuthority),
+            write(&mut buffer_account, &Pubkey::new_rand(), true, 1)
+        );
+
+        assert_eq!(
+            Ok(()),
+            write(&mut buffer_account, &authority_address, true, 1)
+        );
+        let offset = UpgradeableLoaderState::buffer_data_offset().unwrap();
+        assert_eq!(buffer_account.data[offset..], [0, 7, 8, 0]);
+
+        // Case: Write overflow
+        assert_eq!(
+            Err(InstructionError::AccountDataTooSmall),
+            write(&mut buffer_account, &authority_address, true, 3)
+        );
+
+        // Case: Buffer not owned by the loader
+        let mut foreign_buffer_account = buffer_account.clone();
+        foreign_buffer_account.owner = Pubkey::new_rand();
+        assert_eq!(
+            Err(InstructionError::IncorrectProgramId),
+            write(&mut foreign_buffer_account, &authority_address, true, 1)
+        );
+    }
+
+    #[test]
+    fn test_deploy() {
+        let elf = load_elf();
+        let max_data_len = elf.len() * 2;
+        let rent = Rent::default();
+        let payer_address = Pubkey::new_rand();
+        let program_address = Pubkey::new_rand();
+        let programdata_address = get_program_data_address(&program_address);
+        let buffer_address = Pubkey::new_rand();
+        let buffer_authority_address = Pubkey::new_rand();
+        let upgrade_authority_address = Pubkey::new_rand();
+
+        let mut payer_account = Account::new(1_000_000_000, 0, &system_program::id());
+        // The system program would create the ProgramData account
+        let mut programdata_account = Account::new(
+            rent.minimum_balance(UpgradeableLoaderState::programdata_len(max_data_len).unwrap()),
+            UpgradeableLoaderState::programdata_len(max_data_len).unwrap(),
+            &bpf_loader_upgradeable::id(),
+        );
+        let mut program_account = Account::new(
+            rent.minimum_balance(UpgradeableLoaderState::program_len().unwrap()),
+            UpgradeableLoaderState::program_len().unwrap(),
+            &bpf_loader_upgradeable::id(),
+        );
+        let mut buffer_account = new_buffer_account(&elf, &buffer_authority_address);
+        let mut deploy = |programdata_address: &Pubkey,
+                          buffer_authority_address: &Pubkey,
+                          buffer_authority_signed: bool,
+                          max_data_len: usize,
+                          invoke_context: &mut MockInvokeContext| {
+            process_loader_instruction(
+                &mut [
+                    KeyedAccount::new(&payer_address, true, &mut payer_account),
+                    KeyedAccount::new(programdata_address, false, &mut programdata_account),
+                    KeyedAccount::new(&program_address, false, &mut program_account),
+                    KeyedAccount::new(&buffer_address, false, &mut buffer_account),
+                    KeyedAccount::new_readonly(
+                        &rent::id(),
+                        false,
+                        &mut rent::create_account(1, &rent),
+                    ),
+                    KeyedAccount::new_readonly(
+                        &system_program::id(),
+                        false,
+                        &mut Account::new(1, 0, &Pubkey::default()),
+                    ),
+                    KeyedAccount::new_readonly(
+                        buffer_authority_address,
+                        buffer_authority_signed,
+                        &mut Account::default(),
+                    ),
+                    KeyedAccount::new_readonly(
+                        &upgrade_authority_address,
+                        false,
+                        &mut Account::default(),
+                    ),
+                ],
+                &UpgradeableLoaderInstruction::DeployWithMaxDataLen { max_data_len },
+                invoke_context,
+            )
+        };
+        let mut invoke_context = MockInvokeContext::default();
+
+        // Case: ProgramData address not derived from the Program address
+        assert_eq!(
+            Err(InstructionError::InvalidArgument),
+            deploy(
+                &Pubkey::new_rand(),
+                &buffer_authority_address,
+                true,
+                max_data_len,
+                &mut invoke_context
+            )
+        );
+
+        // Case: Max data length too small
+        assert_eq!(
+            Err(InstructionError::AccountDataTooSmall),
+            deploy(
+                &programdata_address,
+                &buffer_authority_address,
+                true,
+                elf.len() - 1,
+                &mut invoke_context
+            )
+        );
+
+        // Case: Not signed by the Buffer's authority
+        assert_eq!(
+            Err(InstructionError::MissingRequiredSignature),
+            deploy(
+                &programdata_address,
+                &buffer_authority_address,
+                false,
+                max_data_len,
+                &mut invoke_context
+            )
+        );
+
+        // Case: Someone else's Buffer
+        assert_eq!(
+            Err(InstructionError::IncorrectAuthority),
+            deploy(
+                &programdata_address,
+                &Pubkey::new_rand(),
+                true,
+                max_data_len,
+                &mut invoke_context
+            )
+        );
+        assert!(invoke_context.invoked.is_empty());
+
+        assert_eq!(
+            Ok(()),
+            deploy(
+                &programdata_address,
+                &buffer_authority_address,
+                true,
+                max_data_len,
+                &mut invoke_context
+            )
+        );
+
+        // Case: Program already deployed
+        assert_eq!(
+            Err(InstructionError::AccountAlreadyInitialized),
+            deploy(
+                &programdata_address,
+                &buffer_authority_address,
+                true,
+                max_data_len,
+                &mut MockInvokeContext::default()
+            )
+        );
+
+        // The ProgramData account is created at the address only the loader can sign for
+        let (_, bump_seed) = Pubkey::find_program_address(
+            &[program_address.as_ref()],
+            &bpf_loader_upgradeable::id(),
+        );
+        assert_eq!(
+            invoke_context.invoked,
+            vec![(
+                system_instruction::create_account(
+                    &payer_address,
+                    &programdata_address,
+                    programdata_account.lamports,
+                    programdata_account.data.len() as u64,
+                    &bpf_loader_upgradeable::id(),
+                ),
+                vec![vec![program_address.as_ref().to_vec(), vec![bump_seed]]]
+            )]
+        );
+        assert_eq!(
+            programdata_account.data,
+            new_programdata_account(&elf, max_data_len, Some(upgrade_authority_address)).data
+        );
+        assert_eq!(
+            program_account.state(),
+            Ok(UpgradeableLoaderState::Program {
+                programdata_address
+            })
+        );
+        assert!(program_account.executable);
+        assert_eq!(payer_account.lamports, 1_000_000_001);
+        assert_eq!(buffer_account.lamports, 0);
+    }
+
+    #[test]
+    fn test_upgrade() {
+        let elf = load_elf();
+        let program_address = Pubkey::new_rand();
+        let programdata_address = get_program_data_address(&program_address);
+        let buffer_address = Pubkey::new_rand();
+        let spill_address = Pubkey::new_rand();
+        let authority_address = Pubkey::new_rand();
+
+        let mut program_account = new_program_account(&programdata_address);
+        let mut spill_account = Account::new(0, 0, &Pubkey::default());
+        let mut authority_account = Account::default();
+        let upgrade = |programdata_account: &mut Account,
+                       buffer_account: &mut Account,
+                       spill_account: &mut Account,
+                       authority_address: &Pubkey,
+                       authority_signed: bool| {
+            let mut program_account = program_account.clone();
+            let mut authority_account = authority_account.clone();
+            process_loader_instruction(
+                &mut [
+                    KeyedAccount::new(&programdata_address, false, programdata_account),
+                    KeyedAccount::new_readonly(&program_address, false, &mut program_account),
+                    KeyedAccount::new(&buffer_address, false, buffer_account),
+                    KeyedAccount::new(&spill_address, false, spill_account),
+                    KeyedAccount::new_readonly(
+                        authority_address,
+                        authority_signed,
+                        &mut authority_account,
+                    ),
+                ],
+                &UpgradeableLoaderInstruction::Upgrade,
+                &mut MockInvokeContext::default(),
+            )
+        };
+
+        // Case: Not signed by the authority
+        let mut programdata_account =
+            new_programdata_account(&[1; 8], elf.len() + 8, Some(authority_address));
+        let mut buffer_account = new_buffer_account(&elf, &authority_address);
+        assert_eq!(
+            Err(InstructionError::MissingRequiredSignature),
+            upgrade(
+                &mut programdata_account,
+                &mut buffer_account,
+                &mut spill_account,
+                &authority_address,
+                false
+            )
+        );
+
+        // Case: Wrong authority
+        assert_eq!(
+            Err(InstructionError::IncorrectAuthority),
+            upgrade(
+                &mut programdata_account,
+                &mut buffer_account,
+                &mut spill_account,
+                &Pubkey::new_rand(),
+                true
+            )
+        );
+
+        // Case: Someone else's Buffer
+        let mut other_buffer_account = new_buffer_account(&elf, &Pubkey::new_rand());
+        assert_eq!(
+            Err(InstructionError::IncorrectAuthority),
+            upgrade(
+                &mut programdata_account,
+                &mut other_buffer_account,
+                &mut spill_account,
+                &authority_address,
+                true
+            )
+        );
+
+        // Case: Buffer not owned by the loader
+        let mut foreign_buffer_account = new_buffer_account(&elf, &authority_address);
+        foreign_buffer_account.owner = Pubkey::new_rand();
+        assert_eq!(
+            Err(InstructionError::IncorrectProgramId),
+            upgrade(
+                &mut programdata_account,
+                &mut foreign_buffer_account,
+                &mut spill_account,
+                &authority_address,
+                true
+            )
+        );
+
+        // Case: Immutable program
+        let mut immutable_programdata_account =
+            new_programdata_account(&[1; 8], elf.len() + 8, None);
+        assert_eq!(
+            Err(InstructionError::Immutable),
+            upgrade(
+                &mut immutable_programdata_account,
+                &mut buffer_account,
+                &mut spill_account,
+                &authority_address,
+                true
+            )
+        );
+
+        // Case: Program data too long
+        let mut small_programdata_account =
+            new_programdata_account(&[1; 8], elf.len() - 1, Some(authority_address));
+        assert_eq!(
+            Err(InstructionError::AccountDataTooSmall),
+            upgrade(
+                &mut small_programdata_account,
+                &mut buffer_account,
+                &mut spill_account,
+                &authority_address,
+                true
+            )
+        );
+
+        // Case: Invalid ELF
+        let mut invalid_buffer_account = new_buffer_account(&[1; 8], &authority_address);
+        assert_eq!(
+            Err(InstructionError::InvalidAccountData),
+            upgrade(
+                &mut programdata_account,
+                &mut invalid_buffer_account,
+                &mut spill_account,
+                &authority_address,
+                true
+            )
+        );
+
+        // Case: Wrong ProgramData account
+        program_account = new_program_account(&Pubkey::new_rand());
+        assert_eq!(
+            Err(InstructionError::InvalidArgument),
+            process_loader_instruction(
+                &mut [
+                    KeyedAccount::new(&programdata_address, false, &mut programdata_account),
+                    KeyedAccount::new_readonly(&program_address, false, &mut program_account),
+                    KeyedAccount::new(&buffer_address, false, &mut buffer_account),
+                    KeyedAccount::new(&spill_address, false, &mut spill_account),
+                    KeyedAccount::new_readonly(&authority_address, true, &mut authority_account),
+                ],
+                &UpgradeableLoaderInstruction::Upgrade,
+                &mut MockInvokeContext::default(),
+            )
+        );
+        program_account = new_program_account(&programdata_address);
+
+        assert_eq!(
+            Ok(()),
+            process_loader_instruction(
+                &mut [
+                    KeyedAccount::new(&programdata_address, false, &mut programdata_account),
+                    KeyedAccount::new_readonly(&program_address, false, &mut program_account),
+                    KeyedAccount::new(&buffer_address, false, &mut buffer_account),
+                    KeyedAccount::new(&spill_address, false, &mut spill_account),
+                    KeyedAccount::new_readonly(&authority_address, true, &mut authority_account),
+                ],
+                &UpgradeableLoaderInstruction::Upgrade,
+                &mut MockInvokeContext::default(),
+            )
+        );
+        // The old program data is replaced in full
+        assert_eq!(
+            programdata_account,
+            new_programdata_account(&elf, elf.len() + 8, Some(authority_address))
+        );
+        assert_eq!(spill_account.lamports, 1);
+        assert_eq!(buffer_account.lamports, 0);
+    }
+
+    #[test]
+    fn test_set_authority() {
+        let programdata_address = Pubkey::new_rand();
+        let authority_address = Pubkey::new_rand();
+        let new_authority_address = Pubkey::new_rand();
+        let mut programdata_account = new_programdata_account(&[1; 8], 8, Some(authority_address));
+        let mut authority_account = Account::default();
+        let mut new_authority_account = Account::default();
+
+        // Case: Not signed by the authority
+        assert_eq!(
+            Err(InstructionError::MissingRequiredSignature),
+            process_loader_instruction(
+                &mut [
+                    KeyedAccount::new(&programdata_address, false, &mut programdata_account),
+                    KeyedAccount::new_readonly(&authority_address, false, &mut authority_account),
+                ],
+                &UpgradeableLoaderInstruction::SetAuthority,
+                &mut MockInvokeContext::default(),
+            )
+        );
+
+        assert_eq!(
+            Ok(()),
+            process_loader_instruction(
+                &mut [
+                    KeyedAccount::new(&programdata_address, false, &mut programdata_account),
+                    KeyedAccount::new_readonly(&authority_address, true, &mut authority_account),
+                    KeyedAccount::new_readonly(
+                        &new_authority_address,
+                        false,
+                        &mut new_authority_account
+                    ),
+                ],
+                &UpgradeableLoaderInstruction::SetAuthority,
+                &mut MockInvokeContext::default(),
+            )
+        );
+        assert_eq!(
+            programdata_account,
+            new_programdata_account(&[1; 8], 8, Some(new_authority_address))
+        );
+
+        // Case: The previous authority no longer can
+        assert_eq!(
+            Err(InstructionError::IncorrectAuthority),
+            process_loader_instruction(
+                &mut [
+                    KeyedAccount::new(&programdata_address, false, &mut programdata_account),
+                    KeyedAccount::new_readonly(&authority_address, true, &mut authority_account),
+                ],
+                &UpgradeableLoaderInstruction::SetAuthority,
+                &mut MockInvokeContext::default(),
+            )
+        );
+
+        // Revoke the authority
+        assert_eq!(
+            Ok(()),
+            process_loader_instruction(
+                &mut [
+                    KeyedAccount::new(&programdata_address, false, &mut programdata_account),
+                    KeyedAccount::new_readonly(
+                        &new_authority_address,
+                        true,
+                        &mut new_authority_account
+                    ),
+                ],
+                &UpgradeableLoaderInstruction::SetAuthority,
+                &mut MockInvokeContext::default(),
+            )
+        );
+        assert_eq!(
+            programdata_account.state(),
+            Ok(UpgradeableLoaderState::ProgramData {
+                upgrade_authority_address: None
+            })
+        );
+        assert_eq!(
+            Err(InstructionError::Immutable),
+            process_loader_instruction(
+                &mut [
+                    KeyedAccount::new(&programdata_address, false, &mut programdata_account),
+                    KeyedAccount::new_readonly(
+                        &new_authority_address,
+                        true,
+                        &mut new_authority_account
+                    ),
+                ],
+                &UpgradeableLoaderInstruction::SetAuthority,
+                &mut MockInvokeContext::default(),
+            )
+        );
+    }
+
+    #[test]
+    fn test_invoke_program() {
+        let program_address = Pubkey::new_rand();
+        let programdata_address = Pubkey::new_rand();
+        let mut program_account = new_program_account(&programdata_address);
+        let elf = load_elf();
+        let mut programdata_account = new_programdata_account(&elf, elf.len(), None);
+        let ix_data = bincode::serialize(&LoaderInstruction::InvokeMain { data: vec![] }).unwrap();
+
+        // Case: Missing the ProgramData account
+        assert_eq!(
+            Err(InstructionError::NotEnoughAccountKeys),
+            process_instruction(
+                &program_address,
+                &mut [KeyedAccount::new(
+                    &program_address,
+                    false,
+                    &mut program_account
+                )],
+                &ix_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+
+        // Case: Wrong ProgramData account
+        let mut other_programdata_account = programdata_account.clone();
+        assert_eq!(
+            Err(InstructionError::InvalidArgument),
+            process_instruction(
+                &program_address,
+                &mut [
+                    KeyedAccount::new_readonly(
+                        &Pubkey::new_rand(),
+                        false,
+                        &mut other_programdata_account
+                    ),
+                    KeyedAccount::new(&program_address, false, &mut program_account),
+                ],
+                &ix_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+
+        // Case: Loader instruction sent to a program
+        assert_eq!(
+            Err(InstructionError::InvalidInstructionData),
+            process_instruction(
+                &program_address,
+                &mut [
+                    KeyedAccount::new_readonly(
+                        &programdata_address,
+                        false,
+                        &mut programdata_account
+                    ),
+                    KeyedAccount::new(&program_address, false, &mut program_account),
+                ],
+                &bincode::serialize(&LoaderInstruction::Finalize).unwrap(),
+                &mut MockInvokeContext::default()
+            )
+        );
+
+        assert_eq!(
+            Ok(()),
+            process_instruction(
+                &program_address,
+                &mut [
+                    KeyedAccount::new_readonly(
+                        &programdata_address,
+                        false,
+                        &mut programdata_account
+                    ),
+                    KeyedAccount::new(&program_address, false, &mut program_account),
+                ],
+                &ix_data,
+                &mut MockInvokeContext::default()
+            )
+        );
+    }
+}
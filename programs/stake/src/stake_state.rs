@@ -167,8 +167,10 @@ impl Delegation {
         self.stake_activating_and_deactivating(epoch, history).0
     }
 
+    /// Returns the (effective, activating, deactivating) portions of the delegated stake at
+    /// `epoch`, warming up and cooling down according to `history`
     #[allow(clippy::comparison_chain)]
-    fn stake_activating_and_deactivating(
+    pub fn stake_activating_and_deactivating(
         &self,
         epoch: Epoch,
         history: Option<&StakeHistory>,
@@ -9,7 +9,9 @@ use log::*;
 use rayon::slice::ParallelSliceMut;
 use solana_metrics::inc_new_counter_error;
 use solana_sdk::account::Account;
+use solana_sdk::account_utils::State;
 use solana_sdk::bank_hash::BankHash;
+use solana_sdk::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
 use solana_sdk::clock::Slot;
 use solana_sdk::native_loader;
 use solana_sdk::pubkey::Pubkey;
@@ -23,6 +25,13 @@ use std::sync::{Arc, Mutex, RwLock};
 
 use crate::transaction_utils::OrderedIterator;
 
+/// Whether `load_largest_accounts` keeps or drops the addresses it is given
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AccountAddressFilter {
+    Exclude, // exclude all addresses matching the filter
+    Include, // only include addresses matching the filter
+}
+
 #[derive(Default, Debug)]
 struct ReadonlyLock {
     lock_count: Mutex<u64>,
@@ -184,22 +193,55 @@ impl Accounts {
 
             // add loader to chain
             let program_owner = program.owner;
+            let programdata_address = Self::programdata_address(&program);
             accounts.insert(0, (program_id, program));
+
+            // the program's data lives in its ProgramData account, ahead of it in the chain
+            if let Some(programdata_address) = programdata_address {
+                let programdata = match AccountsDB::load(
+                    storage,
+                    ancestors,
+                    accounts_index,
+                    &programdata_address,
+                ) {
+                    Some((programdata, _)) => programdata,
+                    None => {
+                        error_counters.account_not_found += 1;
+                        return Err(TransactionError::ProgramAccountNotFound);
+                    }
+                };
+                accounts.insert(0, (programdata_address, programdata));
+            }
             program_id = program_owner;
         }
         Ok(accounts)
     }
 
-    /// For each program_id in the transaction, load its loaders.
+    /// Address of the ProgramData account of a program deployed by the upgradeable loader
+    pub(crate) fn programdata_address(program: &Account) -> Option<Pubkey> {
+        if !bpf_loader_upgradeable::check_id(&program.owner) {
+            return None;
+        }
+        match program.state() {
+            Ok(UpgradeableLoaderState::Program {
+                programdata_address,
+            }) => Some(programdata_address),
+            _ => None,
+        }
+    }
+
+    /// For each program_id in the transaction, load its loaders.  The loaders of the upgradeable
+    /// programs among `accounts` follow, so that the transaction's programs can invoke them
     fn load_loaders(
         storage: &AccountStorage,
         ancestors: &HashMap<Slot, usize>,
         accounts_index: &AccountsIndex<AccountInfo>,
         tx: &Transaction,
+        accounts: &TransactionAccounts,
         error_counters: &mut ErrorCounters,
     ) -> Result<TransactionLoaders> {
         let message = tx.message();
-        message
+        let mut loaders = message
             .instructions
             .iter()
             .map(|ix| {
@@ -216,7 +258,26 @@ impl Accounts {
                     error_counters,
                 )
             })
-            .collect()
+            .collect::<Result<TransactionLoaders>>()?;
+
+        let program_ids = message.program_ids();
+        for (key, account) in message
+            .account_keys
+            .iter()
+            .filter(|key| !program_ids.contains(key))
+            .zip(accounts)
+        {
+            if account.executable && Self::programdata_address(account).is_some() {
+                loaders.push(Self::load_executable_accounts(
+                    storage,
+                    ancestors,
+                    accounts_index,
+                    key,
+                    error_counters,
+                )?);
+            }
+        }
+        Ok(loaders)
     }
 
     pub fn load_accounts(
@@ -268,6 +329,7 @@ impl Accounts {
                         ancestors,
                         &accounts_index,
                         tx,
+                        &accounts,
                         error_counters,
                     );
                     let loaders = match load_res {
@@ -366,6 +428,37 @@ impl Accounts {
         )
     }
 
+    /// Returns the `num` accounts with the largest balances, largest first
+    pub fn load_largest_accounts(
+        &self,
+        ancestors: &HashMap<Slot, usize>,
+        num: usize,
+        filter_by_address: &HashSet<Pubkey>,
+        filter: AccountAddressFilter,
+    ) -> Vec<(Pubkey, u64)> {
+        let mut accounts_balances = self.accounts_db.scan_accounts(
+            ancestors,
+            |collector: &mut Vec<(Pubkey, u64)>, option| {
+                if let Some((pubkey, account, _slot)) = option {
+                    if account.lamports == 0 {
+                        return;
+                    }
+                    let contains_address = filter_by_address.contains(pubkey);
+                    let collect = match filter {
+                        AccountAddressFilter::Exclude => !contains_address,
+                        AccountAddressFilter::Include => contains_address,
+                    };
+                    if collect {
+                        collector.push((*pubkey, account.lamports));
+                    }
+                }
+            },
+        );
+        accounts_balances.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        accounts_balances.truncate(num);
+        accounts_balances
+    }
+
     /// Slow because lock is held for 1 operation instead of many
     pub fn store_slow(&self, slot: Slot, pubkey: &Pubkey, account: &Account) {
         self.accounts_db.store(slot, &[(pubkey, account)]);
@@ -1094,6 +1187,92 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_load_accounts_upgradeable_program() {
+        let mut accounts: Vec<(Pubkey, Account)> = Vec::new();
+        let mut error_counters = ErrorCounters::default();
+
+        let keypair = Keypair::new();
+        let key0 = keypair.pubkey();
+        let loader_key = bpf_loader_upgradeable::id();
+        let program_key = Pubkey::new(&[5u8; 32]);
+        let programdata_key = Pubkey::new(&[6u8; 32]);
+        let other_program_key = Pubkey::new(&[7u8; 32]);
+        let other_programdata_key = Pubkey::new(&[8u8; 32]);
+
+        let mut account = Account::new(1, 1, &Pubkey::default());
+        account.rent_epoch = 1;
+        accounts.push((key0, account));
+
+        let mut account = Account::new(40, 1, &native_loader::id());
+        account.executable = true;
+        account.rent_epoch = 1;
+        accounts.push((loader_key, account));
+
+        for (program_key, programdata_key) in &[
+            (program_key, programdata_key),
+            (other_program_key, other_programdata_key),
+        ] {
+            let mut account = Account::new_data(
+                41,
+                &UpgradeableLoaderState::Program {
+                    programdata_address: *programdata_key,
+                },
+                &loader_key,
+            )
+            .unwrap();
+            account.executable = true;
+            account.rent_epoch = 1;
+            accounts.push((*program_key, account));
+
+            let mut account = Account::new(42, 1, &loader_key);
+            account.rent_epoch = 1;
+            accounts.push((*programdata_key, account));
+        }
+
+        // The program is invoked without its ProgramData account, the other program is passed
+        // along for it to invoke
+        let instructions = vec![CompiledInstruction::new(2, &(), vec![0, 1])];
+        let tx = Transaction::new_with_compiled_instructions(
+            &[&keypair],
+            &[other_program_key],
+            Hash::default(),
+            vec![program_key],
+            instructions,
+        );
+
+        let loaded_accounts = load_accounts(tx, &accounts, &mut error_counters);
+
+        assert_eq!(error_counters.account_not_found, 0);
+        assert_eq!(loaded_accounts.len(), 1);
+        match &loaded_accounts[0] {
+            (
+                Ok((transaction_accounts, transaction_loaders, _transaction_rents)),
+                _hash_age_kind,
+            ) => {
+                assert_eq!(transaction_accounts.len(), 2);
+                assert_eq!(transaction_loaders.len(), 2);
+                assert_eq!(
+                    transaction_loaders[0],
+                    vec![
+                        accounts[1].clone(),
+                        accounts[3].clone(),
+                        accounts[2].clone()
+                    ]
+                );
+                assert_eq!(
+                    transaction_loaders[1],
+                    vec![
+                        accounts[1].clone(),
+                        accounts[5].clone(),
+                        accounts[4].clone()
+                    ]
+                );
+            }
+            (Err(e), _hash_age_kind) => Err(e).unwrap(),
+        }
+    }
+
     #[test]
     fn test_load_account_pay_to_self() {
         let mut accounts: Vec<(Pubkey, Account)> = Vec::new();
@@ -1150,6 +1329,61 @@ mod tests {
         assert_eq!(loaded, vec![]);
     }
 
+    #[test]
+    fn test_load_largest_accounts() {
+        let accounts = Accounts::new(Vec::new());
+        let ancestors = vec![(0, 0)].into_iter().collect();
+
+        let pubkey0 = Pubkey::new_rand();
+        accounts.store_slow(0, &pubkey0, &Account::new(42, 0, &Pubkey::default()));
+        let pubkey1 = Pubkey::new_rand();
+        accounts.store_slow(0, &pubkey1, &Account::new(100, 0, &Pubkey::default()));
+        let pubkey2 = Pubkey::new_rand();
+        accounts.store_slow(0, &pubkey2, &Account::new(1, 0, &Pubkey::default()));
+        let pubkey3 = Pubkey::new_rand();
+        accounts.store_slow(0, &pubkey3, &Account::new(0, 0, &Pubkey::default()));
+
+        let no_addresses = HashSet::new();
+        assert_eq!(
+            accounts.load_largest_accounts(
+                &ancestors,
+                4,
+                &no_addresses,
+                AccountAddressFilter::Exclude
+            ),
+            vec![(pubkey1, 100), (pubkey0, 42), (pubkey2, 1)]
+        );
+        assert_eq!(
+            accounts.load_largest_accounts(
+                &ancestors,
+                1,
+                &no_addresses,
+                AccountAddressFilter::Exclude
+            ),
+            vec![(pubkey1, 100)]
+        );
+
+        let addresses: HashSet<_> = vec![pubkey1, pubkey2].into_iter().collect();
+        assert_eq!(
+            accounts.load_largest_accounts(
+                &ancestors,
+                4,
+                &addresses,
+                AccountAddressFilter::Exclude
+            ),
+            vec![(pubkey0, 42)]
+        );
+        assert_eq!(
+            accounts.load_largest_accounts(
+                &ancestors,
+                4,
+                &addresses,
+                AccountAddressFilter::Include
+            ),
+            vec![(pubkey1, 100), (pubkey2, 1)]
+        );
+    }
+
     #[test]
     fn test_accounts_account_not_found() {
         let accounts = Accounts::new(Vec::new());
@@ -3,10 +3,11 @@
 //! on behalf of the caller, and a low-level API for when they have
 //! already been signed and verified.
 use crate::{
-    accounts::{Accounts, TransactionLoadResult},
+    accounts::{AccountAddressFilter, Accounts, TransactionLoadResult},
     accounts_db::{AccountStorageEntry, AccountsDBSerialize, AppendVecId, ErrorCounters},
     blockhash_queue::BlockhashQueue,
-    message_processor::{MessageProcessor, ProcessInstruction},
+    log_collector::LogCollector,
+    message_processor::{InstructionBalances, MessageProcessor, ProcessInstruction},
     nonce_utils,
     rent_collector::RentCollector,
     serde_utils::{
@@ -36,8 +37,12 @@ use solana_sdk::{
     genesis_config::GenesisConfig,
     hash::{hashv, Hash},
     inflation::Inflation,
+    instruction::CompiledInstruction,
+    instruction_processor_utils::{limited_deserialize, ComputeBudget},
+    message::Message,
     native_loader,
     pubkey::Pubkey,
+    reward_type::RewardType,
     signature::{Keypair, Signature},
     slot_hashes::SlotHashes,
     slot_history::SlotHistory,
@@ -46,12 +51,13 @@ use solana_sdk::{
     timing::years_as_slots,
     transaction::{Result, Transaction, TransactionError},
 };
-use solana_stake_program::stake_state::Delegation;
+use solana_stake_program::{stake_instruction::StakeInstruction, stake_state::Delegation};
 use solana_vote_program::vote_state::VoteState;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     io::{BufReader, Cursor, Error as IOError, Read},
     path::{Path, PathBuf},
+    rc::Rc,
     sync::atomic::{AtomicBool, AtomicU64, Ordering},
     sync::{Arc, RwLock, RwLockReadGuard},
 };
@@ -176,6 +182,58 @@ impl TransactionBalancesSet {
     }
 }
 pub type TransactionBalances = Vec<Vec<u64>>;
+pub type TransactionLogMessages = Vec<String>;
+pub type TransactionRewards = Vec<(Pubkey, RewardInfo)>;
+
+/// Which transactions a bank records log messages for, on behalf of log subscribers
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransactionLogCollectorFilter {
+    All,
+    AllWithVotes,
+    None,
+    OnlyMentionedAddresses,
+}
+
+impl Default for TransactionLogCollectorFilter {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TransactionLogCollectorConfig {
+    pub mentioned_addresses: HashSet<Pubkey>,
+    pub filter: TransactionLogCollectorFilter,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TransactionLogInfo {
+    pub signature: Signature,
+    pub result: Result<()>,
+    pub is_vote: bool,
+    pub log_messages: TransactionLogMessages,
+}
+
+#[derive(Default, Debug)]
+pub struct TransactionLogCollector {
+    // All the logs collected for from this Bank.  Exact contents depend on the
+    // active `TransactionLogCollectorFilter`
+    pub logs: Vec<TransactionLogInfo>,
+
+    // For each `mentioned_addresses`, maintain a list of indices into `logs` to easily
+    // locate the logs from transactions that included the mentioned addresses.
+    pub mentioned_address_map: HashMap<Pubkey, Vec<usize>>,
+}
+
+/// Lamports credited to an account by the bank itself, outside of any transaction
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RewardInfo {
+    pub reward_type: RewardType,
+    /// Reward amount, in lamports
+    pub lamports: i64,
+    /// Account balance, in lamports, after the reward was applied
+    pub post_balance: u64,
+}
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum HashAgeKind {
@@ -310,6 +368,30 @@ pub struct Bank {
     /// Last time when the cluster info vote listener has synced with this bank
     #[serde(skip)]
     pub last_vote_sync: AtomicU64,
+
+    /// Which transaction logs to record, shared by all banks descended from the same root
+    #[serde(skip)]
+    pub transaction_log_collector_config: Arc<RwLock<TransactionLogCollectorConfig>>,
+
+    /// Logs from transactions that this Bank executed collected according to the criteria in
+    /// `transaction_log_collector_config`
+    #[serde(skip)]
+    pub transaction_log_collector: Arc<RwLock<TransactionLogCollector>>,
+
+    /// Fee, rent and inflation rewards credited to accounts by this Bank
+    #[serde(skip)]
+    pub rewards: RwLock<Vec<(Pubkey, RewardInfo)>>,
+
+    /// Compute units each instruction may consume, and what program operations cost.  Not part
+    /// of snapshots, banks loaded from one start from the default budget
+    #[serde(skip)]
+    compute_budget: RwLock<ComputeBudget>,
+}
+
+fn is_simple_vote_transaction(transaction: &Transaction) -> bool {
+    transaction.message.instructions.len() == 1
+        && transaction.message.instructions[0].program_id(&transaction.message.account_keys)
+            == &solana_vote_program::id()
 }
 
 impl Default for BlockhashQueue {
@@ -405,6 +487,10 @@ impl Bank {
             message_processor: MessageProcessor::default(),
             entered_epoch_callback: parent.entered_epoch_callback.clone(),
             last_vote_sync: AtomicU64::new(parent.last_vote_sync.load(Ordering::Relaxed)),
+            transaction_log_collector_config: parent.transaction_log_collector_config.clone(),
+            transaction_log_collector: Arc::new(RwLock::new(TransactionLogCollector::default())),
+            rewards: RwLock::new(vec![]),
+            compute_budget: RwLock::new(parent.compute_budget()),
         };
 
         datapoint_debug!(
@@ -651,6 +737,16 @@ impl Bank {
             // burn a portion of fees
             self.deposit(&self.collector_id, unburned);
             self.capitalization.fetch_sub(burned, Ordering::Relaxed);
+            if unburned > 0 {
+                self.rewards.write().unwrap().push((
+                    self.collector_id,
+                    RewardInfo {
+                        reward_type: RewardType::Fee,
+                        lamports: unburned as i64,
+                        post_balance: self.get_balance(&self.collector_id),
+                    },
+                ));
+            }
         }
     }
 
@@ -907,6 +1003,31 @@ impl Bank {
         TransactionBatch::new(results, &self, txs, iteration_order)
     }
 
+    fn prepare_simulation_batch<'a, 'b>(
+        &'a self,
+        txs: &'b [Transaction],
+    ) -> TransactionBatch<'a, 'b> {
+        // Simulations never commit, so they don't take account locks and can't conflict with
+        // transactions being processed by the bank
+        let mut batch = TransactionBatch::new(vec![Ok(()); txs.len()], self, txs, None);
+        batch.needs_unlock = false;
+        batch.is_simulation = true;
+        batch
+    }
+
+    /// Run a transaction against the bank without committing its results, returning the
+    /// result and any log messages the transaction produced
+    pub fn simulate_transaction(
+        &self,
+        transaction: Transaction,
+    ) -> (Result<()>, TransactionLogMessages) {
+        let txs = &[transaction];
+        let batch = self.prepare_simulation_batch(txs);
+        let (_, mut executed, mut transaction_logs, _, _, _, _, _) =
+            self.load_and_execute_transactions(&batch, MAX_RECENT_BLOCKHASHES, true, false);
+        (executed.remove(0).0, transaction_logs.remove(0))
+    }
+
     pub fn unlock_accounts(&self, batch: &mut TransactionBatch) {
         if batch.needs_unlock {
             batch.needs_unlock = false;
@@ -1068,6 +1189,66 @@ impl Bank {
         balances
     }
 
+    fn collect_transaction_logs(
+        &self,
+        config: &TransactionLogCollectorConfig,
+        tx: &Transaction,
+        result: &Result<()>,
+        log_messages: &TransactionLogMessages,
+    ) {
+        let mut transaction_log_collector = self.transaction_log_collector.write().unwrap();
+        let transaction_log_index = transaction_log_collector.logs.len();
+
+        let mut mentioned_address = false;
+        for key in &tx.message.account_keys {
+            if config.mentioned_addresses.contains(key) {
+                transaction_log_collector
+                    .mentioned_address_map
+                    .entry(*key)
+                    .or_default()
+                    .push(transaction_log_index);
+                mentioned_address = true;
+            }
+        }
+
+        let is_vote = is_simple_vote_transaction(tx);
+        let store = match config.filter {
+            TransactionLogCollectorFilter::All => !is_vote || mentioned_address,
+            TransactionLogCollectorFilter::AllWithVotes => true,
+            TransactionLogCollectorFilter::None => false,
+            TransactionLogCollectorFilter::OnlyMentionedAddresses => mentioned_address,
+        };
+        if store {
+            transaction_log_collector.logs.push(TransactionLogInfo {
+                signature: tx.signatures[0],
+                result: result.clone(),
+                is_vote,
+                log_messages: log_messages.clone(),
+            });
+        }
+    }
+
+    /// Return the logs collected from this bank's transactions, either all of them or only
+    /// those of transactions that mention `address`
+    pub fn get_transaction_logs(
+        &self,
+        address: Option<&Pubkey>,
+    ) -> Option<Vec<TransactionLogInfo>> {
+        let transaction_log_collector = self.transaction_log_collector.read().unwrap();
+        match address {
+            None => Some(transaction_log_collector.logs.clone()),
+            Some(address) => transaction_log_collector
+                .mentioned_address_map
+                .get(address)
+                .map(|log_indices| {
+                    log_indices
+                        .iter()
+                        .map(|i| transaction_log_collector.logs[*i].clone())
+                        .collect()
+                }),
+        }
+    }
+
     fn update_error_counters(error_counters: &ErrorCounters) {
         if 0 != error_counters.blockhash_not_found {
             inc_new_counter_error!(
@@ -1118,9 +1299,14 @@ impl Bank {
         &self,
         batch: &TransactionBatch,
         max_age: usize,
+        enable_log_recording: bool,
+        enable_rewards_recording: bool,
     ) -> (
         Vec<(Result<TransactionLoadResult>, Option<HashAgeKind>)>,
         Vec<TransactionProcessResult>,
+        Vec<TransactionLogMessages>,
+        Vec<u64>,
+        Vec<TransactionRewards>,
         Vec<usize>,
         u64,
         u64,
@@ -1158,18 +1344,74 @@ impl Bank {
 
         let mut execution_time = Measure::start("execution_time");
         let mut signature_count: u64 = 0;
+        let mut transaction_logs: Vec<TransactionLogMessages> = Vec::new();
+        let mut transaction_compute_units: Vec<u64> = Vec::with_capacity(txs.len());
+        let mut transaction_rewards: Vec<TransactionRewards> = Vec::with_capacity(txs.len());
+        let transaction_log_collector_config =
+            self.transaction_log_collector_config.read().unwrap();
+        let collect_subscriber_logs = !batch.is_simulation
+            && transaction_log_collector_config.filter != TransactionLogCollectorFilter::None;
+        let collect_logs = enable_log_recording || collect_subscriber_logs;
+        let compute_budget = self.compute_budget();
         let executed: Vec<TransactionProcessResult> = loaded_accounts
             .iter_mut()
             .zip(OrderedIterator::new(txs, batch.iteration_order()))
             .map(|(accs, tx)| match accs {
-                (Err(e), hash_age_kind) => (Err(e.clone()), hash_age_kind.clone()),
+                (Err(e), hash_age_kind) => {
+                    if enable_log_recording {
+                        transaction_logs.push(vec![]);
+                    }
+                    transaction_compute_units.push(0);
+                    transaction_rewards.push(vec![]);
+                    (Err(e.clone()), hash_age_kind.clone())
+                }
                 (Ok((accounts, loaders, _rents)), hash_age_kind) => {
                     signature_count += u64::from(tx.message().header.num_required_signatures);
-                    (
-                        self.message_processor
-                            .process_message(tx.message(), loaders, accounts),
-                        hash_age_kind.clone(),
-                    )
+                    let log_collector = if collect_logs {
+                        Some(Rc::new(LogCollector::default()))
+                    } else {
+                        None
+                    };
+                    // Only transactions that redeem vote credits credit rewards
+                    let mut instruction_balances =
+                        if enable_rewards_recording && Self::redeems_vote_credits(tx.message()) {
+                            Some(vec![])
+                        } else {
+                            None
+                        };
+                    let mut compute_units_consumed = 0;
+                    let process_result = self.message_processor.process_message(
+                        tx.message(),
+                        loaders,
+                        accounts,
+                        log_collector.clone(),
+                        &compute_budget,
+                        &mut compute_units_consumed,
+                        instruction_balances.as_mut(),
+                    );
+                    transaction_compute_units.push(compute_units_consumed);
+                    transaction_rewards.push(match instruction_balances {
+                        Some(instruction_balances) if process_result.is_ok() => {
+                            Self::redeemed_rewards(tx.message(), &instruction_balances)
+                        }
+                        _ => vec![],
+                    });
+                    if let Some(log_collector) = log_collector {
+                        let log_messages: TransactionLogMessages =
+                            Rc::try_unwrap(log_collector).unwrap_or_default().into();
+                        if collect_subscriber_logs {
+                            self.collect_transaction_logs(
+                                &transaction_log_collector_config,
+                                tx,
+                                &process_result,
+                                &log_messages,
+                            );
+                        }
+                        if enable_log_recording {
+                            transaction_logs.push(log_messages);
+                        }
+                    }
+                    (process_result, hash_age_kind.clone())
                 }
             })
             .collect();
@@ -1207,6 +1449,9 @@ impl Bank {
         (
             loaded_accounts,
             executed,
+            transaction_logs,
+            transaction_compute_units,
+            transaction_rewards,
             retryable_txs,
             tx_count,
             signature_count,
@@ -1263,6 +1508,7 @@ impl Bank {
         iteration_order: Option<&[usize]>,
         loaded_accounts: &mut [(Result<TransactionLoadResult>, Option<HashAgeKind>)],
         executed: &[TransactionProcessResult],
+        transaction_rewards: &[TransactionRewards],
         tx_count: u64,
         signature_count: u64,
     ) -> TransactionResults {
@@ -1285,6 +1531,7 @@ impl Bank {
         }
 
         let mut write_time = Measure::start("write_time");
+        self.record_redeemed_rewards(transaction_rewards);
         self.rc.accounts.store_accounts(
             self.slot(),
             txs,
@@ -1309,6 +1556,67 @@ impl Bank {
         }
     }
 
+    /// Whether `message` has an instruction that redeems vote credits
+    fn redeems_vote_credits(message: &Message) -> bool {
+        message
+            .instructions
+            .iter()
+            .any(|instruction| Self::is_redeem_vote_credits(message, instruction))
+    }
+
+    /// Whether `instruction`, of `message`, redeems vote credits
+    fn is_redeem_vote_credits(message: &Message, instruction: &CompiledInstruction) -> bool {
+        solana_stake_program::check_id(instruction.program_id(&message.account_keys))
+            && limited_deserialize(&instruction.data) == Ok(StakeInstruction::RedeemVoteCredits)
+    }
+
+    /// The inflation rewards that the `RedeemVoteCredits` instructions of `message` credited to
+    /// stake and vote accounts, given the balances of the accounts each instruction was passed
+    fn redeemed_rewards(
+        message: &Message,
+        instruction_balances: &[InstructionBalances],
+    ) -> TransactionRewards {
+        let mut rewards = vec![];
+        for (instruction, balances) in message.instructions.iter().zip(instruction_balances) {
+            if !Self::is_redeem_vote_credits(message, instruction) {
+                continue;
+            }
+            // The stake account is credited the stakers' reward, the vote account the
+            //  voters' commission
+            for (position, reward_type) in
+                [RewardType::Staking, RewardType::Voting].iter().enumerate()
+            {
+                if let (Some(&index), Some(&pre_balance), Some(&post_balance)) = (
+                    instruction.accounts.get(position),
+                    balances.pre_balances.get(position),
+                    balances.post_balances.get(position),
+                ) {
+                    if post_balance > pre_balance {
+                        rewards.push((
+                            message.account_keys[index as usize],
+                            RewardInfo {
+                                reward_type: *reward_type,
+                                lamports: (post_balance - pre_balance) as i64,
+                                post_balance,
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+        rewards
+    }
+
+    /// Record the inflation rewards that the committed transactions' `RedeemVoteCredits`
+    /// instructions credited
+    fn record_redeemed_rewards(&self, transaction_rewards: &[TransactionRewards]) {
+        let mut rewards: TransactionRewards =
+            transaction_rewards.iter().flatten().cloned().collect();
+        if !rewards.is_empty() {
+            self.rewards.write().unwrap().append(&mut rewards);
+        }
+    }
+
     fn distribute_rent_to_validators(
         &self,
         vote_account_hashmap: &HashMap<Pubkey, (u64, Account)>,
@@ -1362,6 +1670,16 @@ impl Bank {
                 let mut account = self.get_account(pubkey).unwrap_or_default();
                 account.lamports += rent_to_be_paid;
                 self.store_account(pubkey, &account);
+                if rent_to_be_paid > 0 {
+                    self.rewards.write().unwrap().push((
+                        *pubkey,
+                        RewardInfo {
+                            reward_type: RewardType::Rent,
+                            lamports: rent_to_be_paid as i64,
+                            post_balance: account.lamports,
+                        },
+                    ));
+                }
             });
     }
 
@@ -1411,20 +1729,41 @@ impl Bank {
         batch: &TransactionBatch,
         max_age: usize,
         collect_balances: bool,
-    ) -> (TransactionResults, TransactionBalancesSet) {
+        enable_log_recording: bool,
+        enable_rewards_recording: bool,
+    ) -> (
+        TransactionResults,
+        TransactionBalancesSet,
+        Vec<TransactionLogMessages>,
+        Vec<u64>,
+    ) {
         let pre_balances = if collect_balances {
             self.collect_balances(batch.transactions())
         } else {
             vec![]
         };
-        let (mut loaded_accounts, executed, _, tx_count, signature_count) =
-            self.load_and_execute_transactions(batch, max_age);
+        let (
+            mut loaded_accounts,
+            executed,
+            transaction_logs,
+            transaction_compute_units,
+            transaction_rewards,
+            _,
+            tx_count,
+            signature_count,
+        ) = self.load_and_execute_transactions(
+            batch,
+            max_age,
+            enable_log_recording,
+            enable_rewards_recording,
+        );
 
         let results = self.commit_transactions(
             batch.transactions(),
             batch.iteration_order(),
             &mut loaded_accounts,
             &executed,
+            &transaction_rewards,
             tx_count,
             signature_count,
         );
@@ -1436,15 +1775,23 @@ impl Bank {
         (
             results,
             TransactionBalancesSet::new(pre_balances, post_balances),
+            transaction_logs,
+            transaction_compute_units,
         )
     }
 
     #[must_use]
     pub fn process_transactions(&self, txs: &[Transaction]) -> Vec<Result<()>> {
         let batch = self.prepare_batch(txs, None);
-        self.load_execute_and_commit_transactions(&batch, MAX_RECENT_BLOCKHASHES, false)
-            .0
-            .fee_collection_results
+        self.load_execute_and_commit_transactions(
+            &batch,
+            MAX_RECENT_BLOCKHASHES,
+            false,
+            false,
+            false,
+        )
+        .0
+        .fee_collection_results
     }
 
     /// Create, sign, and process a Transaction from `keypair` to `to` of
@@ -1532,6 +1879,14 @@ impl Bank {
         *self.inflation.write().unwrap() = inflation;
     }
 
+    pub fn compute_budget(&self) -> ComputeBudget {
+        *self.compute_budget.read().unwrap()
+    }
+
+    pub fn set_compute_budget(&self, compute_budget: ComputeBudget) {
+        *self.compute_budget.write().unwrap() = compute_budget;
+    }
+
     pub fn set_entered_epoch_callback(&self, entered_epoch_callback: EnteredEpochCallback) {
         *self.entered_epoch_callback.write().unwrap() = Some(entered_epoch_callback);
     }
@@ -1549,6 +1904,17 @@ impl Bank {
             .load_by_program(&self.ancestors, program_id)
     }
 
+    pub fn get_largest_accounts(
+        &self,
+        num: usize,
+        filter_by_address: &HashSet<Pubkey>,
+        filter: AccountAddressFilter,
+    ) -> Vec<(Pubkey, u64)> {
+        self.rc
+            .accounts
+            .load_largest_accounts(&self.ancestors, num, filter_by_address, filter)
+    }
+
     pub fn get_program_accounts_modified_since_parent(
         &self,
         program_id: &Pubkey,
@@ -2954,6 +3320,89 @@ mod tests {
         assert_eq!(bank.get_balance(&pubkey), 500);
     }
 
+    #[test]
+    fn test_bank_simulate_transaction() {
+        let (genesis_config, mint_keypair) = create_genesis_config(10_000);
+        let bank = Bank::new(&genesis_config);
+        let pubkey = Pubkey::new_rand();
+
+        let tx = system_transaction::transfer(&mint_keypair, &pubkey, 500, bank.last_blockhash());
+        assert_eq!(bank.simulate_transaction(tx.clone()), (Ok(()), vec![]));
+
+        // Nothing is committed, so the same transaction can still be processed
+        assert_eq!(bank.get_balance(&pubkey), 0);
+        assert_eq!(bank.get_balance(&mint_keypair.pubkey()), 10_000);
+        assert_eq!(bank.get_signature_status(&tx.signatures[0]), None);
+        assert_eq!(bank.transaction_count(), 0);
+        assert_eq!(bank.process_transaction(&tx), Ok(()));
+        assert_eq!(bank.get_balance(&pubkey), 500);
+
+        // Failures are reported without charging a fee
+        let tx =
+            system_transaction::transfer(&mint_keypair, &pubkey, 20_000, bank.last_blockhash());
+        assert_eq!(
+            bank.simulate_transaction(tx).0,
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::new_result_with_negative_lamports(),
+            ))
+        );
+        assert_eq!(bank.get_balance(&mint_keypair.pubkey()), 9_500);
+    }
+
+    #[test]
+    fn test_bank_transaction_log_collector() {
+        let (genesis_config, mint_keypair) = create_genesis_config(10_000);
+        let bank = Bank::new(&genesis_config);
+        let alice = Pubkey::new_rand();
+        let bob = Pubkey::new_rand();
+
+        // Nothing is collected without a filter
+        let tx = system_transaction::transfer(&mint_keypair, &alice, 1, bank.last_blockhash());
+        assert_eq!(bank.process_transaction(&tx), Ok(()));
+        assert_eq!(bank.get_transaction_logs(None), Some(vec![]));
+
+        {
+            let mut config = bank.transaction_log_collector_config.write().unwrap();
+            config.mentioned_addresses.insert(alice);
+            config.filter = TransactionLogCollectorFilter::OnlyMentionedAddresses;
+        }
+        let alice_tx =
+            system_transaction::transfer(&mint_keypair, &alice, 2, bank.last_blockhash());
+        assert_eq!(bank.process_transaction(&alice_tx), Ok(()));
+        let bob_tx = system_transaction::transfer(&mint_keypair, &bob, 2, bank.last_blockhash());
+        assert_eq!(bank.process_transaction(&bob_tx), Ok(()));
+        // Simulations are never collected
+        let (result, _) = bank.simulate_transaction(system_transaction::transfer(
+            &mint_keypair,
+            &alice,
+            3,
+            bank.last_blockhash(),
+        ));
+        assert_eq!(result, Ok(()));
+
+        let alice_logs = bank.get_transaction_logs(Some(&alice)).unwrap();
+        assert_eq!(alice_logs.len(), 1);
+        assert_eq!(alice_logs[0].signature, alice_tx.signatures[0]);
+        assert_eq!(alice_logs[0].result, Ok(()));
+        assert!(!alice_logs[0].is_vote);
+        assert_eq!(bank.get_transaction_logs(Some(&bob)), None);
+        assert_eq!(bank.get_transaction_logs(None), Some(alice_logs));
+
+        // Child banks share the config but collect their own logs
+        let bank = new_from_parent(&Arc::new(bank));
+        bank.transaction_log_collector_config
+            .write()
+            .unwrap()
+            .filter = TransactionLogCollectorFilter::All;
+        assert_eq!(bank.get_transaction_logs(None), Some(vec![]));
+        let bob_tx = system_transaction::transfer(&mint_keypair, &bob, 4, bank.last_blockhash());
+        assert_eq!(bank.process_transaction(&bob_tx), Ok(()));
+        let logs = bank.get_transaction_logs(None).unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].signature, bob_tx.signatures[0]);
+    }
+
     #[test]
     fn test_bank_deposit() {
         let (genesis_config, _mint_keypair) = create_genesis_config(100);
@@ -3042,6 +3491,16 @@ mod tests {
         // verify capitalization
         assert_eq!(capitalization - expected_fee_burned, bank.capitalization());
 
+        // verify the fee was recorded as a reward to the leader
+        assert!(bank.rewards.read().unwrap().contains(&(
+            leader,
+            RewardInfo {
+                reward_type: RewardType::Fee,
+                lamports: expected_fee_collected as i64,
+                post_balance: initial_balance + expected_fee_collected,
+            }
+        )));
+
         // Verify that an InstructionError collects fees, too
         let mut bank = Bank::new_from_parent(&Arc::new(bank), &leader, 1);
         let mut tx =
@@ -3275,7 +3734,13 @@ mod tests {
 
         let lock_result = bank.prepare_batch(&pay_alice, None);
         let results_alice = bank
-            .load_execute_and_commit_transactions(&lock_result, MAX_RECENT_BLOCKHASHES, false)
+            .load_execute_and_commit_transactions(
+                &lock_result,
+                MAX_RECENT_BLOCKHASHES,
+                false,
+                false,
+                false,
+            )
             .0
             .fee_collection_results;
         assert_eq!(results_alice[0], Ok(()));
@@ -4428,6 +4893,28 @@ mod tests {
         assert_eq!(last_ts, 1);
     }
 
+    #[test]
+    fn test_bank_inherit_compute_budget() {
+        let (genesis_config, _) = create_genesis_config(500);
+        let bank0 = Arc::new(Bank::new(&genesis_config));
+        assert_eq!(bank0.compute_budget(), ComputeBudget::default());
+        let compute_budget = ComputeBudget {
+            max_units: 1,
+            ..ComputeBudget::default()
+        };
+        bank0.set_compute_budget(compute_budget);
+        let bank1 = Bank::new_from_parent(&bank0, &Pubkey::default(), 1);
+        assert_eq!(bank1.compute_budget(), compute_budget);
+
+        // The budget is left out of snapshots
+        let mut serialized = vec![];
+        serialize_into(&mut serialized, &bank1).unwrap();
+        bank1.set_compute_budget(ComputeBudget::default());
+        let mut serialized_with_default_budget = vec![];
+        serialize_into(&mut serialized_with_default_budget, &bank1).unwrap();
+        assert_eq!(serialized, serialized_with_default_budget);
+    }
+
     #[test]
     fn test_hash_internal_state_unchanged() {
         let (genesis_config, _) = create_genesis_config(500);
@@ -4854,26 +5341,37 @@ mod tests {
         let txs = vec![tx0, tx1, tx2];
 
         let lock_result = bank0.prepare_batch(&txs, None);
-        let (transaction_results, transaction_balances_set) =
-            bank0.load_execute_and_commit_transactions(&lock_result, MAX_RECENT_BLOCKHASHES, true);
+        let (transaction_results, transaction_balances_set, _, transaction_compute_units) = bank0
+            .load_execute_and_commit_transactions(
+                &lock_result,
+                MAX_RECENT_BLOCKHASHES,
+                true,
+                false,
+                false,
+            );
 
         assert_eq!(transaction_balances_set.pre_balances.len(), 3);
         assert_eq!(transaction_balances_set.post_balances.len(), 3);
+        assert_eq!(transaction_compute_units.len(), 3);
 
         assert!(transaction_results.processing_results[0].0.is_ok());
         assert_eq!(transaction_balances_set.pre_balances[0], vec![8, 11, 1]);
         assert_eq!(transaction_balances_set.post_balances[0], vec![5, 13, 1]);
+        let native_instruction_units = bank0.compute_budget().native_instruction_units;
+        assert_eq!(transaction_compute_units[0], native_instruction_units);
 
         // Failed transactions still produce balance sets
         // This is a TransactionError - not possible to charge fees
         assert!(transaction_results.processing_results[1].0.is_err());
         assert_eq!(transaction_balances_set.pre_balances[1], vec![0, 0, 1]);
         assert_eq!(transaction_balances_set.post_balances[1], vec![0, 0, 1]);
+        assert_eq!(transaction_compute_units[1], 0);
 
         // Failed transactions still produce balance sets
         // This is an InstructionError - fees charged
         assert!(transaction_results.processing_results[2].0.is_err());
         assert_eq!(transaction_balances_set.pre_balances[2], vec![9, 0, 1]);
         assert_eq!(transaction_balances_set.post_balances[2], vec![8, 0, 1]);
+        assert_eq!(transaction_compute_units[2], native_instruction_units);
     }
 }
@@ -77,6 +77,7 @@ pub fn create_genesis_config_with_leader(
         solana_system_program(),
         solana_nonce_program(),
         solana_bpf_loader_program!(),
+        solana_bpf_loader_upgradeable_program!(),
         solana_vote_program!(),
         solana_stake_program!(),
     ];
@@ -8,6 +8,7 @@ mod blockhash_queue;
 pub mod bloom;
 pub mod genesis_utils;
 pub mod loader_utils;
+pub mod log_collector;
 pub mod message_processor;
 mod native_loader;
 mod nonce_utils;
@@ -32,6 +33,9 @@ extern crate solana_stake_program;
 #[macro_use]
 extern crate solana_bpf_loader_program;
 
+#[macro_use]
+extern crate solana_bpf_loader_upgradeable_program;
+
 #[macro_use]
 extern crate serde_derive;
 
@@ -1,19 +1,27 @@
+use crate::accounts::Accounts;
+use crate::log_collector::LogCollector;
 use crate::native_loader;
 use crate::system_instruction_processor;
+use log::*;
 use serde::{Deserialize, Serialize};
 use solana_sdk::account::{create_keyed_readonly_accounts, Account, KeyedAccount};
 use solana_sdk::clock::Epoch;
-use solana_sdk::instruction::{CompiledInstruction, InstructionError};
-use solana_sdk::instruction_processor_utils;
+use solana_sdk::instruction::{CompiledInstruction, Instruction, InstructionError};
+use solana_sdk::instruction_processor_utils::{
+    self, ComputeBudget, ComputeMeter, InvokeContext, Logger, MAX_INVOKE_DEPTH,
+};
 use solana_sdk::loader_instruction::LoaderInstruction;
 use solana_sdk::message::Message;
+use solana_sdk::native_loader::create_loadable_account;
 use solana_sdk::nonce_instruction;
 use solana_sdk::nonce_program;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::system_program;
 use solana_sdk::transaction::TransactionError;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::io::Write;
+use std::rc::Rc;
 use std::sync::RwLock;
 
 #[cfg(unix)]
@@ -185,9 +193,194 @@ fn get_loader_instruction_data<'a>(
     }
 }
 
+/// Balances of the accounts passed to an instruction, in the order it was passed them, before and
+/// after it executed
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InstructionBalances {
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+}
+
+/// A program on the call stack, along with the state of its accounts when it was invoked, or
+/// when the last program it invoked returned
+struct InvokeFrame {
+    program_id: Pubkey,
+    pre_accounts: Vec<(Pubkey, PreInstructionAccount)>,
+}
+
+pub struct ThisInvokeContext<'a> {
+    message_processor: &'a MessageProcessor,
+    /// Loaders of the message's programs, used to load the loaders of invoked programs
+    loaders: Vec<(Pubkey, Account)>,
+    program_stack: Vec<InvokeFrame>,
+    logger: Rc<RefCell<dyn Logger>>,
+    compute_budget: ComputeBudget,
+    compute_meter: Rc<RefCell<ThisComputeMeter>>,
+}
+impl<'a> ThisInvokeContext<'a> {
+    pub fn new(
+        message_processor: &'a MessageProcessor,
+        loaders: Vec<(Pubkey, Account)>,
+        log_collector: Option<Rc<LogCollector>>,
+        compute_budget: ComputeBudget,
+    ) -> Self {
+        Self {
+            message_processor,
+            loaders,
+            program_stack: vec![],
+            logger: Rc::new(RefCell::new(ThisLogger { log_collector })),
+            compute_budget,
+            compute_meter: Rc::new(RefCell::new(ThisComputeMeter {
+                remaining: compute_budget.max_units,
+            })),
+        }
+    }
+
+    /// Give the next instruction of the message its own full budget
+    fn reset_compute_meter(&mut self) {
+        self.compute_meter.borrow_mut().remaining = self.compute_budget.max_units;
+    }
+
+    fn push(
+        &mut self,
+        program_id: &Pubkey,
+        pre_accounts: Vec<(Pubkey, PreInstructionAccount)>,
+    ) -> Result<(), InstructionError> {
+        if self.program_stack.len() >= MAX_INVOKE_DEPTH {
+            return Err(InstructionError::CallDepth);
+        }
+        if self
+            .program_stack
+            .iter()
+            .any(|frame| frame.program_id == *program_id)
+        {
+            return Err(InstructionError::ReentrancyNotAllowed);
+        }
+        self.program_stack.push(InvokeFrame {
+            program_id: *program_id,
+            pre_accounts,
+        });
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Vec<(Pubkey, PreInstructionAccount)> {
+        self.program_stack.pop().unwrap().pre_accounts
+    }
+
+    /// Take the current state of `keyed_accounts` as the starting point against which the rest of
+    /// the current program's changes are verified. If `verify`, the changes the current program
+    /// made since the last starting point are verified first
+    fn update_pre_accounts(
+        &mut self,
+        keyed_accounts: &[KeyedAccount],
+        verify: bool,
+    ) -> Result<(), InstructionError> {
+        let frame = self.program_stack.last_mut().unwrap();
+        for keyed_account in keyed_accounts {
+            if let Some((_, pre_account)) = frame
+                .pre_accounts
+                .iter_mut()
+                .find(|(key, _)| key == keyed_account.unsigned_key())
+            {
+                if verify {
+                    verify_account_changes(&frame.program_id, pre_account, keyed_account.account)?;
+                }
+                let is_writable = pre_account.is_writable;
+                *pre_account = PreInstructionAccount::new(
+                    keyed_account.account,
+                    is_writable,
+                    need_account_data_checked(
+                        &keyed_account.account.owner,
+                        &frame.program_id,
+                        is_writable,
+                    ),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+impl<'a> InvokeContext for ThisInvokeContext<'a> {
+    fn get_logger(&self) -> Rc<RefCell<dyn Logger>> {
+        self.logger.clone()
+    }
+    fn get_compute_budget(&self) -> &ComputeBudget {
+        &self.compute_budget
+    }
+    fn get_compute_meter(&self) -> Rc<RefCell<dyn ComputeMeter>> {
+        self.compute_meter.clone()
+    }
+    fn invoke_signed(
+        &mut self,
+        instruction: &Instruction,
+        keyed_accounts: &mut [KeyedAccount],
+        signers_seeds: &[&[&[u8]]],
+    ) -> Result<(), InstructionError> {
+        self.compute_meter
+            .borrow_mut()
+            .consume(self.compute_budget.invoke_units)?;
+        let caller_program_id = match self.program_stack.last() {
+            Some(frame) => frame.program_id,
+            // Only a running program can invoke another
+            None => return Err(InstructionError::GenericError),
+        };
+        let signers = signers_seeds
+            .iter()
+            .map(|seeds| Pubkey::create_program_address(seeds, &caller_program_id))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| InstructionError::InvalidSeeds)?;
+
+        self.update_pre_accounts(keyed_accounts, true)?;
+        let message_processor = self.message_processor;
+        message_processor.process_cross_program_instruction(
+            instruction,
+            keyed_accounts,
+            &signers,
+            self,
+        )?;
+        // The callee's changes were verified on its return
+        self.update_pre_accounts(keyed_accounts, false)
+    }
+}
+
+pub struct ThisComputeMeter {
+    remaining: u64,
+}
+impl ComputeMeter for ThisComputeMeter {
+    fn consume(&mut self, amount: u64) -> Result<(), InstructionError> {
+        let exceeded = self.remaining < amount;
+        self.remaining = self.remaining.saturating_sub(amount);
+        if exceeded {
+            return Err(InstructionError::ComputationalBudgetExceeded);
+        }
+        Ok(())
+    }
+    fn get_remaining(&self) -> u64 {
+        self.remaining
+    }
+}
+
+pub struct ThisLogger {
+    log_collector: Option<Rc<LogCollector>>,
+}
+impl Logger for ThisLogger {
+    fn log_enabled(&self) -> bool {
+        log_enabled!(log::Level::Info) || self.log_collector.is_some()
+    }
+    fn log(&mut self, message: &str) {
+        info!("{}", message);
+        if let Some(log_collector) = &self.log_collector {
+            log_collector.log(message);
+        }
+    }
+}
+
 pub type ProcessInstruction =
     fn(&Pubkey, &mut [KeyedAccount], &[u8]) -> Result<(), InstructionError>;
 
+pub type ProcessInstructionWithContext =
+    fn(&Pubkey, &mut [KeyedAccount], &[u8], &mut dyn InvokeContext) -> Result<(), InstructionError>;
+
 pub type SymbolCache = RwLock<HashMap<Vec<u8>, Symbol<instruction_processor_utils::Entrypoint>>>;
 
 #[derive(Serialize, Deserialize)]
@@ -195,6 +388,8 @@ pub struct MessageProcessor {
     #[serde(skip)]
     instruction_processors: Vec<(Pubkey, ProcessInstruction)>,
     #[serde(skip)]
+    instruction_processors_with_context: Vec<(Pubkey, ProcessInstructionWithContext)>,
+    #[serde(skip)]
     symbol_cache: SymbolCache,
 }
 
@@ -210,6 +405,7 @@ impl Default for MessageProcessor {
 
         Self {
             instruction_processors,
+            instruction_processors_with_context: vec![],
             symbol_cache: RwLock::new(HashMap::new()),
         }
     }
@@ -226,49 +422,36 @@ impl MessageProcessor {
             .push((program_id, process_instruction));
     }
 
+    /// Same as add_instruction_processor, except that the entrypoint is also passed the
+    /// `InvokeContext`, so that it can invoke other programs
+    pub fn add_instruction_processor_with_context(
+        &mut self,
+        program_id: Pubkey,
+        process_instruction: ProcessInstructionWithContext,
+    ) {
+        self.instruction_processors_with_context
+            .push((program_id, process_instruction));
+    }
+
+    fn has_instruction_processor(&self, program_id: &Pubkey) -> bool {
+        self.instruction_processors
+            .iter()
+            .any(|(id, _)| id == program_id)
+            || self
+                .instruction_processors_with_context
+                .iter()
+                .any(|(id, _)| id == program_id)
+    }
+
     /// Process an instruction
     /// This method calls the instruction's program entrypoint method
     fn process_instruction(
         &self,
-        message: &Message,
-        instruction: &CompiledInstruction,
-        executable_accounts: &mut [(Pubkey, Account)],
-        program_accounts: &mut [&mut Account],
+        program_id: &Pubkey,
+        keyed_accounts: &mut [KeyedAccount],
+        ix_data: &[u8],
+        invoke_context: &mut dyn InvokeContext,
     ) -> Result<(), InstructionError> {
-        let program_id = instruction.program_id(&message.account_keys);
-
-        let mut loader_ix_data = vec![];
-        let ix_data = get_loader_instruction_data(
-            executable_accounts,
-            &instruction.data,
-            &mut loader_ix_data,
-        );
-
-        let mut keyed_accounts = create_keyed_readonly_accounts(executable_accounts);
-        let mut keyed_accounts2: Vec<_> = instruction
-            .accounts
-            .iter()
-            .map(|&index| {
-                let index = index as usize;
-                let key = &message.account_keys[index];
-                let is_writable = message.is_writable(index);
-                (
-                    key,
-                    index < message.header.num_required_signatures as usize,
-                    is_writable,
-                )
-            })
-            .zip(program_accounts.iter_mut())
-            .map(|((key, is_signer, is_writable), account)| {
-                if is_writable {
-                    KeyedAccount::new(key, is_signer, account)
-                } else {
-                    KeyedAccount::new_readonly(key, is_signer, account)
-                }
-            })
-            .collect();
-        keyed_accounts.append(&mut keyed_accounts2);
-
         assert!(
             keyed_accounts[0].account.executable,
             "loader not executable"
@@ -277,20 +460,102 @@ impl MessageProcessor {
         let loader_id = keyed_accounts[0].unsigned_key();
         for (id, process_instruction) in &self.instruction_processors {
             if id == loader_id {
-                return process_instruction(&program_id, &mut keyed_accounts[1..], &ix_data);
+                return instruction_processor_utils::with_program_logger(
+                    invoke_context.get_logger(),
+                    || process_instruction(program_id, &mut keyed_accounts[1..], ix_data),
+                );
+            }
+        }
+        for (id, process_instruction) in &self.instruction_processors_with_context {
+            if id == loader_id {
+                return process_instruction(
+                    program_id,
+                    &mut keyed_accounts[1..],
+                    ix_data,
+                    invoke_context,
+                );
             }
         }
 
         native_loader::invoke_entrypoint(
-            &program_id,
-            &mut keyed_accounts,
+            program_id,
+            keyed_accounts,
             ix_data,
             &self.symbol_cache,
+            invoke_context,
         )
     }
 
-    fn sum_account_lamports(accounts: &mut [&mut Account]) -> u128 {
-        accounts.iter().map(|a| u128::from(a.lamports)).sum()
+    fn sum_account_lamports(keyed_accounts: &[KeyedAccount]) -> u128 {
+        keyed_accounts
+            .iter()
+            .map(|keyed_account| u128::from(keyed_account.account.lamports))
+            .sum()
+    }
+
+    /// Call the program's entrypoint method with `instruction_accounts` and verify that the
+    /// result of the call does not violate the bank's accounting rules.
+    fn execute_keyed_instruction(
+        &self,
+        program_id: &Pubkey,
+        executable_accounts: &mut [(Pubkey, Account)],
+        instruction_accounts: Vec<KeyedAccount>,
+        data: &[u8],
+        invoke_context: &mut ThisInvokeContext,
+    ) -> Result<(), InstructionError> {
+        // Copy only what we need to verify after instruction processing
+        let pre_accounts: Vec<_> = instruction_accounts
+            .iter()
+            .map(|keyed_account| {
+                let is_writable = keyed_account.is_writable();
+                (
+                    *keyed_account.unsigned_key(),
+                    PreInstructionAccount::new(
+                        keyed_account.account,
+                        is_writable,
+                        need_account_data_checked(
+                            &keyed_account.account.owner,
+                            program_id,
+                            is_writable,
+                        ),
+                    ),
+                )
+            })
+            .collect();
+        // Sum total lamports before instruction processing
+        let pre_total = Self::sum_account_lamports(&instruction_accounts);
+
+        let mut loader_ix_data = vec![];
+        let ix_data = get_loader_instruction_data(executable_accounts, data, &mut loader_ix_data);
+        let executable_len = executable_accounts.len();
+        let mut keyed_accounts = create_keyed_readonly_accounts(executable_accounts);
+        keyed_accounts.extend(instruction_accounts);
+
+        // Loaders meter the programs they run, native programs are charged a fixed cost
+        if executable_len == 1 {
+            invoke_context
+                .compute_meter
+                .borrow_mut()
+                .consume(invoke_context.compute_budget.native_instruction_units)?;
+        }
+        invoke_context.push(program_id, pre_accounts)?;
+        let result =
+            self.process_instruction(program_id, &mut keyed_accounts, ix_data, invoke_context);
+        // Programs this one invoked may have moved its starting point forward
+        let pre_accounts = invoke_context.pop();
+        result?;
+
+        // Verify the instruction
+        let instruction_accounts = &keyed_accounts[executable_len..];
+        for ((_, pre_account), post_account) in pre_accounts.iter().zip(instruction_accounts) {
+            verify_account_changes(program_id, pre_account, post_account.account)?;
+        }
+        // The total sum of all the lamports in all the accounts cannot change.
+        let post_total = Self::sum_account_lamports(instruction_accounts);
+        if pre_total != post_total {
+            return Err(InstructionError::UnbalancedInstruction);
+        }
+        Ok(())
     }
 
     /// Execute an instruction
@@ -303,49 +568,190 @@ impl MessageProcessor {
         instruction: &CompiledInstruction,
         executable_accounts: &mut [(Pubkey, Account)],
         program_accounts: &mut [&mut Account],
+        invoke_context: &mut ThisInvokeContext,
     ) -> Result<(), InstructionError> {
         assert_eq!(instruction.accounts.len(), program_accounts.len());
         let program_id = instruction.program_id(&message.account_keys);
-        // Copy only what we need to verify after instruction processing
-        let pre_accounts: Vec<_> = program_accounts
-            .iter_mut()
-            .enumerate()
-            .map(|(i, account)| {
-                let is_writable = message.is_writable(instruction.accounts[i] as usize);
-                PreInstructionAccount::new(
-                    account,
+        let instruction_accounts: Vec<_> = instruction
+            .accounts
+            .iter()
+            .map(|&index| {
+                let index = index as usize;
+                let key = &message.account_keys[index];
+                let is_writable = message.is_writable(index);
+                (
+                    key,
+                    index < message.header.num_required_signatures as usize,
                     is_writable,
-                    need_account_data_checked(&account.owner, program_id, is_writable),
                 )
             })
+            .zip(program_accounts.iter_mut())
+            .map(|((key, is_signer, is_writable), account)| {
+                if is_writable {
+                    KeyedAccount::new(key, is_signer, account)
+                } else {
+                    KeyedAccount::new_readonly(key, is_signer, account)
+                }
+            })
             .collect();
-        // Sum total lamports before instruction processing
-        let pre_total = Self::sum_account_lamports(program_accounts);
 
-        self.process_instruction(message, instruction, executable_accounts, program_accounts)?;
+        self.execute_keyed_instruction(
+            program_id,
+            executable_accounts,
+            instruction_accounts,
+            &instruction.data,
+            invoke_context,
+        )
+    }
 
-        // Verify the instruction
-        for (pre_account, post_account) in pre_accounts.iter().zip(program_accounts.iter()) {
-            verify_account_changes(&program_id, pre_account, post_account)?;
+    /// Load the executable accounts of `program_id`, ordered from the root loader to the
+    /// program itself. The program and its loaders must be among `keyed_accounts` or the
+    /// transaction's loaders, unless they are static instruction processors
+    fn load_executable_accounts(
+        &self,
+        program_id: &Pubkey,
+        keyed_accounts: &[KeyedAccount],
+        loaders: &[(Pubkey, Account)],
+    ) -> Result<Vec<(Pubkey, Account)>, InstructionError> {
+        let find_account = |pubkey: &Pubkey| {
+            keyed_accounts
+                .iter()
+                .find(|keyed_account| keyed_account.unsigned_key() == pubkey)
+                .map(|keyed_account| keyed_account.account.clone())
+                .or_else(|| {
+                    loaders
+                        .iter()
+                        .find(|(key, _)| key == pubkey)
+                        .map(|(_, account)| account.clone())
+                })
+        };
+        let mut executable_accounts = vec![];
+        let mut program_id = *program_id;
+        loop {
+            if executable_accounts.len() > MAX_INVOKE_DEPTH {
+                return Err(InstructionError::CallDepth);
+            }
+            let is_static = self.has_instruction_processor(&program_id);
+            let account = match find_account(&program_id) {
+                Some(account) => account,
+                None if is_static => create_loadable_account(&program_id.to_string()),
+                None => return Err(InstructionError::MissingAccount),
+            };
+            if !account.executable {
+                return Err(InstructionError::AccountNotExecutable);
+            }
+            let owner = account.owner;
+            let programdata_address = Accounts::programdata_address(&account);
+            executable_accounts.insert(0, (program_id, account));
+            if let Some(programdata_address) = programdata_address {
+                let programdata =
+                    find_account(&programdata_address).ok_or(InstructionError::MissingAccount)?;
+                executable_accounts.insert(0, (programdata_address, programdata));
+            }
+            if is_static || solana_sdk::native_loader::check_id(&owner) {
+                return Ok(executable_accounts);
+            }
+            program_id = owner;
         }
-        // The total sum of all the lamports in all the accounts cannot change.
-        let post_total = Self::sum_account_lamports(program_accounts);
-        if pre_total != post_total {
-            return Err(InstructionError::UnbalancedInstruction);
+    }
+
+    /// Process an instruction invoked by the program on top of `invoke_context`'s call stack.
+    /// `keyed_accounts` are the invoking program's accounts, `signers` the program addresses it
+    /// signed for
+    fn process_cross_program_instruction(
+        &self,
+        instruction: &Instruction,
+        keyed_accounts: &mut [KeyedAccount],
+        signers: &[Pubkey],
+        invoke_context: &mut ThisInvokeContext,
+    ) -> Result<(), InstructionError> {
+        let keys: Vec<_> = instruction
+            .accounts
+            .iter()
+            .map(|account_meta| &account_meta.pubkey)
+            .collect();
+        if has_duplicates(&keys) {
+            return Err(InstructionError::DuplicateAccountIndex);
         }
-        Ok(())
+        let mut executable_accounts = self.load_executable_accounts(
+            &instruction.program_id,
+            keyed_accounts,
+            &invoke_context.loaders,
+        )?;
+
+        // The invoked program is given the same accounts, but never more privileges over
+        // them than the invoking program has, besides signing for its program addresses
+        let mut instruction_accounts: Vec<Option<KeyedAccount>> =
+            instruction.accounts.iter().map(|_| None).collect();
+        for keyed_account in keyed_accounts.iter_mut() {
+            let key = *keyed_account.unsigned_key();
+            if let Some(i) = keys.iter().position(|pubkey| **pubkey == key) {
+                let account_meta = &instruction.accounts[i];
+                if (account_meta.is_signer
+                    && keyed_account.signer_key().is_none()
+                    && !signers.contains(&key))
+                    || (account_meta.is_writable && !keyed_account.is_writable())
+                {
+                    return Err(InstructionError::PrivilegeEscalation);
+                }
+                instruction_accounts[i] = Some(if account_meta.is_writable {
+                    KeyedAccount::new(
+                        &account_meta.pubkey,
+                        account_meta.is_signer,
+                        keyed_account.account,
+                    )
+                } else {
+                    KeyedAccount::new_readonly(
+                        &account_meta.pubkey,
+                        account_meta.is_signer,
+                        keyed_account.account,
+                    )
+                });
+            }
+        }
+        let instruction_accounts = instruction_accounts
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or(InstructionError::MissingAccount)?;
+
+        self.execute_keyed_instruction(
+            &instruction.program_id,
+            &mut executable_accounts,
+            instruction_accounts,
+            &instruction.data,
+            invoke_context,
+        )
     }
 
     /// Process a message.
     /// This method calls each instruction in the message over the set of loaded Accounts
-    /// The accounts are committed back to the bank only if every instruction succeeds
+    /// The accounts are committed back to the bank only if every instruction succeeds, and
+    /// each instruction may consume up to `compute_budget.max_units`.  The units the
+    /// instructions consumed, including a failed one, are added to `compute_units_consumed`,
+    /// and the balances of each instruction that succeeded to `instruction_balances`, if given
     pub fn process_message(
         &self,
         message: &Message,
         loaders: &mut [Vec<(Pubkey, Account)>],
         accounts: &mut [Account],
+        log_collector: Option<Rc<LogCollector>>,
+        compute_budget: &ComputeBudget,
+        compute_units_consumed: &mut u64,
+        mut instruction_balances: Option<&mut Vec<InstructionBalances>>,
     ) -> Result<(), TransactionError> {
+        // The programs themselves are left out, their loaders are small
+        let loader_accounts = loaders
+            .iter()
+            .flat_map(|executable_accounts| {
+                executable_accounts[..executable_accounts.len().saturating_sub(1)]
+                    .iter()
+                    .cloned()
+            })
+            .collect();
+        let mut invoke_context =
+            ThisInvokeContext::new(self, loader_accounts, log_collector, *compute_budget);
         for (instruction_index, instruction) in message.instructions.iter().enumerate() {
+            invoke_context.reset_compute_meter();
             let executable_index = message
                 .program_position(instruction.program_id_index as usize)
                 .ok_or(TransactionError::InvalidAccountIndex)?;
@@ -355,13 +761,35 @@ impl MessageProcessor {
             // TODO: `get_subset_unchecked_mut` panics on an index out of bounds if an executable
             // account is also included as a regular account for an instruction, because the
             // executable account is not passed in as part of the accounts slice
-            self.execute_instruction(
+            let pre_balances: Vec<_> = if instruction_balances.is_some() {
+                program_accounts
+                    .iter()
+                    .map(|account| account.lamports)
+                    .collect()
+            } else {
+                vec![]
+            };
+            let result = self.execute_instruction(
                 message,
                 instruction,
                 executable_accounts,
                 &mut program_accounts,
-            )
-            .map_err(|err| TransactionError::InstructionError(instruction_index as u8, err))?;
+                &mut invoke_context,
+            );
+            *compute_units_consumed += compute_budget
+                .max_units
+                .saturating_sub(invoke_context.compute_meter.borrow().remaining);
+            result
+                .map_err(|err| TransactionError::InstructionError(instruction_index as u8, err))?;
+            if let Some(instruction_balances) = instruction_balances.as_mut() {
+                instruction_balances.push(InstructionBalances {
+                    pre_balances,
+                    post_balances: program_accounts
+                        .iter()
+                        .map(|account| account.lamports)
+                        .collect(),
+                });
+            }
         }
         Ok(())
     }
@@ -381,7 +809,8 @@ mod tests {
     use super::*;
     use solana_sdk::instruction::{AccountMeta, Instruction, InstructionError};
     use solana_sdk::message::Message;
-    use solana_sdk::native_loader::create_loadable_account;
+    use solana_sdk::pubkey::MAX_SEED_LEN;
+    use solana_sdk::system_instruction;
 
     #[test]
     fn test_is_zeroed() {
@@ -846,7 +1275,15 @@ mod tests {
             account_metas.clone(),
         )]);
 
-        let result = message_processor.process_message(&message, &mut loaders, &mut accounts);
+        let result = message_processor.process_message(
+            &message,
+            &mut loaders,
+            &mut accounts,
+            None,
+            &ComputeBudget::default(),
+            &mut 0,
+            None,
+        );
         assert_eq!(result, Ok(()));
         assert_eq!(accounts[0].lamports, 100);
         assert_eq!(accounts[1].lamports, 0);
@@ -857,7 +1294,15 @@ mod tests {
             account_metas.clone(),
         )]);
 
-        let result = message_processor.process_message(&message, &mut loaders, &mut accounts);
+        let result = message_processor.process_message(
+            &message,
+            &mut loaders,
+            &mut accounts,
+            None,
+            &ComputeBudget::default(),
+            &mut 0,
+            None,
+        );
         assert_eq!(
             result,
             Err(TransactionError::InstructionError(
@@ -872,7 +1317,15 @@ mod tests {
             account_metas,
         )]);
 
-        let result = message_processor.process_message(&message, &mut loaders, &mut accounts);
+        let result = message_processor.process_message(
+            &message,
+            &mut loaders,
+            &mut accounts,
+            None,
+            &ComputeBudget::default(),
+            &mut 0,
+            None,
+        );
         assert_eq!(
             result,
             Err(TransactionError::InstructionError(
@@ -880,6 +1333,575 @@ mod tests {
                 InstructionError::ReadonlyDataModified
             ))
         );
+
+        // The balances of the accounts each instruction was passed are recorded if asked for
+        let message = Message::new(vec![Instruction::new(
+            mock_system_program_id,
+            &MockSystemInstruction::AttemptCredit { lamports: 50 },
+            vec![
+                AccountMeta::new(from_pubkey, true),
+                AccountMeta::new(to_pubkey, false),
+            ],
+        )]);
+        let pre_balances = vec![accounts[0].lamports, accounts[1].lamports];
+        let mut instruction_balances = vec![];
+        let result = message_processor.process_message(
+            &message,
+            &mut loaders,
+            &mut accounts,
+            None,
+            &ComputeBudget::default(),
+            &mut 0,
+            Some(&mut instruction_balances),
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            instruction_balances,
+            vec![InstructionBalances {
+                pre_balances: pre_balances.clone(),
+                post_balances: vec![pre_balances[0] - 50, pre_balances[1] + 50],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_process_cross_program_instruction() {
+        #[derive(Serialize, Deserialize)]
+        enum MockCalleeInstruction {
+            Transfer { lamports: u64 },
+        }
+
+        fn mock_callee_process_instruction(
+            _program_id: &Pubkey,
+            keyed_accounts: &mut [KeyedAccount],
+            data: &[u8],
+        ) -> Result<(), InstructionError> {
+            match bincode::deserialize(data) {
+                Ok(MockCalleeInstruction::Transfer { lamports }) => {
+                    keyed_accounts[0].account.lamports -= lamports;
+                    keyed_accounts[1].account.lamports += lamports;
+                    Ok(())
+                }
+                Err(_) => Err(InstructionError::InvalidInstructionData),
+            }
+        }
+
+        // Invokes the instruction in its data, if any, with all of its accounts
+        fn mock_forward_process_instruction(
+            _program_id: &Pubkey,
+            keyed_accounts: &mut [KeyedAccount],
+            data: &[u8],
+            invoke_context: &mut dyn InvokeContext,
+        ) -> Result<(), InstructionError> {
+            if data.is_empty() {
+                return Ok(());
+            }
+            let instruction: Instruction =
+                bincode::deserialize(data).map_err(|_| InstructionError::InvalidInstructionData)?;
+            invoke_context.invoke(&instruction, keyed_accounts)
+        }
+
+        let callee_program_id = Pubkey::new_rand();
+        let forward_program_ids: Vec<_> =
+            (0..=MAX_INVOKE_DEPTH).map(|_| Pubkey::new_rand()).collect();
+        let mut message_processor = MessageProcessor::default();
+        message_processor
+            .add_instruction_processor(callee_program_id, mock_callee_process_instruction);
+        for program_id in &forward_program_ids {
+            message_processor.add_instruction_processor_with_context(
+                *program_id,
+                mock_forward_process_instruction,
+            );
+        }
+        let forward_program_id = forward_program_ids[0];
+
+        let from_pubkey = Pubkey::new_rand();
+        let to_pubkey = Pubkey::new_rand();
+        let process = |account_metas: Vec<AccountMeta>, instruction: &Instruction| {
+            let mut accounts = vec![
+                Account::new(100, 0, &callee_program_id),
+                Account::new(0, 0, &callee_program_id),
+            ];
+            let mut loaders = vec![vec![(
+                forward_program_id,
+                create_loadable_account("mock_forward_program"),
+            )]];
+            let message = Message::new(vec![Instruction::new(
+                forward_program_id,
+                instruction,
+                account_metas,
+            )]);
+            message_processor
+                .process_message(
+                    &message,
+                    &mut loaders,
+                    &mut accounts,
+                    None,
+                    &ComputeBudget::default(),
+                    &mut 0,
+                    None,
+                )
+                .map(|_| (accounts[0].lamports, accounts[1].lamports))
+        };
+        let account_metas = vec![
+            AccountMeta::new(from_pubkey, true),
+            AccountMeta::new(to_pubkey, false),
+        ];
+        let transfer = Instruction::new(
+            callee_program_id,
+            &MockCalleeInstruction::Transfer { lamports: 40 },
+            account_metas.clone(),
+        );
+
+        // Neither account is the forwarding program's to debit, but the callee's
+        assert_eq!(process(account_metas.clone(), &transfer), Ok((60, 40)));
+
+        // Privileges can only be narrowed
+        assert_eq!(
+            process(
+                vec![
+                    AccountMeta::new(from_pubkey, true),
+                    AccountMeta::new_readonly(to_pubkey, false),
+                ],
+                &transfer
+            ),
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::PrivilegeEscalation
+            ))
+        );
+        let mut escalated_transfer = transfer.clone();
+        escalated_transfer.accounts[1].is_signer = true;
+        assert_eq!(
+            process(account_metas.clone(), &escalated_transfer),
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::PrivilegeEscalation
+            ))
+        );
+        let narrowed_transfer = Instruction::new(
+            callee_program_id,
+            &MockCalleeInstruction::Transfer { lamports: 40 },
+            vec![
+                AccountMeta::new(from_pubkey, false),
+                AccountMeta::new(to_pubkey, false),
+            ],
+        );
+        assert_eq!(
+            process(account_metas.clone(), &narrowed_transfer),
+            Ok((60, 40))
+        );
+
+        // The callee's changes are verified against the callee's privileges
+        let readonly_transfer = Instruction::new(
+            callee_program_id,
+            &MockCalleeInstruction::Transfer { lamports: 40 },
+            vec![
+                AccountMeta::new(from_pubkey, true),
+                AccountMeta::new_readonly(to_pubkey, false),
+            ],
+        );
+        assert_eq!(
+            process(account_metas.clone(), &readonly_transfer),
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::ReadonlyLamportChange
+            ))
+        );
+
+        // Every account the callee gets must have been passed to the caller
+        let missing_transfer = Instruction::new(
+            callee_program_id,
+            &MockCalleeInstruction::Transfer { lamports: 40 },
+            vec![
+                AccountMeta::new(from_pubkey, true),
+                AccountMeta::new(Pubkey::new_rand(), false),
+            ],
+        );
+        assert_eq!(
+            process(account_metas.clone(), &missing_transfer),
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::MissingAccount
+            ))
+        );
+
+        // A program can't call itself
+        let reentrant = Instruction::new(forward_program_id, &transfer, account_metas.clone());
+        assert_eq!(
+            process(account_metas.clone(), &reentrant),
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::ReentrancyNotAllowed
+            ))
+        );
+
+        // Calls nest up to MAX_INVOKE_DEPTH deep, including the transaction's program
+        let nested = |depth: usize| {
+            forward_program_ids[1..depth].iter().rev().fold(
+                Instruction {
+                    program_id: forward_program_ids[depth],
+                    accounts: account_metas.clone(),
+                    data: vec![],
+                },
+                |instruction, program_id| {
+                    Instruction::new(*program_id, &instruction, account_metas.clone())
+                },
+            )
+        };
+        assert_eq!(
+            process(account_metas.clone(), &nested(MAX_INVOKE_DEPTH - 1)),
+            Ok((100, 0))
+        );
+        assert_eq!(
+            process(account_metas.clone(), &nested(MAX_INVOKE_DEPTH)),
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::CallDepth
+            ))
+        );
+    }
+
+    #[test]
+    fn test_process_cross_program_instruction_signed() {
+        // Invokes the instruction in its data, signing with the seeds in its data
+        fn mock_signing_process_instruction(
+            _program_id: &Pubkey,
+            keyed_accounts: &mut [KeyedAccount],
+            data: &[u8],
+            invoke_context: &mut dyn InvokeContext,
+        ) -> Result<(), InstructionError> {
+            let (instruction, signers_seeds): (Instruction, Vec<Vec<Vec<u8>>>) =
+                bincode::deserialize(data).map_err(|_| InstructionError::InvalidInstructionData)?;
+            let signers_seeds: Vec<Vec<&[u8]>> = signers_seeds
+                .iter()
+                .map(|seeds| seeds.iter().map(|seed| seed.as_slice()).collect())
+                .collect();
+            let signers_seeds: Vec<&[&[u8]]> =
+                signers_seeds.iter().map(|seeds| seeds.as_slice()).collect();
+            invoke_context.invoke_signed(&instruction, keyed_accounts, &signers_seeds)
+        }
+
+        let signing_program_id = Pubkey::new_rand();
+        let mut message_processor = MessageProcessor::default();
+        message_processor.add_instruction_processor_with_context(
+            signing_program_id,
+            mock_signing_process_instruction,
+        );
+
+        let payer_pubkey = Pubkey::new_rand();
+        let (vault_pubkey, bump_seed) =
+            Pubkey::find_program_address(&[b"vault"], &signing_program_id);
+        let create_vault = system_instruction::create_account(
+            &payer_pubkey,
+            &vault_pubkey,
+            10,
+            0,
+            &signing_program_id,
+        );
+        let process = |signers_seeds: Vec<Vec<Vec<u8>>>| {
+            let mut accounts = vec![
+                Account::new(100, 0, &system_program::id()),
+                Account::default(),
+            ];
+            let mut loaders = vec![vec![(
+                signing_program_id,
+                create_loadable_account("mock_signing_program"),
+            )]];
+            // Only the payer signs the transaction
+            let message = Message::new(vec![Instruction::new(
+                signing_program_id,
+                &(create_vault.clone(), signers_seeds),
+                vec![
+                    AccountMeta::new(payer_pubkey, true),
+                    AccountMeta::new(vault_pubkey, false),
+                ],
+            )]);
+            message_processor
+                .process_message(
+                    &message,
+                    &mut loaders,
+                    &mut accounts,
+                    None,
+                    &ComputeBudget::default(),
+                    &mut 0,
+                    None,
+                )
+                .map(|_| accounts[1].clone())
+        };
+
+        let vault_account = process(vec![vec![b"vault".to_vec(), vec![bump_seed]]]).unwrap();
+        assert_eq!(vault_account.lamports, 10);
+        assert_eq!(vault_account.owner, signing_program_id);
+
+        assert_eq!(
+            process(vec![]),
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::PrivilegeEscalation
+            ))
+        );
+
+        // The seeds of another of the program's addresses don't sign for this one
+        let (_, other_bump_seed) = Pubkey::find_program_address(&[b"other"], &signing_program_id);
+        assert_eq!(
+            process(vec![vec![b"other".to_vec(), vec![other_bump_seed]]]),
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::PrivilegeEscalation
+            ))
+        );
+
+        assert_eq!(
+            process(vec![vec![vec![0; MAX_SEED_LEN + 1]]]),
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::InvalidSeeds
+            ))
+        );
+    }
+
+    #[test]
+    fn test_process_message_compute_budget() {
+        // Consumes the number of units in its data
+        fn mock_process_instruction(
+            _program_id: &Pubkey,
+            _keyed_accounts: &mut [KeyedAccount],
+            data: &[u8],
+            invoke_context: &mut dyn InvokeContext,
+        ) -> Result<(), InstructionError> {
+            let units: u64 =
+                bincode::deserialize(data).map_err(|_| InstructionError::InvalidInstructionData)?;
+            invoke_context
+                .get_compute_meter()
+                .borrow_mut()
+                .consume(units)
+        }
+
+        let mock_program_id = Pubkey::new_rand();
+        let mut message_processor = MessageProcessor::default();
+        message_processor
+            .add_instruction_processor_with_context(mock_program_id, mock_process_instruction);
+        let compute_budget = ComputeBudget {
+            max_units: 100,
+            native_instruction_units: 10,
+            ..ComputeBudget::default()
+        };
+        let process = |units: &[u64]| {
+            let mut accounts = vec![Account::new(100, 0, &mock_program_id)];
+            let mut loaders = vec![vec![(
+                mock_program_id,
+                create_loadable_account("mock_program"),
+            )]];
+            let account_metas = vec![AccountMeta::new(Pubkey::new_rand(), false)];
+            let message = Message::new(
+                units
+                    .iter()
+                    .map(|units| Instruction::new(mock_program_id, units, account_metas.clone()))
+                    .collect(),
+            );
+            let mut compute_units_consumed = 0;
+            let result = message_processor.process_message(
+                &message,
+                &mut loaders,
+                &mut accounts,
+                None,
+                &compute_budget,
+                &mut compute_units_consumed,
+                None,
+            );
+            (result, compute_units_consumed)
+        };
+
+        // Each instruction has its own budget, which the native program's own cost comes out of
+        assert_eq!(process(&[90]), (Ok(()), 100));
+        assert_eq!(process(&[50, 60]), (Ok(()), 130));
+        assert_eq!(
+            process(&[50, 91]),
+            (
+                Err(TransactionError::InstructionError(
+                    1,
+                    InstructionError::ComputationalBudgetExceeded
+                )),
+                160
+            )
+        );
+    }
+
+    #[test]
+    fn test_load_executable_accounts() {
+        fn mock_process_instruction(
+            _program_id: &Pubkey,
+            _keyed_accounts: &mut [KeyedAccount],
+            _data: &[u8],
+        ) -> Result<(), InstructionError> {
+            Ok(())
+        }
+
+        let static_program_id = Pubkey::new_rand();
+        let mut message_processor = MessageProcessor::default();
+        message_processor.add_instruction_processor(static_program_id, mock_process_instruction);
+
+        // Static programs need no accounts
+        let executable_accounts = message_processor
+            .load_executable_accounts(&static_program_id, &[], &[])
+            .unwrap();
+        assert_eq!(executable_accounts.len(), 1);
+        assert_eq!(executable_accounts[0].0, static_program_id);
+
+        // Programs are found among the caller's accounts, their loaders also among the
+        // transaction's loaders
+        let loader_id = Pubkey::new_rand();
+        let loader_account = create_loadable_account("mock_loader");
+        let program_id = Pubkey::new_rand();
+        let mut program_account = Account::new(1, 0, &loader_id);
+        program_account.executable = true;
+        let keyed_accounts = vec![KeyedAccount::new_readonly(
+            &program_id,
+            false,
+            &mut program_account,
+        )];
+        let executable_accounts = message_processor
+            .load_executable_accounts(
+                &program_id,
+                &keyed_accounts,
+                &[(loader_id, loader_account.clone())],
+            )
+            .unwrap();
+        assert_eq!(executable_accounts[0], (loader_id, loader_account));
+        assert_eq!(executable_accounts[1].0, program_id);
+
+        assert_eq!(
+            message_processor.load_executable_accounts(&program_id, &keyed_accounts, &[]),
+            Err(InstructionError::MissingAccount)
+        );
+        assert_eq!(
+            message_processor.load_executable_accounts(&Pubkey::new_rand(), &keyed_accounts, &[]),
+            Err(InstructionError::MissingAccount)
+        );
+
+        let mut data_account = Account::new(1, 0, &loader_id);
+        let keyed_accounts = vec![KeyedAccount::new_readonly(
+            &program_id,
+            false,
+            &mut data_account,
+        )];
+        assert_eq!(
+            message_processor.load_executable_accounts(&program_id, &keyed_accounts, &[]),
+            Err(InstructionError::AccountNotExecutable)
+        );
+
+        // Upgradeable programs are preceded by their ProgramData account
+        let loader_id = solana_sdk::bpf_loader_upgradeable::id();
+        let loader_account = create_loadable_account("mock_loader");
+        let programdata_id = Pubkey::new_rand();
+        let programdata_account = Account::new(1, 0, &loader_id);
+        let mut program_account = Account::new_data(
+            1,
+            &solana_sdk::bpf_loader_upgradeable::UpgradeableLoaderState::Program {
+                programdata_address: programdata_id,
+            },
+            &loader_id,
+        )
+        .unwrap();
+        program_account.executable = true;
+        let keyed_accounts = vec![KeyedAccount::new_readonly(
+            &program_id,
+            false,
+            &mut program_account,
+        )];
+        let executable_accounts = message_processor
+            .load_executable_accounts(
+                &program_id,
+                &keyed_accounts,
+                &[
+                    (loader_id, loader_account.clone()),
+                    (programdata_id, programdata_account.clone()),
+                ],
+            )
+            .unwrap();
+        assert_eq!(executable_accounts.len(), 3);
+        assert_eq!(executable_accounts[0], (loader_id, loader_account.clone()));
+        assert_eq!(
+            executable_accounts[1],
+            (programdata_id, programdata_account)
+        );
+        assert_eq!(executable_accounts[2].0, program_id);
+
+        assert_eq!(
+            message_processor.load_executable_accounts(
+                &program_id,
+                &keyed_accounts,
+                &[(loader_id, loader_account)],
+            ),
+            Err(InstructionError::MissingAccount)
+        );
+    }
+
+    #[test]
+    fn test_invoke_context_logger() {
+        let log_collector = Rc::new(LogCollector::default());
+        let message_processor = MessageProcessor::default();
+        let invoke_context = ThisInvokeContext::new(
+            &message_processor,
+            vec![],
+            Some(log_collector.clone()),
+            ComputeBudget::default(),
+        );
+        let logger = invoke_context.get_logger();
+        assert!(logger.borrow().log_enabled());
+        logger.borrow_mut().log("first");
+        logger.borrow_mut().log("second");
+        drop(logger);
+        drop(invoke_context);
+
+        let log_collector = Rc::try_unwrap(log_collector).ok().unwrap();
+        let logs: Vec<String> = log_collector.into();
+        assert_eq!(logs, vec!["first".to_string(), "second".to_string()]);
+    }
+
+    #[test]
+    fn test_process_message_program_log() {
+        fn mock_process_instruction(
+            _program_id: &Pubkey,
+            _keyed_accounts: &mut [KeyedAccount],
+            _data: &[u8],
+        ) -> Result<(), InstructionError> {
+            instruction_processor_utils::program_log("processed");
+            Ok(())
+        }
+
+        let mock_program_id = Pubkey::new(&[2u8; 32]);
+        let mut message_processor = MessageProcessor::default();
+        message_processor.add_instruction_processor(mock_program_id, mock_process_instruction);
+        let mut loaders = vec![vec![(
+            mock_program_id,
+            create_loadable_account("mock_program"),
+        )]];
+        let mut accounts = vec![Account::new(100, 1, &mock_program_id)];
+        let message = Message::new(vec![Instruction::new(
+            mock_program_id,
+            &(),
+            vec![AccountMeta::new(Pubkey::new_rand(), true)],
+        )]);
+
+        // Native programs log into the transaction's log collector
+        let log_collector = Rc::new(LogCollector::default());
+        assert_eq!(
+            message_processor.process_message(
+                &message,
+                &mut loaders,
+                &mut accounts,
+                Some(log_collector.clone()),
+                &ComputeBudget::default(),
+                &mut 0,
+                None,
+            ),
+            Ok(())
+        );
+        let log_collector = Rc::try_unwrap(log_collector).ok().unwrap();
+        let logs: Vec<String> = log_collector.into();
+        assert_eq!(logs, vec!["Program log: processed".to_string()]);
     }
 
     #[test]
@@ -7,7 +7,7 @@ use libloading::os::windows::*;
 use log::*;
 use solana_sdk::account::KeyedAccount;
 use solana_sdk::instruction::InstructionError;
-use solana_sdk::instruction_processor_utils;
+use solana_sdk::instruction_processor_utils::{self, InvokeContext};
 use solana_sdk::pubkey::Pubkey;
 use std::env;
 use std::path::PathBuf;
@@ -69,13 +69,14 @@ pub fn invoke_entrypoint(
     keyed_accounts: &mut [KeyedAccount],
     ix_data: &[u8],
     symbol_cache: &SymbolCache,
+    invoke_context: &mut dyn InvokeContext,
 ) -> Result<(), InstructionError> {
     // dispatch it
     let (names, params) = keyed_accounts.split_at_mut(1);
     let name_vec = &names[0].account.data;
     if let Some(entrypoint) = symbol_cache.read().unwrap().get(name_vec) {
         unsafe {
-            return entrypoint(program_id, params, ix_data);
+            return entrypoint(program_id, params, ix_data, invoke_context);
         }
     }
     let name = match str::from_utf8(name_vec) {
@@ -101,7 +102,7 @@ pub fn invoke_entrypoint(
                         return Err(InstructionError::GenericError);
                     }
                 };
-            let ret = entrypoint(program_id, params, ix_data);
+            let ret = entrypoint(program_id, params, ix_data, invoke_context);
             symbol_cache
                 .write()
                 .unwrap()
@@ -8,6 +8,8 @@ pub struct TransactionBatch<'a, 'b> {
     transactions: &'b [Transaction],
     iteration_order: Option<Vec<usize>>,
     pub(crate) needs_unlock: bool,
+    // Results of simulated batches are never committed
+    pub(crate) is_simulation: bool,
 }
 
 impl<'a, 'b> TransactionBatch<'a, 'b> {
@@ -27,6 +29,7 @@ impl<'a, 'b> TransactionBatch<'a, 'b> {
             transactions,
             iteration_order,
             needs_unlock: true,
+            is_simulation: false,
         }
     }
 
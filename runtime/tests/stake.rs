@@ -1,17 +1,20 @@
 use assert_matches::assert_matches;
 use solana_runtime::{
-    bank::Bank,
+    bank::{Bank, RewardInfo},
     bank_client::BankClient,
     genesis_utils::{create_genesis_config_with_leader, GenesisConfigInfo},
 };
 use solana_sdk::{
     account_utils::State,
     client::SyncClient,
+    clock::MAX_RECENT_BLOCKHASHES,
     message::Message,
     pubkey::Pubkey,
+    reward_type::RewardType,
     signature::{Keypair, KeypairUtil},
     system_instruction::create_address_with_seed,
     sysvar::{self, rewards::Rewards, stake_history::StakeHistory, Sysvar},
+    transaction::Transaction,
 };
 use solana_stake_program::{
     stake_instruction::{self},
@@ -208,9 +211,9 @@ fn test_stake_account_lifetime() {
     assert_matches!(Rewards::from_account(&rewards_account), Some(_));
 
     let pre_staked = get_staked(&bank, &stake_pubkey);
+    let pre_lamports = bank.get_balance(&stake_pubkey);
 
-    // Redeem the credit
-    let bank_client = BankClient::new_shared(&bank);
+    // Redeem the credit, recording the rewards it credits
     let message = Message::new_with_payer(
         vec![stake_instruction::redeem_vote_credits(
             &stake_pubkey,
@@ -218,7 +221,22 @@ fn test_stake_account_lifetime() {
         )],
         Some(&mint_pubkey),
     );
-    assert_matches!(bank_client.send_message(&[&mint_keypair], message), Ok(_));
+    let txs = [Transaction::new(
+        &[&mint_keypair],
+        message,
+        bank.last_blockhash(),
+    )];
+    let batch = bank.prepare_batch(&txs, None);
+    let (results, ..) = bank.load_execute_and_commit_transactions(
+        &batch,
+        MAX_RECENT_BLOCKHASHES,
+        false,
+        false,
+        true,
+    );
+    assert_eq!(results.fee_collection_results, vec![Ok(())]);
+    drop(batch);
+    let bank_client = BankClient::new_shared(&bank);
 
     // Test that balance increased, and that the balance got staked
     let staked = get_staked(&bank, &stake_pubkey);
@@ -226,6 +244,16 @@ fn test_stake_account_lifetime() {
     assert!(staked > pre_staked);
     assert!(lamports > 1_000_000);
 
+    // Test that the redeemed reward was recorded
+    assert!(bank.rewards.read().unwrap().contains(&(
+        stake_pubkey,
+        RewardInfo {
+            reward_type: RewardType::Staking,
+            lamports: (lamports - pre_lamports) as i64,
+            post_balance: lamports,
+        }
+    )));
+
     // split the stake
     let split_stake_keypair = Keypair::new();
     let split_stake_pubkey = split_stake_keypair.pubkey();
@@ -95,6 +95,70 @@ typedef struct {
   uint8_t x[SIZE_PUBKEY];
 } SolPubkey;
 
+/**
+ * Helper function that prints the base58 representation of a public key
+ */
+void sol_log_pubkey(const SolPubkey *);
+
+/**
+ * Byte array pointer and string
+ */
+typedef struct {
+  const uint8_t *addr; /** bytes */
+  uint64_t len; /** number of bytes*/
+} SolBytes;
+
+/**
+ * Length of a sha256 hash result
+ */
+#define SHA256_RESULT_LENGTH 32
+
+/**
+ * Sha256
+ *
+ * @param bytes Array of byte arrays
+ * @param bytes_len Number of byte arrays
+ * @param result 32 byte array to hold the result
+ */
+uint64_t sol_sha256(const SolBytes *bytes, int bytes_len, uint8_t *result);
+
+/**
+ * Copies memory in the runtime, at the cost of a single helper call
+ *
+ * @param dst Destination
+ * @param src Source, must not overlap with the destination
+ * @param n Number of bytes to copy
+ */
+void sol_memcpy_(void *dst, const void *src, uint64_t n);
+
+/**
+ * Copies possibly overlapping memory in the runtime
+ *
+ * @param dst Destination
+ * @param src Source
+ * @param n Number of bytes to copy
+ */
+void sol_memmove_(void *dst, const void *src, uint64_t n);
+
+/**
+ * Fills memory with a byte value in the runtime
+ *
+ * @param s Memory to fill
+ * @param c Byte value
+ * @param n Number of bytes to fill
+ */
+void sol_memset_(void *s, uint8_t c, uint64_t n);
+
+/**
+ * Compares memory in the runtime
+ *
+ * @param s1 First memory to compare
+ * @param s2 Second memory to compare
+ * @param n Number of bytes to compare
+ * @param result Set to the difference of the first pair of bytes that differ, or 0
+ */
+void sol_memcmp_(const void *s1, const void *s2, uint64_t n, int32_t *result);
+
 /**
  * Compares two public keys
  *
@@ -340,6 +404,127 @@ void sol_log_(const char *s, uint64_t len) {
 void sol_log_64(uint64_t arg1, uint64_t arg2, uint64_t arg3, uint64_t arg4, uint64_t arg5) {
   printf("sol_log_64: %llu, %llu, %llu, %llu, %llu\n", arg1, arg2, arg3, arg4, arg5);
 }
+void sol_log_pubkey(const SolPubkey *pubkey) {
+  printf("sol_log_pubkey: ");
+  for (int i = 0; i < SIZE_PUBKEY; i++) {
+    printf("%u ", pubkey->x[i]);
+  }
+  printf("\n");
+}
+
+/**
+ * Runtime helpers when building tests
+ */
+static const uint32_t sol_sha256_k[64] = {
+  0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+  0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+  0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+  0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+  0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+  0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+  0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+  0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+};
+#define SOL_SHA256_ROTR(x, n) (((x) >> (n)) | ((x) << (32 - (n))))
+static void sol_sha256_block(uint32_t *state, const uint8_t *block) {
+  uint32_t w[64];
+  for (int i = 0; i < 16; i++) {
+    w[i] = ((uint32_t)block[4 * i] << 24) | ((uint32_t)block[4 * i + 1] << 16) |
+           ((uint32_t)block[4 * i + 2] << 8) | (uint32_t)block[4 * i + 3];
+  }
+  for (int i = 16; i < 64; i++) {
+    uint32_t s0 = SOL_SHA256_ROTR(w[i - 15], 7) ^ SOL_SHA256_ROTR(w[i - 15], 18) ^ (w[i - 15] >> 3);
+    uint32_t s1 = SOL_SHA256_ROTR(w[i - 2], 17) ^ SOL_SHA256_ROTR(w[i - 2], 19) ^ (w[i - 2] >> 10);
+    w[i] = w[i - 16] + s0 + w[i - 7] + s1;
+  }
+  uint32_t v[8];
+  for (int i = 0; i < 8; i++) {
+    v[i] = state[i];
+  }
+  for (int i = 0; i < 64; i++) {
+    uint32_t s1 = SOL_SHA256_ROTR(v[4], 6) ^ SOL_SHA256_ROTR(v[4], 11) ^ SOL_SHA256_ROTR(v[4], 25);
+    uint32_t ch = (v[4] & v[5]) ^ (~v[4] & v[6]);
+    uint32_t t1 = v[7] + s1 + ch + sol_sha256_k[i] + w[i];
+    uint32_t s0 = SOL_SHA256_ROTR(v[0], 2) ^ SOL_SHA256_ROTR(v[0], 13) ^ SOL_SHA256_ROTR(v[0], 22);
+    uint32_t maj = (v[0] & v[1]) ^ (v[0] & v[2]) ^ (v[1] & v[2]);
+    for (int j = 7; j > 0; j--) {
+      v[j] = v[j - 1];
+    }
+    v[4] += t1;
+    v[0] = t1 + s0 + maj;
+  }
+  for (int i = 0; i < 8; i++) {
+    state[i] += v[i];
+  }
+}
+uint64_t sol_sha256(const SolBytes *bytes, int bytes_len, uint8_t *result) {
+  uint32_t state[8] = {
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+  };
+  uint8_t block[64];
+  uint64_t block_len = 0;
+  uint64_t total_len = 0;
+  for (int i = 0; i < bytes_len; i++) {
+    for (uint64_t j = 0; j < bytes[i].len; j++) {
+      block[block_len++] = bytes[i].addr[j];
+      if (block_len == sizeof(block)) {
+        sol_sha256_block(state, block);
+        block_len = 0;
+      }
+    }
+    total_len += bytes[i].len;
+  }
+  block[block_len++] = 0x80;
+  if (block_len > sizeof(block) - 8) {
+    while (block_len < sizeof(block)) {
+      block[block_len++] = 0;
+    }
+    sol_sha256_block(state, block);
+    block_len = 0;
+  }
+  while (block_len < sizeof(block) - 8) {
+    block[block_len++] = 0;
+  }
+  for (int i = 7; i >= 0; i--) {
+    block[block_len++] = (uint8_t)((total_len * 8) >> (8 * i));
+  }
+  sol_sha256_block(state, block);
+  for (int i = 0; i < 8; i++) {
+    for (int j = 0; j < 4; j++) {
+      result[4 * i + j] = (uint8_t)(state[i] >> (24 - 8 * j));
+    }
+  }
+  return 0;
+}
+void sol_memcpy_(void *dst, const void *src, uint64_t n) {
+  sol_memcpy(dst, src, n);
+}
+void sol_memmove_(void *dst, const void *src, uint64_t n) {
+  uint8_t *d = (uint8_t *)dst;
+  const uint8_t *s = (const uint8_t *)src;
+  if (d < s) {
+    for (uint64_t i = 0; i < n; i++) {
+      d[i] = s[i];
+    }
+  } else {
+    for (uint64_t i = n; i > 0; i--) {
+      d[i - 1] = s[i - 1];
+    }
+  }
+}
+void sol_memset_(void *s, uint8_t c, uint64_t n) {
+  sol_memset(s, c, n);
+}
+void sol_memcmp_(const void *s1, const void *s2, uint64_t n, int32_t *result) {
+  *result = 0;
+  for (uint64_t i = 0; i < n; i++) {
+    int32_t diff = (int32_t)((const uint8_t *)s1)[i] - (int32_t)((const uint8_t *)s2)[i];
+    if (diff) {
+      *result = diff;
+      return;
+    }
+  }
+}
 #endif
 
 #ifdef __cplusplus
@@ -0,0 +1,232 @@
+//! @brief An upgradeable BPF loader.  Program data is staged in a Buffer account, then deployed
+//! to a ProgramData account that the Program account refers to, and that the program's upgrade
+//! authority may replace later
+//!
+//! Instructions to a deployed program list only the Program account.  The runtime loads the
+//! ProgramData account it refers to, which the program does not see
+
+use crate::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    loader_upgradeable_instruction::UpgradeableLoaderInstruction,
+    pubkey::Pubkey,
+    system_instruction, system_program, sysvar,
+};
+
+crate::declare_id!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// State of an account owned by the upgradeable loader, stored at the start of its data
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum UpgradeableLoaderState {
+    /// Account is not initialized
+    Uninitialized,
+    /// A Buffer account, followed by the program data staged in it
+    Buffer {
+        /// Address allowed to write to the Buffer, and to deploy or upgrade a program from it
+        authority_address: Pubkey,
+    },
+    /// A Program account
+    Program {
+        /// Address of the ProgramData account
+        programdata_address: Pubkey,
+    },
+    /// A ProgramData account, followed by the program data
+    ProgramData {
+        /// Address allowed to upgrade the program, `None` if it is immutable
+        upgrade_authority_address: Option<Pubkey>,
+    },
+}
+impl UpgradeableLoaderState {
+    /// Length of a Buffer account holding `program_len` bytes of program data
+    pub fn buffer_len(program_len: usize) -> Result<usize, InstructionError> {
+        Ok(Self::buffer_data_offset()? + program_len)
+    }
+    /// Offset of the program data in a Buffer account
+    pub fn buffer_data_offset() -> Result<usize, InstructionError> {
+        Self::serialized_size(&Self::Buffer {
+            authority_address: Pubkey::default(),
+        })
+    }
+    /// Length of a Program account
+    pub fn program_len() -> Result<usize, InstructionError> {
+        Self::serialized_size(&Self::Program {
+            programdata_address: Pubkey::default(),
+        })
+    }
+    /// Length of a ProgramData account holding up to `program_len` bytes of program data
+    pub fn programdata_len(program_len: usize) -> Result<usize, InstructionError> {
+        Ok(Self::programdata_data_offset()? + program_len)
+    }
+    /// Offset of the program data in a ProgramData account, whatever its upgrade authority
+    pub fn programdata_data_offset() -> Result<usize, InstructionError> {
+        Self::serialized_size(&Self::ProgramData {
+            upgrade_authority_address: Some(Pubkey::default()),
+        })
+    }
+
+    fn serialized_size(state: &Self) -> Result<usize, InstructionError> {
+        bincode::serialized_size(state)
+            .map(|size| size as usize)
+            .map_err(|_| InstructionError::InvalidInstructionData)
+    }
+}
+
+/// Address of the ProgramData account of the program at `program_address`
+#[cfg(not(feature = "program"))]
+pub fn get_program_data_address(program_address: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_address.as_ref()], &id()).0
+}
+
+/// Instructions to create a Buffer account for `program_len` bytes of program data, and
+/// initialize it with `authority_address` as its authority
+pub fn create_buffer(
+    payer_address: &Pubkey,
+    buffer_address: &Pubkey,
+    authority_address: &Pubkey,
+    lamports: u64,
+    program_len: usize,
+) -> Result<Vec<Instruction>, InstructionError> {
+    Ok(vec![
+        system_instruction::create_account(
+            payer_address,
+            buffer_address,
+            lamports,
+            UpgradeableLoaderState::buffer_len(program_len)? as u64,
+            &id(),
+        ),
+        Instruction::new(
+            id(),
+            &UpgradeableLoaderInstruction::InitializeBuffer,
+            vec![
+                AccountMeta::new(*buffer_address, false),
+                AccountMeta::new_readonly(*authority_address, false),
+            ],
+        ),
+    ])
+}
+
+/// Instruction to write `bytes` at `offset` of the program data staged in a Buffer account
+pub fn write(
+    buffer_address: &Pubkey,
+    authority_address: &Pubkey,
+    offset: u32,
+    bytes: Vec<u8>,
+) -> Instruction {
+    Instruction::new(
+        id(),
+        &UpgradeableLoaderInstruction::Write { offset, bytes },
+        vec![
+            AccountMeta::new(*buffer_address, false),
+            AccountMeta::new_readonly(*authority_address, true),
+        ],
+    )
+}
+
+/// Instructions to create the Program account and deploy the program staged in a Buffer
+/// account to it
+#[cfg(not(feature = "program"))]
+pub fn deploy_with_max_program_len(
+    payer_address: &Pubkey,
+    program_address: &Pubkey,
+    buffer_address: &Pubkey,
+    buffer_authority_address: &Pubkey,
+    upgrade_authority_address: Option<&Pubkey>,
+    program_lamports: u64,
+    max_data_len: usize,
+) -> Result<Vec<Instruction>, InstructionError> {
+    let mut account_metas = vec![
+        AccountMeta::new(*payer_address, true),
+        AccountMeta::new(get_program_data_address(program_address), false),
+        AccountMeta::new(*program_address, false),
+        AccountMeta::new(*buffer_address, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(*buffer_authority_address, true),
+    ];
+    if let Some(upgrade_authority_address) = upgrade_authority_address {
+        account_metas.push(AccountMeta::new_readonly(*upgrade_authority_address, false));
+    }
+    Ok(vec![
+        system_instruction::create_account(
+            payer_address,
+            program_address,
+            program_lamports,
+            UpgradeableLoaderState::program_len()? as u64,
+            &id(),
+        ),
+        Instruction::new(
+            id(),
+            &UpgradeableLoaderInstruction::DeployWithMaxDataLen { max_data_len },
+            account_metas,
+        ),
+    ])
+}
+
+/// Instruction to replace a program's data with the data staged in a Buffer account.  The
+/// upgrade authority must also be the Buffer's authority
+#[cfg(not(feature = "program"))]
+pub fn upgrade(
+    program_address: &Pubkey,
+    buffer_address: &Pubkey,
+    authority_address: &Pubkey,
+    spill_address: &Pubkey,
+) -> Instruction {
+    Instruction::new(
+        id(),
+        &UpgradeableLoaderInstruction::Upgrade,
+        vec![
+            AccountMeta::new(get_program_data_address(program_address), false),
+            AccountMeta::new_readonly(*program_address, false),
+            AccountMeta::new(*buffer_address, false),
+            AccountMeta::new(*spill_address, false),
+            AccountMeta::new_readonly(*authority_address, true),
+        ],
+    )
+}
+
+/// Instruction to change a program's upgrade authority, or revoke it if `new_authority_address`
+/// is `None`
+#[cfg(not(feature = "program"))]
+pub fn set_upgrade_authority(
+    program_address: &Pubkey,
+    current_authority_address: &Pubkey,
+    new_authority_address: Option<&Pubkey>,
+) -> Instruction {
+    let mut account_metas = vec![
+        AccountMeta::new(get_program_data_address(program_address), false),
+        AccountMeta::new_readonly(*current_authority_address, true),
+    ];
+    if let Some(new_authority_address) = new_authority_address {
+        account_metas.push(AccountMeta::new_readonly(*new_authority_address, false));
+    }
+    Instruction::new(
+        id(),
+        &UpgradeableLoaderInstruction::SetAuthority,
+        account_metas,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_lengths() {
+        assert_eq!(UpgradeableLoaderState::buffer_data_offset().unwrap(), 36);
+        assert_eq!(UpgradeableLoaderState::buffer_len(10).unwrap(), 46);
+        assert_eq!(UpgradeableLoaderState::program_len().unwrap(), 36);
+        assert_eq!(
+            UpgradeableLoaderState::programdata_data_offset().unwrap(),
+            37
+        );
+        assert_eq!(UpgradeableLoaderState::programdata_len(10).unwrap(), 47);
+
+        // The program data offset does not depend on the upgrade authority
+        let immutable = UpgradeableLoaderState::ProgramData {
+            upgrade_authority_address: None,
+        };
+        assert!(
+            bincode::serialized_size(&immutable).unwrap() as usize
+                <= UpgradeableLoaderState::programdata_data_offset().unwrap()
+        );
+    }
+}
@@ -79,12 +79,31 @@ impl Hash {
 }
 
 /// Return a Sha256 hash for the given data.
+#[cfg(not(feature = "program"))]
 pub fn hashv(vals: &[&[u8]]) -> Hash {
     let mut hasher = Hasher::default();
     hasher.hashv(vals);
     hasher.result()
 }
 
+/// Return a Sha256 hash for the given data.
+#[cfg(feature = "program")]
+pub fn hashv(vals: &[&[u8]]) -> Hash {
+    let mut hash_result = [0; HASH_BYTES];
+    unsafe {
+        sol_sha256(
+            vals as *const _ as *const u8,
+            vals.len() as u64,
+            &mut hash_result as *mut _ as *mut u8,
+        );
+    };
+    Hash::new(&hash_result)
+}
+#[cfg(feature = "program")]
+extern "C" {
+    fn sol_sha256(vals: *const u8, val_len: u64, hash_result: *mut u8) -> u64;
+}
+
 /// Return a Sha256 hash for the given data.
 pub fn hash(val: &[u8]) -> Hash {
     hashv(&[val])
@@ -83,6 +83,31 @@ pub enum InstructionError {
     /// NOTE: u64 requires special serialization to avoid the loss of precision in JS clients and
     /// so is not used for now.
     CustomError(u32),
+
+    /// An account required by an invoked instruction was not passed to the invoking program
+    MissingAccount,
+
+    /// An invoked instruction asked for a signer or writable account that the invoking program
+    /// was not granted
+    PrivilegeEscalation,
+
+    /// Cross-program invocations were nested deeper than `MAX_INVOKE_DEPTH`
+    CallDepth,
+
+    /// A program invoked itself, or a program that is already on the call stack
+    ReentrancyNotAllowed,
+
+    /// The seeds a program presented to sign for a program address were invalid
+    InvalidSeeds,
+
+    /// The instruction consumed more compute units than its budget allows
+    ComputationalBudgetExceeded,
+
+    /// The program can no longer be upgraded
+    Immutable,
+
+    /// The upgrade authority of a program did not match the one given
+    IncorrectAuthority,
 }
 
 impl InstructionError {
@@ -91,7 +116,7 @@ impl InstructionError {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Instruction {
     /// Pubkey of the instruction processor that executes this instruction
     pub program_id: Pubkey,
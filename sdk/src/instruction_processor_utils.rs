@@ -1,14 +1,223 @@
-use crate::{account::KeyedAccount, instruction::InstructionError, pubkey::Pubkey};
+use crate::{
+    account::KeyedAccount,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+};
 use num_traits::{FromPrimitive, ToPrimitive};
+use std::{cell::RefCell, rc::Rc};
 
 // Native program ENTRYPOINT prototype
 pub type Entrypoint = unsafe extern "C" fn(
     program_id: &Pubkey,
     keyed_accounts: &mut [KeyedAccount],
     data: &[u8],
+    invoke_context: &mut dyn InvokeContext,
 ) -> Result<(), InstructionError>;
 
-/// Convenience macro to declare a native program
+/// Maximum number of programs on the call stack, including the program the transaction invoked
+pub const MAX_INVOKE_DEPTH: usize = 4;
+
+/// Compute units an instruction may consume, and what the operations programs perform cost
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ComputeBudget {
+    /// Units an instruction may consume, including the instructions it invokes.  Each BPF
+    /// instruction executed costs one unit
+    pub max_units: u64,
+    /// Units charged to log a message
+    pub log_units: u64,
+    /// Units charged to log 64 bit values
+    pub log_64_units: u64,
+    /// Units charged to log a public key
+    pub log_pubkey_units: u64,
+    /// Units charged to hash a set of byte slices
+    pub sha256_units: u64,
+    /// Units charged for a memory copy, move, set or compare
+    pub mem_op_units: u64,
+    /// Units charged to invoke another program
+    pub invoke_units: u64,
+    /// Units charged to process an instruction of a native program
+    pub native_instruction_units: u64,
+}
+impl Default for ComputeBudget {
+    fn default() -> Self {
+        Self {
+            max_units: 200_000,
+            log_units: 100,
+            log_64_units: 100,
+            log_pubkey_units: 100,
+            sha256_units: 85,
+            mem_op_units: 10,
+            invoke_units: 1_000,
+            native_instruction_units: 150,
+        }
+    }
+}
+
+/// Compute units left to an executing instruction
+pub trait ComputeMeter {
+    /// Consume `amount` units, failing and leaving none if fewer remain
+    fn consume(&mut self, amount: u64) -> Result<(), InstructionError>;
+    /// Get the number of units remaining
+    fn get_remaining(&self) -> u64;
+}
+
+/// Invocation context passed to native programs
+pub trait InvokeContext {
+    /// Get this invocation's logger
+    fn get_logger(&self) -> Rc<RefCell<dyn Logger>>;
+    /// Get the costs and limit that apply to this invocation
+    fn get_compute_budget(&self) -> &ComputeBudget;
+    /// Get the meter of the units left to the instruction, shared with the instructions it
+    /// invokes
+    fn get_compute_meter(&self) -> Rc<RefCell<dyn ComputeMeter>>;
+    /// Process `instruction` on behalf of the currently executing program.
+    ///
+    /// `keyed_accounts` are the accounts the caller was given. Every account the instruction
+    /// references must be among them, and may only be a signer or writable if it is for the
+    /// caller. The caller's changes made so far, and then the callee's, are verified before this
+    /// returns
+    fn invoke(
+        &mut self,
+        instruction: &Instruction,
+        keyed_accounts: &mut [KeyedAccount],
+    ) -> Result<(), InstructionError> {
+        self.invoke_signed(instruction, keyed_accounts, &[])
+    }
+    /// Same as `invoke`, except that the program addresses `Pubkey::create_program_address`
+    /// derives from each of `signers_seeds` and the caller's program id are also signers
+    fn invoke_signed(
+        &mut self,
+        instruction: &Instruction,
+        keyed_accounts: &mut [KeyedAccount],
+        signers_seeds: &[&[&[u8]]],
+    ) -> Result<(), InstructionError>;
+}
+
+/// Log messages
+pub trait Logger {
+    fn log_enabled(&self) -> bool;
+    /// Log a message
+    fn log(&mut self, message: &str);
+}
+
+thread_local! {
+    // Logger of the instruction a native program is processing on this thread
+    static PROGRAM_LOGGER: RefCell<Option<Rc<RefCell<dyn Logger>>>> = RefCell::new(None);
+}
+
+// Restores the logger of the invoking instruction once an invoked one returns
+struct ProgramLoggerGuard(Option<Rc<RefCell<dyn Logger>>>);
+
+impl Drop for ProgramLoggerGuard {
+    fn drop(&mut self) {
+        let logger = self.0.take();
+        PROGRAM_LOGGER.with(|program_logger| *program_logger.borrow_mut() = logger);
+    }
+}
+
+/// Run `f`, a native program's entrypoint, with `program_log` writing to `logger`
+pub fn with_program_logger<R, F>(logger: Rc<RefCell<dyn Logger>>, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let previous = PROGRAM_LOGGER.with(|program_logger| program_logger.replace(Some(logger)));
+    let _guard = ProgramLoggerGuard(previous);
+    f()
+}
+
+/// Log a message to the program log of the transaction a native program is processing.  The
+/// entrypoints `declare_program!` generates collect these, without one the message is dropped
+pub fn program_log(message: &str) {
+    PROGRAM_LOGGER.with(|program_logger| {
+        if let Some(logger) = &*program_logger.borrow() {
+            let mut logger = logger.borrow_mut();
+            if logger.log_enabled() {
+                logger.log(&format!("Program log: {}", message));
+            }
+        }
+    });
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct MockLogger {
+    pub log: Rc<RefCell<Vec<String>>>,
+}
+impl Logger for MockLogger {
+    fn log_enabled(&self) -> bool {
+        true
+    }
+    fn log(&mut self, message: &str) {
+        self.log.borrow_mut().push(message.to_string());
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MockComputeMeter {
+    pub remaining: u64,
+}
+impl ComputeMeter for MockComputeMeter {
+    fn consume(&mut self, amount: u64) -> Result<(), InstructionError> {
+        let exceeded = self.remaining < amount;
+        self.remaining = self.remaining.saturating_sub(amount);
+        if exceeded {
+            return Err(InstructionError::ComputationalBudgetExceeded);
+        }
+        Ok(())
+    }
+    fn get_remaining(&self) -> u64 {
+        self.remaining
+    }
+}
+
+#[derive(Debug)]
+pub struct MockInvokeContext {
+    pub logger: MockLogger,
+    pub compute_budget: ComputeBudget,
+    pub compute_meter: Rc<RefCell<MockComputeMeter>>,
+    /// Instructions the program invoked, in order, with the seeds of their program address
+    /// signers
+    pub invoked: Vec<(Instruction, Vec<Vec<Vec<u8>>>)>,
+}
+impl Default for MockInvokeContext {
+    fn default() -> Self {
+        let compute_budget = ComputeBudget::default();
+        MockInvokeContext {
+            logger: MockLogger::default(),
+            compute_budget,
+            compute_meter: Rc::new(RefCell::new(MockComputeMeter {
+                remaining: compute_budget.max_units,
+            })),
+            invoked: vec![],
+        }
+    }
+}
+impl InvokeContext for MockInvokeContext {
+    fn get_logger(&self) -> Rc<RefCell<dyn Logger>> {
+        Rc::new(RefCell::new(self.logger.clone()))
+    }
+    fn get_compute_budget(&self) -> &ComputeBudget {
+        &self.compute_budget
+    }
+    fn get_compute_meter(&self) -> Rc<RefCell<dyn ComputeMeter>> {
+        self.compute_meter.clone()
+    }
+    fn invoke_signed(
+        &mut self,
+        instruction: &Instruction,
+        _keyed_accounts: &mut [KeyedAccount],
+        signers_seeds: &[&[&[u8]]],
+    ) -> Result<(), InstructionError> {
+        let signers_seeds = signers_seeds
+            .iter()
+            .map(|seeds| seeds.iter().map(|seed| seed.to_vec()).collect())
+            .collect();
+        self.invoked.push((instruction.clone(), signers_seeds));
+        Ok(())
+    }
+}
+
+/// Convenience macro to declare a native program.  While the entrypoint runs, messages it
+/// passes to `program_log` are collected in the transaction's program log
 ///
 /// bs58_string: bs58 string representation the program's id
 /// name: Name of the program, must match the library name in Cargo.toml
@@ -25,6 +234,7 @@ pub type Entrypoint = unsafe extern "C" fn(
 /// use solana_sdk::instruction::InstructionError;
 /// use solana_sdk::pubkey::Pubkey;
 /// use solana_sdk::declare_program;
+/// use solana_sdk::instruction_processor_utils::program_log;
 ///
 /// fn my_process_instruction(
 ///     program_id: &Pubkey,
@@ -32,6 +242,7 @@ pub type Entrypoint = unsafe extern "C" fn(
 ///     data: &[u8],
 /// ) -> Result<(), InstructionError> {
 ///   // Process an instruction
+///   program_log("processed");
 ///   Ok(())
 /// }
 ///
@@ -93,8 +304,77 @@ macro_rules! declare_program(
             program_id: &$crate::pubkey::Pubkey,
             keyed_accounts: &mut [$crate::account::KeyedAccount],
             data: &[u8],
+            invoke_context: &mut dyn $crate::instruction_processor_utils::InvokeContext,
+        ) -> Result<(), $crate::instruction::InstructionError> {
+            $crate::instruction_processor_utils::with_program_logger(
+                invoke_context.get_logger(),
+                || $entrypoint(program_id, keyed_accounts, data),
+            )
+        }
+    )
+);
+
+/// Same as declare_program!, except that the entrypoint is also passed the
+/// `InvokeContext`, giving loaders and other native programs access to the
+/// transaction's logger and the ability to invoke other programs
+///
+/// bs58_string: bs58 string representation the program's id
+/// name: Name of the program, must match the library name in Cargo.toml
+/// entrypoint: Program's entrypoint, must be of `type Entrypoint`
+///
+/// # Examples
+///
+/// ```
+/// # // wrapper is used so that the macro invocation occurs in the item position
+/// # // rather than in the statement position which isn't allowed.
+/// # mod item_wrapper {
+/// use solana_sdk::account::KeyedAccount;
+/// use solana_sdk::instruction::InstructionError;
+/// use solana_sdk::instruction_processor_utils::InvokeContext;
+/// use solana_sdk::pubkey::Pubkey;
+/// use solana_sdk::declare_loader;
+///
+/// fn my_process_instruction(
+///     program_id: &Pubkey,
+///     keyed_accounts: &mut [KeyedAccount],
+///     data: &[u8],
+///     invoke_context: &mut dyn InvokeContext,
+/// ) -> Result<(), InstructionError> {
+///   // Process an instruction
+///   invoke_context.get_logger().borrow_mut().log("processed");
+///   Ok(())
+/// }
+///
+/// declare_loader!(
+///     solana_sdk::system_program::ID,
+///     solana_my_loader,
+///     my_process_instruction
+/// );
+/// # }
+///
+/// # use item_wrapper::id;
+/// assert_eq!(id(), solana_sdk::system_program::ID);
+/// ```
+#[macro_export]
+macro_rules! declare_loader(
+    ($bs58_string:expr, $name:ident, $entrypoint:expr) => (
+        $crate::declare_id!($bs58_string);
+
+        #[macro_export]
+        macro_rules! $name {
+            () => {
+                (stringify!($name).to_string(), $crate::id())
+            };
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $name(
+            program_id: &$crate::pubkey::Pubkey,
+            keyed_accounts: &mut [$crate::account::KeyedAccount],
+            data: &[u8],
+            invoke_context: &mut dyn $crate::instruction_processor_utils::InvokeContext,
         ) -> Result<(), $crate::instruction::InstructionError> {
-            $entrypoint(program_id, keyed_accounts, data)
+            $entrypoint(program_id, keyed_accounts, data, invoke_context)
         }
     )
 );
@@ -142,6 +422,30 @@ mod tests {
     use super::*;
     use num_derive::FromPrimitive;
 
+    #[test]
+    fn test_program_log() {
+        // Nothing is collected outside of an entrypoint
+        program_log("dropped");
+
+        let outer_logger = MockLogger::default();
+        let inner_logger = MockLogger::default();
+        with_program_logger(Rc::new(RefCell::new(outer_logger.clone())), || {
+            program_log("outer");
+            // An invoked program logs to its own logger until it returns
+            with_program_logger(Rc::new(RefCell::new(inner_logger.clone())), || {
+                program_log("inner")
+            });
+            program_log("outer again");
+        });
+        program_log("dropped");
+
+        assert_eq!(
+            *outer_logger.log.borrow(),
+            vec!["Program log: outer", "Program log: outer again"]
+        );
+        assert_eq!(*inner_logger.log.borrow(), vec!["Program log: inner"]);
+    }
+
     #[test]
     fn test_decode_custom_error_to_enum() {
         #[derive(Debug, FromPrimitive, PartialEq)]
@@ -4,6 +4,7 @@ extern crate self as solana_sdk;
 pub mod account;
 pub mod account_utils;
 pub mod bpf_loader;
+pub mod bpf_loader_upgradeable;
 pub mod clock;
 pub mod epoch_schedule;
 pub mod fee_calculator;
@@ -12,6 +13,7 @@ pub mod inflation;
 pub mod instruction;
 pub mod instruction_processor_utils;
 pub mod loader_instruction;
+pub mod loader_upgradeable_instruction;
 pub mod message;
 pub mod move_loader;
 pub mod native_loader;
@@ -23,6 +25,7 @@ pub mod packet;
 pub mod poh_config;
 pub mod pubkey;
 pub mod rent;
+pub mod reward_type;
 pub mod rpc_port;
 pub mod short_vec;
 pub mod slot_hashes;
@@ -59,6 +62,8 @@ pub use solana_sdk_macro::declare_id;
 pub mod account_info;
 pub mod entrypoint;
 pub mod log;
+pub mod program;
+pub mod program_memory;
 
 // Modules not usable by on-chain programs
 #[cfg(not(feature = "program"))]
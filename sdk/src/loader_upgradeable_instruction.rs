@@ -0,0 +1,58 @@
+//! Instructions for the upgradeable BPF loader
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub enum UpgradeableLoaderInstruction {
+    /// Initialize a Buffer account to stage program data in
+    ///
+    /// * key[0] - the uninitialized account to initialize, owned by the loader
+    /// * key[1] - the Buffer's authority, which alone may write to it and deploy from it
+    InitializeBuffer,
+
+    /// Write program data into a Buffer account
+    ///
+    /// * key[0] - the Buffer account to write into
+    /// * key[1] - the Buffer's authority, signer
+    Write {
+        /// Offset from the start of the program data
+        offset: u32,
+        #[serde(with = "serde_bytes")]
+        bytes: Vec<u8>,
+    },
+
+    /// Deploy the program staged in a Buffer account.  The ProgramData account, whose address is
+    /// derived from the Program account's, is created to hold up to `max_data_len` bytes of
+    /// program data, which are copied out of the Buffer.  The Buffer's lamports go to the payer.
+    ///
+    /// Once deployed, the program may be upgraded by its upgrade authority, if it has one
+    ///
+    /// * key[0] - the payer of the ProgramData account, signer
+    /// * key[1] - the uninitialized ProgramData account
+    /// * key[2] - the uninitialized Program account, owned by the loader and rent exempt
+    /// * key[3] - the Buffer account holding the program data
+    /// * key[4] - rent sysvar account
+    /// * key[5] - system program account
+    /// * key[6] - the Buffer's authority, signer
+    /// * key[7] - the upgrade authority, optional.  Without one the program is immutable
+    DeployWithMaxDataLen {
+        /// Maximum length of the program's data, including upgrades
+        max_data_len: usize,
+    },
+
+    /// Replace a program's data with the data staged in a Buffer account.  The Buffer's
+    /// lamports go to the spill account
+    ///
+    /// * key[0] - the ProgramData account
+    /// * key[1] - the Program account
+    /// * key[2] - the Buffer account holding the new program data
+    /// * key[3] - the spill account
+    /// * key[4] - the upgrade authority, signer.  It must also be the Buffer's authority
+    Upgrade,
+
+    /// Change a program's upgrade authority, or revoke it to make the program immutable
+    ///
+    /// * key[0] - the ProgramData account
+    /// * key[1] - the current upgrade authority, signer
+    /// * key[2] - the new upgrade authority, optional.  Without one the program becomes
+    ///   immutable
+    SetAuthority,
+}
@@ -0,0 +1,47 @@
+//! @brief Solana Rust-based BPF program cross-program invocation
+
+#[cfg(feature = "program")]
+use crate::instruction::Instruction;
+
+/// Invoke another program's instruction
+///
+/// The invoked instruction may only reference accounts that were passed to the calling program,
+/// and only as signer or writable if they were for the caller.  Changes the callee makes to the
+/// accounts are visible through the caller's `AccountInfo`s once this returns.  If the invoked
+/// instruction fails, the calling program is aborted with the same error.
+///
+/// @param instruction - The instruction to process
+#[cfg(feature = "program")]
+pub fn invoke(instruction: &Instruction) {
+    invoke_signed(instruction, &[])
+}
+
+/// Invoke another program's instruction, signing for program addresses
+///
+/// Same as `invoke`, except that the program addresses derived from each set of seeds and the
+/// calling program's id are also treated as signers.
+///
+/// @param instruction - The instruction to process
+/// @param signers_seeds - The seeds of each program address to sign for
+#[cfg(feature = "program")]
+pub fn invoke_signed(instruction: &Instruction, signers_seeds: &[&[&[u8]]]) {
+    let instruction = bincode::serialize(instruction).unwrap();
+    let signers_seeds = bincode::serialize(signers_seeds).unwrap();
+    unsafe {
+        sol_invoke_(
+            instruction.as_ptr(),
+            instruction.len() as u64,
+            signers_seeds.as_ptr(),
+            signers_seeds.len() as u64,
+        );
+    }
+}
+#[cfg(feature = "program")]
+extern "C" {
+    fn sol_invoke_(
+        instruction_addr: *const u8,
+        instruction_len: u64,
+        signers_seeds_addr: *const u8,
+        signers_seeds_len: u64,
+    ) -> u64;
+}
@@ -0,0 +1,142 @@
+//! @brief Solana Rust-based BPF memory operations
+
+/// Memcpy
+///
+/// @param dst - Destination
+/// @param src - Source
+/// @param n - Number of bytes to copy
+///
+/// Panics if `n` exceeds the length of `dst` or `src`
+#[inline]
+pub fn sol_memcpy(dst: &mut [u8], src: &[u8], n: usize) {
+    #[cfg(feature = "program")]
+    {
+        extern "C" {
+            fn sol_memcpy_(dst: *mut u8, src: *const u8, n: u64);
+        }
+        assert!(
+            n <= dst.len(),
+            "sol_memcpy: n exceeds the destination's length"
+        );
+        assert!(n <= src.len(), "sol_memcpy: n exceeds the source's length");
+        unsafe {
+            sol_memcpy_(dst.as_mut_ptr(), src.as_ptr(), n as u64);
+        }
+    }
+    #[cfg(not(feature = "program"))]
+    dst[..n].copy_from_slice(&src[..n]);
+}
+
+/// Memmove
+///
+/// @param dst - Destination
+/// @param src - Source
+/// @param n - Number of bytes to copy
+///
+/// # Safety
+/// `dst` and `src` must each be valid for `n` bytes, they may overlap
+#[inline]
+pub unsafe fn sol_memmove(dst: *mut u8, src: *const u8, n: usize) {
+    #[cfg(feature = "program")]
+    {
+        extern "C" {
+            fn sol_memmove_(dst: *mut u8, src: *const u8, n: u64);
+        }
+        sol_memmove_(dst, src, n as u64);
+    }
+    #[cfg(not(feature = "program"))]
+    std::ptr::copy(src, dst, n);
+}
+
+/// Memcmp
+///
+/// @param s1 - Slice to be compared
+/// @param s2 - Slice to be compared
+/// @param n - Number of bytes to compare
+///
+/// Panics if `n` exceeds the length of `s1` or `s2`
+#[inline]
+pub fn sol_memcmp(s1: &[u8], s2: &[u8], n: usize) -> i32 {
+    #[cfg(feature = "program")]
+    {
+        extern "C" {
+            fn sol_memcmp_(s1: *const u8, s2: *const u8, n: u64, result: *mut i32);
+        }
+        assert!(
+            n <= s1.len(),
+            "sol_memcmp: n exceeds the first slice's length"
+        );
+        assert!(
+            n <= s2.len(),
+            "sol_memcmp: n exceeds the second slice's length"
+        );
+        let mut result = 0;
+        unsafe {
+            sol_memcmp_(s1.as_ptr(), s2.as_ptr(), n as u64, &mut result as *mut i32);
+        }
+        result
+    }
+    #[cfg(not(feature = "program"))]
+    s1[..n]
+        .iter()
+        .zip(&s2[..n])
+        .map(|(a, b)| i32::from(*a) - i32::from(*b))
+        .find(|difference| *difference != 0)
+        .unwrap_or(0)
+}
+
+/// Memset
+///
+/// @param s - Slice to be set
+/// @param c - Repeated byte to set
+/// @param n - Number of bytes to set
+///
+/// Panics if `n` exceeds the length of `s`
+#[inline]
+pub fn sol_memset(s: &mut [u8], c: u8, n: usize) {
+    #[cfg(feature = "program")]
+    {
+        extern "C" {
+            fn sol_memset_(s: *mut u8, c: u8, n: u64);
+        }
+        assert!(n <= s.len(), "sol_memset: n exceeds the slice's length");
+        unsafe {
+            sol_memset_(s.as_mut_ptr(), c, n as u64);
+        }
+    }
+    #[cfg(not(feature = "program"))]
+    for byte in s[..n].iter_mut() {
+        *byte = c;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_ops() {
+        let mut dst = [0u8; 4];
+        sol_memcpy(&mut dst, &[1, 2, 3, 4], 3);
+        assert_eq!(dst, [1, 2, 3, 0]);
+
+        let mut buf = [1u8, 2, 3, 4, 5];
+        unsafe { sol_memmove(buf.as_mut_ptr().add(1), buf.as_ptr(), 4) };
+        assert_eq!(buf, [1, 1, 2, 3, 4]);
+
+        sol_memset(&mut buf, 7, 2);
+        assert_eq!(buf, [7, 7, 2, 3, 4]);
+
+        assert_eq!(sol_memcmp(&[1, 2, 3], &[1, 2, 3], 3), 0);
+        assert_eq!(sol_memcmp(&[1, 2, 5], &[1, 2, 3], 3), 2);
+        assert_eq!(sol_memcmp(&[1, 2, 3], &[1, 4, 3], 3), -2);
+        assert_eq!(sol_memcmp(&[1, 2, 3], &[1, 2, 4], 2), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_memcpy_exceeds_len() {
+        let mut dst = [0u8; 2];
+        sol_memcpy(&mut dst, &[1, 2, 3], 3);
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(not(feature = "program"))]
+use crate::hash::hashv;
 use std::convert::TryFrom;
 use std::error;
 use std::fmt;
@@ -6,6 +8,12 @@ use std::str::FromStr;
 
 pub use bs58;
 
+/// Maximum length of each seed of a program address
+pub const MAX_SEED_LEN: usize = 32;
+
+/// Maximum number of seeds of a program address
+pub const MAX_SEEDS: usize = 16;
+
 #[repr(transparent)]
 #[derive(Serialize, Deserialize, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Pubkey([u8; 32]);
@@ -24,6 +32,22 @@ impl fmt::Display for ParsePubkeyError {
 
 impl error::Error for ParsePubkeyError {}
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PubkeyError {
+    /// A seed was longer than MAX_SEED_LEN, or there were more than MAX_SEEDS of them
+    MaxSeedLengthExceeded,
+    /// The seeds derived an address that is on the ed25519 curve
+    InvalidSeeds,
+}
+
+impl fmt::Display for PubkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PubkeyError: {:?}", self)
+    }
+}
+
+impl error::Error for PubkeyError {}
+
 impl FromStr for Pubkey {
     type Err = ParsePubkeyError;
 
@@ -56,11 +80,64 @@ impl Pubkey {
         Self::new(&rand::random::<[u8; 32]>())
     }
 
-    pub fn log(&self) {
-        use crate::log::sol_log_64;
-        for (i, k) in self.0.iter().enumerate() {
-            sol_log_64(0, 0, 0, i as u64, u64::from(*k));
+    /// Derive the address of an account that `program_id` controls.  The address is never a
+    /// valid ed25519 public key, so no private key can sign for it; instead the runtime treats
+    /// it as signed when `program_id` presents the same `seeds` while invoking another program.
+    ///
+    /// Fails with `InvalidSeeds` for the roughly half of all seeds that derive a point on the
+    /// curve; `find_program_address` searches for seeds that don't
+    #[cfg(not(feature = "program"))]
+    pub fn create_program_address(
+        seeds: &[&[u8]],
+        program_id: &Pubkey,
+    ) -> Result<Pubkey, PubkeyError> {
+        if seeds.len() > MAX_SEEDS || seeds.iter().any(|seed| seed.len() > MAX_SEED_LEN) {
+            return Err(PubkeyError::MaxSeedLengthExceeded);
+        }
+
+        let mut vals = seeds.to_vec();
+        vals.push(program_id.as_ref());
+        vals.push(b"ProgramDerivedAddress");
+        let hash = hashv(&vals);
+
+        if curve25519_dalek::edwards::CompressedEdwardsY::from_slice(hash.as_ref())
+            .decompress()
+            .is_some()
+        {
+            return Err(PubkeyError::InvalidSeeds);
         }
+        Ok(Pubkey::new(hash.as_ref()))
+    }
+
+    /// Find a program address for `seeds`, by appending the highest bump seed from 255 down
+    /// that derives a valid one.  Returns the address and the bump seed, which must be
+    /// presented along with `seeds` to sign for the address
+    #[cfg(not(feature = "program"))]
+    pub fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
+        let mut bump_seed = [std::u8::MAX];
+        for _ in 0..std::u8::MAX {
+            let mut seeds_with_bump = seeds.to_vec();
+            seeds_with_bump.push(&bump_seed);
+            if let Ok(address) = Self::create_program_address(&seeds_with_bump, program_id) {
+                return (address, bump_seed[0]);
+            }
+            bump_seed[0] -= 1;
+        }
+        panic!("Unable to find a viable program address bump seed");
+    }
+
+    /// Log the base58 representation of this key to the program log
+    #[cfg(feature = "program")]
+    pub fn log(&self) {
+        extern "C" {
+            fn sol_log_pubkey(pubkey_addr: *const u8);
+        };
+        unsafe { sol_log_pubkey(self.as_ref() as *const _ as *const u8) };
+    }
+
+    #[cfg(not(feature = "program"))]
+    pub fn log(&self) {
+        crate::log::sol_log(&self.to_string());
     }
     pub fn to_bytes(self) -> [u8; 32] {
         self.0
@@ -146,6 +223,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_create_program_address() {
+        let program_id = Pubkey::from_str("BPFLoader1111111111111111111111111111111111").unwrap();
+        let exceeded_seed = &[127; MAX_SEED_LEN + 1];
+        let max_seed = &[0; MAX_SEED_LEN];
+
+        assert_eq!(
+            Pubkey::create_program_address(&[exceeded_seed], &program_id),
+            Err(PubkeyError::MaxSeedLengthExceeded)
+        );
+        assert_eq!(
+            Pubkey::create_program_address(&[b"short_seed", exceeded_seed], &program_id),
+            Err(PubkeyError::MaxSeedLengthExceeded)
+        );
+        assert_eq!(
+            Pubkey::create_program_address(&[&[] as &[u8]; MAX_SEEDS + 1], &program_id),
+            Err(PubkeyError::MaxSeedLengthExceeded)
+        );
+        assert!(Pubkey::create_program_address(&[max_seed], &program_id).is_ok());
+
+        // Derivation is deterministic, and depends on the seeds and the program
+        assert_eq!(
+            Pubkey::create_program_address(&[b"Talking", b"Squirrels"], &program_id),
+            Pubkey::create_program_address(&[b"Talking", b"Squirrels"], &program_id),
+        );
+        assert_ne!(
+            Pubkey::create_program_address(&[b"Talking", b"Squirrels"], &program_id),
+            Pubkey::create_program_address(&[b"Talking"], &program_id),
+        );
+        assert_ne!(
+            Pubkey::create_program_address(&[b"Talking"], &program_id),
+            Pubkey::create_program_address(&[b"Talking"], &Pubkey::default()),
+        );
+    }
+
+    #[test]
+    fn test_find_program_address() {
+        for _ in 0..1_000 {
+            let program_id = Pubkey::new_rand();
+            let (address, bump_seed) =
+                Pubkey::find_program_address(&[b"Lil'", b"Bits"], &program_id);
+            assert_eq!(
+                address,
+                Pubkey::create_program_address(&[b"Lil'", b"Bits", &[bump_seed]], &program_id)
+                    .unwrap()
+            );
+            assert!(
+                curve25519_dalek::edwards::CompressedEdwardsY::from_slice(address.as_ref())
+                    .decompress()
+                    .is_none()
+            );
+        }
+    }
+
     #[test]
     fn test_read_write_pubkey() -> Result<(), Box<dyn error::Error>> {
         let filename = "test_pubkey.json";
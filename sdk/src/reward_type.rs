@@ -0,0 +1,28 @@
+//! The sources of the lamports a bank credits to accounts outside of transactions
+
+use std::fmt;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum RewardType {
+    /// Transaction fees paid to the slot leader
+    Fee,
+    /// Collected rent distributed to staked validators
+    Rent,
+    /// Inflation paid to a stake account
+    Staking,
+    /// Inflation commission paid to a vote account
+    Voting,
+}
+
+impl fmt::Display for RewardType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reward_type = match self {
+            RewardType::Fee => "fee",
+            RewardType::Rent => "rent",
+            RewardType::Staking => "staking",
+            RewardType::Voting => "voting",
+        };
+        write!(f, "{}", reward_type)
+    }
+}
@@ -1,11 +1,11 @@
 use bzip2::bufread::BzDecoder;
-use clap::{crate_description, crate_name, value_t, value_t_or_exit, App, Arg};
+use clap::{crate_description, crate_name, value_t, value_t_or_exit, values_t, App, Arg};
 use console::{style, Emoji};
 use indicatif::{ProgressBar, ProgressStyle};
 use log::*;
 use solana_clap_utils::{
-    input_parsers::pubkey_of,
-    input_validators::{is_keypair, is_pubkey_or_keypair},
+    input_parsers::{pubkey_of, values_of},
+    input_validators::{is_keypair, is_pubkey, is_pubkey_or_keypair},
     keypair::{
         self, keypair_input, KeypairWithSource, ASK_SEED_PHRASE_ARG,
         SKIP_SEED_PHRASE_VALIDATION_ARG,
@@ -17,6 +17,11 @@ use solana_core::{
     cluster_info::{ClusterInfo, Node, VALIDATOR_PORT_RANGE},
     contact_info::ContactInfo,
     gossip_service::GossipService,
+    rpc::{DEFAULT_HEALTH_CHECK_SLOT_DISTANCE, MAX_MULTIPLE_ACCOUNTS},
+    rpc_pubsub::{
+        MAX_ACTIVE_SUBSCRIPTIONS, MAX_SUBSCRIPTIONS_PER_CONNECTION, QUEUE_CAPACITY_ITEMS,
+    },
+    rpc_rate_limiter::RpcRateLimitConfig,
     validator::{Validator, ValidatorConfig},
 };
 use solana_ledger::bank_forks::SnapshotConfig;
@@ -30,7 +35,7 @@ use solana_sdk::{
 use std::{
     fs::{self, File},
     io::{self, Read},
-    net::{SocketAddr, TcpListener},
+    net::{IpAddr, SocketAddr, TcpListener},
     path::{Path, PathBuf},
     process::exit,
     str::FromStr,
@@ -56,6 +61,13 @@ fn port_range_validator(port_range: String) -> Result<(), String> {
     }
 }
 
+fn ip_addr_validator(ip_addr: String) -> Result<(), String> {
+    ip_addr
+        .parse::<IpAddr>()
+        .map(|_| ())
+        .map_err(|e| format!("{:?}", e))
+}
+
 fn hash_validator(hash: String) -> Result<(), String> {
     Hash::from_str(&hash)
         .map(|_| ())
@@ -316,6 +328,12 @@ fn download_ledger(
 pub fn main() {
     let default_dynamic_port_range =
         &format!("{}-{}", VALIDATOR_PORT_RANGE.0, VALIDATOR_PORT_RANGE.1);
+    let default_rpc_max_multiple_accounts = &MAX_MULTIPLE_ACCOUNTS.to_string();
+    let default_health_check_slot_distance = &DEFAULT_HEALTH_CHECK_SLOT_DISTANCE.to_string();
+    let default_rpc_pubsub_max_active_subscriptions = &MAX_ACTIVE_SUBSCRIPTIONS.to_string();
+    let default_rpc_pubsub_max_subscriptions_per_connection =
+        &MAX_SUBSCRIPTIONS_PER_CONNECTION.to_string();
+    let default_rpc_pubsub_queue_capacity_items = &QUEUE_CAPACITY_ITEMS.to_string();
 
     let matches = App::new(crate_name!()).about(crate_description!())
         .version(solana_clap_utils::version!())
@@ -454,6 +472,103 @@ pub fn main() {
                 .validator(solana_net_utils::is_host_port)
                 .help("Enable the JSON RPC 'requestAirdrop' API with this faucet address."),
         )
+        .arg(
+            Arg::with_name("rpc_max_multiple_accounts")
+                .long("rpc-max-multiple-accounts")
+                .value_name("MAX ACCOUNTS")
+                .takes_value(true)
+                .default_value(default_rpc_max_multiple_accounts)
+                .help("Override the default maximum accounts accepted by the JSON RPC 'getMultipleAccounts' API"),
+        )
+        .arg(
+            Arg::with_name("rpc_non_circulating_account")
+                .long("rpc-non-circulating-account")
+                .value_name("PUBKEY")
+                .takes_value(true)
+                .multiple(true)
+                .validator(is_pubkey)
+                .help("Exclude this account from the circulating supply reported by the JSON RPC 'getSupply' API. May be specified multiple times"),
+        )
+        .arg(
+            Arg::with_name("rpc_max_requests_per_second")
+                .long("rpc-max-requests-per-second")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .help("Limit each client to this many JSON RPC requests per second, not counting expensive methods like 'getProgramAccounts' [default: unlimited]"),
+        )
+        .arg(
+            Arg::with_name("rpc_max_expensive_requests_per_second")
+                .long("rpc-max-expensive-requests-per-second")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .help("Limit each client to this many requests per second to expensive JSON RPC methods like 'getProgramAccounts' and 'getConfirmedBlocks' [default: unlimited]"),
+        )
+        .arg(
+            Arg::with_name("rpc_allowed_methods")
+                .long("rpc-allowed-method")
+                .value_name("METHOD")
+                .takes_value(true)
+                .multiple(true)
+                .help("Only serve this JSON RPC method. May be specified multiple times [default: all methods]"),
+        )
+        .arg(
+            Arg::with_name("rpc_denied_methods")
+                .long("rpc-denied-method")
+                .value_name("METHOD")
+                .takes_value(true)
+                .multiple(true)
+                .help("Never serve this JSON RPC method. May be specified multiple times"),
+        )
+        .arg(
+            Arg::with_name("rpc_trusted_proxies")
+                .long("rpc-trusted-proxy")
+                .value_name("IP_ADDRESS")
+                .takes_value(true)
+                .multiple(true)
+                .validator(ip_addr_validator)
+                .help("Rate limit JSON RPC requests from this proxy by the client address in its X-Forwarded-For or X-Real-IP header, rather than by the proxy's own address. May be specified multiple times"),
+        )
+        .arg(
+            Arg::with_name("trusted_validators")
+                .long("trusted-validator")
+                .value_name("PUBKEY")
+                .takes_value(true)
+                .multiple(true)
+                .validator(is_pubkey)
+                .help("A validator whose gossiped slots the JSON RPC health check compares against. May be specified multiple times. Without any, the health check always reports the node as healthy"),
+        )
+        .arg(
+            Arg::with_name("health_check_slot_distance")
+                .long("health-check-slot-distance")
+                .value_name("SLOT_DISTANCE")
+                .takes_value(true)
+                .default_value(default_health_check_slot_distance)
+                .help("Report this node as healthy if its latest slot is within this many slots of the latest slot advertised by the trusted validators"),
+        )
+        .arg(
+            Arg::with_name("rpc_pubsub_max_active_subscriptions")
+                .long("rpc-pubsub-max-active-subscriptions")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .default_value(default_rpc_pubsub_max_active_subscriptions)
+                .help("The maximum number of active subscriptions that the RPC PubSub service accepts across all connections"),
+        )
+        .arg(
+            Arg::with_name("rpc_pubsub_max_subscriptions_per_connection")
+                .long("rpc-pubsub-max-subscriptions-per-connection")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .default_value(default_rpc_pubsub_max_subscriptions_per_connection)
+                .help("The maximum number of active subscriptions that the RPC PubSub service accepts on a single connection"),
+        )
+        .arg(
+            Arg::with_name("rpc_pubsub_queue_capacity_items")
+                .long("rpc-pubsub-queue-capacity-items")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .default_value(default_rpc_pubsub_queue_capacity_items)
+                .help("The maximum number of notifications that the RPC PubSub service queues for a connection. Subscribers that fall further behind are dropped"),
+        )
         .arg(
             Arg::with_name("signer_addr")
                 .long("vote-signer-address")
@@ -586,6 +701,64 @@ pub fn main() {
     validator_config.rpc_config.faucet_addr = matches.value_of("rpc_faucet_addr").map(|address| {
         solana_net_utils::parse_host_port(address).expect("failed to parse faucet address")
     });
+    validator_config.rpc_config.max_multiple_accounts =
+        value_t_or_exit!(matches, "rpc_max_multiple_accounts", usize);
+    validator_config.rpc_config.non_circulating_accounts =
+        values_of::<Pubkey>(&matches, "rpc_non_circulating_account")
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+    validator_config.rpc_config.trusted_validators =
+        values_of::<Pubkey>(&matches, "trusted_validators")
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+    validator_config.rpc_config.health_check_slot_distance =
+        value_t_or_exit!(matches, "health_check_slot_distance", u64);
+    validator_config.rpc_config.rate_limit_config = RpcRateLimitConfig {
+        requests_per_second: if matches.is_present("rpc_max_requests_per_second") {
+            Some(value_t_or_exit!(
+                matches,
+                "rpc_max_requests_per_second",
+                u64
+            ))
+        } else {
+            None
+        },
+        expensive_requests_per_second: if matches
+            .is_present("rpc_max_expensive_requests_per_second")
+        {
+            Some(value_t_or_exit!(
+                matches,
+                "rpc_max_expensive_requests_per_second",
+                u64
+            ))
+        } else {
+            None
+        },
+        allowed_methods: values_t!(matches, "rpc_allowed_methods", String)
+            .ok()
+            .map(|methods| methods.into_iter().collect()),
+        denied_methods: values_t!(matches, "rpc_denied_methods", String)
+            .unwrap_or_default()
+            .into_iter()
+            .collect(),
+        trusted_proxies: values_of::<IpAddr>(&matches, "rpc_trusted_proxies")
+            .unwrap_or_default()
+            .into_iter()
+            .collect(),
+    };
+    validator_config.pubsub_config.max_active_subscriptions =
+        value_t_or_exit!(matches, "rpc_pubsub_max_active_subscriptions", usize);
+    validator_config
+        .pubsub_config
+        .max_subscriptions_per_connection = value_t_or_exit!(
+        matches,
+        "rpc_pubsub_max_subscriptions_per_connection",
+        usize
+    );
+    validator_config.pubsub_config.queue_capacity_items =
+        value_t_or_exit!(matches, "rpc_pubsub_queue_capacity_items", usize);
 
     let dynamic_port_range =
         solana_net_utils::parse_port_range(matches.value_of("dynamic_port_range").unwrap())